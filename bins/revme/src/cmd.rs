@@ -1,7 +1,10 @@
 pub mod bytecode;
 pub mod eofvalidation;
 pub mod evmrunner;
+#[cfg(feature = "repl")]
+pub mod repl;
 pub mod statetest;
+pub mod t8n;
 
 use clap::Parser;
 
@@ -17,6 +20,11 @@ pub enum MainCmd {
     Evm(evmrunner::Cmd),
     /// Print the structure of an EVM bytecode.
     Bytecode(bytecode::Cmd),
+    /// Run a state transition in the format of the `t8n` tool.
+    T8n(t8n::Cmd),
+    /// Start an interactive session on an in-memory chain.
+    #[cfg(feature = "repl")]
+    Repl(repl::Cmd),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -25,6 +33,11 @@ pub enum Error {
     Statetest(#[from] statetest::Error),
     #[error(transparent)]
     EvmRunnerErrors(#[from] evmrunner::Errors),
+    #[error(transparent)]
+    T8n(#[from] t8n::Error),
+    #[cfg(feature = "repl")]
+    #[error(transparent)]
+    Repl(#[from] repl::Error),
     #[error("Eof validation failed: {:?}/{total_tests}", total_tests-failed_test)]
     EofValidation {
         failed_test: usize,
@@ -44,6 +57,9 @@ impl MainCmd {
                 cmd.run();
                 Ok(())
             }
+            Self::T8n(cmd) => cmd.run().map_err(Into::into),
+            #[cfg(feature = "repl")]
+            Self::Repl(cmd) => cmd.run().map_err(Into::into),
         }
     }
 }
@@ -4,7 +4,7 @@ use revm::{
     inspector_handle_register,
     inspectors::TracerEip3155,
     primitives::{address, Address, Bytecode, BytecodeDecodeError, EthereumWiring, TxKind},
-    Database, Evm,
+    Evm,
 };
 use std::io::Error as IoError;
 use std::path::PathBuf;
@@ -74,9 +74,7 @@ impl Cmd {
             .map_err(|_| Errors::InvalidInput)?
             .into();
 
-        let mut db = BenchmarkDB::new_bytecode(Bytecode::new_raw_checked(bytecode.into())?);
-
-        let nonce = db.basic(CALLER).unwrap().map_or(0, |account| account.nonce);
+        let db = BenchmarkDB::new_bytecode(Bytecode::new_raw_checked(bytecode.into())?);
 
         // BenchmarkDB is dummy state that implements Database trait.
         // the bytecode is deployed at zero address.
@@ -87,7 +85,6 @@ impl Cmd {
                 tx.caller = CALLER;
                 tx.transact_to = TxKind::Call(Address::ZERO);
                 tx.data = input;
-                tx.nonce = nonce;
             })
             .build();
 
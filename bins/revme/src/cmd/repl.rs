@@ -0,0 +1,374 @@
+use clap::Parser;
+use revm::{
+    db::{AccountState, CacheDB, EmptyDB},
+    inspector_handle_register,
+    inspectors::TracerEip3155,
+    interpreter::OpCode,
+    primitives::{
+        address, hex, AccountInfo, Address, BlockEnv, Bytes, EthereumWiring, ExecutionResult,
+        HaltReason, Output, SpecId, TxKind, U256,
+    },
+    Database, DatabaseCommit, Evm,
+};
+use std::{
+    io::{self, BufRead, Write},
+    str::FromStr,
+};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("Unknown command `{0}`, type `help` for the list of commands")]
+    UnknownCommand(String),
+    #[error("Missing argument: {0}")]
+    MissingArgument(&'static str),
+    #[error("Invalid {0}: `{1}`")]
+    InvalidArgument(&'static str, String),
+    #[error("EVM error: {0}")]
+    EVMError(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+const HELP: &str = "\
+Commands:
+  deploy <initcode> [value]        Deploys a contract and prints its address
+  call <address> [data] [value]    Sends a transaction and prints its output
+  balance <address> [balance]      Prints or sets the balance of an account
+  storage <address> <slot>         Prints a storage slot
+  code <address>                   Prints the code of an account
+  caller [address]                 Prints or sets the sender of transactions
+  block <field> <value>            Sets `number`, `timestamp`, `basefee`, `gaslimit` or `coinbase`
+  trace <on|off>                   Toggles EIP-3155 tracing of transactions
+  help                             Prints this message
+  exit                             Ends the session";
+
+/// Gas limit of the transactions of a session, capped by the block gas limit.
+const TX_GAS_LIMIT: u64 = 30_000_000;
+
+/// Interactive session on top of an in-memory chain.
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// Hardfork of the session.
+    #[arg(long, default_value = "LATEST")]
+    spec: String,
+}
+
+impl Cmd {
+    /// Runs the session, reading commands from the standard input.
+    pub fn run(&self) -> Result<(), Error> {
+        let spec_id = SpecId::from(self.spec.as_str());
+        if spec_id == SpecId::LATEST && !self.spec.eq_ignore_ascii_case("latest") {
+            return Err(Error::InvalidArgument("spec", self.spec.clone()));
+        }
+        let mut session = Session::new(spec_id);
+
+        println!("{HELP}");
+        let mut stdout = io::stdout();
+        let mut lines = io::stdin().lock().lines();
+        loop {
+            print!("> ");
+            stdout.flush()?;
+            let Some(line) = lines.next().transpose()? else {
+                return Ok(());
+            };
+            match session.execute(&line) {
+                Ok(Some(output)) => println!("{output}"),
+                Ok(None) => return Ok(()),
+                Err(error) => println!("{error}"),
+            }
+        }
+    }
+}
+
+/// State of an interactive session.
+///
+/// Every transaction is committed to the in-memory database, so the state persists for the
+/// whole session.
+#[derive(Debug)]
+pub struct Session {
+    pub db: CacheDB<EmptyDB>,
+    pub block: BlockEnv,
+    pub caller: Address,
+    pub spec_id: SpecId,
+    pub trace: bool,
+}
+
+impl Session {
+    /// Default sender of transactions, funded with 1000 ether.
+    pub const DEFAULT_CALLER: Address = address!("1000000000000000000000000000000000000000");
+
+    /// Creates a new session with a funded [Session::DEFAULT_CALLER].
+    pub fn new(spec_id: SpecId) -> Self {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            Self::DEFAULT_CALLER,
+            AccountInfo::from_balance(U256::from(1000) * U256::from(10).pow(U256::from(18))),
+        );
+        Self {
+            db,
+            block: BlockEnv::default(),
+            caller: Self::DEFAULT_CALLER,
+            spec_id,
+            trace: false,
+        }
+    }
+
+    /// Executes a single command line.
+    ///
+    /// Returns the text to print, or `None` if the session has ended.
+    pub fn execute(&mut self, line: &str) -> Result<Option<String>, Error> {
+        let mut args = line.split_whitespace();
+        let Some(command) = args.next() else {
+            return Ok(Some(String::new()));
+        };
+        let output = match command {
+            "deploy" => {
+                let code = parse_bytes(args.next().ok_or(Error::MissingArgument("initcode"))?)?;
+                let value = args.next().map(parse_u256).transpose()?.unwrap_or_default();
+                match self.transact(TxKind::Create, code, value)? {
+                    ExecutionResult::Success {
+                        output: Output::Create(_, Some(address)),
+                        gas_used,
+                        ..
+                    } => format!("Deployed at {address}, gas used {gas_used}"),
+                    result => format_result(&result),
+                }
+            }
+            "call" => {
+                let to = parse_address(args.next().ok_or(Error::MissingArgument("address"))?)?;
+                let data = args
+                    .next()
+                    .map(parse_bytes)
+                    .transpose()?
+                    .unwrap_or_default();
+                let value = args.next().map(parse_u256).transpose()?.unwrap_or_default();
+                format_result(&self.transact(TxKind::Call(to), data, value)?)
+            }
+            "balance" => {
+                let address = parse_address(args.next().ok_or(Error::MissingArgument("address"))?)?;
+                if let Some(balance) = args.next() {
+                    let balance = parse_u256(balance)?;
+                    let Ok(account) = self.db.load_account(address);
+                    account.info.balance = balance;
+                    // Accounts that were read before they existed are cached as not existing.
+                    if account.account_state == AccountState::NotExisting {
+                        account.account_state = AccountState::Touched;
+                    }
+                }
+                self.account(address).balance.to_string()
+            }
+            "storage" => {
+                let address = parse_address(args.next().ok_or(Error::MissingArgument("address"))?)?;
+                let slot = parse_u256(args.next().ok_or(Error::MissingArgument("slot"))?)?;
+                let value = self.db.storage(address, slot).unwrap_or_default();
+                format!("{value:#x}")
+            }
+            "code" => {
+                let address = parse_address(args.next().ok_or(Error::MissingArgument("address"))?)?;
+                let code_hash = self.account(address).code_hash;
+                let code = self.db.code_by_hash(code_hash).unwrap_or_default();
+                hex::encode_prefixed(code.original_byte_slice())
+            }
+            "caller" => {
+                if let Some(address) = args.next() {
+                    self.caller = parse_address(address)?;
+                }
+                self.caller.to_string()
+            }
+            "block" => {
+                let field = args.next().ok_or(Error::MissingArgument("field"))?;
+                let value = args.next().ok_or(Error::MissingArgument("value"))?;
+                match field {
+                    "number" => self.block.number = parse_u256(value)?,
+                    "timestamp" => self.block.timestamp = parse_u256(value)?,
+                    "basefee" => self.block.basefee = parse_u256(value)?,
+                    "gaslimit" => self.block.gas_limit = parse_u256(value)?,
+                    "coinbase" => self.block.coinbase = parse_address(value)?,
+                    _ => return Err(Error::InvalidArgument("block field", field.to_string())),
+                }
+                format!("{field} = {value}")
+            }
+            "trace" => {
+                self.trace = match args.next() {
+                    Some("on") => true,
+                    Some("off") => false,
+                    Some(arg) => return Err(Error::InvalidArgument("trace", arg.to_string())),
+                    None => return Err(Error::MissingArgument("on|off")),
+                };
+                format!("Tracing {}", if self.trace { "on" } else { "off" })
+            }
+            "help" => HELP.to_string(),
+            "exit" | "quit" => return Ok(None),
+            command => return Err(Error::UnknownCommand(command.to_string())),
+        };
+        Ok(Some(output))
+    }
+
+    /// Returns the account info of `address`, or an empty account if it does not exist.
+    fn account(&mut self, address: Address) -> AccountInfo {
+        self.db.basic(address).ok().flatten().unwrap_or_default()
+    }
+
+    /// Executes a transaction from the caller and commits it.
+    fn transact(
+        &mut self,
+        kind: TxKind,
+        data: Bytes,
+        value: U256,
+    ) -> Result<ExecutionResult<HaltReason>, Error> {
+        let caller = self.caller;
+        let gas_limit = self
+            .block
+            .gas_limit
+            .saturating_to::<u64>()
+            .min(TX_GAS_LIMIT);
+        let gas_price = self.block.basefee;
+        let output: Box<dyn Write> = if self.trace {
+            Box::new(io::stdout())
+        } else {
+            Box::new(io::sink())
+        };
+
+        let mut builder = Evm::<EthereumWiring<&mut CacheDB<EmptyDB>, TracerEip3155>>::builder()
+            .with_db(&mut self.db)
+            .with_external_context(TracerEip3155::new(output))
+            .with_spec_id(self.spec_id)
+            .with_block_env(self.block.clone())
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = kind;
+                tx.data = data;
+                tx.value = value;
+                tx.gas_limit = gas_limit;
+                tx.gas_price = gas_price;
+            });
+        if self.trace {
+            builder = builder.append_handler_register(inspector_handle_register);
+        }
+        let mut evm = builder.build();
+        let result_and_state = evm
+            .transact()
+            .map_err(|error| Error::EVMError(format!("{error:?}")))?;
+        drop(evm);
+
+        self.db.commit(result_and_state.state);
+        Ok(result_and_state.result)
+    }
+}
+
+fn format_result(result: &ExecutionResult<HaltReason>) -> String {
+    match result {
+        ExecutionResult::Success {
+            output, gas_used, ..
+        } => format!(
+            "Success, gas used {gas_used}, output {}",
+            hex::encode_prefixed(output.data())
+        ),
+        ExecutionResult::Revert { gas_used, output } => {
+            format!(
+                "Revert, gas used {gas_used}, output {}",
+                hex::encode_prefixed(output)
+            )
+        }
+        ExecutionResult::Halt {
+            reason,
+            gas_used,
+            location,
+        } => match location {
+            Some(location) => format!(
+                "Halt: {reason:?} at {} pc {} opcode {}, gas used {gas_used}",
+                location.address,
+                location.pc,
+                OpCode::name_by_op(location.opcode)
+            ),
+            None => format!("Halt: {reason:?}, gas used {gas_used}"),
+        },
+    }
+}
+
+fn parse_address(arg: &str) -> Result<Address, Error> {
+    Address::from_str(arg).map_err(|_| Error::InvalidArgument("address", arg.to_string()))
+}
+
+fn parse_u256(arg: &str) -> Result<U256, Error> {
+    U256::from_str(arg).map_err(|_| Error::InvalidArgument("number", arg.to_string()))
+}
+
+fn parse_bytes(arg: &str) -> Result<Bytes, Error> {
+    hex::decode(arg)
+        .map(Into::into)
+        .map_err(|_| Error::InvalidArgument("hex", arg.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deploy_and_call() {
+        let mut session = Session::new(SpecId::CANCUN);
+
+        // Runtime code stores CALLVALUE at slot 0: CALLVALUE PUSH1 0 SSTORE.
+        // Init code copies the 4 bytes of runtime code to memory and returns them.
+        let initcode = "0x600480600b6000396000f334600055";
+        let output = session
+            .execute(&format!("deploy {initcode}"))
+            .unwrap()
+            .unwrap();
+        let contract = session.caller.create(0);
+        assert!(output.starts_with(&format!("Deployed at {contract}")));
+        assert_eq!(
+            session
+                .execute(&format!("code {contract}"))
+                .unwrap()
+                .unwrap(),
+            "0x34600055"
+        );
+
+        session
+            .execute(&format!("call {contract} 0x 7"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            session
+                .execute(&format!("storage {contract} 0"))
+                .unwrap()
+                .unwrap(),
+            "0x7"
+        );
+        assert_eq!(
+            session
+                .execute(&format!("balance {contract}"))
+                .unwrap()
+                .unwrap(),
+            "7"
+        );
+    }
+
+    #[test]
+    fn session_commands() {
+        let mut session = Session::new(SpecId::CANCUN);
+        let account = "0x00000000000000000000000000000000000000aa";
+
+        assert_eq!(
+            session
+                .execute(&format!("balance {account} 100"))
+                .unwrap()
+                .unwrap(),
+            "100"
+        );
+        session.execute("block number 42").unwrap();
+        assert_eq!(session.block.number, U256::from(42));
+        session.execute("trace on").unwrap();
+        assert!(session.trace);
+        assert!(matches!(
+            session.execute("block foo 1"),
+            Err(Error::InvalidArgument(..))
+        ));
+        assert!(matches!(
+            session.execute("jump"),
+            Err(Error::UnknownCommand(_))
+        ));
+        assert_eq!(session.execute("exit").unwrap(), None);
+    }
+}
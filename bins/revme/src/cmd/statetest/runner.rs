@@ -362,7 +362,7 @@ pub fn execute_test_suite(
                     .unwrap()
                     .clone();
 
-                env.tx.nonce = u64::try_from(unit.transaction.nonce).unwrap();
+                env.tx.nonce = Some(u64::try_from(unit.transaction.nonce).unwrap());
                 env.tx.value = unit.transaction.value[test.indexes.value];
 
                 env.tx.access_list = unit
@@ -0,0 +1,140 @@
+mod executor;
+pub mod models;
+
+pub use executor::transition;
+
+use crate::cmd::statetest::models::SpecName;
+use clap::Parser;
+use models::{Alloc, Env, StdinInput, Transaction};
+use revm::primitives::U256;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::{
+    fs,
+    io::{stdin, Read},
+    path::PathBuf,
+};
+
+/// Value of an input path that reads from stdin.
+const STDIN: &str = "stdin";
+/// Value of an output path that writes to stdout.
+const STDOUT: &str = "stdout";
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    SerdeJson(#[from] serde_json::Error),
+    #[error("unsupported fork: {0}")]
+    UnsupportedFork(String),
+    #[error("invalid block number: {0}")]
+    InvalidBlockNumber(String),
+    #[error("`{0}` is missing from stdin input")]
+    MissingStdinInput(&'static str),
+}
+
+/// `t8n` subcommand.
+///
+/// Runs a state transition compatible with the `t8n` tool interface used by
+/// execution-spec-tests: reads the pre state, block environment and transactions,
+/// executes them and writes the post state and the execution result.
+#[derive(Parser, Debug)]
+pub struct Cmd {
+    /// Path of the pre state, or `stdin`.
+    #[arg(long = "input.alloc", default_value = "alloc.json")]
+    input_alloc: String,
+    /// Path of the block environment, or `stdin`.
+    #[arg(long = "input.env", default_value = "env.json")]
+    input_env: String,
+    /// Path of the transactions, or `stdin`.
+    #[arg(long = "input.txs", default_value = "txs.json")]
+    input_txs: String,
+    /// Directory the outputs are written to.
+    #[arg(long = "output.basedir", default_value = ".")]
+    output_basedir: PathBuf,
+    /// File name of the post state, or `stdout`.
+    #[arg(long = "output.alloc", default_value = "alloc.json")]
+    output_alloc: String,
+    /// File name of the execution result, or `stdout`.
+    #[arg(long = "output.result", default_value = "result.json")]
+    output_result: String,
+    /// Name of the fork rules to apply.
+    #[arg(long = "state.fork", default_value = "Cancun")]
+    fork: String,
+    /// Chain ID of the transactions.
+    #[arg(long = "state.chainid", default_value_t = 1)]
+    chain_id: u64,
+    /// Block reward of the coinbase. Negative values disable the reward.
+    #[arg(long = "state.reward", default_value_t = -1, allow_negative_numbers = true)]
+    reward: i64,
+}
+
+impl Cmd {
+    /// Run t8n command.
+    pub fn run(&self) -> Result<(), Error> {
+        let spec_id = match serde_json::from_value(Value::String(self.fork.clone()))? {
+            SpecName::Unknown | SpecName::Constantinople | SpecName::Osaka => {
+                return Err(Error::UnsupportedFork(self.fork.clone()))
+            }
+            spec_name => spec_name.to_spec_id(),
+        };
+
+        let mut stdin_input = None;
+        let alloc: Alloc = self.read_input(&self.input_alloc, &mut stdin_input, |i| {
+            i.alloc.take().ok_or(Error::MissingStdinInput("alloc"))
+        })?;
+        let env: Env = self.read_input(&self.input_env, &mut stdin_input, |i| {
+            i.env.take().ok_or(Error::MissingStdinInput("env"))
+        })?;
+        let txs: Vec<Transaction> = self.read_input(&self.input_txs, &mut stdin_input, |i| {
+            i.txs.take().ok_or(Error::MissingStdinInput("txs"))
+        })?;
+
+        let reward = u64::try_from(self.reward).ok().map(U256::from);
+        let (result, post_alloc) = transition(alloc, &env, txs, spec_id, self.chain_id, reward)?;
+
+        let mut stdout_output = Map::new();
+        self.write_output(&self.output_alloc, "alloc", &post_alloc, &mut stdout_output)?;
+        self.write_output(&self.output_result, "result", &result, &mut stdout_output)?;
+        if !stdout_output.is_empty() {
+            println!("{}", serde_json::to_string_pretty(&stdout_output)?);
+        }
+        Ok(())
+    }
+
+    /// Reads the input from `path`, or takes it from the combined stdin input.
+    fn read_input<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        stdin_input: &mut Option<StdinInput>,
+        take: impl FnOnce(&mut StdinInput) -> Result<T, Error>,
+    ) -> Result<T, Error> {
+        if path != STDIN {
+            return Ok(serde_json::from_str(&fs::read_to_string(path)?)?);
+        }
+        if stdin_input.is_none() {
+            let mut s = String::new();
+            stdin().read_to_string(&mut s)?;
+            *stdin_input = Some(serde_json::from_str(&s)?);
+        }
+        take(stdin_input.as_mut().unwrap())
+    }
+
+    /// Writes the output to a file in the base directory, or adds it to the stdout output.
+    fn write_output<T: serde::Serialize>(
+        &self,
+        name: &str,
+        key: &str,
+        value: &T,
+        stdout_output: &mut Map<String, Value>,
+    ) -> Result<(), Error> {
+        if name == STDOUT {
+            stdout_output.insert(key.to_string(), serde_json::to_value(value)?);
+            return Ok(());
+        }
+        let path = self.output_basedir.join(name);
+        fs::write(path, serde_json::to_string_pretty(value)?)?;
+        Ok(())
+    }
+}
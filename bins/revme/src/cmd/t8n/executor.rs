@@ -0,0 +1,372 @@
+use super::{
+    models::{
+        Alloc, AllocAccount, Env, Receipt, RejectedTransaction, Transaction, TransitionResult,
+    },
+    Error,
+};
+use crate::cmd::statetest::{
+    merkle_trie::{log_rlp_hash, state_merkle_trie_root, KeccakHasher},
+    utils::recover_address,
+};
+use alloy_rlp::RlpEncodable;
+use revm::{
+    db::{EmptyDB, State},
+    interpreter::analysis::to_analysed,
+    primitives::{
+        alloy_primitives::Bloom, calc_excess_blob_gas, keccak256, Address, Bytecode, EnvWiring,
+        EthereumWiring, ExecutionResult, Log, SpecId, TxKind, B256, GAS_PER_BLOB, U256,
+    },
+    CacheState, Evm,
+};
+use std::collections::BTreeMap;
+use triehash::ordered_trie_root;
+
+type T8nEvmWiring<'a> = EthereumWiring<&'a mut State<EmptyDB>, ()>;
+
+/// Gwei to wei conversion factor of withdrawal amounts.
+const GWEI_TO_WEI: u128 = 1_000_000_000;
+
+/// Receipt fields that are part of the receipts trie.
+#[derive(RlpEncodable)]
+struct ReceiptRlp {
+    status: bool,
+    cumulative_gas_used: u64,
+    logs_bloom: Bloom,
+    logs: Vec<Log>,
+}
+
+/// Withdrawal fields that are part of the withdrawals trie.
+#[derive(RlpEncodable)]
+struct WithdrawalRlp {
+    index: u64,
+    validator_index: u64,
+    address: Address,
+    amount: u64,
+}
+
+/// Applies `txs` on top of `alloc` in the block described by `env`.
+///
+/// Transactions that fail validation are reported as rejected and do not change the state.
+/// If `reward` is set, it is added to the balance of the coinbase after all transactions
+/// were executed. Withdrawals are applied last.
+///
+/// Returns the result of the transition and the post state.
+pub fn transition(
+    alloc: Alloc,
+    env: &Env,
+    txs: Vec<Transaction>,
+    spec_id: SpecId,
+    chain_id: u64,
+    reward: Option<U256>,
+) -> Result<(TransitionResult, Alloc), Error> {
+    let mut cache_state = CacheState::new(spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON));
+    for (address, account) in alloc {
+        let code_hash = keccak256(&account.code);
+        let bytecode = to_analysed(Bytecode::new_raw(account.code));
+        let info = revm::primitives::AccountInfo {
+            balance: account.balance,
+            code_hash,
+            code: Some(bytecode),
+            nonce: account.nonce.saturating_to(),
+        };
+        cache_state.insert_account_with_storage(
+            address,
+            info,
+            account.storage.into_iter().collect(),
+        );
+    }
+
+    let block_hashes = env
+        .block_hashes
+        .iter()
+        .map(|(number, hash)| Ok((parse_block_number(number)?, *hash)))
+        .collect::<Result<BTreeMap<_, _>, Error>>()?;
+
+    let mut state = State::builder()
+        .with_cached_prestate(cache_state)
+        .with_bundle_update()
+        .with_block_hashes(block_hashes)
+        .build();
+
+    let mut evm_env = Box::<EnvWiring<T8nEvmWiring<'_>>>::default();
+    evm_env.cfg.chain_id = chain_id;
+
+    evm_env.block.number = env.current_number;
+    evm_env.block.coinbase = env.current_coinbase;
+    evm_env.block.timestamp = env.current_timestamp;
+    evm_env.block.gas_limit = env.current_gas_limit;
+    evm_env.block.basefee = env.current_base_fee.unwrap_or_default();
+    evm_env.block.difficulty = env.current_difficulty;
+    evm_env.block.prevrandao = env.current_random;
+    if spec_id.is_enabled_in(SpecId::MERGE) && evm_env.block.prevrandao.is_none() {
+        evm_env.block.prevrandao = Some(B256::ZERO);
+    }
+    // EIP-4844
+    let excess_blob_gas = env.current_excess_blob_gas.map(|gas| gas.to()).or_else(|| {
+        env.parent_blob_gas_used
+            .zip(env.parent_excess_blob_gas)
+            .map(|(used, excess)| calc_excess_blob_gas(used.to(), excess.to()))
+    });
+    if let Some(excess_blob_gas) = excess_blob_gas {
+        evm_env.block.set_blob_excess_gas_and_price(excess_blob_gas);
+    }
+
+    let mut receipts = Vec::new();
+    let mut rejected = Vec::new();
+    let mut all_logs = Vec::new();
+    let mut block_bloom = Bloom::ZERO;
+    let mut cumulative_gas_used = 0u64;
+    let mut blob_gas_used = 0u64;
+    let mut receipts_rlp = Vec::new();
+
+    {
+        let mut evm = Evm::<T8nEvmWiring<'_>>::builder()
+            .with_db(&mut state)
+            .with_default_ext_ctx()
+            .with_env(evm_env)
+            .with_spec_id(spec_id)
+            .build();
+
+        for (index, tx) in txs.into_iter().enumerate() {
+            let tx_type = tx.tx_type();
+            if tx_type > 3 {
+                rejected.push(RejectedTransaction {
+                    index,
+                    error: format!("unsupported transaction type: {tx_type}"),
+                });
+                continue;
+            }
+            let caller = match (tx.sender, tx.secret_key) {
+                (Some(sender), _) => Some(sender),
+                (None, Some(secret_key)) => recover_address(secret_key.as_slice()),
+                (None, None) => None,
+            };
+            let Some(caller) = caller else {
+                rejected.push(RejectedTransaction {
+                    index,
+                    error: "unknown sender".to_string(),
+                });
+                continue;
+            };
+
+            let tx_env = evm.tx_mut();
+            tx_env.caller = caller;
+            tx_env.gas_limit = tx.gas.saturating_to();
+            tx_env.gas_price = tx.gas_price.or(tx.max_fee_per_gas).unwrap_or_default();
+            tx_env.gas_priority_fee = tx.max_priority_fee_per_gas;
+            tx_env.transact_to = tx.to.map(TxKind::Call).unwrap_or(TxKind::Create);
+            tx_env.value = tx.value;
+            tx_env.data = tx.input;
+            tx_env.nonce = Some(tx.nonce.saturating_to());
+            tx_env.chain_id = tx.chain_id.map(|id| id.saturating_to());
+            tx_env.access_list = tx.access_list.map(|list| list.0).unwrap_or_default();
+            tx_env.blob_hashes = tx.blob_versioned_hashes;
+            tx_env.max_fee_per_blob_gas = tx.max_fee_per_blob_gas;
+
+            let result = match evm.transact_commit() {
+                Ok(result) => result,
+                Err(error) => {
+                    rejected.push(RejectedTransaction {
+                        index,
+                        error: error.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            blob_gas_used += GAS_PER_BLOB * evm.tx().blob_hashes.len() as u64;
+            cumulative_gas_used += result.gas_used();
+            let contract_address = match &result {
+                ExecutionResult::Success { output, .. } => output.address().copied(),
+                _ => None,
+            };
+            let gas_used = result.gas_used();
+            let is_success = result.is_success();
+            let logs = result.into_logs();
+
+            let mut logs_bloom = Bloom::ZERO;
+            for log in &logs {
+                logs_bloom.accrue_log(log);
+            }
+            block_bloom |= logs_bloom;
+
+            let mut encoded = Vec::new();
+            if tx_type != 0 {
+                encoded.push(tx_type);
+            }
+            alloy_rlp::Encodable::encode(
+                &ReceiptRlp {
+                    status: is_success,
+                    cumulative_gas_used,
+                    logs_bloom,
+                    logs: logs.clone(),
+                },
+                &mut encoded,
+            );
+            receipts_rlp.push(encoded);
+
+            all_logs.extend(logs.iter().cloned());
+            receipts.push(Receipt {
+                tx_type: U256::from(tx_type),
+                status: U256::from(is_success as u8),
+                cumulative_gas_used: U256::from(cumulative_gas_used),
+                logs_bloom,
+                logs,
+                contract_address,
+                gas_used: U256::from(gas_used),
+                transaction_index: U256::from(index),
+            });
+        }
+    }
+
+    if let Some(reward) = reward {
+        state
+            .increment_balances([(env.current_coinbase, reward.saturating_to())])
+            .expect("EmptyDB is infallible");
+    }
+
+    let withdrawals_root = env.withdrawals.as_ref().map(|withdrawals| {
+        state
+            .increment_balances(withdrawals.iter().map(|withdrawal| {
+                (
+                    withdrawal.address,
+                    withdrawal.amount.saturating_to::<u128>() * GWEI_TO_WEI,
+                )
+            }))
+            .expect("EmptyDB is infallible");
+        ordered_trie_root::<KeccakHasher, _>(withdrawals.iter().map(|withdrawal| {
+            alloy_rlp::encode(WithdrawalRlp {
+                index: withdrawal.index.saturating_to(),
+                validator_index: withdrawal.validator_index.saturating_to(),
+                address: withdrawal.address,
+                amount: withdrawal.amount.saturating_to(),
+            })
+        }))
+    });
+
+    let post_alloc = state
+        .cache
+        .trie_account()
+        .into_iter()
+        .map(|(address, account)| {
+            let account = AllocAccount {
+                balance: account.info.balance,
+                nonce: U256::from(account.info.nonce),
+                code: account
+                    .info
+                    .code
+                    .as_ref()
+                    .map(Bytecode::original_bytes)
+                    .unwrap_or_default(),
+                storage: account
+                    .storage
+                    .iter()
+                    .filter(|(_, value)| !value.is_zero())
+                    .map(|(key, value)| (*key, *value))
+                    .collect(),
+            };
+            (address, account)
+        })
+        .collect();
+
+    let is_cancun = spec_id.is_enabled_in(SpecId::CANCUN);
+    let result = TransitionResult {
+        state_root: state_merkle_trie_root(state.cache.trie_account()),
+        receipts_root: ordered_trie_root::<KeccakHasher, _>(receipts_rlp),
+        logs_hash: log_rlp_hash(&all_logs),
+        logs_bloom: block_bloom,
+        receipts,
+        rejected,
+        current_difficulty: env.current_difficulty,
+        gas_used: U256::from(cumulative_gas_used),
+        current_base_fee: env.current_base_fee,
+        withdrawals_root,
+        current_excess_blob_gas: excess_blob_gas.filter(|_| is_cancun).map(U256::from),
+        blob_gas_used: is_cancun.then(|| U256::from(blob_gas_used)),
+    };
+
+    Ok((result, post_alloc))
+}
+
+/// Parses a block number given either in decimal or as a `0x`-prefixed hex string.
+fn parse_block_number(number: &str) -> Result<u64, Error> {
+    if let Some(stripped) = number.strip_prefix("0x") {
+        u64::from_str_radix(stripped, 16)
+    } else {
+        number.parse()
+    }
+    .map_err(|_| Error::InvalidBlockNumber(number.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{address, b256};
+
+    #[test]
+    fn value_transfer() {
+        let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+        let receiver = address!("1000000000000000000000000000000000000000");
+        let alloc = Alloc::from([(
+            sender,
+            AllocAccount {
+                balance: U256::from(1_000_000_000),
+                ..Default::default()
+            },
+        )]);
+        let env: Env = serde_json::from_str(
+            r#"{
+                "currentCoinbase": "0x2adc25665018aa1fe0e6bc666dac8fc2697ff9ba",
+                "currentGasLimit": "0x1000000",
+                "currentNumber": "0x1",
+                "currentTimestamp": "0x3e8",
+                "currentBaseFee": "0x7",
+                "blockHashes": { "0": "0x0000000000000000000000000000000000000000000000000000000000000001" }
+            }"#,
+        )
+        .unwrap();
+        let txs: Vec<Transaction> = serde_json::from_str(
+            r#"[
+                {
+                    "nonce": "0x0",
+                    "gasPrice": "0xa",
+                    "gas": "0x5208",
+                    "to": "0x1000000000000000000000000000000000000000",
+                    "value": "0x64",
+                    "input": "0x",
+                    "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+                },
+                {
+                    "nonce": "0x0",
+                    "gasPrice": "0xa",
+                    "gas": "0x5208",
+                    "to": "0x1000000000000000000000000000000000000000",
+                    "value": "0x64",
+                    "input": "0x",
+                    "secretKey": "0x45a915e4d060149eb4365960e6a7a45f334393093061116b197e3240065ff2d8"
+                }
+            ]"#,
+        )
+        .unwrap();
+
+        let (result, post) = transition(alloc, &env, txs, SpecId::LONDON, 1, None).unwrap();
+
+        assert_eq!(result.receipts.len(), 1);
+        assert_eq!(result.receipts[0].status, U256::from(1));
+        assert_eq!(result.gas_used, U256::from(21_000));
+        // Second transaction reuses the nonce.
+        assert_eq!(result.rejected.len(), 1);
+        assert_eq!(result.rejected[0].index, 1);
+        assert_eq!(post[&receiver].balance, U256::from(100));
+        assert_eq!(post[&sender].nonce, U256::from(1));
+        assert_eq!(
+            post[&sender].balance,
+            U256::from(1_000_000_000 - 100 - 21_000 * 10)
+        );
+        assert_ne!(result.state_root, B256::ZERO);
+        assert_eq!(
+            result.logs_hash,
+            b256!("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347")
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use revm::primitives::{alloy_primitives::Bloom, AccessList, Address, Bytes, Log, B256, U256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Accounts of the pre or post state, keyed by address.
+pub type Alloc = BTreeMap<Address, AllocAccount>;
+
+/// Account in the `alloc` input and output.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllocAccount {
+    #[serde(default)]
+    pub balance: U256,
+    #[serde(default)]
+    pub nonce: U256,
+    #[serde(default, skip_serializing_if = "<[u8]>::is_empty")]
+    pub code: Bytes,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Block environment of the `env` input.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Env {
+    pub current_coinbase: Address,
+    #[serde(default)]
+    pub current_difficulty: U256,
+    pub current_gas_limit: U256,
+    pub current_number: U256,
+    pub current_timestamp: U256,
+    pub current_base_fee: Option<U256>,
+    pub current_random: Option<B256>,
+
+    pub parent_blob_gas_used: Option<U256>,
+    pub parent_excess_blob_gas: Option<U256>,
+    pub current_excess_blob_gas: Option<U256>,
+
+    /// Hashes of previous blocks, keyed by the block number.
+    #[serde(default)]
+    pub block_hashes: BTreeMap<String, B256>,
+    #[serde(default)]
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+/// Withdrawal of the `env` input.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    pub index: U256,
+    pub validator_index: U256,
+    pub address: Address,
+    /// Amount in Gwei.
+    pub amount: U256,
+}
+
+/// Transaction of the `txs` input.
+///
+/// Signatures are not verified, the sender is taken from `sender` or derived from `secretKey`.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    #[serde(default, rename = "type")]
+    pub tx_type: Option<U256>,
+    pub chain_id: Option<U256>,
+    pub nonce: U256,
+    pub gas_price: Option<U256>,
+    pub max_priority_fee_per_gas: Option<U256>,
+    pub max_fee_per_gas: Option<U256>,
+    #[serde(alias = "gasLimit")]
+    pub gas: U256,
+    #[serde(default)]
+    pub to: Option<Address>,
+    #[serde(default)]
+    pub value: U256,
+    #[serde(default, alias = "data")]
+    pub input: Bytes,
+    pub access_list: Option<AccessList>,
+    pub max_fee_per_blob_gas: Option<U256>,
+    #[serde(default)]
+    pub blob_versioned_hashes: Vec<B256>,
+    pub sender: Option<Address>,
+    pub secret_key: Option<B256>,
+}
+
+impl Transaction {
+    /// Returns the EIP-2718 transaction type, inferring it from the fee fields if it is not set.
+    pub fn tx_type(&self) -> u8 {
+        if let Some(tx_type) = self.tx_type {
+            return tx_type.saturating_to();
+        }
+        if self.max_fee_per_blob_gas.is_some() {
+            3
+        } else if self.max_fee_per_gas.is_some() {
+            2
+        } else if self.access_list.is_some() {
+            1
+        } else {
+            0
+        }
+    }
+}
+
+/// Combined input read from stdin.
+#[derive(Debug, Default, Deserialize)]
+pub struct StdinInput {
+    pub alloc: Option<Alloc>,
+    pub env: Option<Env>,
+    pub txs: Option<Vec<Transaction>>,
+}
+
+/// Result of the state transition.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionResult {
+    pub state_root: B256,
+    pub receipts_root: B256,
+    pub logs_hash: B256,
+    pub logs_bloom: Bloom,
+    pub receipts: Vec<Receipt>,
+    pub rejected: Vec<RejectedTransaction>,
+    pub current_difficulty: U256,
+    pub gas_used: U256,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_base_fee: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub withdrawals_root: Option<B256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_excess_blob_gas: Option<U256>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_gas_used: Option<U256>,
+}
+
+/// Receipt of an included transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    #[serde(rename = "type")]
+    pub tx_type: U256,
+    pub status: U256,
+    pub cumulative_gas_used: U256,
+    pub logs_bloom: Bloom,
+    pub logs: Vec<Log>,
+    pub contract_address: Option<Address>,
+    pub gas_used: U256,
+    pub transaction_index: U256,
+}
+
+/// Transaction that could not be included in the block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RejectedTransaction {
+    pub index: usize,
+    pub error: String,
+}
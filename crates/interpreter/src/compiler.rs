@@ -0,0 +1,759 @@
+//! Ahead-of-time compilation of contract bytecode, enabled by the `compiler` feature.
+//!
+//! A [Backend] compiles the bytecode of a contract into [CompiledCode], which executes the
+//! contract against the same [Interpreter] and [Host] as the instructions do. Compiled code
+//! does not have to support every instruction: when it reaches one it does not execute, it
+//! leaves the interpreter at that instruction and [Interpreter::run] resumes from there.
+//!
+//! [BlockBackend] is a portable backend that compiles legacy bytecode into basic blocks of
+//! decoded instructions, whose gas and stack bounds are checked once per block. A backend
+//! generating native code, for example with Cranelift or LLVM, implements the same traits.
+
+use crate::{
+    gas,
+    instructions::{arithmetic, bitwise, control, memory, system},
+    opcode, Host, InstructionResult, Interpreter, InterpreterAction, SharedMemory,
+};
+use core::fmt;
+use revm_primitives::{Bytecode, SpecId, U256};
+use std::{sync::Arc, vec::Vec};
+
+/// Compiles the bytecode of contracts.
+pub trait Backend<H: ?Sized> {
+    /// Compiles `bytecode` for `spec_id`, or returns `None` if it is not supported.
+    fn compile(&self, bytecode: &Bytecode, spec_id: SpecId) -> Option<Arc<dyn CompiledCode<H>>>;
+}
+
+/// Code compiled from the bytecode of a contract by a [Backend].
+pub trait CompiledCode<H: ?Sized> {
+    /// Executes the contract from its first instruction.
+    ///
+    /// The code stops by setting [Interpreter::instruction_result], and the
+    /// [Interpreter::next_action] for returns, like the instructions do. Otherwise it leaves
+    /// the instruction pointer at the next instruction to execute, and the interpreter
+    /// resumes from it. Halts must happen with the gas and the stack the interpreter would
+    /// have at the halting instruction.
+    fn execute(&self, interpreter: &mut Interpreter, host: &mut H);
+}
+
+impl Interpreter {
+    /// Executes `code`, compiled from the bytecode of the contract, from the start of the
+    /// contract.
+    ///
+    /// Returns the next action if the code stopped, or `None` if it reached an instruction
+    /// that it does not execute, in which case [Interpreter::run] resumes the execution from
+    /// it. In both cases the shared memory can be taken back with [Interpreter::take_memory].
+    pub fn run_compiled<H: Host + ?Sized>(
+        &mut self,
+        code: &dyn CompiledCode<H>,
+        shared_memory: SharedMemory,
+        host: &mut H,
+    ) -> Option<InterpreterAction> {
+        self.next_action = InterpreterAction::None;
+        self.shared_memory = shared_memory;
+        code.execute(self, host);
+        (self.instruction_result != InstructionResult::Continue).then(|| self.take_next_action())
+    }
+}
+
+/// Portable [Backend] that compiles legacy bytecode into [BlockCode].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BlockBackend;
+
+impl<H: Host + ?Sized> Backend<H> for BlockBackend {
+    fn compile(&self, bytecode: &Bytecode, spec_id: SpecId) -> Option<Arc<dyn CompiledCode<H>>> {
+        Some(Arc::new(BlockCode::compile(bytecode, spec_id)?))
+    }
+}
+
+/// Legacy bytecode compiled into basic blocks.
+///
+/// Stack, arithmetic, comparison and bitwise instructions, `PUSH` constants and jumps to
+/// `PUSH` constants are executed by the blocks, which check their gas and stack bounds on
+/// entry. Memory instructions and a few others call the instructions of the interpreter.
+/// The code exits to the interpreter at dynamic jumps, at any other instruction, and at the
+/// start of a block that would run out of gas or overflow or underflow the stack.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockCode {
+    /// Blocks sorted by program counter, the first one starts at 0.
+    blocks: Vec<Block>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Block {
+    /// Program counter of the first instruction.
+    start: usize,
+    /// Static gas of the instructions of the block.
+    gas: u64,
+    /// Number of stack items read by the block.
+    inputs: usize,
+    /// Maximum growth of the stack during the block.
+    growth: usize,
+    ops: Vec<Op>,
+    end: End,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Op {
+    Push(U256),
+    Pop,
+    Dup(usize),
+    Swap(usize),
+    Add,
+    Mul,
+    Sub,
+    Div,
+    Mod,
+    Lt,
+    Gt,
+    Eq,
+    IsZero,
+    And,
+    Or,
+    Xor,
+    Not,
+    Shl,
+    Shr,
+    Address,
+    Caller,
+    CallValue,
+    CallDataSize,
+}
+
+/// End of a block. Targets are program counters while compiling, then block indices.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum End {
+    /// Falls through to the block at `next`.
+    Next(usize),
+    /// Pops the target pushed by the block and jumps to `target`.
+    Jump(usize),
+    /// Pops the target pushed by the block and the condition, and jumps to `target` if it
+    /// is not zero, or falls through to `next`.
+    JumpI { target: usize, next: usize },
+    /// Stops at `pc`.
+    Stop { pc: usize },
+    /// Calls the instruction at `pc`, then falls through to `next` if it does not stop.
+    Call {
+        instruction: Builtin,
+        pc: usize,
+        next: Option<usize>,
+    },
+    /// Exits to the interpreter at `pc`.
+    Exit { pc: usize },
+}
+
+/// Instructions executed by the interpreter implementation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Builtin {
+    SDiv,
+    SMod,
+    AddMod,
+    MulMod,
+    SignExtend,
+    Byte,
+    SLt,
+    SGt,
+    MLoad,
+    MStore,
+    MStore8,
+    MSize,
+    CallDataLoad,
+    Return,
+    Invalid,
+}
+
+impl Builtin {
+    fn from_opcode(op: u8) -> Option<Self> {
+        Some(match op {
+            opcode::SDIV => Self::SDiv,
+            opcode::SMOD => Self::SMod,
+            opcode::ADDMOD => Self::AddMod,
+            opcode::MULMOD => Self::MulMod,
+            opcode::SIGNEXTEND => Self::SignExtend,
+            opcode::BYTE => Self::Byte,
+            opcode::SLT => Self::SLt,
+            opcode::SGT => Self::SGt,
+            opcode::MLOAD => Self::MLoad,
+            opcode::MSTORE => Self::MStore,
+            opcode::MSTORE8 => Self::MStore8,
+            opcode::MSIZE => Self::MSize,
+            opcode::CALLDATALOAD => Self::CallDataLoad,
+            opcode::RETURN => Self::Return,
+            opcode::INVALID => Self::Invalid,
+            _ => return None,
+        })
+    }
+
+    /// Returns whether the instruction always stops.
+    fn stops(self) -> bool {
+        matches!(self, Self::Return | Self::Invalid)
+    }
+
+    fn call<H: Host + ?Sized>(self, interpreter: &mut Interpreter, host: &mut H) {
+        match self {
+            Self::SDiv => arithmetic::sdiv(interpreter, host),
+            Self::SMod => arithmetic::smod(interpreter, host),
+            Self::AddMod => arithmetic::addmod(interpreter, host),
+            Self::MulMod => arithmetic::mulmod(interpreter, host),
+            Self::SignExtend => arithmetic::signextend(interpreter, host),
+            Self::Byte => bitwise::byte(interpreter, host),
+            Self::SLt => bitwise::slt(interpreter, host),
+            Self::SGt => bitwise::sgt(interpreter, host),
+            Self::MLoad => memory::mload(interpreter, host),
+            Self::MStore => memory::mstore(interpreter, host),
+            Self::MStore8 => memory::mstore8(interpreter, host),
+            Self::MSize => memory::msize(interpreter, host),
+            Self::CallDataLoad => system::calldataload(interpreter, host),
+            Self::Return => control::ret(interpreter, host),
+            Self::Invalid => control::invalid(interpreter, host),
+        }
+    }
+}
+
+impl Op {
+    /// Returns the opcode as an operation executed by blocks, with its static gas, the
+    /// number of stack items it reads and the number it writes.
+    fn from_opcode(op: u8, spec_id: SpecId) -> Option<(Self, u64, usize, usize)> {
+        use gas::{BASE, LOW, VERYLOW};
+        let constantinople = SpecId::enabled(spec_id, SpecId::CONSTANTINOPLE);
+        Some(match op {
+            opcode::POP => (Self::Pop, BASE, 1, 0),
+            opcode::DUP1..=opcode::DUP16 => {
+                let n = (op - opcode::DUP1) as usize + 1;
+                (Self::Dup(n), VERYLOW, n, n + 1)
+            }
+            opcode::SWAP1..=opcode::SWAP16 => {
+                let n = (op - opcode::SWAP1) as usize + 1;
+                (Self::Swap(n), VERYLOW, n + 1, n + 1)
+            }
+            opcode::ADD => (Self::Add, VERYLOW, 2, 1),
+            opcode::MUL => (Self::Mul, LOW, 2, 1),
+            opcode::SUB => (Self::Sub, VERYLOW, 2, 1),
+            opcode::DIV => (Self::Div, LOW, 2, 1),
+            opcode::MOD => (Self::Mod, LOW, 2, 1),
+            opcode::LT => (Self::Lt, VERYLOW, 2, 1),
+            opcode::GT => (Self::Gt, VERYLOW, 2, 1),
+            opcode::EQ => (Self::Eq, VERYLOW, 2, 1),
+            opcode::ISZERO => (Self::IsZero, VERYLOW, 1, 1),
+            opcode::AND => (Self::And, VERYLOW, 2, 1),
+            opcode::OR => (Self::Or, VERYLOW, 2, 1),
+            opcode::XOR => (Self::Xor, VERYLOW, 2, 1),
+            opcode::NOT => (Self::Not, VERYLOW, 1, 1),
+            opcode::SHL if constantinople => (Self::Shl, VERYLOW, 2, 1),
+            opcode::SHR if constantinople => (Self::Shr, VERYLOW, 2, 1),
+            opcode::ADDRESS => (Self::Address, BASE, 0, 1),
+            opcode::CALLER => (Self::Caller, BASE, 0, 1),
+            opcode::CALLVALUE => (Self::CallValue, BASE, 0, 1),
+            opcode::CALLDATASIZE => (Self::CallDataSize, BASE, 0, 1),
+            _ => return None,
+        })
+    }
+
+    #[inline]
+    fn execute(self, interpreter: &mut Interpreter) {
+        let stack = &mut interpreter.stack;
+        // SAFETY: The bounds of the stack are checked on entry of the block.
+        unsafe {
+            match self {
+                Self::Push(value) => stack.push_unchecked(value),
+                Self::Pop => {
+                    stack.pop_unsafe();
+                }
+                Self::Dup(n) => stack.dup_unchecked(n),
+                Self::Swap(n) => stack.swap_unchecked(n),
+                Self::Add => binary(stack, |a, b| a.wrapping_add(b)),
+                Self::Mul => binary(stack, |a, b| a.wrapping_mul(b)),
+                Self::Sub => binary(stack, |a, b| a.wrapping_sub(b)),
+                Self::Div => binary(stack, |a, b| a.checked_div(b).unwrap_or_default()),
+                Self::Mod => binary(stack, |a, b| a.checked_rem(b).unwrap_or_default()),
+                Self::Lt => binary(stack, |a, b| U256::from(a < b)),
+                Self::Gt => binary(stack, |a, b| U256::from(a > b)),
+                Self::Eq => binary(stack, |a, b| U256::from(a == b)),
+                Self::IsZero => {
+                    let top = stack.top_unsafe();
+                    *top = U256::from(top.is_zero());
+                }
+                Self::And => binary(stack, |a, b| a & b),
+                Self::Or => binary(stack, |a, b| a | b),
+                Self::Xor => binary(stack, |a, b| a ^ b),
+                Self::Not => {
+                    let top = stack.top_unsafe();
+                    *top = !*top;
+                }
+                Self::Shl => binary(stack, |shift, value| {
+                    value
+                        .checked_shl(usize::try_from(shift).unwrap_or(usize::MAX))
+                        .unwrap_or_default()
+                }),
+                Self::Shr => binary(stack, |shift, value| {
+                    value
+                        .checked_shr(usize::try_from(shift).unwrap_or(usize::MAX))
+                        .unwrap_or_default()
+                }),
+                Self::Address => {
+                    let address = interpreter.contract.target_address.into_word();
+                    stack.push_unchecked(address.into());
+                }
+                Self::Caller => {
+                    let caller = interpreter.contract.caller.into_word();
+                    stack.push_unchecked(caller.into());
+                }
+                Self::CallValue => {
+                    let value = interpreter.contract.call_value;
+                    stack.push_unchecked(value);
+                }
+                Self::CallDataSize => {
+                    let len = U256::from(interpreter.contract.input.len());
+                    stack.push_unchecked(len);
+                }
+            }
+        }
+    }
+}
+
+/// Replaces the two top items `a` and `b` of the stack with `f(a, b)`.
+///
+/// # Safety
+///
+/// The stack must have at least two items.
+#[inline]
+unsafe fn binary(stack: &mut crate::Stack, f: impl FnOnce(U256, U256) -> U256) {
+    let (a, b) = stack.pop_top_unsafe();
+    *b = f(a, *b);
+}
+
+/// Block being compiled.
+struct BlockBuilder {
+    block: Block,
+    height: isize,
+}
+
+impl BlockBuilder {
+    fn new(start: usize) -> Self {
+        Self {
+            block: Block {
+                start,
+                gas: 0,
+                inputs: 0,
+                growth: 0,
+                ops: Vec::new(),
+                end: End::Exit { pc: start },
+            },
+            height: 0,
+        }
+    }
+
+    /// Records an instruction that reads `inputs` items and writes `outputs` items.
+    fn stack(&mut self, inputs: usize, outputs: usize) {
+        let min = self.height - inputs as isize;
+        self.block.inputs = self.block.inputs.max((-min).max(0) as usize);
+        self.height = min + outputs as isize;
+        self.block.growth = self.block.growth.max(self.height.max(0) as usize);
+    }
+
+    fn push(&mut self, value: U256, gas: u64) {
+        self.block.gas += gas;
+        self.stack(0, 1);
+        self.block.ops.push(Op::Push(value));
+    }
+
+    /// Returns the constant pushed by the last instruction of the block, if any.
+    fn pushed(&self) -> Option<U256> {
+        match self.block.ops.last() {
+            Some(Op::Push(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    fn finish(mut self, end: End) -> Block {
+        self.block.end = end;
+        self.block
+    }
+}
+
+impl BlockCode {
+    /// Compiles `bytecode`, which must be analysed legacy bytecode.
+    pub fn compile(bytecode: &Bytecode, spec_id: SpecId) -> Option<Self> {
+        let Bytecode::LegacyAnalyzed(legacy) = bytecode else {
+            return None;
+        };
+        let code = legacy.bytecode();
+        let len = legacy.original_len();
+        let jump_table = legacy.jump_table();
+        let jump_target = |value: U256| {
+            usize::try_from(value)
+                .ok()
+                .filter(|&target| jump_table.is_valid(target))
+        };
+
+        let mut blocks = Vec::new();
+        let mut current = Some(BlockBuilder::new(0));
+        let mut pc = 0;
+        while pc < len {
+            let op = code[pc];
+            let next = pc + 1;
+            if op == opcode::JUMPDEST {
+                match current.take() {
+                    Some(block) if block.block.start != pc => {
+                        blocks.push(block.finish(End::Next(pc)));
+                        current = Some(BlockBuilder::new(pc));
+                    }
+                    block => current = Some(block.unwrap_or_else(|| BlockBuilder::new(pc))),
+                }
+            }
+            // Instructions that do not follow a block are unreachable.
+            let Some(block) = current.as_mut() else {
+                pc = next + immediate_len(op);
+                continue;
+            };
+
+            let end = match op {
+                opcode::JUMPDEST => {
+                    block.block.gas += gas::JUMPDEST;
+                    None
+                }
+                opcode::PUSH0 if SpecId::enabled(spec_id, SpecId::SHANGHAI) => {
+                    block.push(U256::ZERO, gas::BASE);
+                    None
+                }
+                opcode::PUSH1..=opcode::PUSH32 => {
+                    let n = immediate_len(op);
+                    // The bytecode is padded, so the immediate is complete.
+                    block.push(U256::from_be_slice(&code[next..next + n]), gas::VERYLOW);
+                    pc = next + n;
+                    continue;
+                }
+                opcode::PC => {
+                    block.push(U256::from(pc), gas::BASE);
+                    None
+                }
+                opcode::JUMP => match block.pushed().and_then(jump_target) {
+                    Some(target) => {
+                        block.block.gas += gas::MID;
+                        block.stack(1, 0);
+                        Some(End::Jump(target))
+                    }
+                    None => Some(End::Exit { pc }),
+                },
+                opcode::JUMPI => match block.pushed().and_then(jump_target) {
+                    Some(target) => {
+                        block.block.gas += gas::HIGH;
+                        block.stack(2, 0);
+                        Some(End::JumpI { target, next })
+                    }
+                    None => Some(End::Exit { pc }),
+                },
+                opcode::STOP => Some(End::Stop { pc }),
+                _ => {
+                    if let Some((compiled, gas, inputs, outputs)) = Op::from_opcode(op, spec_id) {
+                        block.block.gas += gas;
+                        block.stack(inputs, outputs);
+                        block.block.ops.push(compiled);
+                        None
+                    } else if let Some(instruction) = Builtin::from_opcode(op) {
+                        let next = (!instruction.stops()).then_some(next);
+                        Some(End::Call {
+                            instruction,
+                            pc,
+                            next,
+                        })
+                    } else {
+                        Some(End::Exit { pc })
+                    }
+                }
+            };
+            if let Some(end) = end {
+                let block = current.take().unwrap();
+                blocks.push(block.finish(end));
+                // Blocks that fall through start after the instruction.
+                if let End::JumpI { next, .. }
+                | End::Call {
+                    next: Some(next), ..
+                } = end
+                {
+                    current = Some(BlockBuilder::new(next));
+                }
+            }
+            pc = next;
+        }
+        // Execution stops at the end of the code, where blocks may fall through.
+        if let Some(block) = current {
+            blocks.push(block.finish(End::Stop { pc: len }));
+        }
+        // Resolve the program counters of the targets to block indices.
+        let index = |pc: usize| {
+            blocks
+                .binary_search_by_key(&pc, |block: &Block| block.start)
+                .expect("targets start blocks")
+        };
+        let ends: Vec<End> = blocks
+            .iter()
+            .map(|block| match block.end {
+                End::Next(next) => End::Next(index(next)),
+                End::Jump(target) => End::Jump(index(target)),
+                End::JumpI { target, next } => End::JumpI {
+                    target: index(target),
+                    next: index(next),
+                },
+                End::Call {
+                    instruction,
+                    pc,
+                    next,
+                } => End::Call {
+                    instruction,
+                    pc,
+                    next: next.map(index),
+                },
+                end => end,
+            })
+            .collect();
+        for (block, end) in blocks.iter_mut().zip(ends) {
+            block.end = end;
+        }
+        Some(Self { blocks })
+    }
+
+    /// Returns the number of basic blocks.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Returns whether the code has no blocks, which never happens for compiled code.
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+}
+
+/// Returns the number of immediate bytes of `op`.
+#[inline]
+fn immediate_len(op: u8) -> usize {
+    if (opcode::PUSH1..=opcode::PUSH32).contains(&op) {
+        (op - opcode::PUSH0) as usize
+    } else {
+        0
+    }
+}
+
+/// Moves the instruction pointer to `pc`.
+#[inline]
+fn set_pc(interpreter: &mut Interpreter, pc: usize) {
+    // SAFETY: `pc` is at most the length of the original bytecode.
+    interpreter.instruction_pointer = unsafe { interpreter.bytecode.as_ptr().add(pc) };
+}
+
+impl<H: Host + ?Sized> CompiledCode<H> for BlockCode {
+    fn execute(&self, interpreter: &mut Interpreter, host: &mut H) {
+        let mut index = 0;
+        loop {
+            let block = &self.blocks[index];
+            let len = interpreter.stack.len();
+            if interpreter.gas.remaining() < block.gas
+                || len < block.inputs
+                || len + block.growth > interpreter.stack.limit()
+            {
+                // The interpreter halts where the block would.
+                set_pc(interpreter, block.start);
+                return;
+            }
+            let _ = interpreter.gas.record_cost(block.gas);
+            for op in &block.ops {
+                op.execute(interpreter);
+            }
+            index = match block.end {
+                End::Next(next) => next,
+                End::Jump(target) => {
+                    let _ = interpreter.stack.pop();
+                    target
+                }
+                End::JumpI { target, next } => {
+                    let _ = interpreter.stack.pop();
+                    let cond = interpreter.stack.pop().unwrap_or_default();
+                    if cond.is_zero() {
+                        next
+                    } else {
+                        target
+                    }
+                }
+                End::Stop { pc } => {
+                    set_pc(interpreter, pc + 1);
+                    interpreter.instruction_result = InstructionResult::Stop;
+                    return;
+                }
+                End::Call {
+                    instruction,
+                    pc,
+                    next,
+                } => {
+                    set_pc(interpreter, pc + 1);
+                    instruction.call(interpreter, host);
+                    match next {
+                        Some(next)
+                            if interpreter.instruction_result == InstructionResult::Continue =>
+                        {
+                            next
+                        }
+                        _ => return,
+                    }
+                }
+                End::Exit { pc } => {
+                    set_pc(interpreter, pc);
+                    return;
+                }
+            };
+        }
+    }
+}
+
+impl fmt::Display for BlockCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, block) in self.blocks.iter().enumerate() {
+            writeln!(
+                f,
+                "block {index} at {:#x}: gas {}, inputs {}, growth {}, {} ops, {:?}",
+                block.start,
+                block.gas,
+                block.inputs,
+                block.growth,
+                block.ops.len(),
+                block.end
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::to_analysed,
+        opcode::*,
+        primitives::{Bytes, DefaultEthereumWiring},
+        DummyHost,
+    };
+
+    fn analysed(code: &[u8]) -> Bytecode {
+        to_analysed(Bytecode::new_raw(Bytes::copy_from_slice(code)))
+    }
+
+    #[test]
+    fn compiles_blocks() {
+        let code = [
+            PUSH1, 0x01, PUSH1, 0x07, JUMPI, GAS, STOP, JUMPDEST, PUSH1, 0x00, MLOAD, PUSH1, 0x20,
+            JUMP,
+        ];
+        let compiled = BlockCode::compile(&analysed(&code), SpecId::CANCUN).unwrap();
+        let ends: Vec<_> = compiled.blocks.iter().map(|block| block.end).collect();
+        assert_eq!(
+            ends,
+            [
+                End::JumpI { target: 2, next: 1 },
+                End::Exit { pc: 5 },
+                End::Call {
+                    instruction: Builtin::MLoad,
+                    pc: 10,
+                    next: Some(3)
+                },
+                End::Exit { pc: 13 },
+            ]
+        );
+        assert_eq!(compiled.blocks[0].gas, 3 + 3 + 10);
+        assert_eq!(compiled.blocks[0].growth, 2);
+        assert_eq!(compiled.blocks[2].gas, 1 + 3);
+
+        assert!(BlockCode::compile(&Bytecode::new_raw(code.into()), SpecId::CANCUN).is_none());
+    }
+
+    /// Executes `code` with or without compilation, and returns everything observable.
+    fn execute(
+        code: &[u8],
+        gas_limit: u64,
+        stack_limit: usize,
+        spec_id: SpecId,
+        compiled: bool,
+    ) -> impl PartialEq {
+        let bytecode = analysed(code);
+        let mut interp = Interpreter::new_standalone(
+            bytecode.clone(),
+            Bytes::from_static(&[0xAB; 40]),
+            gas_limit,
+        )
+        .with_stack_limit(stack_limit);
+        let mut host = DummyHost::<DefaultEthereumWiring>::default();
+        let action = if compiled {
+            let code = BlockCode::compile(&bytecode, spec_id).unwrap();
+            interp.run_compiled(&code, SharedMemory::new(), &mut host)
+        } else {
+            None
+        };
+        let action = action.unwrap_or_else(|| interp.run_standalone(spec_id, &mut host));
+        (
+            action,
+            interp.gas,
+            interp.stack.data().to_vec(),
+            interp.shared_memory.context_memory().to_vec(),
+            interp.halt_location(0),
+        )
+    }
+
+    #[test]
+    fn compiled_execution_matches() {
+        // Compiled instructions, instructions of the interpreter and unsupported ones.
+        const OPCODES: [u8; 28] = [
+            PUSH1,
+            PUSH1,
+            PUSH1,
+            PUSH1,
+            JUMP,
+            JUMPI,
+            JUMPDEST,
+            JUMPDEST,
+            ADD,
+            SUB,
+            DIV,
+            LT,
+            ISZERO,
+            SHL,
+            DUP1,
+            DUP3,
+            SWAP1,
+            SWAP2,
+            POP,
+            PUSH0,
+            PC,
+            MSTORE,
+            MLOAD,
+            SDIV,
+            CALLDATALOAD,
+            RETURN,
+            STOP,
+            GAS,
+        ];
+        let mut seed = 0x9e37_79b9_7f4a_7c15_u64;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for _ in 0..20_000 {
+            let len = 4 + next() as usize % 60;
+            let mut code = Vec::with_capacity(len + 1);
+            while code.len() < len {
+                let op = OPCODES[next() as usize % OPCODES.len()];
+                code.push(op);
+                if op == PUSH1 {
+                    code.push(next() as u8 % (len as u8 + 2));
+                }
+            }
+            let gas_limit = next() % 400;
+            let stack_limit = 1 + next() as usize % 8;
+            let spec_id = [SpecId::BYZANTIUM, SpecId::CANCUN][next() as usize % 2];
+            assert!(
+                execute(&code, gas_limit, stack_limit, spec_id, false)
+                    == execute(&code, gas_limit, stack_limit, spec_id, true),
+                "code {code:02x?}, gas limit {gas_limit}, stack limit {stack_limit}, {spec_id:?}"
+            );
+        }
+    }
+}
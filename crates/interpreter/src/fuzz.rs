@@ -0,0 +1,261 @@
+//! Helpers to fuzz the interpreter.
+//!
+//! [generate_bytecode] turns arbitrary fuzzer input into legacy bytecode, and [FuzzHarness]
+//! executes it with a [DummyHost] for several spec ids, checking a list of [Invariant]s after
+//! every instruction. The `cargo-fuzz` targets of the `fuzz` directory are built on top of them.
+
+use crate::{
+    analysis::to_analysed,
+    opcode::{self, make_instruction_table},
+    primitives::{
+        spec_to_generic, Address, Bytecode, Bytes, DefaultEthereumWiring, Spec, SpecId, U256,
+    },
+    Contract, DummyHost, InstructionResult, Interpreter, SharedMemory,
+};
+use std::{format, string::String, vec, vec::Vec};
+
+/// Generates legacy bytecode from arbitrary bytes.
+///
+/// Every input byte is emitted as an opcode. `PUSH` opcodes take their immediate from the
+/// following input bytes, and `JUMP` and `JUMPI` are preceded by a `PUSH2` of a previously
+/// emitted `JUMPDEST`, if there is one, so that generated programs branch and loop instead
+/// of halting on the first jump.
+pub fn generate_bytecode(data: &[u8]) -> Bytes {
+    let mut code = Vec::with_capacity(data.len() * 2);
+    let mut jumpdests = Vec::new();
+    let mut input = data.iter().copied();
+    while let Some(op) = input.next() {
+        match op {
+            opcode::PUSH1..=opcode::PUSH32 => {
+                code.push(op);
+                let len = (op - opcode::PUSH0) as usize;
+                code.extend(input.by_ref().chain(core::iter::repeat(0)).take(len));
+            }
+            opcode::JUMP | opcode::JUMPI => {
+                if !jumpdests.is_empty() {
+                    let index = input.next().unwrap_or_default() as usize % jumpdests.len();
+                    let target: u16 = jumpdests[index];
+                    code.push(opcode::PUSH2);
+                    code.extend(target.to_be_bytes());
+                }
+                code.push(op);
+            }
+            opcode::JUMPDEST => {
+                if let Ok(pc) = u16::try_from(code.len()) {
+                    jumpdests.push(pc);
+                }
+                code.push(op);
+            }
+            _ => code.push(op),
+        }
+    }
+    code.into()
+}
+
+/// State of the interpreter before an instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepState {
+    /// Spec id of the execution.
+    pub spec_id: SpecId,
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Remaining gas before the instruction.
+    pub gas_remaining: u64,
+    /// Length of the stack before the instruction.
+    pub stack_len: usize,
+}
+
+/// Check of the state of the interpreter after an instruction, given its state before the
+/// instruction. Returns a description of the violation if the check fails.
+pub type Invariant = fn(&StepState, &Interpreter) -> Result<(), String>;
+
+/// Checks that an instruction did not increase the remaining gas.
+pub fn gas_never_increases(before: &StepState, interp: &Interpreter) -> Result<(), String> {
+    let remaining = interp.gas.remaining();
+    if remaining > before.gas_remaining {
+        return Err(format!(
+            "remaining gas increased from {} to {remaining}",
+            before.gas_remaining
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the stack is within its limit.
+pub fn stack_within_limit(_before: &StepState, interp: &Interpreter) -> Result<(), String> {
+    let len = interp.stack.len();
+    if len > interp.stack.limit() {
+        return Err(format!(
+            "stack length {len} exceeds the limit of {}",
+            interp.stack.limit()
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the program counter points inside the bytecode, including its padding.
+pub fn pc_within_bytecode(_before: &StepState, interp: &Interpreter) -> Result<(), String> {
+    let pc = interp.program_counter();
+    if pc > interp.bytecode.len() {
+        return Err(format!(
+            "program counter {pc} is past the end of the bytecode of length {}",
+            interp.bytecode.len()
+        ));
+    }
+    Ok(())
+}
+
+/// Invariant violated by an execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvariantViolation {
+    /// State before the instruction that violated the invariant.
+    pub step: StepState,
+    /// Description of the violation.
+    pub message: String,
+}
+
+/// Executes bytecode for several spec ids and checks [Invariant]s after every instruction.
+///
+/// Panics of the instructions are not caught, fuzzers report them on their own.
+#[derive(Clone, Debug)]
+pub struct FuzzHarness {
+    /// Spec ids the bytecode is executed with.
+    pub spec_ids: Vec<SpecId>,
+    /// Gas limit of every execution, which bounds the length of generated loops.
+    pub gas_limit: u64,
+    invariants: Vec<Invariant>,
+}
+
+impl Default for FuzzHarness {
+    fn default() -> Self {
+        Self {
+            spec_ids: vec![
+                SpecId::FRONTIER,
+                SpecId::HOMESTEAD,
+                SpecId::TANGERINE,
+                SpecId::SPURIOUS_DRAGON,
+                SpecId::BYZANTIUM,
+                SpecId::PETERSBURG,
+                SpecId::ISTANBUL,
+                SpecId::BERLIN,
+                SpecId::LONDON,
+                SpecId::MERGE,
+                SpecId::SHANGHAI,
+                SpecId::CANCUN,
+                SpecId::PRAGUE,
+            ],
+            gas_limit: 1_000_000,
+            invariants: vec![gas_never_increases, stack_within_limit, pc_within_bytecode],
+        }
+    }
+}
+
+impl FuzzHarness {
+    /// Adds an invariant to check after every instruction.
+    pub fn with_invariant(mut self, invariant: Invariant) -> Self {
+        self.invariants.push(invariant);
+        self
+    }
+
+    /// Executes legacy `code` for every spec id.
+    ///
+    /// Returns the result of the executions in the order of [FuzzHarness::spec_ids], or the
+    /// first violated invariant. Executions stop at the first `CALL` or `CREATE`.
+    pub fn run(&self, code: &Bytes) -> Result<Vec<InstructionResult>, InvariantViolation> {
+        let bytecode = to_analysed(Bytecode::new_legacy(code.clone()));
+        self.spec_ids
+            .iter()
+            .map(|&spec_id| spec_to_generic!(spec_id, self.run_spec::<SPEC>(spec_id, &bytecode)))
+            .collect()
+    }
+
+    fn run_spec<SPEC: Spec>(
+        &self,
+        spec_id: SpecId,
+        bytecode: &Bytecode,
+    ) -> Result<InstructionResult, InvariantViolation> {
+        let table = make_instruction_table::<DummyHost<DefaultEthereumWiring>, SPEC>();
+        let mut host = DummyHost::<DefaultEthereumWiring>::default();
+        let contract = Contract::new(
+            Bytes::new(),
+            bytecode.clone(),
+            None,
+            Address::ZERO,
+            None,
+            Address::ZERO,
+            U256::ZERO,
+        );
+        let mut interp = Interpreter::new(contract, self.gas_limit, false);
+        interp.shared_memory = SharedMemory::new();
+
+        while interp.instruction_result == InstructionResult::Continue {
+            let step = StepState {
+                spec_id,
+                pc: interp.program_counter(),
+                opcode: interp.current_opcode(),
+                gas_remaining: interp.gas.remaining(),
+                stack_len: interp.stack.len(),
+            };
+            interp.step(&table, &mut host);
+            for invariant in &self.invariants {
+                invariant(&step, &interp)
+                    .map_err(|message| InvariantViolation { step, message })?;
+            }
+        }
+        Ok(interp.instruction_result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_jumps_target_jumpdests() {
+        let code = generate_bytecode(&[
+            opcode::JUMPDEST,
+            opcode::PUSH1,
+            0x01,
+            opcode::JUMPI,
+            0x00,
+            opcode::PUSH2,
+        ]);
+        assert_eq!(
+            code.as_ref(),
+            [
+                opcode::JUMPDEST,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH2,
+                0x00,
+                0x00,
+                opcode::JUMPI,
+                opcode::PUSH2,
+                0x00,
+                0x00,
+            ]
+        );
+    }
+
+    #[test]
+    fn harness_runs_every_spec() {
+        // Infinite loop that is stopped by the gas limit.
+        let code = generate_bytecode(&[opcode::JUMPDEST, opcode::JUMP, 0x00]);
+        let harness = FuzzHarness {
+            spec_ids: vec![SpecId::FRONTIER, SpecId::CANCUN],
+            ..Default::default()
+        };
+        assert_eq!(harness.run(&code), Ok(vec![InstructionResult::OutOfGas; 2]));
+
+        let harness = harness.with_invariant(|step, _| match step.opcode {
+            opcode::JUMP => Err("jump".into()),
+            _ => Ok(()),
+        });
+        let violation = harness.run(&code).unwrap_err();
+        assert_eq!(violation.step.spec_id, SpecId::FRONTIER);
+        assert_eq!(violation.step.pc, 4);
+        assert_eq!(violation.message, "jump");
+    }
+}
@@ -105,7 +105,13 @@ impl Gas {
     /// Related to EIP-3529: Reduction in refunds
     #[inline]
     pub fn set_final_refund(&mut self, is_london: bool) {
-        let max_refund_quotient = if is_london { 5 } else { 2 };
+        self.set_final_refund_with_quotient(if is_london { 5 } else { 2 });
+    }
+
+    /// Set a refund value for final refund, limited to the gas spent divided by
+    /// `max_refund_quotient`.
+    #[inline]
+    pub fn set_final_refund_with_quotient(&mut self, max_refund_quotient: u64) {
         self.refunded = (self.refunded() as u64).min(self.spent() / max_refund_quotient) as i64;
     }
 
@@ -129,3 +135,35 @@ impl Gas {
         success
     }
 }
+
+/// Gas of a frame once it has returned, passed to the inspectors with its outcome.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FrameGasSummary {
+    /// Gas limit of the frame.
+    pub limit: u64,
+    /// Gas spent by the frame, including its memory expansion and the gas spent by its
+    /// subcalls.
+    pub spent: u64,
+    /// Gas refund accumulated by the frame and its subcalls.
+    pub refunded: i64,
+    /// Gas returned to the caller.
+    pub remaining: u64,
+    /// Gas charged for expanding the memory of the frame.
+    pub memory_expansion: u64,
+}
+
+impl FrameGasSummary {
+    /// Creates the summary of a frame that returned with `gas` and expanded its memory for
+    /// `memory_expansion` gas.
+    #[inline]
+    pub const fn new(gas: &Gas, memory_expansion: u64) -> Self {
+        Self {
+            limit: gas.limit(),
+            spent: gas.spent(),
+            refunded: gas.refunded(),
+            remaining: gas.remaining(),
+            memory_expansion,
+        }
+    }
+}
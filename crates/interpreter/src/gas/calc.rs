@@ -3,7 +3,7 @@ use revm_primitives::eip7702;
 use super::constants::*;
 use crate::{
     num_words,
-    primitives::{AccessListItem, SpecId, U256},
+    primitives::{AccessListItem, ChainRules, SpecId, U256},
     AccountLoad, Eip7702CodeLoad, SStoreResult, SelfDestructResult, StateLoad,
 };
 
@@ -309,20 +309,25 @@ pub const fn call_cost(spec_id: SpecId, transfers_value: bool, account_load: Acc
         gas += CALLVALUE;
     }
 
-    // new account cost
-    if account_load.is_empty {
-        // EIP-161: State trie clearing (invariant-preserving alternative)
-        if spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON) {
-            // account only if there is value transferred.
-            if transfers_value {
-                gas += NEWACCOUNT;
-            }
-        } else {
-            gas += NEWACCOUNT;
-        }
-    }
+    gas + new_account_cost(spec_id, transfers_value, account_load.is_empty)
+}
 
-    gas
+/// Cost of the creation of the account called by a `CALL`.
+///
+/// `is_empty` must be computed with
+/// [`Account::state_clear_aware_is_empty`](crate::primitives::Account::state_clear_aware_is_empty):
+/// before SPURIOUS_DRAGON, an account is created by any call to an account that does not
+/// exist, even without value. Since SPURIOUS_DRAGON, only a call transferring value to an
+/// empty account creates it, while a call without value merely touches it, and the touched
+/// empty account is removed at the end of the transaction.
+#[inline]
+pub const fn new_account_cost(spec_id: SpecId, transfers_value: bool, is_empty: bool) -> u64 {
+    // EIP-161: State trie clearing (invariant-preserving alternative)
+    if is_empty && (transfers_value || !spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON)) {
+        NEWACCOUNT
+    } else {
+        0
+    }
 }
 
 /// Berlin warm and cold storage access cost for account access.
@@ -354,6 +359,8 @@ pub const fn memory_gas_for_len(len: usize) -> u64 {
 }
 
 /// Memory expansion cost calculation for a given number of words.
+///
+/// Saturates on overflow, see [memory_gas_checked] for the expansion of untrusted lengths.
 #[inline]
 pub const fn memory_gas(num_words: u64) -> u64 {
     MEMORY
@@ -361,6 +368,20 @@ pub const fn memory_gas(num_words: u64) -> u64 {
         .saturating_add(num_words.saturating_mul(num_words) / 512)
 }
 
+/// Memory expansion cost calculation for a given number of words.
+///
+/// Returns `None` if the cost does not fit in a `u64`, in which case it can't be paid.
+#[inline]
+pub const fn memory_gas_checked(num_words: u64) -> Option<u64> {
+    let Some(linear) = MEMORY.checked_mul(num_words) else {
+        return None;
+    };
+    let Some(quadratic) = num_words.checked_mul(num_words) else {
+        return None;
+    };
+    linear.checked_add(quadratic / 512)
+}
+
 /// Initial gas that is deducted for transaction to be included.
 /// Initial gas contains initial stipend gas, gas for access list and input data.
 pub fn validate_initial_tx_gas(
@@ -369,20 +390,35 @@ pub fn validate_initial_tx_gas(
     is_create: bool,
     access_list: &[AccessListItem],
     authorization_list_num: u64,
+) -> u64 {
+    validate_initial_tx_gas_with_rules(
+        spec_id,
+        &ChainRules::new(spec_id),
+        input,
+        is_create,
+        access_list,
+        authorization_list_num,
+    )
+}
+
+/// Initial gas that is deducted for transaction to be included, with the base cost of
+/// contract creation taken from the chain rules.
+pub fn validate_initial_tx_gas_with_rules(
+    spec_id: SpecId,
+    rules: &ChainRules,
+    input: &[u8],
+    is_create: bool,
+    access_list: &[AccessListItem],
+    authorization_list_num: u64,
 ) -> u64 {
     let mut initial_gas = 0;
     let zero_data_len = input.iter().filter(|v| **v == 0).count() as u64;
     let non_zero_data_len = input.len() as u64 - zero_data_len;
 
     // initdate stipend
-    initial_gas += zero_data_len * TRANSACTION_ZERO_DATA;
+    initial_gas += zero_data_len * rules.tx_data_zero_gas;
     // EIP-2028: Transaction data gas cost reduction
-    initial_gas += non_zero_data_len
-        * if spec_id.is_enabled_in(SpecId::ISTANBUL) {
-            16
-        } else {
-            68
-        };
+    initial_gas += non_zero_data_len * rules.tx_data_non_zero_gas;
 
     // get number of access list account and storages.
     if spec_id.is_enabled_in(SpecId::BERLIN) {
@@ -393,14 +429,10 @@ pub fn validate_initial_tx_gas(
 
     // base stipend
     initial_gas += if is_create {
-        if spec_id.is_enabled_in(SpecId::HOMESTEAD) {
-            // EIP-2: Homestead Hard-fork Changes
-            53000
-        } else {
-            21000
-        }
+        // EIP-2: Homestead Hard-fork Changes
+        rules.create_tx_base_gas
     } else {
-        21000
+        rules.tx_base_gas
     };
 
     // EIP-3860: Limit and meter initcode
@@ -416,3 +448,124 @@ pub fn validate_initial_tx_gas(
 
     initial_gas
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{format, string::String};
+
+    /// Test vectors of EIP-2200, with the code, used gas, refund, original value, and
+    /// stored values.
+    #[test]
+    fn eip2200_vectors() {
+        let vectors: [(&str, u64, i64, u64, &[u64]); 17] = [
+            ("60006000556000600055", 1612, 0, 0, &[0, 0]),
+            ("60006000556001600055", 20812, 0, 0, &[0, 1]),
+            ("60016000556000600055", 20812, 19200, 0, &[1, 0]),
+            ("60016000556002600055", 20812, 0, 0, &[1, 2]),
+            ("60016000556001600055", 20812, 0, 0, &[1, 1]),
+            ("60006000556000600055", 5812, 15000, 1, &[0, 0]),
+            ("60006000556001600055", 5812, 4200, 1, &[0, 1]),
+            ("60006000556002600055", 5812, 0, 1, &[0, 2]),
+            ("60026000556000600055", 5812, 15000, 1, &[2, 0]),
+            ("60026000556003600055", 5812, 0, 1, &[2, 3]),
+            ("60026000556001600055", 5812, 4200, 1, &[2, 1]),
+            ("60026000556002600055", 5812, 0, 1, &[2, 2]),
+            ("60016000556000600055", 5812, 15000, 1, &[1, 0]),
+            ("60016000556002600055", 5812, 0, 1, &[1, 2]),
+            ("60016000556001600055", 1612, 0, 1, &[1, 1]),
+            (
+                "600160005560006000556001600055",
+                40818,
+                19200,
+                0,
+                &[1, 0, 1],
+            ),
+            (
+                "600060005560016000556000600055",
+                10818,
+                19200,
+                1,
+                &[0, 1, 0],
+            ),
+        ];
+        for (code, used_gas, refund, original, values) in vectors {
+            // Every value is stored with two `PUSH1`.
+            let mut gas = values.len() as u64 * 2 * VERYLOW;
+            let mut gas_refund = 0;
+            let mut present = U256::from(original);
+            for &value in values {
+                let vals = SStoreResult {
+                    original_value: U256::from(original),
+                    present_value: present,
+                    new_value: U256::from(value),
+                };
+                gas += sstore_cost(SpecId::ISTANBUL, &vals, u64::MAX, false).unwrap();
+                gas_refund += sstore_refund(SpecId::ISTANBUL, &vals);
+                present = vals.new_value;
+            }
+            let stores: String = values.iter().map(|v| format!("60{v:02x}600055")).collect();
+            assert_eq!(code, stores);
+            assert_eq!((gas, gas_refund), (used_gas, refund), "{code}");
+        }
+    }
+
+    #[test]
+    fn call_new_account_cost() {
+        let load = |is_empty, is_cold| AccountLoad {
+            load: Eip7702CodeLoad::new_not_delegated((), is_cold),
+            is_empty,
+        };
+        // (spec, transfers value, is empty, is cold, expected cost)
+        let cases = [
+            (SpecId::FRONTIER, false, false, false, 40),
+            (SpecId::FRONTIER, false, true, false, 40 + NEWACCOUNT),
+            (
+                SpecId::FRONTIER,
+                true,
+                true,
+                false,
+                40 + CALLVALUE + NEWACCOUNT,
+            ),
+            (SpecId::TANGERINE, false, true, false, 700 + NEWACCOUNT),
+            (SpecId::TANGERINE, true, false, false, 700 + CALLVALUE),
+            // A call without value to an empty account does not create it.
+            (SpecId::SPURIOUS_DRAGON, false, true, false, 700),
+            (
+                SpecId::SPURIOUS_DRAGON,
+                true,
+                true,
+                false,
+                700 + CALLVALUE + NEWACCOUNT,
+            ),
+            (SpecId::SPURIOUS_DRAGON, true, false, false, 700 + CALLVALUE),
+            (SpecId::BERLIN, false, true, true, COLD_ACCOUNT_ACCESS_COST),
+            (
+                SpecId::BERLIN,
+                true,
+                true,
+                true,
+                COLD_ACCOUNT_ACCESS_COST + CALLVALUE + NEWACCOUNT,
+            ),
+            (SpecId::CANCUN, false, true, false, WARM_STORAGE_READ_COST),
+        ];
+        for (spec_id, transfers_value, is_empty, is_cold, expected) in cases {
+            assert_eq!(
+                call_cost(spec_id, transfers_value, load(is_empty, is_cold)),
+                expected,
+                "{spec_id:?} transfers value: {transfers_value}, empty: {is_empty}",
+            );
+        }
+    }
+
+    #[test]
+    fn sstore_sentry() {
+        let vals = SStoreResult::default();
+        assert_eq!(
+            sstore_cost(SpecId::ISTANBUL, &vals, CALL_STIPEND, false),
+            None
+        );
+        assert!(sstore_cost(SpecId::ISTANBUL, &vals, CALL_STIPEND + 1, false).is_some());
+        assert!(sstore_cost(SpecId::PETERSBURG, &vals, CALL_STIPEND, false).is_some());
+    }
+}
@@ -50,5 +50,5 @@ pub const WARM_SSTORE_RESET: u64 = SSTORE_RESET - COLD_SLOAD_COST;
 /// EIP-3860 : Limit and meter initcode
 pub const INITCODE_WORD_COST: u64 = 2;
 
-pub const CALL_STIPEND: u64 = 2300;
+pub const CALL_STIPEND: u64 = crate::primitives::CALL_STIPEND;
 pub const MIN_CALLEE_GAS: u64 = CALL_STIPEND;
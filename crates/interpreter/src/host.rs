@@ -1,4 +1,4 @@
-use crate::primitives::{Address, Bytes, Log, B256, U256};
+use crate::primitives::{Address, Bytes, EvmLog, SpecId, B256, U256};
 use core::ops::{Deref, DerefMut};
 
 mod dummy;
@@ -6,15 +6,31 @@ pub use dummy::DummyHost;
 use revm_primitives::{EnvWiring, EvmWiring};
 
 /// EVM context host.
-pub trait Host {
+///
+/// Implemented for every type that implements both [HostRead] and [HostWrite].
+///
+/// Instructions only get read access to the environment. Mutable access is provided by
+/// [HostEnvMut], which is meant for setup and handlers, so that instructions can not
+/// change block or transaction fields in the middle of execution.
+pub trait Host: HostRead + HostWrite {}
+
+impl<H: HostRead + HostWrite + ?Sized> Host for H {}
+
+/// Read half of the [Host]: the environment and the loaded state.
+///
+/// Loads can warm accounts and storage slots, but never change their values, so tools that
+/// must not modify the state, like static analyzers or read-only simulators, can implement
+/// this trait only.
+pub trait HostRead {
     /// Chain specification.
     type EvmWiringT: EvmWiring;
 
     /// Returns a reference to the environment.
     fn env(&self) -> &EnvWiring<Self::EvmWiringT>;
 
-    /// Returns a mutable reference to the environment.
-    fn env_mut(&mut self) -> &mut EnvWiring<Self::EvmWiringT>;
+    /// Returns the spec of the execution, read by instructions compiled for
+    /// [RuntimeSpec](crate::primitives::RuntimeSpec).
+    fn spec_id(&self) -> SpecId;
 
     /// Load an account code.
     fn load_account_delegated(&mut self, address: Address) -> Option<AccountLoad>;
@@ -26,6 +42,9 @@ pub trait Host {
     fn balance(&mut self, address: Address) -> Option<StateLoad<U256>>;
 
     /// Get code of `address` and if the account is cold.
+    ///
+    /// The returned [`Bytes`] are reference counted and should share the buffer of the
+    /// loaded bytecode instead of copying it.
     fn code(&mut self, address: Address) -> Option<Eip7702CodeLoad<Bytes>>;
 
     /// Get code hash of `address` and if the account is cold.
@@ -34,6 +53,15 @@ pub trait Host {
     /// Get storage value of `address` at `index` and if the account is cold.
     fn sload(&mut self, address: Address, index: U256) -> Option<StateLoad<U256>>;
 
+    /// Get the transient storage value of `address` at `index`.
+    fn tload(&mut self, address: Address, index: U256) -> U256;
+}
+
+/// Write half of the [Host]: changes to the state and emitted logs.
+///
+/// Calls and creates are not executed by the host, they are returned to the caller of the
+/// interpreter as an [InterpreterAction](crate::InterpreterAction).
+pub trait HostWrite: HostRead {
     /// Set storage value of account address at index.
     ///
     /// Returns [`StateLoad`] with [`SStoreResult`] that contains original/new/old storage value.
@@ -44,14 +72,11 @@ pub trait Host {
         value: U256,
     ) -> Option<StateLoad<SStoreResult>>;
 
-    /// Get the transient storage value of `address` at `index`.
-    fn tload(&mut self, address: Address, index: U256) -> U256;
-
     /// Set the transient storage value of `address` at `index`.
     fn tstore(&mut self, address: Address, index: U256, value: U256);
 
-    /// Emit a log owned by `address` with given `LogData`.
-    fn log(&mut self, log: Log);
+    /// Emit a log.
+    fn log(&mut self, log: EvmLog);
 
     /// Mark `address` to be deleted, with funds transferred to `target`.
     fn selfdestruct(
@@ -61,6 +86,14 @@ pub trait Host {
     ) -> Option<StateLoad<SelfDestructResult>>;
 }
 
+/// Host whose environment can be modified.
+///
+/// Instructions are generic over [Host] only and can not rely on this trait.
+pub trait HostEnvMut: HostRead {
+    /// Returns a mutable reference to the environment.
+    fn env_mut(&mut self) -> &mut EnvWiring<Self::EvmWiringT>;
+}
+
 /// Represents the result of an `sstore` operation.
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -265,9 +298,12 @@ mod tests {
 
     fn assert_host<H: Host + ?Sized>() {}
 
+    fn assert_host_read<H: HostRead + ?Sized>() {}
+
     #[test]
     fn object_safety() {
         assert_host::<DummyHost<EthereumWiring<EmptyDB, ()>>>();
         assert_host::<dyn Host<EvmWiringT = EthereumWiring<EmptyDB, ()>>>();
+        assert_host_read::<dyn HostRead<EvmWiringT = EthereumWiring<EmptyDB, ()>>>();
     }
 }
@@ -3,24 +3,31 @@ use revm_primitives::EnvWiring;
 
 use crate::{
     primitives::{
-        hash_map::Entry, Address, Bytes, Env, EvmWiring, HashMap, Log, B256, KECCAK_EMPTY, U256,
+        hash_map::Entry, Address, Bytes, Env, EvmLog, EvmWiring, HashMap, SpecId, B256,
+        KECCAK_EMPTY, U256,
     },
-    Host, SStoreResult, SelfDestructResult,
+    HostEnvMut, HostRead, HostWrite, SStoreResult, SelfDestructResult,
 };
 use std::vec::Vec;
 
 use super::{AccountLoad, Eip7702CodeLoad, StateLoad};
 
 /// A dummy [Host] implementation.
+///
+/// Keeps the storage, transient storage and logs of a single contract in memory, and
+/// reports every other account as empty. Useful to execute bytecode without an EVM, see
+/// [Interpreter::run_standalone](crate::Interpreter::run_standalone).
 #[derive_where(Clone, Debug, Default; EvmWiringT::Block, EvmWiringT::Transaction)]
 pub struct DummyHost<EvmWiringT>
 where
     EvmWiringT: EvmWiring,
 {
     pub env: Env<EvmWiringT::Block, EvmWiringT::Transaction>,
+    /// Spec returned by [HostRead::spec_id], the latest by default.
+    pub spec_id: SpecId,
     pub storage: HashMap<U256, U256>,
     pub transient_storage: HashMap<U256, U256>,
-    pub log: Vec<Log>,
+    pub log: Vec<EvmLog>,
 }
 
 impl<EvmWiringT> DummyHost<EvmWiringT>
@@ -32,6 +39,7 @@ where
     pub fn new(env: EnvWiring<EvmWiringT>) -> Self {
         Self {
             env,
+            spec_id: SpecId::LATEST,
             storage: HashMap::new(),
             transient_storage: HashMap::new(),
             log: Vec::new(),
@@ -46,7 +54,7 @@ where
     }
 }
 
-impl<EvmWiringT> Host for DummyHost<EvmWiringT>
+impl<EvmWiringT> HostRead for DummyHost<EvmWiringT>
 where
     EvmWiringT: EvmWiring,
 {
@@ -58,8 +66,8 @@ where
     }
 
     #[inline]
-    fn env_mut(&mut self) -> &mut EnvWiring<Self::EvmWiringT> {
-        &mut self.env
+    fn spec_id(&self) -> SpecId {
+        self.spec_id
     }
 
     #[inline]
@@ -98,6 +106,19 @@ where
         }
     }
 
+    #[inline]
+    fn tload(&mut self, _address: Address, index: U256) -> U256 {
+        self.transient_storage
+            .get(&index)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl<EvmWiringT> HostWrite for DummyHost<EvmWiringT>
+where
+    EvmWiringT: EvmWiring,
+{
     #[inline]
     fn sstore(
         &mut self,
@@ -116,21 +137,13 @@ where
         })
     }
 
-    #[inline]
-    fn tload(&mut self, _address: Address, index: U256) -> U256 {
-        self.transient_storage
-            .get(&index)
-            .copied()
-            .unwrap_or_default()
-    }
-
     #[inline]
     fn tstore(&mut self, _address: Address, index: U256, value: U256) {
         self.transient_storage.insert(index, value);
     }
 
     #[inline]
-    fn log(&mut self, log: Log) {
+    fn log(&mut self, log: EvmLog) {
         self.log.push(log)
     }
 
@@ -143,3 +156,13 @@ where
         Some(StateLoad::default())
     }
 }
+
+impl<EvmWiringT> HostEnvMut for DummyHost<EvmWiringT>
+where
+    EvmWiringT: EvmWiring,
+{
+    #[inline]
+    fn env_mut(&mut self) -> &mut EnvWiring<Self::EvmWiringT> {
+        &mut self.env
+    }
+}
@@ -97,6 +97,8 @@ pub enum InstructionResult {
     EofAuxDataTooSmall,
     /// `EXT*CALL` target address needs to be padded with 0s.
     InvalidEXTCALLTarget,
+    /// Execution budget of instructions or time has been exhausted.
+    ExecutionBudgetExceeded,
 }
 
 impl From<SuccessReason> for InstructionResult {
@@ -142,6 +144,7 @@ impl From<HaltReason> for InstructionResult {
             HaltReason::EofAuxDataTooSmall => Self::EofAuxDataTooSmall,
             HaltReason::EOFFunctionStackOverflow => Self::EOFFunctionStackOverflow,
             HaltReason::InvalidEXTCALLTarget => Self::InvalidEXTCALLTarget,
+            HaltReason::ExecutionBudgetExceeded => Self::ExecutionBudgetExceeded,
         }
     }
 }
@@ -200,6 +203,7 @@ macro_rules! return_error {
             | InstructionResult::EofAuxDataTooSmall
             | InstructionResult::EofAuxDataOverflow
             | InstructionResult::InvalidEXTCALLTarget
+            | InstructionResult::ExecutionBudgetExceeded
     };
 }
 
@@ -356,6 +360,9 @@ impl<EvmWiringT: EvmWiring> From<InstructionResult> for SuccessOrHalt<EvmWiringT
             InstructionResult::InvalidEXTCALLTarget => {
                 Self::Halt(HaltReason::InvalidEXTCALLTarget.into())
             }
+            InstructionResult::ExecutionBudgetExceeded => {
+                Self::Halt(HaltReason::ExecutionBudgetExceeded.into())
+            }
             InstructionResult::InvalidExtDelegateCallTarget => {
                 Self::Internal(InternalResult::InvalidExtDelegateCallTarget)
             }
@@ -427,6 +434,7 @@ mod tests {
             InstructionResult::CreateContractStartingWithEF,
             InstructionResult::CreateInitCodeSizeLimit,
             InstructionResult::FatalExternalError,
+            InstructionResult::ExecutionBudgetExceeded,
         ];
 
         for result in error_results {
@@ -7,6 +7,7 @@ pub mod bitwise;
 pub mod contract;
 pub mod control;
 pub mod data;
+pub mod fused;
 pub mod host;
 pub mod host_env;
 pub mod i256;
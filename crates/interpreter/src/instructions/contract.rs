@@ -6,7 +6,7 @@ use crate::{
     gas::{self, cost_per_word, EOF_CREATE_GAS, KECCAK256WORD, MIN_CALLEE_GAS},
     interpreter::Interpreter,
     primitives::{
-        eof::EofHeader, keccak256, Address, BerlinSpec, Bytes, Eof, Spec, SpecId::*, B256, U256,
+        eof::EofHeader, eofcreate_address, Address, BerlinSpec, Bytes, Eof, Spec, B256, U256,
     },
     CallInputs, CallScheme, CallValue, CreateInputs, CreateScheme, EOFCreateInputs, Host,
     InstructionResult, InterpreterAction, InterpreterResult, MAX_INITCODE_SIZE,
@@ -59,10 +59,11 @@ pub fn eofcreate<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H)
         cost_per_word(sub_container.len() as u64, KECCAK256WORD)
     );
 
-    let created_address = interpreter
-        .contract
-        .target_address
-        .create2(salt.to_be_bytes(), keccak256(sub_container));
+    let created_address = eofcreate_address(
+        interpreter.contract.target_address,
+        salt.into(),
+        &sub_container,
+    );
 
     let gas_limit = interpreter.gas().remaining_63_of_64_parts();
     gas!(interpreter, gas_limit);
@@ -333,7 +334,7 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
 
     // EIP-1014: Skinny CREATE2
     if IS_CREATE2 {
-        check!(interpreter, PETERSBURG);
+        check!(interpreter, host, PETERSBURG);
     }
 
     pop!(interpreter, value, code_offset, len);
@@ -342,7 +343,7 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
     let mut code = Bytes::new();
     if len != 0 {
         // EIP-3860: Limit and meter initcode
-        if SPEC::enabled(SHANGHAI) {
+        if spec_enabled!(host, SHANGHAI) {
             // Limit is set as double of max contract bytecode size
             let max_initcode_size = host
                 .env()
@@ -376,7 +377,7 @@ pub fn create<const IS_CREATE2: bool, H: Host + ?Sized, SPEC: Spec>(
     let mut gas_limit = interpreter.gas().remaining();
 
     // EIP-150: Gas cost changes for IO-heavy operations
-    if SPEC::enabled(TANGERINE) {
+    if spec_enabled!(host, TANGERINE) {
         // take remaining gas and deduce l64 part of it.
         gas_limit -= gas_limit / 64
     }
@@ -416,9 +417,13 @@ pub fn call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
-    let Some(mut gas_limit) =
-        calc_call_gas::<SPEC>(interpreter, account_load, has_transfer, local_gas_limit)
-    else {
+    let Some(mut gas_limit) = calc_call_gas(
+        interpreter,
+        spec_id!(host),
+        account_load,
+        has_transfer,
+        local_gas_limit,
+    ) else {
         return;
     };
 
@@ -426,7 +431,7 @@ pub fn call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &
 
     // add call stipend if there is value to be transferred.
     if has_transfer {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        gas_limit = gas_limit.saturating_add(host.env().cfg.call_stipend);
     }
 
     // Call host to interact with target contract
@@ -464,9 +469,13 @@ pub fn call_code<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
     };
     // set is_empty to false as we are not creating this account.
     load.is_empty = false;
-    let Some(mut gas_limit) =
-        calc_call_gas::<SPEC>(interpreter, load, !value.is_zero(), local_gas_limit)
-    else {
+    let Some(mut gas_limit) = calc_call_gas(
+        interpreter,
+        spec_id!(host),
+        load,
+        !value.is_zero(),
+        local_gas_limit,
+    ) else {
         return;
     };
 
@@ -474,7 +483,7 @@ pub fn call_code<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
 
     // add call stipend if there is value to be transferred.
     if !value.is_zero() {
-        gas_limit = gas_limit.saturating_add(gas::CALL_STIPEND);
+        gas_limit = gas_limit.saturating_add(host.env().cfg.call_stipend);
     }
 
     // Call host to interact with target contract
@@ -496,7 +505,7 @@ pub fn call_code<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
 }
 
 pub fn delegate_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, HOMESTEAD);
+    check!(interpreter, host, HOMESTEAD);
     pop!(interpreter, local_gas_limit);
     pop_address!(interpreter, to);
     // max gas limit is not possible in real ethereum situation.
@@ -512,7 +521,8 @@ pub fn delegate_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter
     };
     // set is_empty to false as we are not creating this account.
     load.is_empty = false;
-    let Some(gas_limit) = calc_call_gas::<SPEC>(interpreter, load, false, local_gas_limit) else {
+    let Some(gas_limit) = calc_call_gas(interpreter, spec_id!(host), load, false, local_gas_limit)
+    else {
         return;
     };
 
@@ -537,7 +547,7 @@ pub fn delegate_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter
 }
 
 pub fn static_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, BYZANTIUM);
+    check!(interpreter, host, BYZANTIUM);
     pop!(interpreter, local_gas_limit);
     pop_address!(interpreter, to);
     // max gas limit is not possible in real ethereum situation.
@@ -553,7 +563,8 @@ pub fn static_call<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     };
     // set is_empty to false as we are not creating this account.
     load.is_empty = false;
-    let Some(gas_limit) = calc_call_gas::<SPEC>(interpreter, load, false, local_gas_limit) else {
+    let Some(gas_limit) = calc_call_gas(interpreter, spec_id!(host), load, false, local_gas_limit)
+    else {
         return;
     };
     gas!(interpreter, gas_limit);
@@ -1,7 +1,7 @@
 use crate::{
     gas,
     interpreter::Interpreter,
-    primitives::{Bytes, Spec, SpecId::*, U256},
+    primitives::{Bytes, SpecId, U256},
     AccountLoad,
 };
 use core::{cmp::min, ops::Range};
@@ -24,7 +24,7 @@ pub fn get_memory_input_and_out_ranges(
 }
 
 /// Resize memory and return range of memory.
-/// If `len` is 0 dont touch memory and return `usize::MAX` as offset and 0 as length.
+/// If `len` is 0 dont touch memory and return an empty range, the offset is not used.
 #[inline]
 pub fn resize_memory(
     interpreter: &mut Interpreter,
@@ -37,23 +37,24 @@ pub fn resize_memory(
         resize_memory!(interpreter, offset, len, None);
         offset
     } else {
-        usize::MAX //unrealistic value so we are sure it is not used
+        0
     };
     Some(offset..offset + len)
 }
 
 #[inline]
-pub fn calc_call_gas<SPEC: Spec>(
+pub fn calc_call_gas(
     interpreter: &mut Interpreter,
+    spec_id: SpecId,
     account_load: AccountLoad,
     has_transfer: bool,
     local_gas_limit: u64,
 ) -> Option<u64> {
-    let call_cost = gas::call_cost(SPEC::SPEC_ID, has_transfer, account_load);
+    let call_cost = gas::call_cost(spec_id, has_transfer, account_load);
     gas!(interpreter, call_cost, None);
 
     // EIP-150: Gas cost changes for IO-heavy operations
-    let gas_limit = if SPEC::enabled(TANGERINE) {
+    let gas_limit = if spec_id.is_enabled_in(SpecId::TANGERINE) {
         // take l64 part of gas_limit
         min(
             interpreter.gas().remaining_63_of_64_parts(),
@@ -0,0 +1,164 @@
+//! Superinstructions of fused bytecode, see [fusion](crate::interpreter::fusion).
+//!
+//! Each handler first checks that the whole sequence executes without halting. Otherwise it
+//! executes the first instruction of the sequence only, and the following ones are
+//! dispatched normally, so that the sequence halts where it would without fusion.
+
+use super::stack;
+use crate::{
+    gas, interpreter::fusion, opcode::Instruction, primitives::U256, Host, InstructionResult,
+    Interpreter,
+};
+
+/// Halts like an undefined opcode if the interpreter does not execute fused bytecode.
+macro_rules! require_fused {
+    ($interp:expr) => {
+        if !$interp.is_fused {
+            $interp.instruction_result = InstructionResult::OpcodeNotFound;
+            return;
+        }
+    };
+}
+
+/// Returns the handler of the fused instruction `opcode`, if it is one.
+///
+/// The handlers are part of every instruction table, and halt like undefined opcodes unless
+/// the interpreter executes fused bytecode.
+pub const fn instruction<H: Host + ?Sized>(opcode: u8) -> Option<Instruction<H>> {
+    match opcode {
+        fusion::PUSH1_JUMP => Some(push1_jump),
+        fusion::PUSH1_JUMPI => Some(push1_jumpi),
+        fusion::PUSH1_PUSH1 => Some(push1_push1),
+        fusion::PUSH1_ADD => Some(push1_add),
+        fusion::DUP_SWAP => Some(dup_swap),
+        _ => None,
+    }
+}
+
+/// Returns the byte at `offset` from the instruction pointer.
+#[inline]
+fn immediate(interpreter: &Interpreter, offset: usize) -> u8 {
+    // SAFETY: The bytecode is padded, and fused sequences are complete in the original code.
+    unsafe { *interpreter.instruction_pointer.add(offset) }
+}
+
+/// `PUSH1 x JUMP`.
+pub fn push1_jump<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
+    require_fused!(interpreter);
+    let target = immediate(interpreter, 0) as usize;
+    if interpreter.gas.remaining() >= gas::VERYLOW + gas::MID
+        && interpreter.stack.len() < interpreter.stack.limit()
+        && interpreter.contract.is_valid_jump(target)
+    {
+        let _ = interpreter.gas.record_cost(gas::VERYLOW + gas::MID);
+        // SAFETY: `is_valid_jump` ensures that `target` is in bounds.
+        interpreter.instruction_pointer = unsafe { interpreter.bytecode.as_ptr().add(target) };
+    } else {
+        stack::push::<1, H>(interpreter, host);
+    }
+}
+
+/// `PUSH1 x JUMPI`.
+pub fn push1_jumpi<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
+    require_fused!(interpreter);
+    let target = immediate(interpreter, 0) as usize;
+    let len = interpreter.stack.len();
+    if interpreter.gas.remaining() >= gas::VERYLOW + gas::HIGH
+        && len >= 1
+        && len < interpreter.stack.limit()
+    {
+        // SAFETY: The stack has at least one element.
+        let jumps = unsafe { !interpreter.stack.top_unsafe().is_zero() };
+        if !jumps || interpreter.contract.is_valid_jump(target) {
+            let _ = interpreter.gas.record_cost(gas::VERYLOW + gas::HIGH);
+            // SAFETY: The stack has at least one element, and `is_valid_jump` ensures that
+            // `target` is in bounds.
+            unsafe {
+                interpreter.stack.pop_unsafe();
+                interpreter.instruction_pointer = if jumps {
+                    interpreter.bytecode.as_ptr().add(target)
+                } else {
+                    interpreter.instruction_pointer.add(2)
+                };
+            }
+            return;
+        }
+    }
+    stack::push::<1, H>(interpreter, host);
+}
+
+/// `PUSH1 x PUSH1 y`.
+pub fn push1_push1<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
+    require_fused!(interpreter);
+    if interpreter.gas.remaining() >= 2 * gas::VERYLOW
+        && interpreter.stack.len() + 2 <= interpreter.stack.limit()
+    {
+        let _ = interpreter.gas.record_cost(2 * gas::VERYLOW);
+        let (x, y) = (immediate(interpreter, 0), immediate(interpreter, 2));
+        // SAFETY: The stack has room for both values, and see `immediate`.
+        unsafe {
+            interpreter.stack.push_unchecked(U256::from(x));
+            interpreter.stack.push_unchecked(U256::from(y));
+            interpreter.instruction_pointer = interpreter.instruction_pointer.add(3);
+        }
+    } else {
+        stack::push::<1, H>(interpreter, host);
+    }
+}
+
+/// `PUSH1 x ADD`.
+pub fn push1_add<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
+    require_fused!(interpreter);
+    let len = interpreter.stack.len();
+    if interpreter.gas.remaining() >= 2 * gas::VERYLOW
+        && len >= 1
+        && len < interpreter.stack.limit()
+    {
+        let _ = interpreter.gas.record_cost(2 * gas::VERYLOW);
+        let x = U256::from(immediate(interpreter, 0));
+        // SAFETY: The stack has at least one element, and the immediate is followed by `ADD`.
+        unsafe {
+            let top = interpreter.stack.top_unsafe();
+            *top = top.wrapping_add(x);
+            interpreter.instruction_pointer = interpreter.instruction_pointer.add(2);
+        }
+    } else {
+        stack::push::<1, H>(interpreter, host);
+    }
+}
+
+/// `DUPn SWAPm`.
+pub fn dup_swap<H: Host + ?Sized>(interpreter: &mut Interpreter, _host: &mut H) {
+    require_fused!(interpreter);
+    let operands = immediate(interpreter, 0);
+    let (n, m) = ((operands >> 4) as usize + 1, (operands & 0x0F) as usize + 1);
+    let len = interpreter.stack.len();
+    if interpreter.gas.remaining() >= 2 * gas::VERYLOW
+        && len >= n
+        && len >= m
+        && len < interpreter.stack.limit()
+    {
+        let _ = interpreter.gas.record_cost(2 * gas::VERYLOW);
+        // SAFETY: The stack has the elements to duplicate and swap, and room for the duplicate.
+        unsafe {
+            interpreter.stack.dup_unchecked(n);
+            interpreter.stack.swap_unchecked(m);
+        }
+    } else {
+        gas!(interpreter, gas::VERYLOW);
+        if let Err(result) = interpreter.stack.dup(n) {
+            interpreter.instruction_result = result;
+            return;
+        }
+        // The `SWAPm` is executed as the next instruction would be.
+        // SAFETY: The byte of the `SWAPm` is in the original code.
+        interpreter.instruction_pointer = unsafe { interpreter.instruction_pointer.add(1) };
+        gas!(interpreter, gas::VERYLOW);
+        if let Err(result) = interpreter.stack.swap(m) {
+            interpreter.instruction_result = result;
+        }
+        return;
+    }
+    // SAFETY: The byte of the `SWAPm` is in the original code.
+    interpreter.instruction_pointer = unsafe { interpreter.instruction_pointer.add(1) };
+}
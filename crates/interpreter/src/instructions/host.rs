@@ -1,13 +1,12 @@
 use crate::{
     gas::{self, warm_cold_cost, warm_cold_cost_with_delegation},
     interpreter::Interpreter,
-    primitives::{Bytes, Log, LogData, Spec, SpecId::*, B256, U256},
-    Host, InstructionResult,
+    primitives::{Bytes, EvmLog, LogTopics, Spec, B256, U256},
+    Host, HostRead, InstructionResult,
 };
 use core::cmp::min;
-use std::vec::Vec;
 
-pub fn balance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn balance<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     pop_address!(interpreter, address);
     let Some(balance) = host.balance(address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
@@ -15,12 +14,12 @@ pub fn balance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host
     };
     gas!(
         interpreter,
-        if SPEC::enabled(BERLIN) {
+        if spec_enabled!(host, BERLIN) {
             warm_cold_cost(balance.is_cold)
-        } else if SPEC::enabled(ISTANBUL) {
+        } else if spec_enabled!(host, ISTANBUL) {
             // EIP-1884: Repricing for trie-size-dependent opcodes
             700
-        } else if SPEC::enabled(TANGERINE) {
+        } else if spec_enabled!(host, TANGERINE) {
             400
         } else {
             20
@@ -30,8 +29,8 @@ pub fn balance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host
 }
 
 /// EIP-1884: Repricing for trie-size-dependent opcodes
-pub fn selfbalance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, ISTANBUL);
+pub fn selfbalance<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+    check!(interpreter, host, ISTANBUL);
     gas!(interpreter, gas::LOW);
     let Some(balance) = host.balance(interpreter.contract.target_address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
@@ -40,16 +39,16 @@ pub fn selfbalance<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     push!(interpreter, balance.data);
 }
 
-pub fn extcodesize<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extcodesize<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     pop_address!(interpreter, address);
     let Some(code) = host.code(address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
     let (code, load) = code.into_components();
-    if SPEC::enabled(BERLIN) {
+    if spec_enabled!(host, BERLIN) {
         gas!(interpreter, warm_cold_cost_with_delegation(load));
-    } else if SPEC::enabled(TANGERINE) {
+    } else if spec_enabled!(host, TANGERINE) {
         gas!(interpreter, 700);
     } else {
         gas!(interpreter, 20);
@@ -59,17 +58,17 @@ pub fn extcodesize<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
 }
 
 /// EIP-1052: EXTCODEHASH opcode
-pub fn extcodehash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, CONSTANTINOPLE);
+pub fn extcodehash<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+    check!(interpreter, host, CONSTANTINOPLE);
     pop_address!(interpreter, address);
     let Some(code_hash) = host.code_hash(address) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
     let (code_hash, load) = code_hash.into_components();
-    if SPEC::enabled(BERLIN) {
+    if spec_enabled!(host, BERLIN) {
         gas!(interpreter, warm_cold_cost_with_delegation(load))
-    } else if SPEC::enabled(ISTANBUL) {
+    } else if spec_enabled!(host, ISTANBUL) {
         gas!(interpreter, 700);
     } else {
         gas!(interpreter, 400);
@@ -77,7 +76,7 @@ pub fn extcodehash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     push_b256!(interpreter, code_hash);
 }
 
-pub fn extcodecopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn extcodecopy<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     pop_address!(interpreter, address);
     pop!(interpreter, memory_offset, code_offset, len_u256);
 
@@ -90,7 +89,7 @@ pub fn extcodecopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     let (code, load) = code.into_components();
     gas_or_fail!(
         interpreter,
-        gas::extcodecopy_cost(SPEC::SPEC_ID, len as u64, load)
+        gas::extcodecopy_cost(spec_id!(host), len as u64, load)
     );
     if len == 0 {
         return;
@@ -105,7 +104,7 @@ pub fn extcodecopy<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
         .set_data(memory_offset, code_offset, len, &code);
 }
 
-pub fn blockhash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn blockhash<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     gas!(interpreter, gas::BLOCKHASH);
     pop_top!(interpreter, number);
 
@@ -117,18 +116,24 @@ pub fn blockhash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
     *number = U256::from_be_bytes(hash.0);
 }
 
-pub fn sload<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+pub fn sload<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     pop_top!(interpreter, index);
     let Some(value) = host.sload(interpreter.contract.target_address, *index) else {
         interpreter.instruction_result = InstructionResult::FatalExternalError;
         return;
     };
-    gas!(interpreter, gas::sload_cost(SPEC::SPEC_ID, value.is_cold));
+    gas!(interpreter, gas::sload_cost(spec_id!(host), value.is_cold));
     *index = value.data;
 }
 
 pub fn sstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     require_non_staticcall!(interpreter);
+    // EIP-1706: Disable SSTORE with gasleft lower than call stipend.
+    // Checked before the slot is loaded, so a failing SSTORE does not touch the state.
+    if spec_enabled!(host, ISTANBUL) && interpreter.gas.remaining() <= gas::CALL_STIPEND {
+        interpreter.instruction_result = InstructionResult::OutOfGas;
+        return;
+    }
 
     pop!(interpreter, index, value);
     let Some(state_load) = host.sstore(interpreter.contract.target_address, index, value) else {
@@ -138,7 +143,7 @@ pub fn sstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host:
     gas_or_fail!(interpreter, {
         let remaining_gas = interpreter.gas.remaining();
         gas::sstore_cost(
-            SPEC::SPEC_ID,
+            spec_id!(host),
             &state_load.data,
             remaining_gas,
             state_load.is_cold,
@@ -146,14 +151,14 @@ pub fn sstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host:
     });
     refund!(
         interpreter,
-        gas::sstore_refund(SPEC::SPEC_ID, &state_load.data)
+        gas::sstore_refund(spec_id!(host), &state_load.data)
     );
 }
 
 /// EIP-1153: Transient storage opcodes
 /// Store value to transient storage
 pub fn tstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, CANCUN);
+    check!(interpreter, host, CANCUN);
     require_non_staticcall!(interpreter);
     gas!(interpreter, gas::WARM_STORAGE_READ_COST);
 
@@ -164,8 +169,8 @@ pub fn tstore<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host:
 
 /// EIP-1153: Transient storage opcodes
 /// Load value from transient storage
-pub fn tload<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, CANCUN);
+pub fn tload<H: HostRead + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
+    check!(interpreter, host, CANCUN);
     gas!(interpreter, gas::WARM_STORAGE_READ_COST);
 
     pop_top!(interpreter, index);
@@ -192,18 +197,17 @@ pub fn log<const N: usize, H: Host + ?Sized>(interpreter: &mut Interpreter, host
         return;
     }
 
-    let mut topics = Vec::with_capacity(N);
+    let mut topics = LogTopics::new();
     for _ in 0..N {
         // SAFETY: stack bounds already checked few lines above
         topics.push(B256::from(unsafe { interpreter.stack.pop_unsafe() }));
     }
 
-    let log = Log {
-        address: interpreter.contract.target_address,
-        data: LogData::new(topics, data).expect("LogData should have <=4 topics"),
-    };
-
-    host.log(log);
+    host.log(EvmLog::new(
+        interpreter.contract.target_address,
+        topics,
+        data,
+    ));
 }
 
 pub fn selfdestruct<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
@@ -216,10 +220,58 @@ pub fn selfdestruct<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter,
     };
 
     // EIP-3529: Reduction in refunds
-    if !SPEC::enabled(LONDON) && !res.previously_destroyed {
+    if !spec_enabled!(host, LONDON) && !res.previously_destroyed {
         refund!(interpreter, gas::SELFDESTRUCT)
     }
-    gas!(interpreter, gas::selfdestruct_cost(SPEC::SPEC_ID, res));
+    gas!(interpreter, gas::selfdestruct_cost(spec_id!(host), res));
 
     interpreter.instruction_result = InstructionResult::SelfDestruct;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        opcode::{make_instruction_table, SSTORE},
+        primitives::{Bytecode, DefaultEthereumWiring, IstanbulSpec, LatestSpec, PetersburgSpec},
+        DummyHost, Gas,
+    };
+
+    /// Executes `SSTORE(0, 1)` with `gas_limit`, and returns the result and the host.
+    fn sstore_with_gas<SPEC: Spec>(
+        gas_limit: u64,
+    ) -> (InstructionResult, DummyHost<DefaultEthereumWiring>) {
+        let table = make_instruction_table::<DummyHost<DefaultEthereumWiring>, SPEC>();
+        let mut host = DummyHost::default();
+        let mut interp = Interpreter::new_bytecode(Bytecode::LegacyRaw([SSTORE].into()));
+        interp.gas = Gas::new(gas_limit);
+        interp.stack.push(U256::from(1)).unwrap();
+        interp.stack.push(U256::ZERO).unwrap();
+        interp.step(&table, &mut host);
+        (interp.instruction_result, host)
+    }
+
+    #[test]
+    fn sstore_sentry() {
+        // At or below the stipend, SSTORE fails without loading or writing the slot.
+        for spec_result in [
+            sstore_with_gas::<IstanbulSpec>(gas::CALL_STIPEND),
+            sstore_with_gas::<LatestSpec>(gas::CALL_STIPEND),
+        ] {
+            let (result, host) = spec_result;
+            assert_eq!(result, InstructionResult::OutOfGas);
+            assert!(host.storage.is_empty());
+        }
+
+        // Above the stipend, only the cost of the store applies.
+        let (result, host) = sstore_with_gas::<LatestSpec>(gas::CALL_STIPEND + 1);
+        assert_eq!(result, InstructionResult::OutOfGas);
+        assert_eq!(host.storage.get(&U256::ZERO), Some(&U256::from(1)));
+        let (result, _) = sstore_with_gas::<LatestSpec>(gas::SSTORE_SET + gas::COLD_SLOAD_COST);
+        assert_eq!(result, InstructionResult::Continue);
+
+        // Before Istanbul, there is no sentry.
+        let (result, _) = sstore_with_gas::<PetersburgSpec>(gas::SSTORE_SET);
+        assert_eq!(result, InstructionResult::Continue);
+    }
+}
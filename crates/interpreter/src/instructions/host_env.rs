@@ -1,14 +1,14 @@
 use crate::{
     gas,
-    primitives::{Block, Spec, SpecId::*, Transaction, U256},
+    primitives::{Block, Spec, Transaction, U256},
     Host, Interpreter,
 };
 
 /// EIP-1344: ChainID opcode
 pub fn chainid<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, ISTANBUL);
+    check!(interpreter, host, ISTANBUL);
     gas!(interpreter, gas::BASE);
-    push!(interpreter, U256::from(host.env().cfg.chain_id));
+    push!(interpreter, host.env().cfg.chain_id_word());
 }
 
 pub fn coinbase<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
@@ -28,7 +28,7 @@ pub fn block_number<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut
 
 pub fn difficulty<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
     gas!(interpreter, gas::BASE);
-    if SPEC::enabled(MERGE) {
+    if spec_enabled!(host, MERGE) {
         push_b256!(interpreter, *host.env().block.prevrandao().unwrap());
     } else {
         push!(interpreter, *host.env().block.difficulty());
@@ -47,7 +47,7 @@ pub fn gasprice<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
 
 /// EIP-3198: BASEFEE opcode
 pub fn basefee<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, LONDON);
+    check!(interpreter, host, LONDON);
     gas!(interpreter, gas::BASE);
     push!(interpreter, *host.env().block.basefee());
 }
@@ -59,7 +59,7 @@ pub fn origin<H: Host + ?Sized>(interpreter: &mut Interpreter, host: &mut H) {
 
 // EIP-4844: Shard Blob Transactions
 pub fn blob_hash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, CANCUN);
+    check!(interpreter, host, CANCUN);
     gas!(interpreter, gas::VERYLOW);
     pop_top!(interpreter, index);
     let i = as_usize_saturated!(index);
@@ -71,7 +71,7 @@ pub fn blob_hash<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, ho
 
 /// EIP-7516: BLOBBASEFEE opcode
 pub fn blob_basefee<H: Host + ?Sized, SPEC: Spec>(interpreter: &mut Interpreter, host: &mut H) {
-    check!(interpreter, CANCUN);
+    check!(interpreter, host, CANCUN);
     gas!(interpreter, gas::BASE);
     push!(
         interpreter,
@@ -33,13 +33,43 @@ macro_rules! require_init_eof {
     };
 }
 
+/// Returns the [SpecId](crate::primitives::SpecId) the instruction is executed with: `SPEC`, or
+/// the spec of the host for [RuntimeSpec](crate::primitives::RuntimeSpec).
+#[macro_export]
+macro_rules! spec_id {
+    ($host:expr) => {
+        match const { <SPEC as $crate::primitives::Spec>::STATIC_SPEC_ID } {
+            Some(spec_id) => spec_id,
+            None => $crate::HostRead::spec_id(&*$host),
+        }
+    };
+}
+
+/// Returns whether the spec `$spec` is enabled in the spec the instruction is executed with.
+#[macro_export]
+macro_rules! spec_enabled {
+    ($host:expr, $spec:ident) => {
+        $crate::spec_id!($host).is_enabled_in($crate::primitives::SpecId::$spec)
+    };
+}
+
 /// Check if the `SPEC` is enabled, and fail the instruction if it is not.
+///
+/// The check is resolved at compile time, unless `SPEC` is
+/// [RuntimeSpec](crate::primitives::RuntimeSpec).
 #[macro_export]
 macro_rules! check {
-    ($interp:expr, $min:ident) => {
-        if const {
-            !<SPEC as $crate::primitives::Spec>::SPEC_ID
-                .is_enabled_in($crate::primitives::SpecId::$min)
+    ($interp:expr, $host:expr, $min:ident) => {
+        if const { <SPEC as $crate::primitives::Spec>::STATIC_SPEC_ID.is_none() } {
+            if !$crate::spec_enabled!($host, $min) {
+                $interp.instruction_result = $crate::InstructionResult::NotActivated;
+                return;
+            }
+        } else if const {
+            match <SPEC as $crate::primitives::Spec>::STATIC_SPEC_ID {
+                Some(spec_id) => !spec_id.is_enabled_in($crate::primitives::SpecId::$min),
+                None => false,
+            }
         } {
             $interp.instruction_result = $crate::InstructionResult::NotActivated;
             return;
@@ -91,23 +121,15 @@ macro_rules! resize_memory {
         $crate::resize_memory!($interp, $offset, $len, ())
     };
     ($interp:expr, $offset:expr, $len:expr, $ret:expr) => {
-        let new_size = $offset.saturating_add($len);
-        if new_size > $interp.shared_memory.len() {
-            #[cfg(feature = "memory_limit")]
-            if $interp.shared_memory.limit_reached(new_size) {
-                $interp.instruction_result = $crate::InstructionResult::MemoryLimitOOG;
-                return $ret;
-            }
-
-            // Note: we can't use `Interpreter` directly here because of potential double-borrows.
-            if !$crate::interpreter::resize_memory(
-                &mut $interp.shared_memory,
-                &mut $interp.gas,
-                new_size,
-            ) {
-                $interp.instruction_result = $crate::InstructionResult::MemoryOOG;
-                return $ret;
-            }
+        // Note: we can't use `Interpreter` directly here because of potential double-borrows.
+        if let Err(result) = $crate::interpreter::resize_memory_range(
+            &mut $interp.shared_memory,
+            &mut $interp.gas,
+            $offset,
+            $len,
+        ) {
+            $interp.instruction_result = result;
+            return $ret;
         }
     };
 }
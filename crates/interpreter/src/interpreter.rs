@@ -1,20 +1,26 @@
 pub mod analysis;
+mod budget;
 mod contract;
+pub mod fusion;
+mod memory_snapshot;
+mod observer;
 #[cfg(feature = "serde")]
 pub mod serde;
 mod shared_memory;
 mod stack;
 
+pub use budget::ExecutionBudget;
 pub use contract::Contract;
+pub use memory_snapshot::{MemorySnapshot, MemorySnapshotMode};
+pub use observer::{OpcodeCounter, OpcodeObserver};
 pub use shared_memory::{num_words, SharedMemory, EMPTY_SHARED_MEMORY};
-pub use stack::{Stack, STACK_LIMIT};
+pub use stack::{Stack, StackError, STACK_LIMIT};
 
 use crate::{
-    gas, primitives::Bytes, push, push_b256, return_ok, return_revert, CallOutcome, CreateOutcome,
-    FunctionStack, Gas, Host, InstructionResult, InterpreterAction,
+    gas, opcode, primitives::Bytes, push, push_b256, return_ok, return_revert, CallOutcome,
+    CreateOutcome, FunctionStack, Gas, Host, InstructionResult, InterpreterAction,
 };
-use core::cmp::min;
-use revm_primitives::{Bytecode, Eof, U256};
+use revm_primitives::{spec_to_generic, Address, Bytecode, Eof, HaltLocation, Spec, SpecId, U256};
 use std::borrow::ToOwned;
 use std::sync::Arc;
 
@@ -33,6 +39,8 @@ pub struct Interpreter {
     /// Currently run Bytecode that instruction result will point to.
     /// Bytecode is owned by the contract.
     pub bytecode: Bytes,
+    /// Whether [Interpreter::bytecode] has fused instructions, see [fusion].
+    pub is_fused: bool,
     /// Whether we are Interpreting the Ethereum Object Format (EOF) bytecode.
     /// This is local field that is set from `contract.is_eof()`.
     pub is_eof: bool,
@@ -43,6 +51,10 @@ pub struct Interpreter {
     /// Note: This field is only set while running the interpreter loop.
     /// Otherwise it is taken and replaced with empty shared memory.
     pub shared_memory: SharedMemory,
+    /// Memory captured before the current instruction, if requested by an inspector.
+    ///
+    /// It is captured once per instruction, and shared by all the consumers of the step.
+    pub memory_snapshot: Option<MemorySnapshot>,
     /// Stack.
     pub stack: Stack,
     /// EOF function stack.
@@ -84,15 +96,58 @@ impl Interpreter {
             instruction_result: InstructionResult::Continue,
             function_stack: FunctionStack::default(),
             is_static,
+            is_fused: false,
             is_eof,
             is_eof_init: false,
             return_data_buffer: Bytes::new(),
             shared_memory: EMPTY_SHARED_MEMORY,
+            memory_snapshot: None,
             stack: Stack::new(),
             next_action: InterpreterAction::None,
         }
     }
 
+    /// Sets the maximum number of words of the stack, see [Stack::set_limit].
+    #[inline]
+    pub fn with_stack_limit(mut self, limit: usize) -> Self {
+        self.stack.set_limit(limit);
+        self
+    }
+
+    /// Executes `fused`, the bytecode of the contract with fused instructions returned by
+    /// [fusion::fuse], from the current program counter.
+    ///
+    /// Fused instructions are executed as a single step, so they are seen as one instruction
+    /// by [OpcodeObserver]s and the [ExecutionBudget].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the contract is not legacy bytecode of the same length.
+    pub fn set_fused_bytecode(&mut self, fused: Bytes) {
+        assert!(
+            !self.is_eof && fused.len() == self.contract.bytecode.bytecode().len(),
+            "fused bytecode does not match the contract"
+        );
+        let pc = self.program_counter();
+        self.bytecode = fused;
+        // SAFETY: The fused bytecode has the length of the contract bytecode.
+        self.instruction_pointer = unsafe { self.bytecode.as_ptr().add(pc) };
+        self.is_fused = true;
+    }
+
+    /// Executes the bytecode of the contract instead of the fused bytecode, if set, from the
+    /// current program counter. See [Interpreter::set_fused_bytecode].
+    pub fn unfuse(&mut self) {
+        if !self.is_fused {
+            return;
+        }
+        let pc = self.program_counter();
+        self.bytecode = self.contract.bytecode.bytecode().clone();
+        // SAFETY: The fused bytecode has the length of the contract bytecode.
+        self.instruction_pointer = unsafe { self.bytecode.as_ptr().add(pc) };
+        self.is_fused = false;
+    }
+
     /// Set is_eof_init to true, this is used to enable `RETURNCONTRACT` opcode.
     #[inline]
     pub fn set_is_eof_init(&mut self) {
@@ -255,19 +310,16 @@ impl Interpreter {
     ) {
         self.instruction_result = InstructionResult::Continue;
 
-        let out_offset = call_outcome.memory_start();
-        let out_len = call_outcome.memory_length();
         let out_ins_result = *call_outcome.instruction_result();
         let out_gas = call_outcome.gas();
-        self.return_data_buffer = call_outcome.result.output;
+        self.return_data_buffer = call_outcome.output().clone();
 
-        let target_len = min(out_len, self.return_data_buffer.len());
         match out_ins_result {
             return_ok!() => {
                 // return unspend gas.
                 self.gas.erase_cost(out_gas.remaining());
                 self.gas.record_refund(out_gas.refunded());
-                shared_memory.set(out_offset, &self.return_data_buffer[..target_len]);
+                call_outcome.write_output(shared_memory);
                 push!(
                     self,
                     if self.is_eof {
@@ -279,7 +331,7 @@ impl Interpreter {
             }
             return_revert!() => {
                 self.gas.erase_cost(out_gas.remaining());
-                shared_memory.set(out_offset, &self.return_data_buffer[..target_len]);
+                call_outcome.write_output(shared_memory);
                 push!(
                     self,
                     if self.is_eof {
@@ -343,6 +395,50 @@ impl Interpreter {
         unsafe { self.instruction_pointer.offset_from(self.bytecode.as_ptr()) as usize }
     }
 
+    /// Returns the program counter of the instruction that halted the interpreter.
+    ///
+    /// The instruction pointer is advanced before an instruction is executed, so this is the
+    /// offset of the last executed instruction, unless the execution budget was exhausted
+    /// before the next instruction started.
+    #[inline]
+    pub fn halt_program_counter(&self) -> usize {
+        let pc = self.program_counter();
+        if self.instruction_result == InstructionResult::ExecutionBudgetExceeded {
+            pc
+        } else {
+            pc.saturating_sub(1)
+        }
+    }
+
+    /// Returns the opcode of the instruction that halted the interpreter.
+    ///
+    /// See [Interpreter::halt_program_counter].
+    #[inline]
+    pub fn halt_opcode(&self) -> u8 {
+        // Fused bytecode has internal opcodes, the contract has the original ones.
+        let bytecode = if self.is_fused {
+            self.contract.bytecode.bytecode()
+        } else {
+            &self.bytecode
+        };
+        bytecode
+            .get(self.halt_program_counter())
+            .copied()
+            .unwrap_or(opcode::STOP)
+    }
+
+    /// Returns the location of the instruction that halted the interpreter, whose frame is
+    /// at call depth `depth`.
+    #[inline]
+    pub fn halt_location(&self, depth: u64) -> HaltLocation {
+        HaltLocation {
+            address: self.contract.target_address,
+            pc: self.halt_program_counter(),
+            opcode: self.halt_opcode(),
+            depth,
+        }
+    }
+
     /// Executes the instruction at the current instruction pointer.
     ///
     /// Internally it will increment instruction pointer by one.
@@ -375,6 +471,112 @@ impl Interpreter {
         instruction_table: &[FN; 256],
         host: &mut H,
     ) -> InterpreterAction
+    where
+        FN: Fn(&mut Interpreter, &mut H),
+    {
+        self.run_observed(shared_memory, instruction_table, host, None, &mut ())
+    }
+
+    /// Creates an interpreter that executes `bytecode` with `input` as call data, outside of
+    /// an EVM.
+    ///
+    /// The contract is executed at the zero address, is called by the zero address and
+    /// receives no value. Use [Interpreter::new] with a [Contract] to set those.
+    pub fn new_standalone(bytecode: Bytecode, input: Bytes, gas_limit: u64) -> Self {
+        let contract = Contract::new(
+            input,
+            bytecode,
+            None,
+            Address::ZERO,
+            None,
+            Address::ZERO,
+            U256::ZERO,
+        );
+        Self::new(contract, gas_limit, false)
+    }
+
+    /// Executes the interpreter with the instructions of `spec_id` against `host`, until it
+    /// returns or stops.
+    ///
+    /// This does not require an EVM: the state is only accessed through the [Host], for
+    /// which the in-memory [DummyHost](crate::DummyHost) can be used. Calls and creates are
+    /// not executed, they are returned as [InterpreterAction::Call],
+    /// [InterpreterAction::Create] or [InterpreterAction::EOFCreate]. Their outcome can be
+    /// inserted with [Interpreter::insert_create_outcome], or with
+    /// [Interpreter::insert_call_outcome] and the memory taken with [Interpreter::take_memory],
+    /// before running the interpreter again.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use revm_interpreter::{
+    ///     opcode, primitives::{Bytecode, Bytes, DefaultEthereumWiring, SpecId, U256},
+    ///     DummyHost, InstructionResult, Interpreter, InterpreterAction,
+    /// };
+    ///
+    /// // SSTORE(0, 42), then STOP.
+    /// let code = [opcode::PUSH1, 42, opcode::PUSH0, opcode::SSTORE, opcode::STOP];
+    /// let bytecode = Bytecode::new_legacy(Bytes::from(code.to_vec()));
+    /// let mut interpreter = Interpreter::new_standalone(bytecode, Bytes::new(), 100_000);
+    /// let mut host = DummyHost::<DefaultEthereumWiring>::default();
+    ///
+    /// let InterpreterAction::Return { result } = interpreter.run_standalone(SpecId::CANCUN, &mut host)
+    /// else {
+    ///     unreachable!("the bytecode does not call")
+    /// };
+    /// assert_eq!(result.result, InstructionResult::Stop);
+    /// assert_eq!(host.storage[&U256::ZERO], U256::from(42));
+    /// ```
+    pub fn run_standalone<H: Host>(&mut self, spec_id: SpecId, host: &mut H) -> InterpreterAction {
+        // The memory is kept between runs, so it is preserved when the interpreter is resumed.
+        let shared_memory = self.take_memory();
+        spec_to_generic!(
+            spec_id,
+            self.run(
+                shared_memory,
+                &opcode::make_instruction_table::<H, SPEC>(),
+                host
+            )
+        )
+    }
+
+    /// Executes the interpreter until it returns or stops, spending one instruction of
+    /// `budget` per step.
+    ///
+    /// Halts with [InstructionResult::ExecutionBudgetExceeded] once the budget is exhausted.
+    pub fn run_with_budget<FN, H: Host + ?Sized>(
+        &mut self,
+        shared_memory: SharedMemory,
+        instruction_table: &[FN; 256],
+        host: &mut H,
+        budget: &mut ExecutionBudget,
+    ) -> InterpreterAction
+    where
+        FN: Fn(&mut Interpreter, &mut H),
+    {
+        self.run_observed(
+            shared_memory,
+            instruction_table,
+            host,
+            Some(budget),
+            &mut (),
+        )
+    }
+
+    /// Executes the interpreter until it returns or stops, calling `observer` before every
+    /// instruction and spending one instruction of `budget`, if any, per step.
+    ///
+    /// The observer is statically dispatched, so counting instructions does not require
+    /// wrapping the instruction table like an inspector does.
+    #[inline]
+    pub fn run_observed<FN, H: Host + ?Sized, O: OpcodeObserver + ?Sized>(
+        &mut self,
+        shared_memory: SharedMemory,
+        instruction_table: &[FN; 256],
+        host: &mut H,
+        mut budget: Option<&mut ExecutionBudget>,
+        observer: &mut O,
+    ) -> InterpreterAction
     where
         FN: Fn(&mut Interpreter, &mut H),
     {
@@ -382,9 +584,41 @@ impl Interpreter {
         self.shared_memory = shared_memory;
         // main loop
         while self.instruction_result == InstructionResult::Continue {
+            if let Some(budget) = budget.as_deref_mut() {
+                if !budget.spend_instruction() {
+                    self.instruction_result = InstructionResult::ExecutionBudgetExceeded;
+                    break;
+                }
+            }
+            observer.step(self, self.current_opcode());
             self.step(instruction_table, host);
         }
+        self.take_next_action()
+    }
+
+    /// Executes the interpreter until it returns or stops, dispatching the instructions of
+    /// `SPEC` with a `match` on the opcode instead of an instruction table.
+    ///
+    /// This is meant for comparing the two dispatch strategies, see the `eval/match`
+    /// benchmarks. The EVM always executes with an instruction table, which can be customized.
+    pub fn run_match<H: Host + ?Sized, SPEC: Spec>(
+        &mut self,
+        shared_memory: SharedMemory,
+        host: &mut H,
+    ) -> InterpreterAction {
+        self.next_action = InterpreterAction::None;
+        self.shared_memory = shared_memory;
+        while self.instruction_result == InstructionResult::Continue {
+            let opcode = self.current_opcode();
+            // SAFETY: See [Interpreter::step].
+            self.instruction_pointer = unsafe { self.instruction_pointer.offset(1) };
+            opcode::eval::<H, SPEC>(opcode, self, host);
+        }
+        self.take_next_action()
+    }
 
+    /// Returns the action of a stopped interpreter.
+    pub(crate) fn take_next_action(&mut self) -> InterpreterAction {
         // Return next action if it is some.
         if self.next_action.is_some() {
             return core::mem::take(&mut self.next_action);
@@ -406,6 +640,32 @@ impl Interpreter {
     pub fn resize_memory(&mut self, new_size: usize) -> bool {
         resize_memory(&mut self.shared_memory, &mut self.gas, new_size)
     }
+
+    /// Writes `data` to the `out_len` bytes of memory at `out_offset`, like the output of a call.
+    ///
+    /// The data is truncated to `out_len` bytes, and the rest of the range is left untouched if
+    /// the data is shorter. The memory is resized to fit the range and the expansion is charged.
+    /// An empty range writes nothing, whatever its offset.
+    ///
+    /// Returns the number of written bytes, or `None` if the memory could not be resized, in
+    /// which case the instruction result is set.
+    pub fn write_call_output(
+        &mut self,
+        out_offset: usize,
+        out_len: usize,
+        data: &[u8],
+    ) -> Option<usize> {
+        if out_len == 0 {
+            return Some(0);
+        }
+        if let Err(result) =
+            resize_memory_range(&mut self.shared_memory, &mut self.gas, out_offset, out_len)
+        {
+            self.instruction_result = result;
+            return None;
+        }
+        Some(self.shared_memory.set_truncated(out_offset, out_len, data))
+    }
 }
 
 /// The result of an interpreter operation.
@@ -449,13 +709,42 @@ impl InterpreterResult {
     }
 }
 
+/// Resizes the memory to fit `len` bytes at `offset`, and charges the expansion gas.
+///
+/// Returns the instruction result to halt with if the range overflows, the memory limit is
+/// reached or the gas is not enough.
+#[inline]
+pub fn resize_memory_range(
+    memory: &mut SharedMemory,
+    gas: &mut Gas,
+    offset: usize,
+    len: usize,
+) -> Result<(), InstructionResult> {
+    let Some(new_size) = offset.checked_add(len) else {
+        return Err(InstructionResult::MemoryOOG);
+    };
+    if new_size > memory.len() {
+        #[cfg(feature = "memory_limit")]
+        if memory.limit_reached(new_size) {
+            return Err(InstructionResult::MemoryLimitOOG);
+        }
+
+        if !resize_memory(memory, gas, new_size) {
+            return Err(InstructionResult::MemoryOOG);
+        }
+    }
+    Ok(())
+}
+
 /// Resize the memory to the new size. Returns whether the gas was enough to resize the memory.
 #[inline(never)]
 #[cold]
 #[must_use]
 pub fn resize_memory(memory: &mut SharedMemory, gas: &mut Gas, new_size: usize) -> bool {
     let new_words = num_words(new_size as u64);
-    let new_cost = gas::memory_gas(new_words);
+    let Some(new_cost) = gas::memory_gas_checked(new_words) else {
+        return false;
+    };
     let current_cost = memory.current_expansion_cost();
     let cost = new_cost - current_cost;
     let success = gas.record_cost(cost);
@@ -490,4 +779,200 @@ mod tests {
             >();
         let _ = interp.run(EMPTY_SHARED_MEMORY, table, host);
     }
+
+    #[test]
+    fn match_dispatch_matches_table() {
+        use crate::opcode::{
+            ADD, DUP1, DUP2, JUMPDEST, JUMPI, MSTORE, PUSH1, RETURN, SLOAD, SSTORE, SUB, SWAP1,
+        };
+
+        // for n in (1..=10).rev() { sstore(n, sload(n) + n) }, then returns 32 zero bytes.
+        let code = [
+            PUSH1, 10, JUMPDEST, DUP1, DUP1, SLOAD, ADD, DUP2, SSTORE, PUSH1, 1, SWAP1, SUB, DUP1,
+            PUSH1, 2, JUMPI, PUSH1, 0, MSTORE, PUSH1, 32, PUSH1, 0, RETURN,
+        ];
+        let table =
+            crate::opcode::make_instruction_table::<DummyHost<DefaultEthereumWiring>, CancunSpec>();
+        let run = |matched: bool| {
+            let bytecode = Bytecode::new_legacy(Bytes::from(code.to_vec()));
+            let mut interp = Interpreter::new_standalone(bytecode, Bytes::new(), 1_000_000);
+            let mut host = DummyHost::<DefaultEthereumWiring>::default();
+            let action = if matched {
+                interp.run_match::<_, CancunSpec>(SharedMemory::new(), &mut host)
+            } else {
+                interp.run(SharedMemory::new(), &table, &mut host)
+            };
+            (action, host.storage)
+        };
+
+        let (action, storage) = run(false);
+        assert!(matches!(
+            &action,
+            InterpreterAction::Return { result } if result.result == InstructionResult::Return
+        ));
+        assert_eq!(storage.len(), 10);
+        assert_eq!(run(true), (action, storage));
+    }
+
+    #[test]
+    fn observer_counts_opcodes() {
+        use crate::opcode::{ADD, PUSH1, STOP};
+
+        let code = [PUSH1, 1, PUSH1, 2, ADD, STOP];
+        let bytecode = Bytecode::new_legacy(Bytes::from(code.to_vec()));
+        let mut interp = Interpreter::new_standalone(bytecode, Bytes::new(), 100_000);
+        let mut host = DummyHost::<DefaultEthereumWiring>::default();
+        let table = crate::opcode::make_instruction_table::<_, CancunSpec>();
+
+        let mut counter = OpcodeCounter::default();
+        let _ = interp.run_observed(EMPTY_SHARED_MEMORY, &table, &mut host, None, &mut counter);
+        assert_eq!(counter.count(PUSH1), 2);
+        assert_eq!(counter.count(ADD), 1);
+        assert_eq!(counter.count(STOP), 1);
+        assert_eq!(counter.total(), 4);
+    }
+
+    #[test]
+    fn standalone_call_is_resumed() {
+        use crate::opcode::{CALL, GAS, PUSH0, PUSH1, RETURN};
+
+        // CALL(gas, 0xa2, 0, 0, 0, 0, 32), then RETURN(0, 32).
+        let code = [
+            PUSH1, 32, PUSH0, PUSH0, PUSH0, PUSH0, PUSH1, 0xa2, GAS, CALL, PUSH1, 32, PUSH0, RETURN,
+        ];
+        let bytecode = Bytecode::new_legacy(Bytes::from(code.to_vec()));
+        let mut interp = Interpreter::new_standalone(bytecode, Bytes::new(), 100_000);
+        let mut host = DummyHost::<DefaultEthereumWiring>::default();
+
+        let InterpreterAction::Call { inputs } = interp.run_standalone(SpecId::CANCUN, &mut host)
+        else {
+            panic!("expected a call");
+        };
+        assert_eq!(inputs.target_address, Address::with_last_byte(0xa2));
+
+        let output = Bytes::from_static(&[7; 32]);
+        let result = InterpreterResult::new(
+            InstructionResult::Return,
+            output.clone(),
+            Gas::new(inputs.gas_limit),
+        );
+        let mut memory = interp.take_memory();
+        interp.insert_call_outcome(&mut memory, CallOutcome::new(result, 0..32));
+        interp.shared_memory = memory;
+
+        let InterpreterAction::Return { result } = interp.run_standalone(SpecId::CANCUN, &mut host)
+        else {
+            panic!("expected a return");
+        };
+        assert_eq!(result.result, InstructionResult::Return);
+        assert_eq!(result.output, output);
+    }
+
+    #[test]
+    fn call_stipend_is_configurable() {
+        use crate::opcode::{CALL, PUSH0, PUSH1, PUSH2};
+
+        // CALL(1000, 0xa2, 1, 0, 0, 0, 0)
+        let code = [
+            PUSH0, PUSH0, PUSH0, PUSH0, PUSH1, 1, PUSH1, 0xa2, PUSH2, 0x03, 0xe8, CALL,
+        ];
+        for stipend in [gas::CALL_STIPEND, 0, 10_000] {
+            let bytecode = Bytecode::new_legacy(Bytes::from(code.to_vec()));
+            let mut interp = Interpreter::new_standalone(bytecode, Bytes::new(), 100_000);
+            let mut host = DummyHost::<DefaultEthereumWiring>::default();
+            host.env.cfg.call_stipend = stipend;
+
+            let InterpreterAction::Call { inputs } =
+                interp.run_standalone(SpecId::CANCUN, &mut host)
+            else {
+                panic!("expected a call");
+            };
+            assert_eq!(inputs.gas_limit, 1_000 + stipend);
+        }
+    }
+
+    #[test]
+    fn memory_expansion_boundaries() {
+        use crate::opcode::{CALLDATACOPY, EXTCODECOPY, MLOAD, MSTORE, RETURNDATACOPY};
+
+        let table =
+            crate::opcode::make_instruction_table::<DummyHost<DefaultEthereumWiring>, CancunSpec>();
+        // Executes `opcode` with `stack`, whose last element is the top, and returns the result.
+        let run = |opcode: u8, stack: &[U256], gas_limit: u64| {
+            let mut host = DummyHost::<DefaultEthereumWiring>::default();
+            let mut interp = Interpreter::new_bytecode(Bytecode::LegacyRaw([opcode].into()));
+            interp.shared_memory = SharedMemory::new();
+            interp.gas = Gas::new(gas_limit);
+            interp.return_data_buffer = Bytes::from_static(&[0; 32]);
+            for value in stack {
+                interp.stack.push(*value).unwrap();
+            }
+            interp.step(&table, &mut host);
+            interp.instruction_result
+        };
+
+        let word = U256::from(32);
+        for offset in [
+            // Largest end of the range, whose cost overflows.
+            usize::MAX - 31,
+            // End of the range overflows.
+            usize::MAX - 30,
+            usize::MAX,
+            // Fits in 32 bits, but the cost exceeds any realistic gas limit.
+            u32::MAX as usize,
+        ] {
+            let offset = U256::from(offset);
+            for (opcode, stack) in [
+                (MLOAD, vec![offset]),
+                (MSTORE, vec![U256::ZERO, offset]),
+                (CALLDATACOPY, vec![word, U256::ZERO, offset]),
+                (RETURNDATACOPY, vec![word, U256::ZERO, offset]),
+                (EXTCODECOPY, vec![word, U256::ZERO, offset, U256::ZERO]),
+            ] {
+                let gas_limit = if offset > U256::from(u32::MAX) {
+                    u64::MAX
+                } else {
+                    30_000_000
+                };
+                assert_eq!(
+                    run(opcode, &stack, gas_limit),
+                    InstructionResult::MemoryOOG,
+                    "opcode {opcode:#x} at offset {offset}"
+                );
+            }
+        }
+
+        // Offsets that don't fit in an `usize`.
+        let offset = U256::from(u64::MAX) + U256::from(1);
+        assert_eq!(
+            run(MLOAD, &[offset], u64::MAX),
+            InstructionResult::InvalidOperandOOG
+        );
+
+        // Expanding to 33 words costs 3 * 33 + 33 * 33 / 512 = 101, on top of the 3 of MSTORE.
+        let stack = [U256::ZERO, U256::from(1024)];
+        assert_eq!(run(MSTORE, &stack, 104), InstructionResult::Continue);
+        assert_eq!(run(MSTORE, &stack, 103), InstructionResult::MemoryOOG);
+    }
+
+    #[test]
+    fn call_output_is_written() {
+        let mut interp = Interpreter::new_bytecode(Bytecode::default());
+        interp.shared_memory = SharedMemory::new();
+        interp.gas = Gas::new(1_000);
+
+        // The range is expanded to a word, and the output truncated to the range.
+        assert_eq!(interp.write_call_output(2, 3, &[1, 2, 3, 4]), Some(3));
+        assert_eq!(interp.shared_memory.context_memory(), {
+            let mut memory = [0; 32];
+            memory[2..5].copy_from_slice(&[1, 2, 3]);
+            memory
+        });
+        assert_eq!(interp.gas.spent(), 3);
+
+        // Empty ranges are never written, whatever their offset.
+        assert_eq!(interp.write_call_output(usize::MAX, 0, &[1]), Some(0));
+        assert_eq!(interp.write_call_output(usize::MAX, 1, &[1]), None);
+        assert_eq!(interp.instruction_result, InstructionResult::MemoryOOG);
+    }
 }
@@ -0,0 +1,112 @@
+use core::time::Duration;
+
+/// Number of instructions executed between two checks of the deadline.
+#[cfg(feature = "std")]
+const DEADLINE_CHECK_INTERVAL: u32 = 1024;
+
+/// Limits the number of instructions and the wall-clock time of an execution.
+///
+/// The budget is shared by all frames of a transaction, see
+/// [Interpreter::run_with_budget](crate::Interpreter::run_with_budget). The default budget is
+/// unlimited.
+///
+/// The deadline is only enforced with the `std` feature, and is checked every 1024
+/// instructions, so the execution can slightly overrun it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ExecutionBudget {
+    /// Number of instructions that can still be executed.
+    remaining_instructions: Option<u64>,
+    /// Time after which the execution is aborted.
+    #[cfg(feature = "std")]
+    deadline: Option<std::time::Instant>,
+    /// Instructions executed since the deadline was last checked.
+    #[cfg(feature = "std")]
+    unchecked_instructions: u32,
+}
+
+impl ExecutionBudget {
+    /// Creates a budget of at most `instruction_limit` instructions, that expires `timeout`
+    /// after its creation.
+    pub fn new(instruction_limit: Option<u64>, timeout: Option<Duration>) -> Self {
+        #[cfg(not(feature = "std"))]
+        let _ = timeout;
+        Self {
+            remaining_instructions: instruction_limit,
+            #[cfg(feature = "std")]
+            deadline: timeout.and_then(|timeout| std::time::Instant::now().checked_add(timeout)),
+            #[cfg(feature = "std")]
+            unchecked_instructions: 0,
+        }
+    }
+
+    /// Returns whether the budget has neither an instruction limit nor a deadline.
+    #[inline]
+    pub fn is_unlimited(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.deadline.is_some() {
+            return false;
+        }
+        self.remaining_instructions.is_none()
+    }
+
+    /// Returns the number of instructions that can still be executed, if limited.
+    #[inline]
+    pub fn remaining_instructions(&self) -> Option<u64> {
+        self.remaining_instructions
+    }
+
+    /// Spends one instruction of the budget.
+    ///
+    /// Returns `false` if the budget is exhausted and the instruction must not be executed.
+    #[inline]
+    pub fn spend_instruction(&mut self) -> bool {
+        if let Some(remaining) = &mut self.remaining_instructions {
+            if *remaining == 0 {
+                return false;
+            }
+            *remaining -= 1;
+        }
+        #[cfg(feature = "std")]
+        if let Some(deadline) = self.deadline {
+            self.unchecked_instructions += 1;
+            if self.unchecked_instructions >= DEADLINE_CHECK_INTERVAL {
+                self.unchecked_instructions = 0;
+                if std::time::Instant::now() >= deadline {
+                    // Keep failing without querying the clock again.
+                    self.remaining_instructions = Some(0);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instruction_limit() {
+        let mut budget = ExecutionBudget::new(Some(2), None);
+        assert!(!budget.is_unlimited());
+        assert!(budget.spend_instruction());
+        assert!(budget.spend_instruction());
+        assert!(!budget.spend_instruction());
+        assert_eq!(budget.remaining_instructions(), Some(0));
+
+        assert!(ExecutionBudget::default().is_unlimited());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn expired_deadline() {
+        let mut budget = ExecutionBudget::new(None, Some(Duration::ZERO));
+        assert!(!budget.is_unlimited());
+        let executed = (0..2 * DEADLINE_CHECK_INTERVAL)
+            .take_while(|_| budget.spend_instruction())
+            .count();
+        assert_eq!(executed, DEADLINE_CHECK_INTERVAL as usize - 1);
+        assert!(!budget.spend_instruction());
+    }
+}
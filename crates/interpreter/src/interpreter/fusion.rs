@@ -0,0 +1,201 @@
+//! Fusion of common instruction sequences into superinstructions.
+//!
+//! [fuse] rewrites a copy of legacy bytecode so that the first opcode of each fused sequence
+//! is one of the internal opcodes below, which are unused by both legacy and EOF bytecode.
+//! The handlers of [fused](crate::instructions::fused) execute the whole sequence at once.
+//!
+//! The fused bytecode has the length and the jump destinations of the original one, so
+//! program counters, jumps and `PUSH` immediates are unchanged. It is only executed by an
+//! [Interpreter](crate::Interpreter) with [Interpreter::set_fused_bytecode]; the contract
+//! keeps the original bytecode for `CODECOPY` and friends.
+//!
+//! [Interpreter::set_fused_bytecode]: crate::Interpreter::set_fused_bytecode
+
+use crate::{opcode, primitives::Bytecode, primitives::Bytes};
+
+/// `PUSH1 x JUMP`.
+pub const PUSH1_JUMP: u8 = 0xB0;
+/// `PUSH1 x JUMPI`.
+pub const PUSH1_JUMPI: u8 = 0xB1;
+/// `PUSH1 x PUSH1 y`.
+pub const PUSH1_PUSH1: u8 = 0xB2;
+/// `PUSH1 x ADD`.
+pub const PUSH1_ADD: u8 = 0xB3;
+/// `DUPn SWAPm`, where the byte of the `SWAPm` is replaced by `(n - 1) << 4 | (m - 1)`.
+pub const DUP_SWAP: u8 = 0xB4;
+
+/// Opcode replacing the internal opcodes found in the original bytecode.
+///
+/// Those bytes are undefined opcodes that must still halt, and this one is unused too but
+/// has no fused handler.
+pub const UNDEFINED: u8 = 0xBF;
+
+/// Returns whether `opcode` is one of the internal opcodes of fused sequences.
+#[inline]
+pub const fn is_fused(opcode: u8) -> bool {
+    matches!(
+        opcode,
+        PUSH1_JUMP | PUSH1_JUMPI | PUSH1_PUSH1 | PUSH1_ADD | DUP_SWAP
+    )
+}
+
+/// Returns the bytecode of `bytecode` with fused instructions, if it is analysed legacy
+/// bytecode.
+pub fn fuse(bytecode: &Bytecode) -> Option<Bytes> {
+    let Bytecode::LegacyAnalyzed(legacy) = bytecode else {
+        return None;
+    };
+    let code = legacy.bytecode();
+    let len = legacy.original_len();
+    let mut fused = code.to_vec();
+    let mut i = 0;
+    while i < len {
+        let op = code[i];
+        match op {
+            opcode::PUSH1 if i + 2 < len => {
+                fused[i] = match code[i + 2] {
+                    opcode::JUMP => PUSH1_JUMP,
+                    opcode::JUMPI => PUSH1_JUMPI,
+                    opcode::PUSH1 => PUSH1_PUSH1,
+                    opcode::ADD => PUSH1_ADD,
+                    _ => op,
+                };
+            }
+            opcode::DUP1..=opcode::DUP16 if i + 1 < len => {
+                if let swap @ opcode::SWAP1..=opcode::SWAP16 = code[i + 1] {
+                    fused[i] = DUP_SWAP;
+                    fused[i + 1] = (op - opcode::DUP1) << 4 | (swap - opcode::SWAP1);
+                    i += 2;
+                    continue;
+                }
+            }
+            _ if is_fused(op) => fused[i] = UNDEFINED,
+            _ => {}
+        }
+        i += 1;
+        if (opcode::PUSH1..=opcode::PUSH32).contains(&op) {
+            i += (op - opcode::PUSH0) as usize;
+        }
+    }
+    Some(fused.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::to_analysed,
+        opcode::*,
+        primitives::{DefaultEthereumWiring, SpecId},
+        DummyHost, Interpreter, OPCODE_INFO_JUMPTABLE,
+    };
+
+    #[test]
+    fn internal_opcodes_are_unused() {
+        for op in [
+            PUSH1_JUMP,
+            PUSH1_JUMPI,
+            PUSH1_PUSH1,
+            PUSH1_ADD,
+            DUP_SWAP,
+            UNDEFINED,
+        ] {
+            assert!(OPCODE_INFO_JUMPTABLE[op as usize].is_none(), "{op:#x}");
+        }
+        assert!(!is_fused(UNDEFINED));
+    }
+
+    #[test]
+    fn fuses_sequences() {
+        let code = [
+            PUSH1, 0x05, JUMP, JUMPDEST, PUSH1, 0x01, PUSH1, 0x02, ADD, DUP2, SWAP3, PUSH1, JUMP,
+            PUSH1_JUMP, PUSH2, DUP1, SWAP1, PUSH1, 0x00, JUMPI,
+        ];
+        let bytecode = to_analysed(Bytecode::new_raw(Bytes::copy_from_slice(&code)));
+        let fused = fuse(&bytecode).unwrap();
+        assert_eq!(fused.len(), bytecode.bytecode().len());
+        assert_eq!(
+            fused[..code.len()],
+            [
+                PUSH1_JUMP,
+                0x05,
+                JUMP,
+                JUMPDEST,
+                PUSH1_PUSH1,
+                0x01,
+                PUSH1_ADD,
+                0x02,
+                ADD,
+                DUP_SWAP,
+                0x12,
+                PUSH1,
+                JUMP,
+                UNDEFINED,
+                PUSH2,
+                DUP1,
+                SWAP1,
+                PUSH1_JUMPI,
+                0x00,
+                JUMPI,
+            ]
+        );
+
+        assert_eq!(
+            fuse(&Bytecode::new_raw(Bytes::copy_from_slice(&code))),
+            None
+        );
+    }
+
+    /// Executes `code` with or without fusion, and returns everything observable.
+    fn execute(code: &[u8], gas_limit: u64, stack_limit: usize, fused: bool) -> impl PartialEq {
+        let bytecode = to_analysed(Bytecode::new_raw(Bytes::copy_from_slice(code)));
+        let mut interp = Interpreter::new_standalone(bytecode.clone(), Bytes::new(), gas_limit)
+            .with_stack_limit(stack_limit);
+        if fused {
+            interp.set_fused_bytecode(fuse(&bytecode).unwrap());
+        }
+        let mut host = DummyHost::<DefaultEthereumWiring>::default();
+        let action = interp.run_standalone(SpecId::CANCUN, &mut host);
+        (
+            action,
+            interp.gas,
+            interp.stack.data().to_vec(),
+            interp.halt_location(0),
+            host.storage,
+        )
+    }
+
+    #[test]
+    fn fused_execution_matches() {
+        // Opcodes of the fused sequences, and a few others to mix them.
+        const OPCODES: [u8; 20] = [
+            PUSH1, PUSH1, PUSH1, PUSH1, JUMP, JUMPI, JUMPDEST, JUMPDEST, ADD, DUP1, DUP3, SWAP1,
+            SWAP2, POP, SSTORE, PUSH1_JUMP, DUP_SWAP, GAS, PUSH0, ISZERO,
+        ];
+        let mut seed = 0x2545_f491_4f6c_dd1d_u64;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        for _ in 0..20_000 {
+            let len = 4 + next() as usize % 60;
+            let mut code = Vec::with_capacity(len + 1);
+            while code.len() < len {
+                let op = OPCODES[next() as usize % OPCODES.len()];
+                code.push(op);
+                if op == PUSH1 {
+                    code.push(next() as u8 % (len as u8 + 2));
+                }
+            }
+            let gas_limit = next() % 300;
+            let stack_limit = 1 + next() as usize % 8;
+            assert!(
+                execute(&code, gas_limit, stack_limit, false)
+                    == execute(&code, gas_limit, stack_limit, true),
+                "code {code:02x?}, gas limit {gas_limit}, stack limit {stack_limit}"
+            );
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use super::SharedMemory;
+use crate::primitives::Bytes;
+
+/// Part of the memory captured by a [MemorySnapshot].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MemorySnapshotMode {
+    /// The whole memory of the frame.
+    #[default]
+    Full,
+    /// At most the given number of bytes at the start of the memory.
+    Prefix(usize),
+    /// The smallest range containing the bytes written since the previous snapshot of the
+    /// frame, see [SharedMemory::touched].
+    Touched,
+}
+
+/// Memory of a frame captured before an instruction is executed.
+///
+/// The data is reference counted, so snapshots can be kept by tracers without copying the
+/// memory again.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MemorySnapshot {
+    /// Offset of the captured bytes in the memory.
+    pub offset: usize,
+    /// Captured bytes.
+    pub data: Bytes,
+    /// Size of the memory, larger than the captured bytes if they are truncated.
+    pub memory_size: usize,
+}
+
+impl MemorySnapshot {
+    /// Captures the part of the current context of `memory` selected by `mode`.
+    ///
+    /// The touched range of the memory is reset in every mode, so that the next
+    /// [MemorySnapshotMode::Touched] snapshot only contains the bytes written after this one.
+    pub fn capture(memory: &mut SharedMemory, mode: MemorySnapshotMode) -> Self {
+        let touched = memory.take_touched();
+        let memory_size = memory.len();
+        let range = match mode {
+            MemorySnapshotMode::Full => 0..memory_size,
+            MemorySnapshotMode::Prefix(size) => 0..size.min(memory_size),
+            MemorySnapshotMode::Touched => touched.unwrap_or_default(),
+        };
+        Self {
+            offset: range.start,
+            data: Bytes::copy_from_slice(memory.slice_range(range)),
+            memory_size,
+        }
+    }
+
+    /// Returns whether the snapshot contains the whole memory.
+    #[inline]
+    pub fn is_full(&self) -> bool {
+        self.offset == 0 && self.data.len() == self.memory_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capture_modes() {
+        let mut memory = SharedMemory::new();
+        memory.new_context();
+        memory.resize(64);
+        memory.set(0, &[1, 2, 3]);
+
+        let full = MemorySnapshot::capture(&mut memory, MemorySnapshotMode::Full);
+        assert!(full.is_full());
+        assert_eq!(full.data.len(), 64);
+
+        memory.set(40, &[4, 5]);
+        let prefix = MemorySnapshot::capture(&mut memory, MemorySnapshotMode::Prefix(2));
+        assert_eq!((prefix.offset, &prefix.data[..]), (0, &[1, 2][..]));
+        assert_eq!(prefix.memory_size, 64);
+        assert!(!prefix.is_full());
+
+        // The write before the prefix snapshot is not reported again.
+        let touched = MemorySnapshot::capture(&mut memory, MemorySnapshotMode::Touched);
+        assert!(touched.data.is_empty());
+        memory.set(40, &[6, 7]);
+        let touched = MemorySnapshot::capture(&mut memory, MemorySnapshotMode::Touched);
+        assert_eq!((touched.offset, &touched.data[..]), (40, &[6, 7][..]));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::Interpreter;
+
+/// Hook called by [Interpreter::run_observed] before every instruction.
+///
+/// Unlike an inspector, which wraps every instruction of the table in a dynamically
+/// dispatched closure, the observer is a type parameter of the interpreter loop. The no-op
+/// observer `()` is compiled out, and a counter only costs the increment.
+pub trait OpcodeObserver {
+    /// Called before the instruction `opcode` is executed, with the instruction pointer still
+    /// pointing to it.
+    fn step(&mut self, interpreter: &Interpreter, opcode: u8);
+}
+
+impl OpcodeObserver for () {
+    #[inline(always)]
+    fn step(&mut self, _interpreter: &Interpreter, _opcode: u8) {}
+}
+
+impl<O: OpcodeObserver + ?Sized> OpcodeObserver for &mut O {
+    #[inline]
+    fn step(&mut self, interpreter: &Interpreter, opcode: u8) {
+        (**self).step(interpreter, opcode)
+    }
+}
+
+/// [OpcodeObserver] counting the executed instructions by opcode.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OpcodeCounter {
+    counts: [u64; 256],
+}
+
+impl Default for OpcodeCounter {
+    fn default() -> Self {
+        Self { counts: [0; 256] }
+    }
+}
+
+impl OpcodeCounter {
+    /// Returns the number of executed instructions of `opcode`.
+    #[inline]
+    pub fn count(&self, opcode: u8) -> u64 {
+        self.counts[opcode as usize]
+    }
+
+    /// Returns the number of executed instructions, indexed by opcode.
+    #[inline]
+    pub fn counts(&self) -> &[u64; 256] {
+        &self.counts
+    }
+
+    /// Returns the total number of executed instructions.
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Resets the counts to zero.
+    pub fn clear(&mut self) {
+        self.counts = [0; 256];
+    }
+}
+
+impl OpcodeObserver for OpcodeCounter {
+    #[inline]
+    fn step(&mut self, _interpreter: &Interpreter, opcode: u8) {
+        self.counts[opcode as usize] += 1;
+    }
+}
@@ -13,6 +13,7 @@ struct InterpreterSerde<'a> {
     contract: &'a Contract,
     instruction_result: InstructionResult,
     bytecode: &'a Bytes,
+    is_fused: bool,
     is_eof: bool,
     is_eof_init: bool,
     shared_memory: &'a SharedMemory,
@@ -31,6 +32,8 @@ struct InterpreterDe {
     contract: Contract,
     instruction_result: InstructionResult,
     bytecode: Bytes,
+    #[serde(default)]
+    is_fused: bool,
     is_eof: bool,
     is_eof_init: bool,
     shared_memory: SharedMemory,
@@ -52,6 +55,7 @@ impl Serialize for Interpreter {
             contract: &self.contract,
             instruction_result: self.instruction_result,
             bytecode: &self.bytecode,
+            is_fused: self.is_fused,
             is_eof: self.is_eof,
             is_eof_init: self.is_eof_init,
             shared_memory: &self.shared_memory,
@@ -76,6 +80,7 @@ impl<'de> Deserialize<'de> for Interpreter {
             contract,
             instruction_result,
             bytecode,
+            is_fused,
             is_eof,
             is_eof_init,
             shared_memory,
@@ -100,9 +105,11 @@ impl<'de> Deserialize<'de> for Interpreter {
             contract,
             instruction_result,
             bytecode,
+            is_fused,
             is_eof,
             is_eof_init,
             shared_memory,
+            memory_snapshot: None,
             stack,
             function_stack,
             return_data_buffer,
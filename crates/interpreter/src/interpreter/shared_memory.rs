@@ -16,6 +16,13 @@ pub struct SharedMemory {
     checkpoints: Vec<usize>,
     /// Invariant: equals `self.checkpoints.last()`
     last_checkpoint: usize,
+    /// Range of the buffer written in the current context since the last
+    /// [`take_touched`](Self::take_touched).
+    #[cfg_attr(feature = "serde", serde(skip))]
+    touched: Option<Range<usize>>,
+    /// Touched ranges of the parent contexts.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    touched_checkpoints: Vec<Option<Range<usize>>>,
     /// Memory limit. See [`CfgEnv`](revm_primitives::CfgEnv).
     #[cfg(feature = "memory_limit")]
     memory_limit: u64,
@@ -28,6 +35,8 @@ pub const EMPTY_SHARED_MEMORY: SharedMemory = SharedMemory {
     buffer: Vec::new(),
     checkpoints: Vec::new(),
     last_checkpoint: 0,
+    touched: None,
+    touched_checkpoints: Vec::new(),
     #[cfg(feature = "memory_limit")]
     memory_limit: u64::MAX,
 };
@@ -67,6 +76,8 @@ impl SharedMemory {
             buffer: Vec::with_capacity(capacity),
             checkpoints: Vec::with_capacity(32),
             last_checkpoint: 0,
+            touched: None,
+            touched_checkpoints: Vec::with_capacity(32),
             #[cfg(feature = "memory_limit")]
             memory_limit: u64::MAX,
         }
@@ -99,6 +110,7 @@ impl SharedMemory {
         let new_checkpoint = self.buffer.len();
         self.checkpoints.push(new_checkpoint);
         self.last_checkpoint = new_checkpoint;
+        self.touched_checkpoints.push(self.touched.take());
     }
 
     /// Prepares the shared memory for returning to the previous context.
@@ -106,6 +118,7 @@ impl SharedMemory {
     pub fn free_context(&mut self) {
         if let Some(old_checkpoint) = self.checkpoints.pop() {
             self.last_checkpoint = self.checkpoints.last().cloned().unwrap_or_default();
+            self.touched = self.touched_checkpoints.pop().flatten();
             // SAFETY: buffer length is less than or equal `old_checkpoint`
             unsafe { self.buffer.set_len(old_checkpoint) };
         }
@@ -132,9 +145,44 @@ impl SharedMemory {
     /// Resizes the memory in-place so that `len` is equal to `new_len`.
     #[inline]
     pub fn resize(&mut self, new_size: usize) {
+        self.touch(self.len(), new_size);
         self.buffer.resize(self.last_checkpoint + new_size, 0);
     }
 
+    /// Returns the range of the current context memory written since the last
+    /// [`take_touched`](Self::take_touched), or since the context was created.
+    ///
+    /// Expansions of the memory count as writes of zeroes.
+    #[inline]
+    pub fn touched(&self) -> Option<Range<usize>> {
+        let touched = self.touched.as_ref()?;
+        let len = self.len();
+        let start = (touched.start - self.last_checkpoint).min(len);
+        let end = (touched.end - self.last_checkpoint).min(len);
+        (start < end).then_some(start..end)
+    }
+
+    /// Returns the [touched](Self::touched) range and resets it.
+    #[inline]
+    pub fn take_touched(&mut self) -> Option<Range<usize>> {
+        let touched = self.touched();
+        self.touched = None;
+        touched
+    }
+
+    /// Extends the touched range with the `start..end` range of the current context memory.
+    #[inline]
+    fn touch(&mut self, start: usize, end: usize) {
+        if start >= end {
+            return;
+        }
+        let (start, end) = (self.last_checkpoint + start, self.last_checkpoint + end);
+        self.touched = Some(match self.touched.take() {
+            Some(touched) => touched.start.min(start)..touched.end.max(end),
+            None => start..end,
+        });
+    }
+
     /// Returns a byte slice of the memory region at the given offset.
     ///
     /// # Panics
@@ -169,7 +217,9 @@ impl SharedMemory {
     #[cfg_attr(debug_assertions, track_caller)]
     pub fn slice_mut(&mut self, offset: usize, size: usize) -> &mut [u8] {
         let end = offset + size;
-        match self.context_memory_mut().get_mut(offset..end) {
+        self.touch(offset, end);
+        let checkpoint = self.last_checkpoint;
+        match self.buffer.get_mut(checkpoint + offset..checkpoint + end) {
             Some(slice) => slice,
             None => debug_unreachable!("slice OOB: {offset}..{end}"),
         }
@@ -251,6 +301,20 @@ impl SharedMemory {
         }
     }
 
+    /// Set at most `len` bytes of `value` at given `offset`, leaving the rest of the region
+    /// untouched if `value` is shorter. Returns the number of set bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics on out of bounds.
+    #[inline]
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn set_truncated(&mut self, offset: usize, len: usize, value: &[u8]) -> usize {
+        let len = min(len, value.len());
+        self.set(offset, &value[..len]);
+        len
+    }
+
     /// Set memory from data. Our memory offset+len is expected to be correct but we
     /// are doing bound checks on data/data_offeset/len and zeroing parts that is not copied.
     ///
@@ -286,7 +350,9 @@ impl SharedMemory {
     #[inline]
     #[cfg_attr(debug_assertions, track_caller)]
     pub fn copy(&mut self, dst: usize, src: usize, len: usize) {
-        self.context_memory_mut().copy_within(src..src + len, dst);
+        self.touch(dst, dst + len);
+        let checkpoint = self.last_checkpoint;
+        self.buffer[checkpoint..].copy_within(src..src + len, dst);
     }
 
     /// Returns a reference to the memory of the current context, the active memory.
@@ -300,8 +366,11 @@ impl SharedMemory {
     }
 
     /// Returns a mutable reference to the memory of the current context.
+    ///
+    /// The whole memory of the context is marked as [touched](Self::touched).
     #[inline]
     pub fn context_memory_mut(&mut self) -> &mut [u8] {
+        self.touch(0, self.len());
         let buf_len = self.buffer.len();
         // SAFETY: access bounded by buffer length
         unsafe { self.buffer.get_unchecked_mut(self.last_checkpoint..buf_len) }
@@ -404,4 +473,28 @@ mod tests {
         assert_eq!(shared_memory.len(), 64);
         assert_eq!(shared_memory.buffer.get(0..64), Some(&[0_u8; 64] as &[u8]));
     }
+
+    #[test]
+    fn touched() {
+        let mut shared_memory = SharedMemory::new();
+        shared_memory.new_context();
+        shared_memory.resize(96);
+        assert_eq!(shared_memory.take_touched(), Some(0..96));
+        assert_eq!(shared_memory.touched(), None);
+
+        shared_memory.set_byte(40, 1);
+        shared_memory.copy(64, 0, 8);
+        assert_eq!(shared_memory.touched(), Some(40..72));
+
+        // Writes of a child context are tracked separately.
+        shared_memory.new_context();
+        shared_memory.resize(32);
+        shared_memory.set_word(0, &B256::ZERO);
+        assert_eq!(shared_memory.take_touched(), Some(0..32));
+        shared_memory.free_context();
+        assert_eq!(shared_memory.take_touched(), Some(40..72));
+
+        shared_memory.set_data(10, 0, 4, &[1, 2]);
+        assert_eq!(shared_memory.take_touched(), Some(10..14));
+    }
 }
@@ -2,24 +2,107 @@ use crate::{
     primitives::{B256, U256},
     InstructionResult,
 };
-use core::{fmt, ptr};
-use std::vec::Vec;
-
-/// EVM interpreter stack limit.
-pub const STACK_LIMIT: usize = 1024;
-
-/// EVM stack with [STACK_LIMIT] capacity of words.
-#[derive(Debug, PartialEq, Eq, Hash)]
-#[cfg_attr(feature = "serde", derive(serde::Serialize))]
-pub struct Stack {
-    /// The underlying data of the stack.
-    data: Vec<U256>,
+use core::{
+    fmt,
+    hash::{Hash, Hasher},
+    iter::Rev,
+    mem::MaybeUninit,
+    ptr, slice,
+};
+use std::{boxed::Box, vec::Vec};
+
+pub use crate::primitives::STACK_LIMIT;
+
+/// Error of a [Stack] operation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StackError {
+    /// The stack does not have enough words.
+    Underflow,
+    /// The operation would exceed the stack limit.
+    Overflow,
+}
+
+impl fmt::Display for StackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Underflow => f.write_str("stack underflow"),
+            Self::Overflow => f.write_str("stack overflow"),
+        }
+    }
+}
+
+impl core::error::Error for StackError {}
+
+impl From<StackError> for InstructionResult {
+    #[inline]
+    fn from(error: StackError) -> Self {
+        match error {
+            StackError::Underflow => Self::StackUnderflow,
+            StackError::Overflow => Self::StackOverflow,
+        }
+    }
+}
+
+/// EVM stack with a capacity of `CAP` words, [STACK_LIMIT] by default.
+///
+/// The words are stored in a buffer that is allocated once. The stack caches pointers to the
+/// slot above its topmost word and to the slot at its limit, so that the hot paths compare and
+/// move pointers instead of indexing. The `*_unsafe` and `*_unchecked` methods skip the bounds
+/// checks, for callers that validated them ahead, for example once per basic block.
+pub struct Stack<const CAP: usize = STACK_LIMIT> {
+    /// First slot of the buffer of `CAP` words.
+    base: *mut U256,
+    /// Slot above the topmost word. The words below it are initialized.
+    top: *mut U256,
+    /// Slot at the limit of the stack, at most `CAP` words above `base`.
+    end: *mut U256,
+}
+
+// SAFETY: The stack owns its buffer, the pointers are not shared.
+unsafe impl<const CAP: usize> Send for Stack<CAP> {}
+// SAFETY: The buffer is only mutated through `&mut self`.
+unsafe impl<const CAP: usize> Sync for Stack<CAP> {}
+
+impl<const CAP: usize> Drop for Stack<CAP> {
+    fn drop(&mut self) {
+        // SAFETY: `base` is the buffer of `CAP` words allocated in `Stack::default`.
+        unsafe {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(
+                self.base.cast::<MaybeUninit<U256>>(),
+                CAP,
+            )));
+        }
+    }
 }
 
-impl fmt::Display for Stack {
+impl<const CAP: usize> fmt::Debug for Stack<CAP> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Stack")
+            .field("data", &self.as_slice())
+            .field("limit", &self.limit())
+            .finish()
+    }
+}
+
+impl<const CAP: usize> PartialEq for Stack<CAP> {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice() && self.limit() == other.limit()
+    }
+}
+
+impl<const CAP: usize> Eq for Stack<CAP> {}
+
+impl<const CAP: usize> Hash for Stack<CAP> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_slice().hash(state);
+        self.limit().hash(state);
+    }
+}
+
+impl<const CAP: usize> fmt::Display for Stack<CAP> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str("[")?;
-        for (i, x) in self.data.iter().enumerate() {
+        for (i, x) in self.as_slice().iter().enumerate() {
             if i > 0 {
                 f.write_str(", ")?;
             }
@@ -29,10 +112,17 @@ impl fmt::Display for Stack {
     }
 }
 
-impl Default for Stack {
+impl<const CAP: usize> Default for Stack<CAP> {
+    /// Creates an empty stack whose limit is its capacity.
     #[inline]
     fn default() -> Self {
-        Self::new()
+        let base = Box::into_raw(Box::<[U256]>::new_uninit_slice(CAP)).cast::<U256>();
+        Self {
+            base,
+            top: base,
+            // SAFETY: The buffer has `CAP` words.
+            end: unsafe { base.add(CAP) },
+        }
     }
 }
 
@@ -40,47 +130,93 @@ impl Stack {
     /// Instantiate a new stack with the [default stack limit][STACK_LIMIT].
     #[inline]
     pub fn new() -> Self {
-        Self {
-            // SAFETY: expansion functions assume that capacity is `STACK_LIMIT`.
-            data: Vec::with_capacity(STACK_LIMIT),
-        }
+        Self::default()
+    }
+}
+
+impl<const CAP: usize> Stack<CAP> {
+    /// Returns the maximum number of words of the stack.
+    #[inline]
+    pub fn limit(&self) -> usize {
+        // SAFETY: `end` is in the buffer, at or above `base`.
+        unsafe { self.end.offset_from(self.base) as usize }
+    }
+
+    /// Sets the maximum number of words of the stack, capped to its capacity.
+    ///
+    /// Words that are already on the stack are kept even if they exceed the new limit.
+    #[inline]
+    pub fn set_limit(&mut self, limit: usize) {
+        // SAFETY: The limit is capped to the capacity of the buffer.
+        self.end = unsafe { self.base.add(limit.min(CAP)) };
     }
 
     /// Returns the length of the stack in words.
     #[inline]
     pub fn len(&self) -> usize {
-        self.data.len()
+        // SAFETY: `top` is in the buffer, at or above `base`.
+        unsafe { self.top.offset_from(self.base) as usize }
     }
 
     /// Returns whether the stack is empty.
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.data.is_empty()
+        self.top == self.base
+    }
+
+    /// Returns the words of the stack, from the bottom to the top.
+    #[inline]
+    pub fn data(&self) -> &[U256] {
+        self.as_slice()
+    }
+
+    /// Returns the words of the stack, from the bottom to the top.
+    #[inline]
+    pub fn as_slice(&self) -> &[U256] {
+        // SAFETY: The words below `top` are initialized.
+        unsafe { slice::from_raw_parts(self.base, self.len()) }
+    }
+
+    /// Returns the `n` topmost words of the stack, from the bottom to the top, without removing
+    /// them.
+    #[inline]
+    pub fn top_n(&self, n: usize) -> Result<&[U256], StackError> {
+        let data = self.as_slice();
+        let len = data.len();
+        if n > len {
+            return Err(StackError::Underflow);
+        }
+        Ok(&data[len - n..])
     }
 
-    /// Returns a reference to the underlying data buffer.
+    /// Returns an iterator over the words of the stack, from the top to the bottom.
     #[inline]
-    pub fn data(&self) -> &Vec<U256> {
-        &self.data
+    pub fn iter(&self) -> Rev<slice::Iter<'_, U256>> {
+        self.as_slice().iter().rev()
     }
 
-    /// Returns a mutable reference to the underlying data buffer.
+    /// Returns the words of the stack mutably, from the bottom to the top.
     #[inline]
-    pub fn data_mut(&mut self) -> &mut Vec<U256> {
-        &mut self.data
+    pub fn data_mut(&mut self) -> &mut [U256] {
+        // SAFETY: The words below `top` are initialized.
+        unsafe { slice::from_raw_parts_mut(self.base, self.len()) }
     }
 
-    /// Consumes the stack and returns the underlying data buffer.
+    /// Consumes the stack and returns its words, from the bottom to the top.
     #[inline]
     pub fn into_data(self) -> Vec<U256> {
-        self.data
+        self.as_slice().to_vec()
     }
 
     /// Removes the topmost element from the stack and returns it, or `StackUnderflow` if it is
     /// empty.
     #[inline]
     pub fn pop(&mut self) -> Result<U256, InstructionResult> {
-        self.data.pop().ok_or(InstructionResult::StackUnderflow)
+        if self.is_empty() {
+            return Err(InstructionResult::StackUnderflow);
+        }
+        // SAFETY: The stack is not empty.
+        Ok(unsafe { self.pop_unsafe() })
     }
 
     /// Removes the topmost element from the stack and returns it.
@@ -90,7 +226,8 @@ impl Stack {
     /// The caller is responsible for checking the length of the stack.
     #[inline]
     pub unsafe fn pop_unsafe(&mut self) -> U256 {
-        self.data.pop().unwrap_unchecked()
+        self.top = self.top.sub(1);
+        self.top.read()
     }
 
     /// Peeks the top of the stack.
@@ -100,8 +237,7 @@ impl Stack {
     /// The caller is responsible for checking the length of the stack.
     #[inline]
     pub unsafe fn top_unsafe(&mut self) -> &mut U256 {
-        let len = self.data.len();
-        self.data.get_unchecked_mut(len - 1)
+        &mut *self.top.sub(1)
     }
 
     /// Pop the topmost value, returning the value and the new topmost value.
@@ -200,22 +336,40 @@ impl Stack {
     /// unchanged.
     #[inline]
     pub fn push(&mut self, value: U256) -> Result<(), InstructionResult> {
-        // Allows the compiler to optimize out the `Vec::push` capacity check.
-        assume!(self.data.capacity() == STACK_LIMIT);
-        if self.data.len() == STACK_LIMIT {
-            return Err(InstructionResult::StackOverflow);
+        self.try_push(value).map_err(Into::into)
+    }
+
+    /// Push a new value onto the stack, or returns [StackError::Overflow] and leaves the stack
+    /// unchanged if it would exceed the stack limit.
+    #[inline]
+    pub fn try_push(&mut self, value: U256) -> Result<(), StackError> {
+        if self.top >= self.end {
+            return Err(StackError::Overflow);
         }
-        self.data.push(value);
+        // SAFETY: The stack is below its limit.
+        unsafe { self.push_unchecked(value) };
         Ok(())
     }
 
+    /// Pushes a new value onto the stack.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for checking that the stack is below its limit.
+    #[inline]
+    pub unsafe fn push_unchecked(&mut self, value: U256) {
+        self.top.write(value);
+        self.top = self.top.add(1);
+    }
+
     /// Peek a value at given index for the stack, where the top of
     /// the stack is at index `0`. If the index is too large,
     /// `StackError::Underflow` is returned.
     #[inline]
     pub fn peek(&self, no_from_top: usize) -> Result<U256, InstructionResult> {
-        if self.data.len() > no_from_top {
-            Ok(self.data[self.data.len() - no_from_top - 1])
+        if self.len() > no_from_top {
+            // SAFETY: The index is below the top.
+            Ok(unsafe { self.top.sub(no_from_top + 1).read() })
         } else {
             Err(InstructionResult::StackUnderflow)
         }
@@ -229,23 +383,42 @@ impl Stack {
     #[inline]
     #[cfg_attr(debug_assertions, track_caller)]
     pub fn dup(&mut self, n: usize) -> Result<(), InstructionResult> {
+        self.try_dup(n).map_err(Into::into)
+    }
+
+    /// Duplicates the `N`th value from the top of the stack, returning a [StackError] if the
+    /// stack has less than `N` words or is full.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    #[inline]
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn try_dup(&mut self, n: usize) -> Result<(), StackError> {
         assume!(n > 0, "attempted to dup 0");
-        let len = self.data.len();
-        if len < n {
-            Err(InstructionResult::StackUnderflow)
-        } else if len + 1 > STACK_LIMIT {
-            Err(InstructionResult::StackOverflow)
+        if self.len() < n {
+            Err(StackError::Underflow)
+        } else if self.top >= self.end {
+            Err(StackError::Overflow)
         } else {
             // SAFETY: check for out of bounds is done above and it makes this safe to do.
-            unsafe {
-                let ptr = self.data.as_mut_ptr().add(len);
-                ptr::copy_nonoverlapping(ptr.sub(n), ptr, 1);
-                self.data.set_len(len + 1);
-            }
+            unsafe { self.dup_unchecked(n) };
             Ok(())
         }
     }
 
+    /// Duplicates the `N`th value from the top of the stack.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for checking that `n` is not 0, that the stack has at least
+    /// `n` words and that it is below its limit.
+    #[inline]
+    pub unsafe fn dup_unchecked(&mut self, n: usize) {
+        ptr::copy_nonoverlapping(self.top.sub(n), self.top, 1);
+        self.top = self.top.add(1);
+    }
+
     /// Swaps the topmost value with the `N`th value from the top.
     ///
     /// # Panics
@@ -257,6 +430,29 @@ impl Stack {
         self.exchange(0, n)
     }
 
+    /// Swaps the topmost value with the `N`th value from the top, returning
+    /// [StackError::Underflow] if the stack has at most `N` words.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is 0.
+    #[inline]
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn try_swap(&mut self, n: usize) -> Result<(), StackError> {
+        self.try_exchange(0, n)
+    }
+
+    /// Swaps the topmost value with the `N`th value from the top.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for checking that `n` is not 0 and that the stack has more
+    /// than `n` words.
+    #[inline]
+    pub unsafe fn swap_unchecked(&mut self, n: usize) {
+        self.exchange_unchecked(0, n);
+    }
+
     /// Exchange two values on the stack.
     ///
     /// `n` is the first index, and the second index is calculated as `n + m`.
@@ -267,24 +463,45 @@ impl Stack {
     #[inline]
     #[cfg_attr(debug_assertions, track_caller)]
     pub fn exchange(&mut self, n: usize, m: usize) -> Result<(), InstructionResult> {
+        self.try_exchange(n, m).map_err(Into::into)
+    }
+
+    /// Exchange two values on the stack, returning [StackError::Underflow] if the second
+    /// index is out of bounds.
+    ///
+    /// `n` is the first index, and the second index is calculated as `n + m`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `m` is zero.
+    #[inline]
+    #[cfg_attr(debug_assertions, track_caller)]
+    pub fn try_exchange(&mut self, n: usize, m: usize) -> Result<(), StackError> {
         assume!(m > 0, "overlapping exchange");
-        let len = self.data.len();
-        let n_m_index = n + m;
-        if n_m_index >= len {
-            return Err(InstructionResult::StackUnderflow);
-        }
-        // SAFETY: `n` and `n_m` are checked to be within bounds, and they don't overlap.
-        unsafe {
-            // NOTE: `ptr::swap_nonoverlapping` is more efficient than `slice::swap` or `ptr::swap`
-            // because it operates under the assumption that the pointers do not overlap,
-            // eliminating an intemediate copy,
-            // which is a condition we know to be true in this context.
-            let top = self.data.as_mut_ptr().add(len - 1);
-            core::ptr::swap_nonoverlapping(top.sub(n), top.sub(n_m_index), 1);
+        if n + m >= self.len() {
+            return Err(StackError::Underflow);
         }
+        // SAFETY: `n` and `n + m` are checked to be within bounds, and they don't overlap.
+        unsafe { self.exchange_unchecked(n, m) };
         Ok(())
     }
 
+    /// Exchange two values on the stack, where the second index is `n + m`.
+    ///
+    /// # Safety
+    ///
+    /// The caller is responsible for checking that `m` is not 0 and that the stack has more
+    /// than `n + m` words.
+    #[inline]
+    pub unsafe fn exchange_unchecked(&mut self, n: usize, m: usize) {
+        // NOTE: `ptr::swap_nonoverlapping` is more efficient than `slice::swap` or `ptr::swap`
+        // because it operates under the assumption that the pointers do not overlap,
+        // eliminating an intemediate copy,
+        // which is a condition we know to be true in this context.
+        let top = self.top.sub(1);
+        ptr::swap_nonoverlapping(top.sub(n), top.sub(n + m), 1);
+    }
+
     /// Pushes an arbitrary length slice of bytes onto the stack, padding the last word with zeros
     /// if necessary.
     #[inline]
@@ -294,15 +511,14 @@ impl Stack {
         }
 
         let n_words = (slice.len() + 31) / 32;
-        let new_len = self.data.len() + n_words;
-        if new_len > STACK_LIMIT {
+        if n_words > self.limit().saturating_sub(self.len()) {
             return Err(InstructionResult::StackOverflow);
         }
 
         // SAFETY: length checked above.
         unsafe {
-            let dst = self.data.as_mut_ptr().add(self.data.len()).cast::<u64>();
-            self.data.set_len(new_len);
+            let dst = self.top.cast::<u64>();
+            self.top = self.top.add(n_words);
 
             let mut i = 0;
 
@@ -355,9 +571,9 @@ impl Stack {
     /// `StackError::Underflow` is returned.
     #[inline]
     pub fn set(&mut self, no_from_top: usize, val: U256) -> Result<(), InstructionResult> {
-        if self.data.len() > no_from_top {
-            let len = self.data.len();
-            self.data[len - no_from_top - 1] = val;
+        if self.len() > no_from_top {
+            // SAFETY: The index is below the top.
+            unsafe { self.top.sub(no_from_top + 1).write(val) };
             Ok(())
         } else {
             Err(InstructionResult::StackUnderflow)
@@ -366,21 +582,39 @@ impl Stack {
 }
 
 #[cfg(feature = "serde")]
-impl<'de> serde::Deserialize<'de> for Stack {
+impl<const CAP: usize> serde::Serialize for Stack<CAP> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Stack", 1)?;
+        state.serialize_field("data", self.as_slice())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const CAP: usize> serde::Deserialize<'de> for Stack<CAP> {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let mut data = Vec::<U256>::deserialize(deserializer)?;
-        if data.len() > STACK_LIMIT {
+        let data = Vec::<U256>::deserialize(deserializer)?;
+        if data.len() > CAP {
             return Err(serde::de::Error::custom(std::format!(
                 "stack size exceeds limit: {} > {}",
                 data.len(),
-                STACK_LIMIT
+                CAP
             )));
         }
-        data.reserve(STACK_LIMIT - data.len());
-        Ok(Self { data })
+        let mut stack = Self::default();
+        for value in data {
+            // SAFETY: The length is checked above.
+            unsafe { stack.push_unchecked(value) };
+        }
+        Ok(stack)
     }
 }
 
@@ -391,10 +625,8 @@ mod tests {
     fn run(f: impl FnOnce(&mut Stack)) {
         let mut stack = Stack::new();
         // fill capacity with non-zero values
-        unsafe {
-            stack.data.set_len(STACK_LIMIT);
-            stack.data.fill(U256::MAX);
-            stack.data.set_len(0);
+        for i in 0..STACK_LIMIT {
+            unsafe { stack.base.add(i).write(U256::MAX) };
         }
         f(&mut stack);
     }
@@ -404,44 +636,240 @@ mod tests {
         // no-op
         run(|stack| {
             stack.push_slice(b"").unwrap();
-            assert_eq!(stack.data, []);
+            assert_eq!(stack.data(), []);
         });
 
         // one word
         run(|stack| {
             stack.push_slice(&[42]).unwrap();
-            assert_eq!(stack.data, [U256::from(42)]);
+            assert_eq!(stack.data(), [U256::from(42)]);
         });
 
         let n = 0x1111_2222_3333_4444_5555_6666_7777_8888_u128;
         run(|stack| {
             stack.push_slice(&n.to_be_bytes()).unwrap();
-            assert_eq!(stack.data, [U256::from(n)]);
+            assert_eq!(stack.data(), [U256::from(n)]);
         });
 
         // more than one word
         run(|stack| {
             let b = [U256::from(n).to_be_bytes::<32>(); 2].concat();
             stack.push_slice(&b).unwrap();
-            assert_eq!(stack.data, [U256::from(n); 2]);
+            assert_eq!(stack.data(), [U256::from(n); 2]);
         });
 
         run(|stack| {
             let b = [&[0; 32][..], &[42u8]].concat();
             stack.push_slice(&b).unwrap();
-            assert_eq!(stack.data, [U256::ZERO, U256::from(42)]);
+            assert_eq!(stack.data(), [U256::ZERO, U256::from(42)]);
         });
 
         run(|stack| {
             let b = [&[0; 32][..], &n.to_be_bytes()].concat();
             stack.push_slice(&b).unwrap();
-            assert_eq!(stack.data, [U256::ZERO, U256::from(n)]);
+            assert_eq!(stack.data(), [U256::ZERO, U256::from(n)]);
         });
 
         run(|stack| {
             let b = [&[0; 64][..], &n.to_be_bytes()].concat();
             stack.push_slice(&b).unwrap();
-            assert_eq!(stack.data, [U256::ZERO, U256::ZERO, U256::from(n)]);
+            assert_eq!(stack.data(), [U256::ZERO, U256::ZERO, U256::from(n)]);
+        });
+    }
+
+    #[test]
+    fn limit() {
+        run(|stack| {
+            stack.set_limit(2);
+            stack.push(U256::from(1)).unwrap();
+            stack.push(U256::from(2)).unwrap();
+            assert_eq!(
+                stack.push(U256::from(3)),
+                Err(InstructionResult::StackOverflow)
+            );
+            assert_eq!(stack.dup(1), Err(InstructionResult::StackOverflow));
+            assert_eq!(
+                stack.push_slice(&[1]),
+                Err(InstructionResult::StackOverflow)
+            );
+            assert_eq!(stack.len(), 2);
+
+            stack.set_limit(STACK_LIMIT + 1);
+            assert_eq!(stack.limit(), STACK_LIMIT);
+        });
+    }
+
+    #[test]
+    fn introspection_and_typed_errors() {
+        run(|stack| {
+            for value in 1..=3 {
+                stack.try_push(U256::from(value)).unwrap();
+            }
+            assert_eq!(stack.as_slice(), [1, 2, 3].map(U256::from));
+            assert_eq!(stack.top_n(2), Ok(&[2, 3].map(U256::from)[..]));
+            assert_eq!(stack.top_n(4), Err(StackError::Underflow));
+            assert!(stack.iter().copied().eq([3, 2, 1].map(U256::from)));
+
+            assert_eq!(stack.try_swap(3), Err(StackError::Underflow));
+            stack.try_swap(2).unwrap();
+            assert_eq!(stack.peek(0), Ok(U256::from(1)));
+            assert_eq!(stack.try_dup(4), Err(StackError::Underflow));
+
+            stack.set_limit(3);
+            assert_eq!(stack.try_dup(1), Err(StackError::Overflow));
+            assert_eq!(stack.try_push(U256::ZERO), Err(StackError::Overflow));
+            assert_eq!(
+                InstructionResult::from(StackError::Overflow),
+                InstructionResult::StackOverflow
+            );
+            assert_eq!(stack.len(), 3);
         });
     }
+
+    /// Vector backed stack, as the stack was implemented before its words were stored behind
+    /// a top pointer.
+    #[derive(Debug, Default)]
+    struct VecStack {
+        data: Vec<U256>,
+        limit: usize,
+    }
+
+    impl VecStack {
+        fn push(&mut self, value: U256) -> Result<(), StackError> {
+            if self.data.len() >= self.limit {
+                return Err(StackError::Overflow);
+            }
+            self.data.push(value);
+            Ok(())
+        }
+
+        fn pop(&mut self) -> Result<U256, StackError> {
+            self.data.pop().ok_or(StackError::Underflow)
+        }
+
+        fn dup(&mut self, n: usize) -> Result<(), StackError> {
+            let len = self.data.len();
+            if len < n {
+                return Err(StackError::Underflow);
+            }
+            self.push(self.data[len - n])
+        }
+
+        fn exchange(&mut self, n: usize, m: usize) -> Result<(), StackError> {
+            let len = self.data.len();
+            if n + m >= len {
+                return Err(StackError::Underflow);
+            }
+            self.data.swap(len - 1 - n, len - 1 - n - m);
+            Ok(())
+        }
+
+        fn push_slice(&mut self, slice: &[u8]) -> Result<(), StackError> {
+            let words = slice.chunks(32).map(U256::from_be_slice);
+            if !slice.is_empty() && self.data.len() + slice.len().div_ceil(32) > self.limit {
+                return Err(StackError::Overflow);
+            }
+            self.data.extend(words);
+            Ok(())
+        }
+
+        fn peek(&self, n: usize) -> Result<U256, StackError> {
+            self.data
+                .iter()
+                .rev()
+                .nth(n)
+                .copied()
+                .ok_or(StackError::Underflow)
+        }
+
+        fn set(&mut self, n: usize, value: U256) -> Result<(), StackError> {
+            let slot = self
+                .data
+                .iter_mut()
+                .rev()
+                .nth(n)
+                .ok_or(StackError::Underflow)?;
+            *slot = value;
+            Ok(())
+        }
+    }
+
+    /// Applies random operations to a stack of capacity `CAP` and to the reference model, with
+    /// the unchecked operations applied when the model succeeds.
+    fn differential<const CAP: usize>(seed: u64, ops: usize) {
+        let mut seed = seed;
+        let mut next = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        let mut stack = Stack::<CAP>::default();
+        let mut model = VecStack {
+            data: Vec::new(),
+            limit: CAP,
+        };
+        for _ in 0..ops {
+            let value = U256::from(next());
+            let n = next() as usize % 18;
+            let m = 1 + next() as usize % 17;
+            let result = match next() % 12 {
+                0 | 1 => stack.try_push(value) == model.push(value),
+                2 => {
+                    if model.push(value).is_ok() {
+                        unsafe { stack.push_unchecked(value) };
+                    }
+                    true
+                }
+                3 => stack.pop() == model.pop().map_err(InstructionResult::from),
+                4 => {
+                    let n = n.max(1);
+                    match model.dup(n) {
+                        Ok(()) => unsafe { stack.dup_unchecked(n) },
+                        Err(error) => assert_eq!(stack.try_dup(n), Err(error)),
+                    }
+                    true
+                }
+                5 => stack.try_dup(n.max(1)) == model.dup(n.max(1)),
+                6 => {
+                    match model.exchange(0, m) {
+                        Ok(()) => unsafe { stack.swap_unchecked(m) },
+                        Err(error) => assert_eq!(stack.try_swap(m), Err(error)),
+                    }
+                    true
+                }
+                7 => stack.try_exchange(n, m) == model.exchange(n, m),
+                8 => {
+                    let len = next() as usize % 80;
+                    let bytes: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+                    stack.push_slice(&bytes)
+                        == model.push_slice(&bytes).map_err(InstructionResult::from)
+                }
+                9 => stack.peek(n) == model.peek(n).map_err(InstructionResult::from),
+                10 => stack.set(n, value) == model.set(n, value).map_err(InstructionResult::from),
+                _ => {
+                    let limit = next() as usize % (CAP + 4);
+                    stack.set_limit(limit);
+                    model.limit = limit.min(CAP);
+                    stack.limit() == model.limit
+                }
+            };
+            assert!(result, "{stack:?} {model:?}");
+            assert_eq!(stack.as_slice(), model.data);
+            assert_eq!(stack.len(), model.data.len());
+            assert_eq!(stack.is_empty(), model.data.is_empty());
+            if let Some(top) = model.data.last() {
+                assert_eq!(unsafe { *stack.top_unsafe() }, *top);
+            }
+        }
+        assert_eq!(stack.into_data(), model.data);
+    }
+
+    #[test]
+    fn matches_vec_stack() {
+        for seed in 1..=200u64 {
+            differential::<8>(seed.wrapping_mul(0x2545_f491_4f6c_dd1d), 500);
+        }
+        differential::<STACK_LIMIT>(0x9e37_79b9_7f4a_7c15, 100_000);
+    }
 }
@@ -1,4 +1,4 @@
-use crate::{Gas, InstructionResult, InterpreterResult};
+use crate::{FrameGasSummary, Gas, InstructionResult, InterpreterResult, SharedMemory};
 use core::ops::Range;
 use revm_primitives::Bytes;
 
@@ -11,11 +11,14 @@ use revm_primitives::Bytes;
 ///
 /// * `result` - The result of the interpreter's execution, including output data and gas usage.
 /// * `memory_offset` - The range in memory where the output data is located.
+/// * `gas_summary` - The gas of the returned frame.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CallOutcome {
     pub result: InterpreterResult,
     pub memory_offset: Range<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_summary: FrameGasSummary,
 }
 
 impl CallOutcome {
@@ -27,8 +30,11 @@ impl CallOutcome {
     ///
     /// * `result` - The result of the interpreter's execution.
     /// * `memory_offset` - The range in memory indicating where the output data is stored.
+    ///
+    /// The gas summary is taken from the gas of the result, without memory expansion.
     pub fn new(result: InterpreterResult, memory_offset: Range<usize>) -> Self {
         Self {
+            gas_summary: FrameGasSummary::new(&result.gas, 0),
             result,
             memory_offset,
         }
@@ -88,4 +94,59 @@ impl CallOutcome {
     pub fn memory_length(&self) -> usize {
         self.memory_offset.len()
     }
+
+    /// Returns the memory range where the output is written, or `None` if no output is written.
+    ///
+    /// The start of an empty memory range is not a valid memory offset and must not be used.
+    pub fn memory_return_range(&self) -> Option<Range<usize>> {
+        (!self.memory_offset.is_empty()).then(|| self.memory_offset.clone())
+    }
+
+    /// Writes the output to the memory range, truncated to the shorter of the two.
+    ///
+    /// Returns the number of written bytes. The memory must have been resized to hold the range
+    /// when the call was made, see [`Interpreter::write_call_output`] otherwise.
+    ///
+    /// [`Interpreter::write_call_output`]: crate::Interpreter::write_call_output
+    pub fn write_output(&self, shared_memory: &mut SharedMemory) -> usize {
+        let Some(range) = self.memory_return_range() else {
+            return 0;
+        };
+        shared_memory.set_truncated(range.start, range.len(), &self.result.output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(output: &'static [u8], memory_offset: Range<usize>) -> CallOutcome {
+        CallOutcome::new(
+            InterpreterResult::new(
+                InstructionResult::Return,
+                Bytes::from_static(output),
+                Gas::new(0),
+            ),
+            memory_offset,
+        )
+    }
+
+    #[test]
+    fn output_is_written_to_memory_range() {
+        let mut shared_memory = SharedMemory::new();
+        shared_memory.resize(32);
+
+        // The output is truncated to the memory range, and the memory range to the output.
+        assert_eq!(
+            outcome(&[1, 2, 3], 4..6).write_output(&mut shared_memory),
+            2
+        );
+        assert_eq!(outcome(&[4], 8..12).write_output(&mut shared_memory), 1);
+        assert_eq!(shared_memory.slice(4, 6), [1, 2, 0, 0, 4, 0]);
+
+        // Empty ranges are never written, whatever their start.
+        let empty = outcome(&[5], usize::MAX..usize::MAX);
+        assert_eq!(empty.memory_return_range(), None);
+        assert_eq!(empty.write_output(&mut shared_memory), 0);
+    }
 }
@@ -1,7 +1,7 @@
 use revm_primitives::Transaction;
 
 pub use crate::primitives::CreateScheme;
-use crate::primitives::{Address, Bytes, TxKind, U256};
+use crate::primitives::{create2_address_from_code, create_address, Address, Bytes, TxKind, U256};
 use std::boxed::Box;
 
 /// Inputs for a create call.
@@ -44,10 +44,10 @@ impl CreateInputs {
     /// Returns the address that this create call will create.
     pub fn created_address(&self, nonce: u64) -> Address {
         match self.scheme {
-            CreateScheme::Create => self.caller.create(nonce),
-            CreateScheme::Create2 { salt } => self
-                .caller
-                .create2_from_code(salt.to_be_bytes(), &self.init_code),
+            CreateScheme::Create => create_address(self.caller, nonce),
+            CreateScheme::Create2 { salt } => {
+                create2_address_from_code(self.caller, salt.into(), &self.init_code)
+            }
         }
     }
 }
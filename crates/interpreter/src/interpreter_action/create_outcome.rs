@@ -1,4 +1,4 @@
-use crate::{Gas, InstructionResult, InterpreterResult};
+use crate::{FrameGasSummary, Gas, InstructionResult, InterpreterResult};
 use revm_primitives::{Address, Bytes};
 
 /// Represents the outcome of a create operation in an interpreter.
@@ -12,6 +12,9 @@ pub struct CreateOutcome {
     pub result: InterpreterResult,
     // An optional address associated with the create operation.
     pub address: Option<Address>,
+    // The gas of the returned frame.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub gas_summary: FrameGasSummary,
 }
 
 impl CreateOutcome {
@@ -24,9 +27,14 @@ impl CreateOutcome {
     ///
     /// # Returns
     ///
-    /// A new `CreateOutcome` instance.
+    /// A new `CreateOutcome` instance, whose gas summary is taken from the gas of the result,
+    /// without memory expansion.
     pub fn new(result: InterpreterResult, address: Option<Address>) -> Self {
-        Self { result, address }
+        Self {
+            gas_summary: FrameGasSummary::new(&result.gas, 0),
+            result,
+            address,
+        }
     }
 
     /// Retrieves a reference to the `InstructionResult` from the `InterpreterResult`.
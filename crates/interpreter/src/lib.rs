@@ -17,7 +17,10 @@ use serde_json as _;
 #[cfg(test)]
 use walkdir as _;
 
+#[cfg(feature = "compiler")]
+pub mod compiler;
 mod function_stack;
+pub mod fuzz;
 pub mod gas;
 mod host;
 mod instruction_result;
@@ -28,14 +31,16 @@ pub mod opcode;
 
 // Reexport primary types.
 pub use function_stack::{FunctionReturnFrame, FunctionStack};
-pub use gas::Gas;
+pub use gas::{FrameGasSummary, Gas};
 pub use host::{
-    AccountLoad, DummyHost, Eip7702CodeLoad, Host, SStoreResult, SelfDestructResult, StateLoad,
+    AccountLoad, DummyHost, Eip7702CodeLoad, Host, HostEnvMut, HostRead, HostWrite, SStoreResult,
+    SelfDestructResult, StateLoad,
 };
 pub use instruction_result::*;
 pub use interpreter::{
-    analysis, num_words, Contract, Interpreter, InterpreterResult, SharedMemory, Stack,
-    EMPTY_SHARED_MEMORY, STACK_LIMIT,
+    analysis, fusion, num_words, Contract, ExecutionBudget, Interpreter, InterpreterResult,
+    MemorySnapshot, MemorySnapshotMode, OpcodeCounter, OpcodeObserver, SharedMemory, Stack,
+    StackError, EMPTY_SHARED_MEMORY, STACK_LIMIT,
 };
 pub use interpreter_action::{
     CallInputs, CallOutcome, CallScheme, CallValue, CreateInputs, CreateOutcome, CreateScheme,
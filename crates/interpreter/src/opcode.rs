@@ -1,5 +1,6 @@
 //! EVM opcode definitions and utilities.
 
+pub mod disasm;
 pub mod eof_printer;
 
 mod tables;
@@ -9,7 +10,12 @@ pub use tables::{
     InstructionTables,
 };
 
-use crate::{instructions::*, primitives::Spec, Host};
+use crate::{
+    gas,
+    instructions::*,
+    primitives::{Spec, SpecId},
+    Host, Interpreter,
+};
 use core::{fmt, ptr::NonNull};
 
 /// An error indicating that an opcode is invalid.
@@ -167,6 +173,32 @@ impl OpCode {
         self.info().io_diff()
     }
 
+    /// Returns the gas charged by the opcode in the latest spec, before any dynamic cost.
+    ///
+    /// See [`OpCodeInfo::static_gas`].
+    #[inline]
+    pub const fn static_gas(&self) -> u64 {
+        self.info().static_gas()
+    }
+
+    /// Returns the spec in which the opcode was introduced.
+    #[inline]
+    pub const fn introduced_in(&self) -> SpecId {
+        self.info().introduced_in()
+    }
+
+    /// Returns true if the opcode is available in `spec_id`.
+    #[inline]
+    pub const fn is_enabled_in(&self, spec_id: SpecId) -> bool {
+        spec_id.is_enabled_in(self.introduced_in())
+    }
+
+    /// Returns true if the opcode stops execution, e.g. `STOP`, `RETURN`, etc.
+    #[inline]
+    pub const fn is_terminating(&self) -> bool {
+        self.info().is_terminating()
+    }
+
     /// Returns the opcode information for the given opcode.
     #[inline]
     pub const fn info_by_op(opcode: u8) -> Option<OpCodeInfo> {
@@ -250,6 +282,10 @@ pub struct OpCodeInfo {
     not_eof: bool,
     /// If the opcode stops execution. aka STOP, RETURN, ..
     terminating: bool,
+    /// Gas charged by the opcode in the latest spec, before any dynamic cost.
+    static_gas: u16,
+    /// Spec in which the opcode was introduced.
+    introduced_in: SpecId,
 }
 
 impl fmt::Debug for OpCodeInfo {
@@ -261,6 +297,8 @@ impl fmt::Debug for OpCodeInfo {
             .field("not_eof", &self.is_disabled_in_eof())
             .field("terminating", &self.is_terminating())
             .field("immediate_size", &self.immediate_size())
+            .field("static_gas", &self.static_gas())
+            .field("introduced_in", &self.introduced_in())
             .finish()
     }
 }
@@ -277,6 +315,8 @@ impl OpCodeInfo {
             not_eof: false,
             terminating: false,
             immediate_size: 0,
+            static_gas: 0,
+            introduced_in: SpecId::FRONTIER,
         }
     }
 
@@ -326,6 +366,25 @@ impl OpCodeInfo {
     pub const fn immediate_size(&self) -> u8 {
         self.immediate_size
     }
+
+    /// Returns the gas charged by the opcode in the latest spec, before any dynamic cost.
+    ///
+    /// This is the minimum gas the opcode charges. Opcodes accessing accounts or storage
+    /// include the warm access cost, while costs such as memory expansion, cold access, copied
+    /// words or call value transfers are dynamic.
+    #[inline]
+    pub const fn static_gas(&self) -> u64 {
+        self.static_gas as u64
+    }
+
+    /// Returns the spec in which the opcode was introduced.
+    ///
+    /// Executing the opcode in an earlier spec halts with
+    /// [`InstructionResult::NotActivated`](crate::InstructionResult::NotActivated).
+    #[inline]
+    pub const fn introduced_in(&self) -> SpecId {
+        self.introduced_in
+    }
 }
 
 /// Sets the EOF flag to false.
@@ -352,6 +411,21 @@ pub const fn terminating(mut op: OpCodeInfo) -> OpCodeInfo {
     op
 }
 
+/// Sets the static gas.
+#[inline]
+pub const fn static_gas(mut op: OpCodeInfo, gas: u64) -> OpCodeInfo {
+    assert!(gas <= u16::MAX as u64, "static gas is too large");
+    op.static_gas = gas as u16;
+    op
+}
+
+/// Sets the spec in which the opcode was introduced.
+#[inline]
+pub const fn introduced_in(mut op: OpCodeInfo, spec_id: SpecId) -> OpCodeInfo {
+    op.introduced_in = spec_id;
+    op
+}
+
 /// Sets the number of stack inputs and outputs.
 #[inline]
 pub const fn stack_io(mut op: OpCodeInfo, inputs: u8, outputs: u8) -> OpCodeInfo {
@@ -423,6 +497,21 @@ macro_rules! opcodes {
                 _ => control::unknown,
             }
         }
+
+        /// Executes the instruction of `opcode` by matching on it, without an instruction table.
+        ///
+        /// See [Interpreter::run_match].
+        #[inline]
+        pub fn eval<H: Host + ?Sized, SPEC: Spec>(
+            opcode: u8,
+            interpreter: &mut Interpreter,
+            host: &mut H,
+        ) {
+            match opcode {
+                $($name => $f(interpreter, host),)*
+                _ => control::unknown(interpreter, host),
+            }
+        }
     };
 }
 
@@ -431,40 +520,40 @@ macro_rules! opcodes {
 // 2. implement the opcode in the corresponding module;
 //    the function signature must be the exact same as the others
 opcodes! {
-    0x00 => STOP => control::stop => stack_io(0, 0), terminating;
-
-    0x01 => ADD        => arithmetic::add            => stack_io(2, 1);
-    0x02 => MUL        => arithmetic::mul            => stack_io(2, 1);
-    0x03 => SUB        => arithmetic::sub            => stack_io(2, 1);
-    0x04 => DIV        => arithmetic::div            => stack_io(2, 1);
-    0x05 => SDIV       => arithmetic::sdiv           => stack_io(2, 1);
-    0x06 => MOD        => arithmetic::rem            => stack_io(2, 1);
-    0x07 => SMOD       => arithmetic::smod           => stack_io(2, 1);
-    0x08 => ADDMOD     => arithmetic::addmod         => stack_io(3, 1);
-    0x09 => MULMOD     => arithmetic::mulmod         => stack_io(3, 1);
-    0x0A => EXP        => arithmetic::exp::<H, SPEC> => stack_io(2, 1);
-    0x0B => SIGNEXTEND => arithmetic::signextend     => stack_io(2, 1);
+    0x00 => STOP => control::stop => stack_io(0, 0), terminating, static_gas(0);
+
+    0x01 => ADD        => arithmetic::add            => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x02 => MUL        => arithmetic::mul            => stack_io(2, 1), static_gas(gas::LOW);
+    0x03 => SUB        => arithmetic::sub            => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x04 => DIV        => arithmetic::div            => stack_io(2, 1), static_gas(gas::LOW);
+    0x05 => SDIV       => arithmetic::sdiv           => stack_io(2, 1), static_gas(gas::LOW);
+    0x06 => MOD        => arithmetic::rem            => stack_io(2, 1), static_gas(gas::LOW);
+    0x07 => SMOD       => arithmetic::smod           => stack_io(2, 1), static_gas(gas::LOW);
+    0x08 => ADDMOD     => arithmetic::addmod         => stack_io(3, 1), static_gas(gas::MID);
+    0x09 => MULMOD     => arithmetic::mulmod         => stack_io(3, 1), static_gas(gas::MID);
+    0x0A => EXP        => arithmetic::exp::<H, SPEC> => stack_io(2, 1), static_gas(gas::EXP);
+    0x0B => SIGNEXTEND => arithmetic::signextend     => stack_io(2, 1), static_gas(gas::LOW);
     // 0x0C
     // 0x0D
     // 0x0E
     // 0x0F
-    0x10 => LT     => bitwise::lt             => stack_io(2, 1);
-    0x11 => GT     => bitwise::gt             => stack_io(2, 1);
-    0x12 => SLT    => bitwise::slt            => stack_io(2, 1);
-    0x13 => SGT    => bitwise::sgt            => stack_io(2, 1);
-    0x14 => EQ     => bitwise::eq             => stack_io(2, 1);
-    0x15 => ISZERO => bitwise::iszero         => stack_io(1, 1);
-    0x16 => AND    => bitwise::bitand         => stack_io(2, 1);
-    0x17 => OR     => bitwise::bitor          => stack_io(2, 1);
-    0x18 => XOR    => bitwise::bitxor         => stack_io(2, 1);
-    0x19 => NOT    => bitwise::not            => stack_io(1, 1);
-    0x1A => BYTE   => bitwise::byte           => stack_io(2, 1);
-    0x1B => SHL    => bitwise::shl::<H, SPEC> => stack_io(2, 1);
-    0x1C => SHR    => bitwise::shr::<H, SPEC> => stack_io(2, 1);
-    0x1D => SAR    => bitwise::sar::<H, SPEC> => stack_io(2, 1);
+    0x10 => LT     => bitwise::lt             => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x11 => GT     => bitwise::gt             => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x12 => SLT    => bitwise::slt            => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x13 => SGT    => bitwise::sgt            => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x14 => EQ     => bitwise::eq             => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x15 => ISZERO => bitwise::iszero         => stack_io(1, 1), static_gas(gas::VERYLOW);
+    0x16 => AND    => bitwise::bitand         => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x17 => OR     => bitwise::bitor          => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x18 => XOR    => bitwise::bitxor         => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x19 => NOT    => bitwise::not            => stack_io(1, 1), static_gas(gas::VERYLOW);
+    0x1A => BYTE   => bitwise::byte           => stack_io(2, 1), static_gas(gas::VERYLOW);
+    0x1B => SHL    => bitwise::shl::<H, SPEC> => stack_io(2, 1), static_gas(gas::VERYLOW), introduced_in(SpecId::CONSTANTINOPLE);
+    0x1C => SHR    => bitwise::shr::<H, SPEC> => stack_io(2, 1), static_gas(gas::VERYLOW), introduced_in(SpecId::CONSTANTINOPLE);
+    0x1D => SAR    => bitwise::sar::<H, SPEC> => stack_io(2, 1), static_gas(gas::VERYLOW), introduced_in(SpecId::CONSTANTINOPLE);
     // 0x1E
     // 0x1F
-    0x20 => KECCAK256 => system::keccak256    => stack_io(2, 1);
+    0x20 => KECCAK256 => system::keccak256    => stack_io(2, 1), static_gas(gas::KECCAK256);
     // 0x21
     // 0x22
     // 0x23
@@ -480,128 +569,128 @@ opcodes! {
     // 0x2D
     // 0x2E
     // 0x2F
-    0x30 => ADDRESS      => system::address          => stack_io(0, 1);
-    0x31 => BALANCE      => host::balance::<H, SPEC> => stack_io(1, 1);
-    0x32 => ORIGIN       => host_env::origin         => stack_io(0, 1);
-    0x33 => CALLER       => system::caller           => stack_io(0, 1);
-    0x34 => CALLVALUE    => system::callvalue        => stack_io(0, 1);
-    0x35 => CALLDATALOAD => system::calldataload     => stack_io(1, 1);
-    0x36 => CALLDATASIZE => system::calldatasize     => stack_io(0, 1);
-    0x37 => CALLDATACOPY => system::calldatacopy     => stack_io(3, 0);
-    0x38 => CODESIZE     => system::codesize         => stack_io(0, 1), not_eof;
-    0x39 => CODECOPY     => system::codecopy         => stack_io(3, 0), not_eof;
-
-    0x3A => GASPRICE       => host_env::gasprice                => stack_io(0, 1);
-    0x3B => EXTCODESIZE    => host::extcodesize::<H, SPEC>      => stack_io(1, 1), not_eof;
-    0x3C => EXTCODECOPY    => host::extcodecopy::<H, SPEC>      => stack_io(4, 0), not_eof;
-    0x3D => RETURNDATASIZE => system::returndatasize::<H, SPEC> => stack_io(0, 1);
-    0x3E => RETURNDATACOPY => system::returndatacopy::<H, SPEC> => stack_io(3, 0);
-    0x3F => EXTCODEHASH    => host::extcodehash::<H, SPEC>      => stack_io(1, 1), not_eof;
-    0x40 => BLOCKHASH      => host::blockhash::<H, SPEC>        => stack_io(1, 1);
-    0x41 => COINBASE       => host_env::coinbase                => stack_io(0, 1);
-    0x42 => TIMESTAMP      => host_env::timestamp               => stack_io(0, 1);
-    0x43 => NUMBER         => host_env::block_number            => stack_io(0, 1);
-    0x44 => DIFFICULTY     => host_env::difficulty::<H, SPEC>   => stack_io(0, 1);
-    0x45 => GASLIMIT       => host_env::gaslimit                => stack_io(0, 1);
-    0x46 => CHAINID        => host_env::chainid::<H, SPEC>      => stack_io(0, 1);
-    0x47 => SELFBALANCE    => host::selfbalance::<H, SPEC>      => stack_io(0, 1);
-    0x48 => BASEFEE        => host_env::basefee::<H, SPEC>      => stack_io(0, 1);
-    0x49 => BLOBHASH       => host_env::blob_hash::<H, SPEC>    => stack_io(1, 1);
-    0x4A => BLOBBASEFEE    => host_env::blob_basefee::<H, SPEC> => stack_io(0, 1);
+    0x30 => ADDRESS      => system::address          => stack_io(0, 1), static_gas(gas::BASE);
+    0x31 => BALANCE      => host::balance::<H, SPEC> => stack_io(1, 1), static_gas(gas::WARM_STORAGE_READ_COST);
+    0x32 => ORIGIN       => host_env::origin         => stack_io(0, 1), static_gas(gas::BASE);
+    0x33 => CALLER       => system::caller           => stack_io(0, 1), static_gas(gas::BASE);
+    0x34 => CALLVALUE    => system::callvalue        => stack_io(0, 1), static_gas(gas::BASE);
+    0x35 => CALLDATALOAD => system::calldataload     => stack_io(1, 1), static_gas(gas::VERYLOW);
+    0x36 => CALLDATASIZE => system::calldatasize     => stack_io(0, 1), static_gas(gas::BASE);
+    0x37 => CALLDATACOPY => system::calldatacopy     => stack_io(3, 0), static_gas(gas::VERYLOW);
+    0x38 => CODESIZE     => system::codesize         => stack_io(0, 1), not_eof, static_gas(gas::BASE);
+    0x39 => CODECOPY     => system::codecopy         => stack_io(3, 0), not_eof, static_gas(gas::VERYLOW);
+
+    0x3A => GASPRICE       => host_env::gasprice                => stack_io(0, 1), static_gas(gas::BASE);
+    0x3B => EXTCODESIZE    => host::extcodesize::<H, SPEC>      => stack_io(1, 1), not_eof, static_gas(gas::WARM_STORAGE_READ_COST);
+    0x3C => EXTCODECOPY    => host::extcodecopy::<H, SPEC>      => stack_io(4, 0), not_eof, static_gas(gas::WARM_STORAGE_READ_COST);
+    0x3D => RETURNDATASIZE => system::returndatasize::<H, SPEC> => stack_io(0, 1), static_gas(gas::BASE), introduced_in(SpecId::BYZANTIUM);
+    0x3E => RETURNDATACOPY => system::returndatacopy::<H, SPEC> => stack_io(3, 0), static_gas(gas::VERYLOW), introduced_in(SpecId::BYZANTIUM);
+    0x3F => EXTCODEHASH    => host::extcodehash::<H, SPEC>      => stack_io(1, 1), not_eof, static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::CONSTANTINOPLE);
+    0x40 => BLOCKHASH      => host::blockhash::<H, SPEC>        => stack_io(1, 1), static_gas(gas::BLOCKHASH);
+    0x41 => COINBASE       => host_env::coinbase                => stack_io(0, 1), static_gas(gas::BASE);
+    0x42 => TIMESTAMP      => host_env::timestamp               => stack_io(0, 1), static_gas(gas::BASE);
+    0x43 => NUMBER         => host_env::block_number            => stack_io(0, 1), static_gas(gas::BASE);
+    0x44 => DIFFICULTY     => host_env::difficulty::<H, SPEC>   => stack_io(0, 1), static_gas(gas::BASE);
+    0x45 => GASLIMIT       => host_env::gaslimit                => stack_io(0, 1), static_gas(gas::BASE);
+    0x46 => CHAINID        => host_env::chainid::<H, SPEC>      => stack_io(0, 1), static_gas(gas::BASE), introduced_in(SpecId::ISTANBUL);
+    0x47 => SELFBALANCE    => host::selfbalance::<H, SPEC>      => stack_io(0, 1), static_gas(gas::LOW), introduced_in(SpecId::ISTANBUL);
+    0x48 => BASEFEE        => host_env::basefee::<H, SPEC>      => stack_io(0, 1), static_gas(gas::BASE), introduced_in(SpecId::LONDON);
+    0x49 => BLOBHASH       => host_env::blob_hash::<H, SPEC>    => stack_io(1, 1), static_gas(gas::VERYLOW), introduced_in(SpecId::CANCUN);
+    0x4A => BLOBBASEFEE    => host_env::blob_basefee::<H, SPEC> => stack_io(0, 1), static_gas(gas::BASE), introduced_in(SpecId::CANCUN);
     // 0x4B
     // 0x4C
     // 0x4D
     // 0x4E
     // 0x4F
-    0x50 => POP      => stack::pop               => stack_io(1, 0);
-    0x51 => MLOAD    => memory::mload            => stack_io(1, 1);
-    0x52 => MSTORE   => memory::mstore           => stack_io(2, 0);
-    0x53 => MSTORE8  => memory::mstore8          => stack_io(2, 0);
-    0x54 => SLOAD    => host::sload::<H, SPEC>   => stack_io(1, 1);
-    0x55 => SSTORE   => host::sstore::<H, SPEC>  => stack_io(2, 0);
-    0x56 => JUMP     => control::jump            => stack_io(1, 0), not_eof;
-    0x57 => JUMPI    => control::jumpi           => stack_io(2, 0), not_eof;
-    0x58 => PC       => control::pc              => stack_io(0, 1), not_eof;
-    0x59 => MSIZE    => memory::msize            => stack_io(0, 1);
-    0x5A => GAS      => system::gas              => stack_io(0, 1), not_eof;
-    0x5B => JUMPDEST => control::jumpdest_or_nop => stack_io(0, 0);
-    0x5C => TLOAD    => host::tload::<H, SPEC>   => stack_io(1, 1);
-    0x5D => TSTORE   => host::tstore::<H, SPEC>  => stack_io(2, 0);
-    0x5E => MCOPY    => memory::mcopy::<H, SPEC> => stack_io(3, 0);
-
-    0x5F => PUSH0  => stack::push0::<H, SPEC> => stack_io(0, 1);
-    0x60 => PUSH1  => stack::push::<1, H>     => stack_io(0, 1), immediate_size(1);
-    0x61 => PUSH2  => stack::push::<2, H>     => stack_io(0, 1), immediate_size(2);
-    0x62 => PUSH3  => stack::push::<3, H>     => stack_io(0, 1), immediate_size(3);
-    0x63 => PUSH4  => stack::push::<4, H>     => stack_io(0, 1), immediate_size(4);
-    0x64 => PUSH5  => stack::push::<5, H>     => stack_io(0, 1), immediate_size(5);
-    0x65 => PUSH6  => stack::push::<6, H>     => stack_io(0, 1), immediate_size(6);
-    0x66 => PUSH7  => stack::push::<7, H>     => stack_io(0, 1), immediate_size(7);
-    0x67 => PUSH8  => stack::push::<8, H>     => stack_io(0, 1), immediate_size(8);
-    0x68 => PUSH9  => stack::push::<9, H>     => stack_io(0, 1), immediate_size(9);
-    0x69 => PUSH10 => stack::push::<10, H>    => stack_io(0, 1), immediate_size(10);
-    0x6A => PUSH11 => stack::push::<11, H>    => stack_io(0, 1), immediate_size(11);
-    0x6B => PUSH12 => stack::push::<12, H>    => stack_io(0, 1), immediate_size(12);
-    0x6C => PUSH13 => stack::push::<13, H>    => stack_io(0, 1), immediate_size(13);
-    0x6D => PUSH14 => stack::push::<14, H>    => stack_io(0, 1), immediate_size(14);
-    0x6E => PUSH15 => stack::push::<15, H>    => stack_io(0, 1), immediate_size(15);
-    0x6F => PUSH16 => stack::push::<16, H>    => stack_io(0, 1), immediate_size(16);
-    0x70 => PUSH17 => stack::push::<17, H>    => stack_io(0, 1), immediate_size(17);
-    0x71 => PUSH18 => stack::push::<18, H>    => stack_io(0, 1), immediate_size(18);
-    0x72 => PUSH19 => stack::push::<19, H>    => stack_io(0, 1), immediate_size(19);
-    0x73 => PUSH20 => stack::push::<20, H>    => stack_io(0, 1), immediate_size(20);
-    0x74 => PUSH21 => stack::push::<21, H>    => stack_io(0, 1), immediate_size(21);
-    0x75 => PUSH22 => stack::push::<22, H>    => stack_io(0, 1), immediate_size(22);
-    0x76 => PUSH23 => stack::push::<23, H>    => stack_io(0, 1), immediate_size(23);
-    0x77 => PUSH24 => stack::push::<24, H>    => stack_io(0, 1), immediate_size(24);
-    0x78 => PUSH25 => stack::push::<25, H>    => stack_io(0, 1), immediate_size(25);
-    0x79 => PUSH26 => stack::push::<26, H>    => stack_io(0, 1), immediate_size(26);
-    0x7A => PUSH27 => stack::push::<27, H>    => stack_io(0, 1), immediate_size(27);
-    0x7B => PUSH28 => stack::push::<28, H>    => stack_io(0, 1), immediate_size(28);
-    0x7C => PUSH29 => stack::push::<29, H>    => stack_io(0, 1), immediate_size(29);
-    0x7D => PUSH30 => stack::push::<30, H>    => stack_io(0, 1), immediate_size(30);
-    0x7E => PUSH31 => stack::push::<31, H>    => stack_io(0, 1), immediate_size(31);
-    0x7F => PUSH32 => stack::push::<32, H>    => stack_io(0, 1), immediate_size(32);
-
-    0x80 => DUP1  => stack::dup::<1, H>  => stack_io(1, 2);
-    0x81 => DUP2  => stack::dup::<2, H>  => stack_io(2, 3);
-    0x82 => DUP3  => stack::dup::<3, H>  => stack_io(3, 4);
-    0x83 => DUP4  => stack::dup::<4, H>  => stack_io(4, 5);
-    0x84 => DUP5  => stack::dup::<5, H>  => stack_io(5, 6);
-    0x85 => DUP6  => stack::dup::<6, H>  => stack_io(6, 7);
-    0x86 => DUP7  => stack::dup::<7, H>  => stack_io(7, 8);
-    0x87 => DUP8  => stack::dup::<8, H>  => stack_io(8, 9);
-    0x88 => DUP9  => stack::dup::<9, H>  => stack_io(9, 10);
-    0x89 => DUP10 => stack::dup::<10, H> => stack_io(10, 11);
-    0x8A => DUP11 => stack::dup::<11, H> => stack_io(11, 12);
-    0x8B => DUP12 => stack::dup::<12, H> => stack_io(12, 13);
-    0x8C => DUP13 => stack::dup::<13, H> => stack_io(13, 14);
-    0x8D => DUP14 => stack::dup::<14, H> => stack_io(14, 15);
-    0x8E => DUP15 => stack::dup::<15, H> => stack_io(15, 16);
-    0x8F => DUP16 => stack::dup::<16, H> => stack_io(16, 17);
-
-    0x90 => SWAP1  => stack::swap::<1, H>  => stack_io(2, 2);
-    0x91 => SWAP2  => stack::swap::<2, H>  => stack_io(3, 3);
-    0x92 => SWAP3  => stack::swap::<3, H>  => stack_io(4, 4);
-    0x93 => SWAP4  => stack::swap::<4, H>  => stack_io(5, 5);
-    0x94 => SWAP5  => stack::swap::<5, H>  => stack_io(6, 6);
-    0x95 => SWAP6  => stack::swap::<6, H>  => stack_io(7, 7);
-    0x96 => SWAP7  => stack::swap::<7, H>  => stack_io(8, 8);
-    0x97 => SWAP8  => stack::swap::<8, H>  => stack_io(9, 9);
-    0x98 => SWAP9  => stack::swap::<9, H>  => stack_io(10, 10);
-    0x99 => SWAP10 => stack::swap::<10, H> => stack_io(11, 11);
-    0x9A => SWAP11 => stack::swap::<11, H> => stack_io(12, 12);
-    0x9B => SWAP12 => stack::swap::<12, H> => stack_io(13, 13);
-    0x9C => SWAP13 => stack::swap::<13, H> => stack_io(14, 14);
-    0x9D => SWAP14 => stack::swap::<14, H> => stack_io(15, 15);
-    0x9E => SWAP15 => stack::swap::<15, H> => stack_io(16, 16);
-    0x9F => SWAP16 => stack::swap::<16, H> => stack_io(17, 17);
-
-    0xA0 => LOG0 => host::log::<0, H> => stack_io(2, 0);
-    0xA1 => LOG1 => host::log::<1, H> => stack_io(3, 0);
-    0xA2 => LOG2 => host::log::<2, H> => stack_io(4, 0);
-    0xA3 => LOG3 => host::log::<3, H> => stack_io(5, 0);
-    0xA4 => LOG4 => host::log::<4, H> => stack_io(6, 0);
+    0x50 => POP      => stack::pop               => stack_io(1, 0), static_gas(gas::BASE);
+    0x51 => MLOAD    => memory::mload            => stack_io(1, 1), static_gas(gas::VERYLOW);
+    0x52 => MSTORE   => memory::mstore           => stack_io(2, 0), static_gas(gas::VERYLOW);
+    0x53 => MSTORE8  => memory::mstore8          => stack_io(2, 0), static_gas(gas::VERYLOW);
+    0x54 => SLOAD    => host::sload::<H, SPEC>   => stack_io(1, 1), static_gas(gas::WARM_STORAGE_READ_COST);
+    0x55 => SSTORE   => host::sstore::<H, SPEC>  => stack_io(2, 0), static_gas(gas::WARM_STORAGE_READ_COST);
+    0x56 => JUMP     => control::jump            => stack_io(1, 0), not_eof, static_gas(gas::MID);
+    0x57 => JUMPI    => control::jumpi           => stack_io(2, 0), not_eof, static_gas(gas::HIGH);
+    0x58 => PC       => control::pc              => stack_io(0, 1), not_eof, static_gas(gas::BASE);
+    0x59 => MSIZE    => memory::msize            => stack_io(0, 1), static_gas(gas::BASE);
+    0x5A => GAS      => system::gas              => stack_io(0, 1), not_eof, static_gas(gas::BASE);
+    0x5B => JUMPDEST => control::jumpdest_or_nop => stack_io(0, 0), static_gas(gas::JUMPDEST);
+    0x5C => TLOAD    => host::tload::<H, SPEC>   => stack_io(1, 1), static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::CANCUN);
+    0x5D => TSTORE   => host::tstore::<H, SPEC>  => stack_io(2, 0), static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::CANCUN);
+    0x5E => MCOPY    => memory::mcopy::<H, SPEC> => stack_io(3, 0), static_gas(gas::VERYLOW), introduced_in(SpecId::CANCUN);
+
+    0x5F => PUSH0  => stack::push0::<H, SPEC> => stack_io(0, 1), static_gas(gas::BASE), introduced_in(SpecId::SHANGHAI);
+    0x60 => PUSH1  => stack::push::<1, H>     => stack_io(0, 1), immediate_size(1), static_gas(gas::VERYLOW);
+    0x61 => PUSH2  => stack::push::<2, H>     => stack_io(0, 1), immediate_size(2), static_gas(gas::VERYLOW);
+    0x62 => PUSH3  => stack::push::<3, H>     => stack_io(0, 1), immediate_size(3), static_gas(gas::VERYLOW);
+    0x63 => PUSH4  => stack::push::<4, H>     => stack_io(0, 1), immediate_size(4), static_gas(gas::VERYLOW);
+    0x64 => PUSH5  => stack::push::<5, H>     => stack_io(0, 1), immediate_size(5), static_gas(gas::VERYLOW);
+    0x65 => PUSH6  => stack::push::<6, H>     => stack_io(0, 1), immediate_size(6), static_gas(gas::VERYLOW);
+    0x66 => PUSH7  => stack::push::<7, H>     => stack_io(0, 1), immediate_size(7), static_gas(gas::VERYLOW);
+    0x67 => PUSH8  => stack::push::<8, H>     => stack_io(0, 1), immediate_size(8), static_gas(gas::VERYLOW);
+    0x68 => PUSH9  => stack::push::<9, H>     => stack_io(0, 1), immediate_size(9), static_gas(gas::VERYLOW);
+    0x69 => PUSH10 => stack::push::<10, H>    => stack_io(0, 1), immediate_size(10), static_gas(gas::VERYLOW);
+    0x6A => PUSH11 => stack::push::<11, H>    => stack_io(0, 1), immediate_size(11), static_gas(gas::VERYLOW);
+    0x6B => PUSH12 => stack::push::<12, H>    => stack_io(0, 1), immediate_size(12), static_gas(gas::VERYLOW);
+    0x6C => PUSH13 => stack::push::<13, H>    => stack_io(0, 1), immediate_size(13), static_gas(gas::VERYLOW);
+    0x6D => PUSH14 => stack::push::<14, H>    => stack_io(0, 1), immediate_size(14), static_gas(gas::VERYLOW);
+    0x6E => PUSH15 => stack::push::<15, H>    => stack_io(0, 1), immediate_size(15), static_gas(gas::VERYLOW);
+    0x6F => PUSH16 => stack::push::<16, H>    => stack_io(0, 1), immediate_size(16), static_gas(gas::VERYLOW);
+    0x70 => PUSH17 => stack::push::<17, H>    => stack_io(0, 1), immediate_size(17), static_gas(gas::VERYLOW);
+    0x71 => PUSH18 => stack::push::<18, H>    => stack_io(0, 1), immediate_size(18), static_gas(gas::VERYLOW);
+    0x72 => PUSH19 => stack::push::<19, H>    => stack_io(0, 1), immediate_size(19), static_gas(gas::VERYLOW);
+    0x73 => PUSH20 => stack::push::<20, H>    => stack_io(0, 1), immediate_size(20), static_gas(gas::VERYLOW);
+    0x74 => PUSH21 => stack::push::<21, H>    => stack_io(0, 1), immediate_size(21), static_gas(gas::VERYLOW);
+    0x75 => PUSH22 => stack::push::<22, H>    => stack_io(0, 1), immediate_size(22), static_gas(gas::VERYLOW);
+    0x76 => PUSH23 => stack::push::<23, H>    => stack_io(0, 1), immediate_size(23), static_gas(gas::VERYLOW);
+    0x77 => PUSH24 => stack::push::<24, H>    => stack_io(0, 1), immediate_size(24), static_gas(gas::VERYLOW);
+    0x78 => PUSH25 => stack::push::<25, H>    => stack_io(0, 1), immediate_size(25), static_gas(gas::VERYLOW);
+    0x79 => PUSH26 => stack::push::<26, H>    => stack_io(0, 1), immediate_size(26), static_gas(gas::VERYLOW);
+    0x7A => PUSH27 => stack::push::<27, H>    => stack_io(0, 1), immediate_size(27), static_gas(gas::VERYLOW);
+    0x7B => PUSH28 => stack::push::<28, H>    => stack_io(0, 1), immediate_size(28), static_gas(gas::VERYLOW);
+    0x7C => PUSH29 => stack::push::<29, H>    => stack_io(0, 1), immediate_size(29), static_gas(gas::VERYLOW);
+    0x7D => PUSH30 => stack::push::<30, H>    => stack_io(0, 1), immediate_size(30), static_gas(gas::VERYLOW);
+    0x7E => PUSH31 => stack::push::<31, H>    => stack_io(0, 1), immediate_size(31), static_gas(gas::VERYLOW);
+    0x7F => PUSH32 => stack::push::<32, H>    => stack_io(0, 1), immediate_size(32), static_gas(gas::VERYLOW);
+
+    0x80 => DUP1  => stack::dup::<1, H>  => stack_io(1, 2), static_gas(gas::VERYLOW);
+    0x81 => DUP2  => stack::dup::<2, H>  => stack_io(2, 3), static_gas(gas::VERYLOW);
+    0x82 => DUP3  => stack::dup::<3, H>  => stack_io(3, 4), static_gas(gas::VERYLOW);
+    0x83 => DUP4  => stack::dup::<4, H>  => stack_io(4, 5), static_gas(gas::VERYLOW);
+    0x84 => DUP5  => stack::dup::<5, H>  => stack_io(5, 6), static_gas(gas::VERYLOW);
+    0x85 => DUP6  => stack::dup::<6, H>  => stack_io(6, 7), static_gas(gas::VERYLOW);
+    0x86 => DUP7  => stack::dup::<7, H>  => stack_io(7, 8), static_gas(gas::VERYLOW);
+    0x87 => DUP8  => stack::dup::<8, H>  => stack_io(8, 9), static_gas(gas::VERYLOW);
+    0x88 => DUP9  => stack::dup::<9, H>  => stack_io(9, 10), static_gas(gas::VERYLOW);
+    0x89 => DUP10 => stack::dup::<10, H> => stack_io(10, 11), static_gas(gas::VERYLOW);
+    0x8A => DUP11 => stack::dup::<11, H> => stack_io(11, 12), static_gas(gas::VERYLOW);
+    0x8B => DUP12 => stack::dup::<12, H> => stack_io(12, 13), static_gas(gas::VERYLOW);
+    0x8C => DUP13 => stack::dup::<13, H> => stack_io(13, 14), static_gas(gas::VERYLOW);
+    0x8D => DUP14 => stack::dup::<14, H> => stack_io(14, 15), static_gas(gas::VERYLOW);
+    0x8E => DUP15 => stack::dup::<15, H> => stack_io(15, 16), static_gas(gas::VERYLOW);
+    0x8F => DUP16 => stack::dup::<16, H> => stack_io(16, 17), static_gas(gas::VERYLOW);
+
+    0x90 => SWAP1  => stack::swap::<1, H>  => stack_io(2, 2), static_gas(gas::VERYLOW);
+    0x91 => SWAP2  => stack::swap::<2, H>  => stack_io(3, 3), static_gas(gas::VERYLOW);
+    0x92 => SWAP3  => stack::swap::<3, H>  => stack_io(4, 4), static_gas(gas::VERYLOW);
+    0x93 => SWAP4  => stack::swap::<4, H>  => stack_io(5, 5), static_gas(gas::VERYLOW);
+    0x94 => SWAP5  => stack::swap::<5, H>  => stack_io(6, 6), static_gas(gas::VERYLOW);
+    0x95 => SWAP6  => stack::swap::<6, H>  => stack_io(7, 7), static_gas(gas::VERYLOW);
+    0x96 => SWAP7  => stack::swap::<7, H>  => stack_io(8, 8), static_gas(gas::VERYLOW);
+    0x97 => SWAP8  => stack::swap::<8, H>  => stack_io(9, 9), static_gas(gas::VERYLOW);
+    0x98 => SWAP9  => stack::swap::<9, H>  => stack_io(10, 10), static_gas(gas::VERYLOW);
+    0x99 => SWAP10 => stack::swap::<10, H> => stack_io(11, 11), static_gas(gas::VERYLOW);
+    0x9A => SWAP11 => stack::swap::<11, H> => stack_io(12, 12), static_gas(gas::VERYLOW);
+    0x9B => SWAP12 => stack::swap::<12, H> => stack_io(13, 13), static_gas(gas::VERYLOW);
+    0x9C => SWAP13 => stack::swap::<13, H> => stack_io(14, 14), static_gas(gas::VERYLOW);
+    0x9D => SWAP14 => stack::swap::<14, H> => stack_io(15, 15), static_gas(gas::VERYLOW);
+    0x9E => SWAP15 => stack::swap::<15, H> => stack_io(16, 16), static_gas(gas::VERYLOW);
+    0x9F => SWAP16 => stack::swap::<16, H> => stack_io(17, 17), static_gas(gas::VERYLOW);
+
+    0xA0 => LOG0 => host::log::<0, H> => stack_io(2, 0), static_gas(gas::LOG);
+    0xA1 => LOG1 => host::log::<1, H> => stack_io(3, 0), static_gas(gas::LOG + gas::LOGTOPIC);
+    0xA2 => LOG2 => host::log::<2, H> => stack_io(4, 0), static_gas(gas::LOG + 2 * gas::LOGTOPIC);
+    0xA3 => LOG3 => host::log::<3, H> => stack_io(5, 0), static_gas(gas::LOG + 3 * gas::LOGTOPIC);
+    0xA4 => LOG4 => host::log::<4, H> => stack_io(6, 0), static_gas(gas::LOG + 4 * gas::LOGTOPIC);
     // 0xA5
     // 0xA6
     // 0xA7
@@ -645,10 +734,10 @@ opcodes! {
     // 0xCD
     // 0xCE
     // 0xCF
-    0xD0 => DATALOAD  => data::data_load   => stack_io(1, 1);
-    0xD1 => DATALOADN => data::data_loadn  => stack_io(0, 1), immediate_size(2);
-    0xD2 => DATASIZE  => data::data_size   => stack_io(0, 1);
-    0xD3 => DATACOPY  => data::data_copy   => stack_io(3, 0);
+    0xD0 => DATALOAD  => data::data_load   => stack_io(1, 1), static_gas(gas::DATA_LOAD_GAS), introduced_in(SpecId::PRAGUE_EOF);
+    0xD1 => DATALOADN => data::data_loadn  => stack_io(0, 1), immediate_size(2), static_gas(gas::DATA_LOADN_GAS), introduced_in(SpecId::PRAGUE_EOF);
+    0xD2 => DATASIZE  => data::data_size   => stack_io(0, 1), static_gas(gas::BASE), introduced_in(SpecId::PRAGUE_EOF);
+    0xD3 => DATACOPY  => data::data_copy   => stack_io(3, 0), static_gas(gas::VERYLOW), introduced_in(SpecId::PRAGUE_EOF);
     // 0xD4
     // 0xD5
     // 0xD6
@@ -661,38 +750,38 @@ opcodes! {
     // 0xDD
     // 0xDE
     // 0xDF
-    0xE0 => RJUMP    => control::rjump  => stack_io(0, 0), immediate_size(2), terminating;
-    0xE1 => RJUMPI   => control::rjumpi => stack_io(1, 0), immediate_size(2);
-    0xE2 => RJUMPV   => control::rjumpv => stack_io(1, 0), immediate_size(1);
-    0xE3 => CALLF    => control::callf  => stack_io(0, 0), immediate_size(2);
-    0xE4 => RETF     => control::retf   => stack_io(0, 0), terminating;
-    0xE5 => JUMPF    => control::jumpf  => stack_io(0, 0), immediate_size(2), terminating;
-    0xE6 => DUPN     => stack::dupn     => stack_io(0, 1), immediate_size(1);
-    0xE7 => SWAPN    => stack::swapn    => stack_io(0, 0), immediate_size(1);
-    0xE8 => EXCHANGE => stack::exchange => stack_io(0, 0), immediate_size(1);
+    0xE0 => RJUMP    => control::rjump  => stack_io(0, 0), immediate_size(2), terminating, static_gas(gas::BASE), introduced_in(SpecId::PRAGUE_EOF);
+    0xE1 => RJUMPI   => control::rjumpi => stack_io(1, 0), immediate_size(2), static_gas(gas::CONDITION_JUMP_GAS), introduced_in(SpecId::PRAGUE_EOF);
+    0xE2 => RJUMPV   => control::rjumpv => stack_io(1, 0), immediate_size(1), static_gas(gas::CONDITION_JUMP_GAS), introduced_in(SpecId::PRAGUE_EOF);
+    0xE3 => CALLF    => control::callf  => stack_io(0, 0), immediate_size(2), static_gas(gas::LOW), introduced_in(SpecId::PRAGUE_EOF);
+    0xE4 => RETF     => control::retf   => stack_io(0, 0), terminating, static_gas(gas::RETF_GAS), introduced_in(SpecId::PRAGUE_EOF);
+    0xE5 => JUMPF    => control::jumpf  => stack_io(0, 0), immediate_size(2), terminating, static_gas(gas::LOW), introduced_in(SpecId::PRAGUE_EOF);
+    0xE6 => DUPN     => stack::dupn     => stack_io(0, 1), immediate_size(1), static_gas(gas::VERYLOW), introduced_in(SpecId::PRAGUE_EOF);
+    0xE7 => SWAPN    => stack::swapn    => stack_io(0, 0), immediate_size(1), static_gas(gas::VERYLOW), introduced_in(SpecId::PRAGUE_EOF);
+    0xE8 => EXCHANGE => stack::exchange => stack_io(0, 0), immediate_size(1), static_gas(gas::VERYLOW), introduced_in(SpecId::PRAGUE_EOF);
     // 0xE9
     // 0xEA
     // 0xEB
-    0xEC => EOFCREATE       => contract::eofcreate            => stack_io(4, 1), immediate_size(1);
+    0xEC => EOFCREATE       => contract::eofcreate            => stack_io(4, 1), immediate_size(1), static_gas(gas::EOF_CREATE_GAS), introduced_in(SpecId::PRAGUE_EOF);
     // 0xED
-    0xEE => RETURNCONTRACT  => contract::return_contract      => stack_io(2, 0), immediate_size(1), terminating;
+    0xEE => RETURNCONTRACT  => contract::return_contract      => stack_io(2, 0), immediate_size(1), terminating, static_gas(0), introduced_in(SpecId::PRAGUE_EOF);
     // 0xEF
-    0xF0 => CREATE       => contract::create::<false, H, SPEC> => stack_io(3, 1), not_eof;
-    0xF1 => CALL         => contract::call::<H, SPEC>          => stack_io(7, 1), not_eof;
-    0xF2 => CALLCODE     => contract::call_code::<H, SPEC>     => stack_io(7, 1), not_eof;
-    0xF3 => RETURN       => control::ret                       => stack_io(2, 0), terminating;
-    0xF4 => DELEGATECALL => contract::delegate_call::<H, SPEC> => stack_io(6, 1), not_eof;
-    0xF5 => CREATE2      => contract::create::<true, H, SPEC>  => stack_io(4, 1), not_eof;
+    0xF0 => CREATE       => contract::create::<false, H, SPEC> => stack_io(3, 1), not_eof, static_gas(gas::CREATE);
+    0xF1 => CALL         => contract::call::<H, SPEC>          => stack_io(7, 1), not_eof, static_gas(gas::WARM_STORAGE_READ_COST);
+    0xF2 => CALLCODE     => contract::call_code::<H, SPEC>     => stack_io(7, 1), not_eof, static_gas(gas::WARM_STORAGE_READ_COST);
+    0xF3 => RETURN       => control::ret                       => stack_io(2, 0), terminating, static_gas(0);
+    0xF4 => DELEGATECALL => contract::delegate_call::<H, SPEC> => stack_io(6, 1), not_eof, static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::HOMESTEAD);
+    0xF5 => CREATE2      => contract::create::<true, H, SPEC>  => stack_io(4, 1), not_eof, static_gas(gas::CREATE), introduced_in(SpecId::CONSTANTINOPLE);
     // 0xF6
-    0xF7 => RETURNDATALOAD  => system::returndataload                => stack_io(1, 1);
-    0xF8 => EXTCALL         => contract::extcall::<H, SPEC>          => stack_io(4, 1);
-    0xF9 => EXTDELEGATECALL => contract::extdelegatecall::<H, SPEC>  => stack_io(3, 1);
-    0xFA => STATICCALL      => contract::static_call::<H, SPEC>      => stack_io(6, 1), not_eof;
-    0xFB => EXTSTATICCALL   => contract::extstaticcall               => stack_io(3, 1);
+    0xF7 => RETURNDATALOAD  => system::returndataload                => stack_io(1, 1), static_gas(gas::VERYLOW), introduced_in(SpecId::PRAGUE_EOF);
+    0xF8 => EXTCALL         => contract::extcall::<H, SPEC>          => stack_io(4, 1), static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::PRAGUE_EOF);
+    0xF9 => EXTDELEGATECALL => contract::extdelegatecall::<H, SPEC>  => stack_io(3, 1), static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::PRAGUE_EOF);
+    0xFA => STATICCALL      => contract::static_call::<H, SPEC>      => stack_io(6, 1), not_eof, static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::BYZANTIUM);
+    0xFB => EXTSTATICCALL   => contract::extstaticcall               => stack_io(3, 1), static_gas(gas::WARM_STORAGE_READ_COST), introduced_in(SpecId::PRAGUE_EOF);
     // 0xFC
-    0xFD => REVERT       => control::revert::<H, SPEC>    => stack_io(2, 0), terminating;
-    0xFE => INVALID      => control::invalid              => stack_io(0, 0), terminating;
-    0xFF => SELFDESTRUCT => host::selfdestruct::<H, SPEC> => stack_io(1, 0), not_eof, terminating;
+    0xFD => REVERT       => control::revert::<H, SPEC>    => stack_io(2, 0), terminating, static_gas(0), introduced_in(SpecId::BYZANTIUM);
+    0xFE => INVALID      => control::invalid              => stack_io(0, 0), terminating, static_gas(0);
+    0xFF => SELFDESTRUCT => host::selfdestruct::<H, SPEC> => stack_io(1, 0), not_eof, terminating, static_gas(5000);
 }
 
 #[cfg(test)]
@@ -796,6 +885,79 @@ mod tests {
         assert_eq!(eof_opcode_num, 152);
     }
 
+    /// Executes legacy `opcode` with a stack of zeros, and returns its result and spent gas.
+    fn execute_opcode(opcode: u8, spec_id: SpecId) -> (crate::InstructionResult, u64) {
+        use crate::{
+            primitives::{spec_to_generic, Bytecode, DefaultEthereumWiring, U256},
+            DummyHost, Gas, Interpreter, SharedMemory,
+        };
+
+        let mut interp = Interpreter::new_bytecode(Bytecode::LegacyRaw([opcode; 1].into()));
+        interp.shared_memory = SharedMemory::new();
+        interp.gas = Gas::new(1_000_000);
+        for _ in 0..17 {
+            interp.stack.push(U256::ZERO).unwrap();
+        }
+        let mut host = DummyHost::<DefaultEthereumWiring>::default();
+        spec_to_generic!(
+            spec_id,
+            interp.step(
+                &make_instruction_table::<DummyHost<DefaultEthereumWiring>, SPEC>(),
+                &mut host
+            )
+        );
+        (interp.instruction_result, interp.gas.spent())
+    }
+
+    #[test]
+    fn test_introduced_in() {
+        for opcode in OPCODE_INFO_JUMPTABLE
+            .iter()
+            .enumerate()
+            .filter_map(|(i, info)| info.map(|_| OpCode::new(i as u8).unwrap()))
+        {
+            let spec_id = opcode.introduced_in();
+            if spec_id == SpecId::PRAGUE_EOF {
+                continue;
+            }
+            let (result, _) = execute_opcode(opcode.get(), spec_id);
+            assert_ne!(result, crate::InstructionResult::NotActivated, "{opcode}");
+            if spec_id != SpecId::FRONTIER {
+                let previous = SpecId::n(spec_id as u8 - 1).unwrap();
+                let (result, _) = execute_opcode(opcode.get(), previous);
+                assert_eq!(result, crate::InstructionResult::NotActivated, "{opcode}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_static_gas() {
+        // Opcodes whose cost on a stack of zeros includes a dynamic part, such as the
+        // expansion of memory or a cold access.
+        const DYNAMIC: &[u8] = &[
+            MLOAD,
+            MSTORE,
+            MSTORE8,
+            SLOAD,
+            SSTORE,
+            SELFDESTRUCT,
+            CREATE,
+            CREATE2,
+            INVALID,
+        ];
+        for opcode in OPCODE_INFO_JUMPTABLE
+            .iter()
+            .enumerate()
+            .filter_map(|(i, info)| info.map(|_| OpCode::new(i as u8).unwrap()))
+        {
+            if opcode.introduced_in() == SpecId::PRAGUE_EOF || DYNAMIC.contains(&opcode.get()) {
+                continue;
+            }
+            let (_, spent) = execute_opcode(opcode.get(), SpecId::LATEST);
+            assert_eq!(spent, opcode.static_gas(), "{opcode}");
+        }
+    }
+
     #[test]
     fn test_terminating_opcodes() {
         let terminating = [
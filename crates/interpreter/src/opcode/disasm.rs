@@ -0,0 +1,233 @@
+//! Bytecode disassembler.
+//!
+//! [`disassemble`] decodes the code of a [`Bytecode`] into [`DisasmInstruction`]s, skipping
+//! the immediates of `PUSH` instructions in legacy code and of all instructions with
+//! immediates in EOF code. The [`Display`](fmt::Display) implementation of [`Disassembler`]
+//! prints one instruction per line, with a label before every jump destination.
+
+use super::{OpCode, PUSH1, PUSH32, RJUMPV};
+use crate::primitives::{hex, Bytecode, JumpTable};
+use core::fmt;
+use std::{vec, vec::Vec};
+
+/// Instruction decoded by a [`Disassembler`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DisasmInstruction<'a> {
+    /// Offset of the instruction in the code.
+    pub pc: usize,
+    /// Opcode byte, which may not be a known opcode.
+    pub opcode: u8,
+    /// Immediate bytes, shorter than expected if the code ends in the middle of them.
+    pub immediate: &'a [u8],
+    /// Whether the instruction is a valid jump destination.
+    pub is_jumpdest: bool,
+    /// Expected number of immediate bytes.
+    immediate_size: usize,
+}
+
+impl DisasmInstruction<'_> {
+    /// Returns the opcode, or `None` if the byte is not a known opcode.
+    #[inline]
+    pub const fn op(&self) -> Option<OpCode> {
+        OpCode::new(self.opcode)
+    }
+
+    /// Returns whether the code ends before the immediate bytes of the instruction.
+    #[inline]
+    pub const fn is_truncated(&self) -> bool {
+        self.immediate.len() < self.immediate_size
+    }
+}
+
+impl fmt::Display for DisasmInstruction<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op() {
+            Some(op) => f.write_str(op.as_str())?,
+            None => write!(f, "UNKNOWN(0x{:02X})", self.opcode)?,
+        }
+        if !self.immediate.is_empty() {
+            write!(f, " 0x{}", hex::encode(self.immediate))?;
+        }
+        if self.is_truncated() {
+            f.write_str(" (truncated)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the instructions of code, see [`disassemble`].
+#[derive(Clone, Debug)]
+pub struct Disassembler<'a> {
+    code: &'a [u8],
+    jump_table: Option<&'a JumpTable>,
+    is_eof: bool,
+    pc: usize,
+}
+
+impl<'a> Disassembler<'a> {
+    /// Creates a disassembler of legacy code.
+    ///
+    /// Only `PUSH` instructions have immediates, and jump destinations are the `JUMPDEST`
+    /// instructions.
+    pub const fn new(code: &'a [u8]) -> Self {
+        Self {
+            code,
+            jump_table: None,
+            is_eof: false,
+            pc: 0,
+        }
+    }
+
+    /// Creates a disassembler of an EOF code section.
+    ///
+    /// All instructions with immediates are decoded, including the jump table of `RJUMPV`.
+    /// EOF code has no jump destinations.
+    pub const fn new_eof(code: &'a [u8]) -> Self {
+        Self {
+            code,
+            jump_table: None,
+            is_eof: true,
+            pc: 0,
+        }
+    }
+
+    /// Uses the jump destinations of `jump_table`, computed by the analysis of the code.
+    pub const fn with_jump_table(mut self, jump_table: &'a JumpTable) -> Self {
+        self.jump_table = Some(jump_table);
+        self
+    }
+
+    /// Returns the expected number of immediate bytes of the instruction at `pc`.
+    fn immediate_size(&self, pc: usize) -> usize {
+        let opcode = self.code[pc];
+        if !self.is_eof {
+            return if (PUSH1..=PUSH32).contains(&opcode) {
+                (opcode - PUSH1 + 1) as usize
+            } else {
+                0
+            };
+        }
+        match OpCode::new(opcode) {
+            // The immediate is the maximum index of the jump table, followed by the table.
+            Some(_) if opcode == RJUMPV => self
+                .code
+                .get(pc + 1)
+                .map_or(1, |&max_index| 1 + (max_index as usize + 1) * 2),
+            Some(op) => op.info().immediate_size() as usize,
+            None => 0,
+        }
+    }
+}
+
+impl<'a> Iterator for Disassembler<'a> {
+    type Item = DisasmInstruction<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let pc = self.pc;
+        let opcode = *self.code.get(pc)?;
+        let immediate_size = self.immediate_size(pc);
+        let end = (pc + 1 + immediate_size).min(self.code.len());
+        self.pc = pc + 1 + immediate_size;
+        let is_jumpdest = match self.jump_table {
+            Some(jump_table) => jump_table.is_valid(pc),
+            None => !self.is_eof && opcode == super::JUMPDEST,
+        };
+        Some(DisasmInstruction {
+            pc,
+            opcode,
+            immediate: &self.code[pc + 1..end],
+            is_jumpdest,
+            immediate_size,
+        })
+    }
+}
+
+impl fmt::Display for Disassembler<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for instruction in self.clone() {
+            if instruction.is_jumpdest {
+                writeln!(f, "label_{:04x}:", instruction.pc)?;
+            }
+            writeln!(f, "    {:04x}: {instruction}", instruction.pc)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a disassembler for every code section of `bytecode`.
+///
+/// Legacy bytecode has a single code section, without the padding added by the analysis, and
+/// uses the jump table of the analysis if it is analysed. EIP-7702 bytecode has no code.
+pub fn disassemble(bytecode: &Bytecode) -> Vec<Disassembler<'_>> {
+    match bytecode {
+        Bytecode::LegacyRaw(code) => vec![Disassembler::new(code)],
+        Bytecode::LegacyAnalyzed(analyzed) => {
+            vec![Disassembler::new(analyzed.original_byte_slice())
+                .with_jump_table(analyzed.jump_table())]
+        }
+        Bytecode::Eof(eof) => eof
+            .body
+            .code_section
+            .iter()
+            .map(|code| Disassembler::new_eof(code))
+            .collect(),
+        Bytecode::Eip7702(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        analysis::to_analysed,
+        opcode::{ADD, JUMP, JUMPDEST, PUSH0, PUSH1, PUSH2, STOP},
+        primitives::Bytes,
+    };
+    use std::string::ToString;
+
+    #[test]
+    fn push_immediates_are_skipped() {
+        // The immediate of the PUSH2 contains a JUMPDEST byte, which is not a jump destination.
+        let code = [
+            PUSH1, 0x05, JUMP, PUSH2, JUMPDEST, ADD, JUMPDEST, 0xfe, PUSH1,
+        ];
+        let bytecode = to_analysed(Bytecode::new_legacy(Bytes::from(code.to_vec())));
+        let [disassembler] = &disassemble(&bytecode)[..] else {
+            panic!("legacy bytecode has one code section");
+        };
+        let instructions: Vec<_> = disassembler
+            .clone()
+            .map(|instruction| (instruction.pc, instruction.is_jumpdest))
+            .collect();
+        assert_eq!(
+            instructions,
+            [
+                (0, false),
+                (2, false),
+                (3, false),
+                (6, true),
+                (7, false),
+                (8, false)
+            ]
+        );
+        assert_eq!(
+            disassembler.to_string(),
+            "    0000: PUSH1 0x05\n    0002: JUMP\n    0003: PUSH2 0x5b01\nlabel_0006:\n    \
+             0006: JUMPDEST\n    0007: INVALID\n    0008: PUSH1 (truncated)\n"
+        );
+    }
+
+    #[test]
+    fn eof_immediates_are_skipped() {
+        // RJUMPV with two entries, then PUSH0 and STOP.
+        let code = [PUSH0, RJUMPV, 0x01, 0x00, 0x01, 0x00, 0x02, PUSH0, STOP];
+        let instructions: Vec<_> = Disassembler::new_eof(&code)
+            .map(|instruction| (instruction.pc, instruction.immediate.len()))
+            .collect();
+        assert_eq!(instructions, [(0, 0), (1, 5), (7, 0), (8, 0)]);
+        assert_eq!(
+            Disassembler::new(&[0x0c]).next().unwrap().to_string(),
+            "UNKNOWN(0x0C)"
+        );
+    }
+}
@@ -1,7 +1,11 @@
 #![allow(clippy::wrong_self_convention)]
 
 use super::instruction;
-use crate::{instructions::control, primitives::Spec, Host, Interpreter};
+use crate::{
+    instructions::{control, fused},
+    primitives::{RuntimeSpec, Spec},
+    Host, Interpreter,
+};
 use std::boxed::Box;
 
 /// EVM opcode function signature.
@@ -33,6 +37,13 @@ impl<'a, H: Host + ?Sized> InstructionTables<'a, H> {
     pub const fn new_plain<SPEC: Spec>() -> Self {
         Self::Plain(make_instruction_table::<H, SPEC>())
     }
+
+    /// Creates a plain instruction table for every spec, the instructions read the spec from
+    /// [HostRead::spec_id](crate::HostRead::spec_id). See [`RuntimeSpec`].
+    #[inline]
+    pub const fn new_runtime() -> Self {
+        Self::new_plain::<RuntimeSpec>()
+    }
 }
 
 impl<'a, H: Host + ?Sized + 'a> InstructionTables<'a, H> {
@@ -137,7 +148,10 @@ pub const fn make_instruction_table<H: Host + ?Sized, SPEC: Spec>() -> Instructi
         let mut tables: InstructionTable<H> = [control::unknown; 256];
         let mut i = 0;
         while i < 256 {
-            tables[i] = instruction::<H, SPEC>(i as u8);
+            tables[i] = match fused::instruction::<H>(i as u8) {
+                Some(instruction) => instruction,
+                None => instruction::<H, SPEC>(i as u8),
+            };
             i += 1;
         }
         tables
@@ -63,7 +63,7 @@ impl Transaction for TxEnv {
         self.base.data()
     }
 
-    fn nonce(&self) -> u64 {
+    fn nonce(&self) -> Option<u64> {
         self.base.nonce()
     }
 
@@ -90,6 +90,10 @@ impl Transaction for TxEnv {
     fn authorization_list(&self) -> Option<&AuthorizationList> {
         self.base.authorization_list()
     }
+
+    fn is_system(&self) -> bool {
+        self.base.is_system()
+    }
 }
 
 impl OptimismTransaction for TxEnv {
@@ -1,18 +1,17 @@
 //! Handler related to Optimism chain
 
 use super::{
-    optimism_spec_to_generic, OptimismContext, OptimismHaltReason, OptimismInvalidTransaction,
-    OptimismSpec, OptimismSpecId, OptimismTransaction, OptimismWiring,
+    optimism_precompiles, optimism_spec_to_generic, OptimismContext, OptimismHaltReason,
+    OptimismInvalidTransaction, OptimismSpec, OptimismSpecId, OptimismTransaction, OptimismWiring,
 };
 use crate::{BASE_FEE_RECIPIENT, L1_FEE_RECIPIENT};
-use core::ops::Mul;
 use revm::{
     handler::{
         mainnet::{self, deduct_caller_inner},
         register::EvmHandler,
+        FeeRecipients,
     },
     interpreter::{return_ok, return_revert, Gas, InstructionResult},
-    precompile::{secp256r1, PrecompileSpecId},
     primitives::{
         db::Database, Account, Block, EVMError, EVMResult, EVMResultGeneric, EnvWiring,
         ExecutionResult, HashMap, InvalidTransaction, ResultAndState, Transaction, U256,
@@ -40,6 +39,8 @@ where
         // Refund is calculated differently then mainnet.
         handler.execution.last_frame_return = Arc::new(last_frame_return::<EvmWiringT, SPEC>);
         handler.post_execution.refund = Arc::new(refund::<EvmWiringT, SPEC>);
+        // The base fee is sent to the Base Fee Vault instead of being burned.
+        handler.post_execution.fee_recipients = Arc::new(fee_recipients::<EvmWiringT>);
         handler.post_execution.reward_beneficiary =
             Arc::new(reward_beneficiary::<EvmWiringT, SPEC>);
         // In case of halt of deposit transaction return Error.
@@ -52,14 +53,6 @@ where
 pub fn validate_env<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     env: &EnvWiring<EvmWiringT>,
 ) -> EVMResultGeneric<(), EvmWiringT> {
-    // Do not perform any extra validation for deposit transactions, they are pre-verified on L1.
-    if env.tx.source_hash().is_some() {
-        return Ok(());
-    }
-
-    // Important: validate block before tx.
-    env.validate_block_env::<SPEC>()?;
-
     // Do not allow for a system transaction to be processed if Regolith is enabled.
     if env.tx.is_system_transaction().unwrap_or(false)
         && SPEC::optimism_enabled(OptimismSpecId::REGOLITH)
@@ -67,6 +60,14 @@ pub fn validate_env<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
         return Err(OptimismInvalidTransaction::DepositSystemTxPostRegolith.into());
     }
 
+    // Do not perform any extra validation for deposit transactions, they are pre-verified on L1.
+    if env.tx.is_deposit() {
+        return Ok(());
+    }
+
+    // Important: validate block before tx.
+    env.validate_block_env::<SPEC>()?;
+
     env.validate_tx::<SPEC>()
         .map_err(OptimismInvalidTransaction::Base)?;
 
@@ -77,7 +78,7 @@ pub fn validate_env<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 pub fn validate_tx_against_state<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     context: &mut Context<EvmWiringT>,
 ) -> EVMResultGeneric<(), EvmWiringT> {
-    if context.evm.inner.env.tx.source_hash().is_some() {
+    if context.evm.inner.env.tx.is_deposit() {
         return Ok(());
     }
     mainnet::validate_tx_against_state::<EvmWiringT, SPEC>(context)
@@ -90,7 +91,7 @@ pub fn last_frame_return<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     frame_result: &mut FrameResult,
 ) -> EVMResultGeneric<(), EvmWiringT> {
     let env = context.evm.inner.env();
-    let is_deposit = env.tx.source_hash().is_some();
+    let is_deposit = env.tx.is_deposit();
     let tx_system = env.tx.is_system_transaction();
     let tx_gas_limit = env.tx.gas_limit();
     let is_regolith = SPEC::optimism_enabled(OptimismSpecId::REGOLITH);
@@ -160,7 +161,7 @@ pub fn refund<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     gas.record_refund(eip7702_refund);
 
     let env = context.evm.inner.env();
-    let is_deposit = env.tx.source_hash().is_some();
+    let is_deposit = env.tx.is_deposit();
     let is_regolith = SPEC::optimism_enabled(OptimismSpecId::REGOLITH);
 
     // Prior to Regolith, deposit transactions did not receive gas refunds.
@@ -174,23 +175,7 @@ pub fn refund<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 #[inline]
 pub fn load_precompiles<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 ) -> ContextPrecompiles<EvmWiringT> {
-    let mut precompiles = ContextPrecompiles::new(PrecompileSpecId::from_spec_id(SPEC::SPEC_ID));
-
-    if SPEC::optimism_enabled(OptimismSpecId::FJORD) {
-        precompiles.extend([
-            // EIP-7212: secp256r1 P256verify
-            secp256r1::P256VERIFY,
-        ])
-    }
-
-    if SPEC::optimism_enabled(OptimismSpecId::GRANITE) {
-        precompiles.extend([
-            // Restrict bn256Pairing input size
-            crate::bn128::pair::GRANITE,
-        ])
-    }
-
-    precompiles
+    ContextPrecompiles::from_static_precompiles(optimism_precompiles(SPEC::OPTIMISM_SPEC_ID))
 }
 
 /// Load account (make them warm) and l1 data from database.
@@ -200,7 +185,7 @@ pub fn load_accounts<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
 ) -> EVMResultGeneric<(), EvmWiringT> {
     // the L1-cost fee is only computed for Optimism non-deposit transactions.
 
-    if context.evm.env.tx.source_hash().is_none() {
+    if !context.evm.env.tx.is_deposit() {
         let l1_block_info =
             super::L1BlockInfo::try_fetch(&mut context.evm.inner.db, SPEC::OPTIMISM_SPEC_ID)
                 .map_err(EVMError::Database)?;
@@ -239,9 +224,9 @@ pub fn deduct_caller<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     // l1 cost, max values is already checked in pre_validate but l1 cost wasn't.
     deduct_caller_inner::<EvmWiringT, SPEC>(caller_account.data, &context.evm.inner.env);
 
-    // If the transaction is not a deposit transaction, subtract the L1 data fee from the
-    // caller's balance directly after minting the requested amount of ETH.
-    if context.evm.inner.env.tx.source_hash().is_none() {
+    // If the transaction is not a deposit or system transaction, subtract the L1 data fee
+    // from the caller's balance directly after minting the requested amount of ETH.
+    if !context.evm.inner.env.tx.is_deposit() && !context.evm.inner.env.tx.is_system() {
         // get envelope
         let Some(enveloped_tx) = &context.evm.inner.env.tx.enveloped_tx() else {
             return Err(EVMError::Custom(
@@ -270,22 +255,32 @@ pub fn deduct_caller<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     Ok(())
 }
 
+/// Fee recipients for the Optimism chain: the priority fee goes to the block coinbase and
+/// the base fee to the Base Fee Vault.
+#[inline]
+pub fn fee_recipients<EvmWiringT: OptimismWiring>(
+    context: &mut Context<EvmWiringT>,
+) -> FeeRecipients {
+    FeeRecipients {
+        priority_fee: *context.evm.inner.env.block.coinbase(),
+        base_fee: Some(BASE_FEE_RECIPIENT),
+    }
+}
+
 /// Reward beneficiary with gas fee.
 #[inline]
 pub fn reward_beneficiary<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     context: &mut Context<EvmWiringT>,
     gas: &Gas,
+    recipients: &FeeRecipients,
 ) -> EVMResultGeneric<(), EvmWiringT> {
-    let is_deposit = context.evm.inner.env.tx.source_hash().is_some();
+    let is_deposit = context.evm.inner.env.tx.is_deposit();
 
-    // transfer fee to coinbase/beneficiary.
-    if !is_deposit {
-        mainnet::reward_beneficiary::<EvmWiringT, SPEC>(context, gas)?;
-    }
+    if !is_deposit && !context.evm.inner.env.tx.is_system() {
+        // If the transaction is not a deposit or system transaction, fees are paid out
+        // to the fee recipients as well as the L1 Fee Vault.
+        mainnet::reward_beneficiary::<EvmWiringT, SPEC>(context, gas, recipients)?;
 
-    if !is_deposit {
-        // If the transaction is not a deposit transaction, fees are paid out
-        // to both the Base Fee Vault as well as the L1 Fee Vault.
         let l1_block_info = context
             .evm
             .chain
@@ -309,22 +304,6 @@ pub fn reward_beneficiary<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
             .map_err(EVMError::Database)?;
         l1_fee_vault_account.mark_touch();
         l1_fee_vault_account.info.balance += l1_cost;
-
-        // Send the base fee of the transaction to the Base Fee Vault.
-        let mut base_fee_vault_account = context
-            .evm
-            .inner
-            .journaled_state
-            .load_account(BASE_FEE_RECIPIENT, &mut context.evm.inner.db)
-            .map_err(EVMError::Database)?;
-        base_fee_vault_account.mark_touch();
-        base_fee_vault_account.info.balance += context
-            .evm
-            .inner
-            .env
-            .block
-            .basefee()
-            .mul(U256::from(gas.spent() - gas.refunded() as u64));
     }
     Ok(())
 }
@@ -341,7 +320,7 @@ pub fn output<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
         // Post-regolith, if the transaction is a deposit transaction and it halts,
         // we bubble up to the global return handler. The mint value will be persisted
         // and the caller nonce will be incremented there.
-        let is_deposit = context.evm.inner.env.tx.source_hash().is_some();
+        let is_deposit = context.evm.inner.env.tx.is_deposit();
         if is_deposit && SPEC::optimism_enabled(OptimismSpecId::REGOLITH) {
             return Err(EVMError::Transaction(
                 OptimismInvalidTransaction::HaltedDepositPostRegolith,
@@ -358,9 +337,7 @@ pub fn end<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
     evm_output: EVMResult<EvmWiringT>,
 ) -> EVMResult<EvmWiringT> {
     evm_output.or_else(|err| {
-        if matches!(err, EVMError::Transaction(_))
-            && context.evm.inner.env().tx.source_hash().is_some()
-        {
+        if matches!(err, EVMError::Transaction(_)) && context.evm.inner.env().tx.is_deposit() {
             // If the transaction is a deposit transaction and it failed
             // for any reason, the caller nonce must be bumped, and the
             // gas reported must be altered depending on the Hardfork. This is
@@ -409,6 +386,7 @@ pub fn end<EvmWiringT: OptimismWiring, SPEC: OptimismSpec>(
                 result: ExecutionResult::Halt {
                     reason: OptimismHaltReason::FailedDeposit,
                     gas_used,
+                    location: None,
                 },
                 state,
             })
@@ -425,7 +403,8 @@ mod tests {
     use revm::{
         db::{EmptyDB, InMemoryDB},
         interpreter::{CallOutcome, InterpreterResult},
-        primitives::{bytes, state::AccountInfo, Address, Bytes, B256},
+        primitives::{bytes, state::AccountInfo, Address, Bytes, TxKind, B256},
+        Evm,
     };
     use std::boxed::Box;
 
@@ -668,6 +647,16 @@ mod tests {
 
         // Pre-regolith system transactions should be allowed.
         assert!(validate_env::<TestEmptyOpWiring, BedrockSpec>(&env).is_ok());
+
+        // System deposit transactions are rejected as well.
+        env.tx.source_hash = Some(B256::ZERO);
+        assert_eq!(
+            validate_env::<TestEmptyOpWiring, RegolithSpec>(&env),
+            Err(EVMError::Transaction(
+                OptimismInvalidTransaction::DepositSystemTxPostRegolith
+            ))
+        );
+        assert!(validate_env::<TestEmptyOpWiring, BedrockSpec>(&env).is_ok());
     }
 
     #[test]
@@ -687,4 +676,33 @@ mod tests {
         // Nonce and balance checks should be skipped for deposit transactions.
         assert!(validate_env::<TestEmptyOpWiring, LatestSpec>(&env).is_ok());
     }
+
+    #[test]
+    fn test_deposit_tx_execution() {
+        let caller = Address::with_last_byte(0xaa);
+        let receiver = Address::with_last_byte(0xbb);
+
+        let mut evm = Evm::<TestMemOpWiring>::builder()
+            .with_db(InMemoryDB::default())
+            .with_default_ext_ctx()
+            .with_spec_id(OptimismSpecId::FJORD)
+            .modify_tx_env(|tx| {
+                tx.base.caller = caller;
+                tx.base.transact_to = TxKind::Call(receiver);
+                tx.base.value = U256::from(10);
+                tx.base.gas_limit = 100_000;
+                tx.source_hash = Some(B256::ZERO);
+                tx.mint = Some(100);
+            })
+            .build();
+
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert_eq!(result.gas_used(), 21_000);
+        assert_eq!(state[&caller].info.balance, U256::from(90));
+        assert_eq!(state[&caller].info.nonce, 1);
+        assert_eq!(state[&receiver].info.balance, U256::from(10));
+        // Deposits do not pay fees to the vaults.
+        assert!(!state.contains_key(&L1_FEE_RECIPIENT));
+        assert!(!state.contains_key(&BASE_FEE_RECIPIENT));
+    }
 }
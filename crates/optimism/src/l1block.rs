@@ -2,7 +2,7 @@ use crate::fast_lz::flz_compress_len;
 use core::ops::Mul;
 use revm::primitives::{address, db::Database, Address, U256};
 
-use super::OptimismSpecId;
+use super::{OptimismSpecId, DEPOSIT_TRANSACTION_TYPE};
 
 const ZERO_BYTE_COST: u64 = 4;
 const NON_ZERO_BYTE_COST: u64 = 16;
@@ -169,7 +169,7 @@ impl L1BlockInfo {
     /// Calculate the gas cost of a transaction based on L1 block data posted on L2, depending on the [OptimismSpecId] passed.
     pub fn calculate_tx_l1_cost(&self, input: &[u8], spec_id: OptimismSpecId) -> U256 {
         // If the input is a deposit transaction or empty, the default value is zero.
-        if input.is_empty() || input.first() == Some(&0x7F) {
+        if input.is_empty() || input.first() == Some(&DEPOSIT_TRANSACTION_TYPE) {
             return U256::ZERO;
         }
 
@@ -329,8 +329,8 @@ mod tests {
         let gas_cost = l1_block_info.calculate_tx_l1_cost(&input, OptimismSpecId::REGOLITH);
         assert_eq!(gas_cost, U256::ZERO);
 
-        // Deposit transactions with the EIP-2718 type of 0x7F should result in zero
-        let input = bytes!("7FFACADE");
+        // Deposit transactions with the EIP-2718 type of 0x7E should result in zero
+        let input = bytes!("7EFACADE");
         let gas_cost = l1_block_info.calculate_tx_l1_cost(&input, OptimismSpecId::REGOLITH);
         assert_eq!(gas_cost, U256::ZERO);
     }
@@ -358,8 +358,8 @@ mod tests {
         let gas_cost = l1_block_info.calculate_tx_l1_cost(&input, OptimismSpecId::ECOTONE);
         assert_eq!(gas_cost, U256::ZERO);
 
-        // Deposit transactions with the EIP-2718 type of 0x7F should result in zero
-        let input = bytes!("7FFACADE");
+        // Deposit transactions with the EIP-2718 type of 0x7E should result in zero
+        let input = bytes!("7EFACADE");
         let gas_cost = l1_block_info.calculate_tx_l1_cost(&input, OptimismSpecId::ECOTONE);
         assert_eq!(gas_cost, U256::ZERO);
 
@@ -410,8 +410,8 @@ mod tests {
         let gas_cost = l1_block_info.calculate_tx_l1_cost(&input, OptimismSpecId::FJORD);
         assert_eq!(gas_cost, U256::ZERO);
 
-        // Deposit transactions with the EIP-2718 type of 0x7F should result in zero
-        let input = bytes!("7FFACADE");
+        // Deposit transactions with the EIP-2718 type of 0x7E should result in zero
+        let input = bytes!("7EFACADE");
         let gas_cost = l1_block_info.calculate_tx_l1_cost(&input, OptimismSpecId::FJORD);
         assert_eq!(gas_cost, U256::ZERO);
     }
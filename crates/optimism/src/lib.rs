@@ -10,19 +10,24 @@ mod env;
 mod fast_lz;
 mod handler_register;
 mod l1block;
+mod precompiles;
 mod result;
 mod spec;
 
 pub use handler_register::{
-    deduct_caller, end, last_frame_return, load_accounts, load_precompiles,
+    deduct_caller, end, fee_recipients, last_frame_return, load_accounts, load_precompiles,
     optimism_handle_register, output, refund, reward_beneficiary, validate_env,
     validate_tx_against_state,
 };
 pub use l1block::{L1BlockInfo, BASE_FEE_RECIPIENT, L1_BLOCK_CONTRACT, L1_FEE_RECIPIENT};
+pub use precompiles::optimism_precompiles;
 pub use result::{OptimismHaltReason, OptimismInvalidTransaction};
 use revm::primitives::{Bytes, TransactionValidation, B256};
 pub use spec::*;
 
+/// EIP-2718 transaction type of deposit transactions.
+pub const DEPOSIT_TRANSACTION_TYPE: u8 = 0x7E;
+
 pub trait OptimismContext {
     /// A reference to the cached L1 block info.
     fn l1_block_info(&self) -> Option<&L1BlockInfo>;
@@ -57,6 +62,11 @@ pub trait OptimismTransaction {
     /// opposed to requiring downstream apps to compute the cost
     /// externally.
     fn enveloped_tx(&self) -> Option<Bytes>;
+
+    /// Returns `true` if the transaction is a deposit transaction.
+    fn is_deposit(&self) -> bool {
+        self.source_hash().is_some()
+    }
 }
 
 /// Trait for an Optimism chain spec.
@@ -0,0 +1,81 @@
+//! Optimism precompile sets.
+
+use crate::OptimismSpecId;
+use once_cell::race::OnceBox;
+use revm::precompile::{secp256r1, PrecompileSpecId, Precompiles};
+use std::boxed::Box;
+
+/// Returns the precompiles active at the given Optimism spec.
+///
+/// Like the Ethereum precompile sets, the sets are built once on first use and shared
+/// afterwards.
+pub fn optimism_precompiles(spec_id: OptimismSpecId) -> &'static Precompiles {
+    if spec_id.is_enabled_in(OptimismSpecId::PRAGUE) {
+        prague()
+    } else if spec_id.is_enabled_in(OptimismSpecId::GRANITE) {
+        granite()
+    } else if spec_id.is_enabled_in(OptimismSpecId::FJORD) {
+        fjord()
+    } else {
+        Precompiles::new(PrecompileSpecId::from(spec_id))
+    }
+}
+
+/// Returns precompiles for Fjord spec.
+fn fjord() -> &'static Precompiles {
+    static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
+    INSTANCE.get_or_init(|| {
+        let mut precompiles = Precompiles::cancun().clone();
+        precompiles.extend([
+            // EIP-7212: secp256r1 P256verify
+            secp256r1::P256VERIFY,
+        ]);
+        Box::new(precompiles)
+    })
+}
+
+/// Returns precompiles for Granite spec.
+fn granite() -> &'static Precompiles {
+    static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
+    INSTANCE.get_or_init(|| {
+        let mut precompiles = fjord().clone();
+        precompiles.extend([
+            // Restrict bn256Pairing input size
+            crate::bn128::pair::GRANITE,
+        ]);
+        Box::new(precompiles)
+    })
+}
+
+/// Returns precompiles for Prague spec, which include the precompiles of Granite.
+fn prague() -> &'static Precompiles {
+    static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
+    INSTANCE.get_or_init(|| {
+        let mut precompiles = Precompiles::prague().clone();
+        precompiles.extend([secp256r1::P256VERIFY, crate::bn128::pair::GRANITE]);
+        Box::new(precompiles)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_precompile_sets() {
+        let granite = optimism_precompiles(OptimismSpecId::GRANITE);
+        assert!(core::ptr::eq(
+            granite,
+            optimism_precompiles(OptimismSpecId::GRANITE)
+        ));
+        assert!(granite.contains(secp256r1::P256VERIFY.address()));
+        assert_eq!(granite.len(), Precompiles::cancun().len() + 1);
+
+        let ecotone = optimism_precompiles(OptimismSpecId::ECOTONE);
+        assert!(core::ptr::eq(ecotone, Precompiles::cancun()));
+
+        let prague = optimism_precompiles(OptimismSpecId::PRAGUE);
+        assert!(prague.contains(secp256r1::P256VERIFY.address()));
+        assert_eq!(prague.len(), Precompiles::prague().len() + 1);
+    }
+}
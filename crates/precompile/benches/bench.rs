@@ -1,10 +1,13 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use revm_precompile::{
+    blake2,
     bn128::{
         add::ISTANBUL_ADD_GAS_COST,
         pair::{ISTANBUL_PAIR_BASE, ISTANBUL_PAIR_PER_POINT},
         run_add, run_pair,
     },
+    hash::{ripemd160_run, sha256_run},
+    identity::identity_run,
     kzg_point_evaluation::run,
     secp256k1::ec_recover_run,
     Bytes,
@@ -137,6 +140,23 @@ pub fn benchmark_crypto_precompiles(c: &mut Criterion) {
         })
     });
 
+    // === BLAKE2F ===
+
+    // 12 rounds of the last block of "abc"
+    let mut blake2_input = [0u8; 213];
+    blake2_input[3] = 12;
+    blake2_input[68..71].copy_from_slice(b"abc");
+    blake2_input[196] = 3;
+    blake2_input[212] = 1;
+    let blake2_input = Bytes::from(blake2_input);
+
+    group.bench_function(group_name("blake2f precompile"), |b| {
+        b.iter(|| {
+            blake2::run(&blake2_input, u64::MAX).unwrap();
+            black_box(())
+        })
+    });
+
     group.bench_function(group_name("kzg precompile"), |b| {
         b.iter(|| {
             run(&kzg_input, gas, &env).unwrap();
@@ -145,9 +165,34 @@ pub fn benchmark_crypto_precompiles(c: &mut Criterion) {
     });
 }
 
+/// Benchmarks the precompiles whose cost is dominated by handling the input and output.
+pub fn benchmark_data_precompiles(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Data Precompile benchmarks");
+    let group_name = |description: &str| format!("precompile bench | {description}");
+
+    for size in [32, 1024, 32 * 1024] {
+        let input = Bytes::from(vec![0xab; size]);
+
+        group.bench_function(
+            group_name(&format!("identity precompile {size} bytes")),
+            |b| b.iter(|| black_box(identity_run(black_box(&input), u64::MAX).unwrap())),
+        );
+
+        group.bench_function(
+            group_name(&format!("sha256 precompile {size} bytes")),
+            |b| b.iter(|| black_box(sha256_run(black_box(&input), u64::MAX).unwrap())),
+        );
+
+        group.bench_function(
+            group_name(&format!("ripemd160 precompile {size} bytes")),
+            |b| b.iter(|| black_box(ripemd160_run(black_box(&input), u64::MAX).unwrap())),
+        );
+    }
+}
+
 criterion_group! {
     name = benches;
     config = Criterion::default();
-    targets = benchmark_crypto_precompiles
+    targets = benchmark_crypto_precompiles, benchmark_data_precompiles
 }
 criterion_main!(benches);
@@ -4,12 +4,27 @@ use revm_primitives::{Bytes, PrecompileOutput};
 const F_ROUND: u64 = 1;
 const INPUT_LENGTH: usize = 213;
 
-pub const FUN: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(9), Precompile::Standard(run));
+pub const FUN: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(9),
+    Precompile::Metered(run, required_gas),
+);
+
+/// Returns the gas required for the number of rounds of `input`.
+///
+/// Inputs of the wrong length require no gas, as they fail before the rounds are charged.
+pub fn required_gas(input: &[u8]) -> u64 {
+    if input.len() != INPUT_LENGTH {
+        return 0;
+    }
+    u32::from_be_bytes(input[..4].try_into().unwrap()) as u64 * F_ROUND
+}
 
 /// reference: <https://eips.ethereum.org/EIPS/eip-152>
 /// input format:
 /// [4 bytes for rounds][64 bytes for h][128 bytes for m][8 bytes for t_0][8 bytes for t_1][1 byte for f]
+///
+/// The rounds are charged before any of the input is decoded, so the work is bounded by the
+/// gas limit whatever the number of rounds.
 pub fn run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     let input = &input[..];
 
@@ -19,7 +34,7 @@ pub fn run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
 
     // rounds 4 bytes
     let rounds = u32::from_be_bytes(input[..4].try_into().unwrap()) as usize;
-    let gas_used = rounds as u64 * F_ROUND;
+    let gas_used = required_gas(input);
     if gas_used > gas_limit {
         return Err(Error::OutOfGas.into());
     }
@@ -44,7 +59,13 @@ pub fn run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
         u64::from_le_bytes(input[204..204 + 8].try_into().unwrap()),
     ];
 
-    algo::compress(rounds, &mut h, m, t, f);
+    cfg_if::cfg_if! {
+        if #[cfg(feature = "blake2-unrolled")] {
+            algo::compress_unrolled(rounds, &mut h, m, t, f);
+        } else {
+            algo::compress(rounds, &mut h, m, t, f);
+        }
+    }
 
     let mut out = [0u8; 64];
     for (i, h) in (0..64).step_by(8).zip(h.iter()) {
@@ -131,4 +152,123 @@ pub mod algo {
             h[i] ^= v[i] ^ v[i + 8];
         }
     }
+
+    /// Same as [`compress`], with the rounds unrolled ten at a time.
+    ///
+    /// Every unrolled round uses a constant row of [`SIGMA`], which lets the compiler keep the
+    /// local vector in registers and drop the bounds checks of the message word selection.
+    #[cfg(feature = "blake2-unrolled")]
+    #[allow(clippy::many_single_char_names)]
+    pub fn compress_unrolled(rounds: usize, h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], f: bool) {
+        let mut v = [0u64; 16];
+        v[..h.len()].copy_from_slice(h);
+        v[h.len()..].copy_from_slice(&IV);
+
+        v[12] ^= t[0];
+        v[13] ^= t[1];
+
+        if f {
+            v[14] = !v[14]
+        }
+
+        for _ in 0..rounds / 10 {
+            round(&mut v, &m, &SIGMA[0]);
+            round(&mut v, &m, &SIGMA[1]);
+            round(&mut v, &m, &SIGMA[2]);
+            round(&mut v, &m, &SIGMA[3]);
+            round(&mut v, &m, &SIGMA[4]);
+            round(&mut v, &m, &SIGMA[5]);
+            round(&mut v, &m, &SIGMA[6]);
+            round(&mut v, &m, &SIGMA[7]);
+            round(&mut v, &m, &SIGMA[8]);
+            round(&mut v, &m, &SIGMA[9]);
+        }
+        for s in &SIGMA[..rounds % 10] {
+            round(&mut v, &m, s);
+        }
+
+        for i in 0..8 {
+            h[i] ^= v[i] ^ v[i + 8];
+        }
+    }
+
+    /// A single round of [`compress_unrolled`].
+    #[cfg(feature = "blake2-unrolled")]
+    #[inline(always)]
+    fn round(v: &mut [u64; 16], m: &[u64; 16], s: &[usize; 16]) {
+        g16(v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g16(v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g16(v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g16(v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+
+        g16(v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g16(v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g16(v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g16(v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    /// [`g`] on a fixed size vector, so the indices are checked at compile time once inlined.
+    #[cfg(feature = "blake2-unrolled")]
+    #[inline(always)]
+    #[allow(clippy::many_single_char_names)]
+    fn g16(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+        v[d] = (v[d] ^ v[a]).rotate_right(32);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(24);
+        v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+        v[d] = (v[d] ^ v[a]).rotate_right(16);
+        v[c] = v[c].wrapping_add(v[d]);
+        v[b] = (v[b] ^ v[c]).rotate_right(63);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Input of the fifth test vector of EIP-152, with the given number of rounds.
+    fn input(rounds: u32) -> Bytes {
+        let mut input = revm_primitives::hex!(
+            "0000000c48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b61626300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000300000000000000000000000000000001"
+        );
+        input[..4].copy_from_slice(&rounds.to_be_bytes());
+        input.into()
+    }
+
+    #[test]
+    fn eip152_vector() {
+        let output = run(&input(12), 12).unwrap();
+        assert_eq!(output.gas_used, 12);
+        assert_eq!(
+            output.bytes[..],
+            revm_primitives::hex!("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923")
+        );
+    }
+
+    #[test]
+    fn absurd_rounds_are_charged_before_work() {
+        assert_eq!(
+            run(&input(u32::MAX), 30_000_000),
+            Err(Error::OutOfGas.into())
+        );
+        // The gas is checked before the final block indicator flag.
+        let mut input = input(u32::MAX).to_vec();
+        input[212] = 2;
+        assert_eq!(run(&input.into(), 30_000_000), Err(Error::OutOfGas.into()));
+    }
+
+    #[cfg(feature = "blake2-unrolled")]
+    #[test]
+    fn unrolled_matches_reference() {
+        let m = core::array::from_fn(|i| (i as u64).wrapping_mul(0x9e3779b97f4a7c15));
+        for rounds in [0, 1, 9, 10, 12, 23, 100] {
+            for f in [false, true] {
+                let (mut expected, mut h) = (algo::IV, algo::IV);
+                algo::compress(rounds, &mut expected, m, [3, 5], f);
+                algo::compress_unrolled(rounds, &mut h, m, [3, 5], f);
+                assert_eq!(h, expected, "{rounds} rounds");
+            }
+        }
+    }
 }
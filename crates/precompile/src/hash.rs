@@ -3,12 +3,14 @@ use crate::{Error, Precompile, PrecompileResult, PrecompileWithAddress};
 use revm_primitives::{Bytes, PrecompileOutput};
 use sha2::Digest;
 
-pub const SHA256: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(2), Precompile::Standard(sha256_run));
+pub const SHA256: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(2),
+    Precompile::Metered(sha256_run, sha256_gas),
+);
 
 pub const RIPEMD160: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(3),
-    Precompile::Standard(ripemd160_run),
+    Precompile::Metered(ripemd160_run, ripemd160_gas),
 );
 
 /// Computes the SHA-256 hash of the input data.
@@ -18,15 +20,20 @@ pub const RIPEMD160: PrecompileWithAddress = PrecompileWithAddress(
 /// - [Solidity Documentation on Mathematical and Cryptographic Functions](https://docs.soliditylang.org/en/develop/units-and-global-variables.html#mathematical-and-cryptographic-functions)
 /// - [Address 0x02](https://etherscan.io/address/0000000000000000000000000000000000000002)
 pub fn sha256_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    let cost = calc_linear_cost_u32(input.len(), 60, 12);
+    let cost = sha256_gas(input);
     if cost > gas_limit {
         Err(Error::OutOfGas.into())
     } else {
         let output = sha2::Sha256::digest(input);
-        Ok(PrecompileOutput::new(cost, output.to_vec().into()))
+        Ok(PrecompileOutput::new(cost, Bytes::copy_from_slice(&output)))
     }
 }
 
+/// Returns the gas required to compute the SHA-256 hash of `input`.
+pub fn sha256_gas(input: &[u8]) -> u64 {
+    calc_linear_cost_u32(input.len(), 60, 12)
+}
+
 /// Computes the RIPEMD-160 hash of the input data.
 ///
 /// This function follows specifications defined in the following references:
@@ -34,7 +41,7 @@ pub fn sha256_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
 /// - [Solidity Documentation on Mathematical and Cryptographic Functions](https://docs.soliditylang.org/en/develop/units-and-global-variables.html#mathematical-and-cryptographic-functions)
 /// - [Address 03](https://etherscan.io/address/0000000000000000000000000000000000000003)
 pub fn ripemd160_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    let gas_used = calc_linear_cost_u32(input.len(), 600, 120);
+    let gas_used = ripemd160_gas(input);
     if gas_used > gas_limit {
         Err(Error::OutOfGas.into())
     } else {
@@ -43,6 +50,40 @@ pub fn ripemd160_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
 
         let mut output = [0u8; 32];
         hasher.finalize_into((&mut output[12..]).into());
-        Ok(PrecompileOutput::new(gas_used, output.to_vec().into()))
+        Ok(PrecompileOutput::new(
+            gas_used,
+            Bytes::copy_from_slice(&output),
+        ))
+    }
+}
+
+/// Returns the gas required to compute the RIPEMD-160 hash of `input`.
+pub fn ripemd160_gas(input: &[u8]) -> u64 {
+    calc_linear_cost_u32(input.len(), 600, 120)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::hex;
+
+    #[test]
+    fn hashes() {
+        let input = Bytes::from_static(b"abc");
+        let output = sha256_run(&input, u64::MAX).unwrap();
+        assert_eq!(output.gas_used, 72);
+        assert_eq!(
+            output.bytes[..],
+            hex!("ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad")
+        );
+
+        let output = ripemd160_run(&input, u64::MAX).unwrap();
+        assert_eq!(output.gas_used, 720);
+        assert_eq!(
+            output.bytes[..],
+            hex!("0000000000000000000000008eb208f7e05d987a9b044a8e98c6b087f15a0bfc")
+        );
+
+        assert_eq!(sha256_run(&input, 71).unwrap_err(), Error::OutOfGas.into());
     }
 }
@@ -2,22 +2,50 @@ use super::calc_linear_cost_u32;
 use crate::{Error, Precompile, PrecompileResult, PrecompileWithAddress};
 use revm_primitives::{Bytes, PrecompileOutput};
 
-pub const FUN: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(4), Precompile::Standard(identity_run));
+pub const FUN: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(4),
+    Precompile::Metered(identity_run, identity_gas),
+);
 
 /// The base cost of the operation.
 pub const IDENTITY_BASE: u64 = 15;
 /// The cost per word.
 pub const IDENTITY_PER_WORD: u64 = 3;
 
-/// Takes the input bytes, copies them, and returns it as the output.
+/// Takes the input bytes and returns them as the output.
+///
+/// The output shares the buffer of the input, so the input is not copied.
 ///
 /// See: <https://ethereum.github.io/yellowpaper/paper.pdf>
 /// See: <https://etherscan.io/address/0000000000000000000000000000000000000004>
 pub fn identity_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    let gas_used = calc_linear_cost_u32(input.len(), IDENTITY_BASE, IDENTITY_PER_WORD);
+    let gas_used = identity_gas(input);
     if gas_used > gas_limit {
         return Err(Error::OutOfGas.into());
     }
     Ok(PrecompileOutput::new(gas_used, input.clone()))
 }
+
+/// Returns the gas required to copy `input`.
+pub fn identity_gas(input: &[u8]) -> u64 {
+    calc_linear_cost_u32(input.len(), IDENTITY_BASE, IDENTITY_PER_WORD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_shares_input() {
+        let input = Bytes::from(vec![0xab; 100]);
+        let output = identity_run(&input, u64::MAX).unwrap();
+        assert_eq!(output.gas_used, 27);
+        assert_eq!(output.bytes, input);
+        assert_eq!(output.bytes.as_ptr(), input.as_ptr());
+
+        assert_eq!(
+            identity_run(&input, 26).unwrap_err(),
+            Error::OutOfGas.into()
+        );
+    }
+}
@@ -66,6 +66,15 @@ impl Precompiles {
         }
     }
 
+    /// Returns the precompiles active at the given [`SpecId`](primitives::SpecId).
+    ///
+    /// Precompile sets are built once on first use and shared afterwards, so this is cheap to
+    /// call for every EVM instance or transaction.
+    #[inline]
+    pub fn from_spec_id(spec_id: primitives::SpecId) -> &'static Self {
+        Self::new(PrecompileSpecId::from_spec_id(spec_id))
+    }
+
     /// Returns precompiles for Homestead spec.
     pub fn homestead() -> &'static Self {
         static INSTANCE: OnceBox<Precompiles> = OnceBox::new();
@@ -208,6 +217,13 @@ impl Precompiles {
         self.inner.get(address)
     }
 
+    /// Returns the gas the precompile at `address` requires for `input`, if it is a
+    /// precompile and the gas is known without executing it.
+    #[inline]
+    pub fn required_gas(&self, address: &Address, input: &[u8]) -> Option<u64> {
+        self.get(address)?.required_gas(input)
+    }
+
     /// Returns the precompile for the given address.
     #[inline]
     pub fn get_mut(&mut self, address: &Address) -> Option<&mut Precompile> {
@@ -309,3 +325,54 @@ pub const fn u64_to_address(x: u64) -> Address {
         0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, x[0], x[1], x[2], x[3], x[4], x[5], x[6], x[7],
     ])
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::{hex, CfgEnv};
+
+    #[test]
+    fn required_gas_matches_execution() {
+        // Empty input, a blake2f input with 12 rounds, and a modexp of 3^5 mod 7.
+        let mut blake2 = [0u8; 213];
+        blake2[3] = 12;
+        let modexp = hex!(
+            "0000000000000000000000000000000000000000000000000000000000000001
+             0000000000000000000000000000000000000000000000000000000000000001
+             0000000000000000000000000000000000000000000000000000000000000001
+             030507"
+        );
+        let inputs = [Bytes::new(), Bytes::from(blake2), Bytes::from(modexp)];
+
+        let env = CfgEnv::default();
+        for spec in [PrecompileSpecId::BYZANTIUM, PrecompileSpecId::LATEST] {
+            let precompiles = Precompiles::new(spec);
+            for address in precompiles.addresses() {
+                let precompile = precompiles.get(address).unwrap();
+                for input in &inputs {
+                    let Some(required) = precompiles.required_gas(address, input) else {
+                        continue;
+                    };
+                    if let Ok(output) = precompile.call_ref(input, u64::MAX, &env) {
+                        assert_eq!(output.gas_used, required, "{address} {input}");
+                    }
+                    if let Some(limit) = required.checked_sub(1) {
+                        assert_eq!(
+                            precompile.call_ref(input, limit, &env),
+                            Err(Error::OutOfGas.into()),
+                            "{address} {input}"
+                        );
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            Precompiles::latest().required_gas(&u64_to_address(9), &blake2),
+            Some(12)
+        );
+        assert_eq!(
+            Precompiles::latest().required_gas(&u64_to_address(1), &[]),
+            Some(3_000)
+        );
+    }
+}
@@ -9,11 +9,13 @@ use revm_primitives::{Bytes, PrecompileOutput};
 
 pub const BYZANTIUM: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(5),
-    Precompile::Standard(byzantium_run),
+    Precompile::Metered(byzantium_run, byzantium_required_gas),
 );
 
-pub const BERLIN: PrecompileWithAddress =
-    PrecompileWithAddress(crate::u64_to_address(5), Precompile::Standard(berlin_run));
+pub const BERLIN: PrecompileWithAddress = PrecompileWithAddress(
+    crate::u64_to_address(5),
+    Precompile::Metered(berlin_run, berlin_required_gas),
+);
 
 /// See: <https://eips.ethereum.org/EIPS/eip-198>
 /// See: <https://etherscan.io/address/0000000000000000000000000000000000000005>
@@ -29,6 +31,94 @@ pub fn berlin_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     })
 }
 
+/// Returns the gas required by [byzantium_run] for `input`.
+pub fn byzantium_required_gas(input: &[u8]) -> u64 {
+    required_gas(input, 0, byzantium_gas_calc)
+}
+
+/// Returns the gas required by [berlin_run] for `input`.
+pub fn berlin_required_gas(input: &[u8]) -> u64 {
+    required_gas(input, 200, berlin_gas_calc)
+}
+
+/// Returns the gas required for `input`.
+fn required_gas(input: &[u8], min_gas: u64, calc_gas: fn(u64, u64, u64, &U256) -> u64) -> u64 {
+    let header = read_header(input);
+    if header.is_empty() {
+        return min_gas;
+    }
+    max(min_gas, header.gas(calc_gas))
+}
+
+/// Lengths of the values of a modexp input, and the leading bytes of the exponent.
+///
+/// Lengths that do not fit in a `u64` are saturated, the gas of such inputs is saturated as
+/// well.
+struct Header {
+    base_len: u64,
+    exp_len: u64,
+    mod_len: u64,
+    exp_highp: U256,
+}
+
+impl Header {
+    /// Returns `true` if both the base and the modulus are empty, so the output is empty.
+    fn is_empty(&self) -> bool {
+        self.base_len == 0 && self.mod_len == 0
+    }
+
+    /// Returns the gas of the input.
+    fn gas<F>(&self, calc_gas: F) -> u64
+    where
+        F: FnOnce(u64, u64, u64, &U256) -> u64,
+    {
+        calc_gas(self.base_len, self.exp_len, self.mod_len, &self.exp_highp)
+    }
+}
+
+/// Length of the header of the input.
+///
+/// The format of input is:
+/// <length_of_BASE> <length_of_EXPONENT> <length_of_MODULUS> <BASE> <EXPONENT> <MODULUS>
+/// Where every length is a 32-byte left-padded integer representing the number of bytes
+/// to be taken up by the next value
+const HEADER_LENGTH: usize = 96;
+
+/// Reads the header of `input` and the leading bytes of the exponent.
+///
+/// Never fails, missing bytes are zero as if the input was right padded.
+fn read_header(input: &[u8]) -> Header {
+    let read_len = |offset| {
+        U256::from_be_bytes(right_pad_with_offset::<32>(input, offset).into_owned())
+            .saturating_to::<u64>()
+    };
+    let base_len = read_len(0);
+    let exp_len = read_len(32);
+    let mod_len = read_len(64);
+
+    // Used to extract ADJUSTED_EXPONENT_LENGTH.
+    let exp_highp_len = min(exp_len, 32) as usize;
+
+    // Throw away the header data as we already extracted lengths.
+    let input = input.get(HEADER_LENGTH..).unwrap_or_default();
+
+    let exp_highp = {
+        // get right padded bytes so if data.len is less then exp_len we will get right padded zeroes.
+        let offset = usize::try_from(base_len).unwrap_or(usize::MAX);
+        let right_padded_highp = right_pad_with_offset::<32>(input, offset);
+        // If exp_len is less then 32 bytes get only exp_len bytes and do left padding.
+        let out = left_pad::<32>(&right_padded_highp[..exp_highp_len]);
+        U256::from_be_bytes(out.into_owned())
+    };
+
+    Header {
+        base_len,
+        exp_len,
+        mod_len,
+        exp_highp,
+    }
+}
+
 pub fn calculate_iteration_count(exp_length: u64, exp_highp: &U256) -> u64 {
     let mut iteration_count: u64 = 0;
 
@@ -44,6 +134,11 @@ pub fn calculate_iteration_count(exp_length: u64, exp_highp: &U256) -> u64 {
     max(iteration_count, 1)
 }
 
+/// Runs the modexp of `input`, charging the gas computed by `calc_gas` and at least `min_gas`.
+///
+/// The gas is charged before the values are read, so the size of the values is bounded by
+/// the gas limit. Lengths that pass the gas check but do not fit in the address space fail
+/// with an overflow error instead of panicking.
 pub fn run_inner<F>(input: &[u8], gas_limit: u64, min_gas: u64, calc_gas: F) -> PrecompileResult
 where
     F: FnOnce(u64, u64, u64, &U256) -> u64,
@@ -53,57 +148,41 @@ where
         return Err(Error::OutOfGas.into());
     }
 
-    // The format of input is:
-    // <length_of_BASE> <length_of_EXPONENT> <length_of_MODULUS> <BASE> <EXPONENT> <MODULUS>
-    // Where every length is a 32-byte left-padded integer representing the number of bytes
-    // to be taken up by the next value
-    const HEADER_LENGTH: usize = 96;
+    let header = read_header(input);
+
+    // Handle a special case when both the base and mod length are zero.
+    if header.is_empty() {
+        return Ok(PrecompileOutput::new(min_gas, Bytes::new()));
+    }
 
-    // Extract the header.
-    let base_len = U256::from_be_bytes(right_pad_with_offset::<32>(input, 0).into_owned());
-    let exp_len = U256::from_be_bytes(right_pad_with_offset::<32>(input, 32).into_owned());
-    let mod_len = U256::from_be_bytes(right_pad_with_offset::<32>(input, 64).into_owned());
+    // Check if we have enough gas. Saturated gas can not be paid with any gas limit.
+    let gas_cost = header.gas(calc_gas);
+    if gas_cost > gas_limit || gas_cost == u64::MAX {
+        return Err(Error::OutOfGas.into());
+    }
 
-    // cast base and modulus to usize, it does not make sense to handle larger values
-    let Ok(base_len) = usize::try_from(base_len) else {
+    // Lengths are bounded by the gas, they can only overflow on 32-bit targets.
+    let Ok(base_len) = usize::try_from(header.base_len) else {
         return Err(Error::ModexpBaseOverflow.into());
     };
-    let Ok(mod_len) = usize::try_from(mod_len) else {
+    let Ok(exp_len) = usize::try_from(header.exp_len) else {
+        return Err(Error::ModexpExpOverflow.into());
+    };
+    let Ok(mod_len) = usize::try_from(header.mod_len) else {
         return Err(Error::ModexpModOverflow.into());
     };
-
-    // Handle a special case when both the base and mod length are zero.
-    if base_len == 0 && mod_len == 0 {
-        return Ok(PrecompileOutput::new(min_gas, Bytes::new()));
-    }
-
-    // Cast exponent length to usize, since it does not make sense to handle larger values.
-    let Ok(exp_len) = usize::try_from(exp_len) else {
+    let Some(input_len) = base_len
+        .checked_add(exp_len)
+        .and_then(|len| len.checked_add(mod_len))
+        .filter(|&len| len <= isize::MAX as usize)
+    else {
         return Err(Error::ModexpModOverflow.into());
     };
 
-    // Used to extract ADJUSTED_EXPONENT_LENGTH.
-    let exp_highp_len = min(exp_len, 32);
-
     // Throw away the header data as we already extracted lengths.
     let input = input.get(HEADER_LENGTH..).unwrap_or_default();
 
-    let exp_highp = {
-        // get right padded bytes so if data.len is less then exp_len we will get right padded zeroes.
-        let right_padded_highp = right_pad_with_offset::<32>(input, base_len);
-        // If exp_len is less then 32 bytes get only exp_len bytes and do left padding.
-        let out = left_pad::<32>(&right_padded_highp[..exp_highp_len]);
-        U256::from_be_bytes(out.into_owned())
-    };
-
-    // Check if we have enough gas.
-    let gas_cost = calc_gas(base_len as u64, exp_len as u64, mod_len as u64, &exp_highp);
-    if gas_cost > gas_limit {
-        return Err(Error::OutOfGas.into());
-    }
-
     // Padding is needed if the input does not contain all 3 values.
-    let input_len = base_len.saturating_add(exp_len).saturating_add(mod_len);
     let input = right_pad_vec(input, input_len);
     let (base, input) = input.split_at(base_len);
     let (exponent, modulus) = input.split_at(exp_len);
@@ -382,4 +461,29 @@ mod tests {
         let expected: Vec<u8> = Vec::new();
         assert_eq!(res.bytes, expected)
     }
+
+    #[test]
+    fn test_modexp_huge_lengths() {
+        let header = |base_len: &str, exp_len: &str, mod_len: &str| -> Bytes {
+            hex::decode(format!("{base_len:0>64}{exp_len:0>64}{mod_len:0>64}"))
+                .unwrap()
+                .into()
+        };
+        let max = "f".repeat(64);
+
+        // Lengths that do not fit in memory are charged before they are read. They can not be
+        // paid for with a realistic gas limit, and are rejected without panicking otherwise.
+        for input in [
+            header(&max, "1", "1"),
+            header("1", &max, "1"),
+            header("1", "1", &max),
+            header("1", "ffffffffffffffff", "1"),
+        ] {
+            for run in [byzantium_run, berlin_run] {
+                assert_eq!(run(&input, 30_000_000), Err(Error::OutOfGas.into()));
+                assert!(run(&input, u64::MAX).is_err());
+            }
+        }
+        assert_eq!(berlin_required_gas(&header(&max, "1", "1")), u64::MAX);
+    }
 }
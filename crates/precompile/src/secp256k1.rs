@@ -1,9 +1,15 @@
+//! The `ecrecover` precompile.
+//!
+//! The public key is recovered with `k256`, a pure Rust implementation that supports `no_std`,
+//! or with the faster native `libsecp256k1` when the `secp256k1` feature is enabled.
+
 use crate::{utilities::right_pad, Error, Precompile, PrecompileResult, PrecompileWithAddress};
 use revm_primitives::{alloy_primitives::B512, Bytes, PrecompileOutput, B256};
+use std::vec::Vec;
 
 pub const ECRECOVER: PrecompileWithAddress = PrecompileWithAddress(
     crate::u64_to_address(1),
-    Precompile::Standard(ec_recover_run),
+    Precompile::Metered(ec_recover_run, |_| ECRECOVER_BASE),
 );
 
 pub use self::secp256k1::ecrecover;
@@ -46,7 +52,7 @@ mod secp256k1 {
     use revm_primitives::{alloy_primitives::B512, keccak256, B256};
     use secp256k1::{
         ecdsa::{RecoverableSignature, RecoveryId},
-        Message, Secp256k1,
+        Message, SECP256K1,
     };
 
     // Silence the unused crate dependency warning.
@@ -56,9 +62,9 @@ mod secp256k1 {
         let recid = RecoveryId::from_i32(recid as i32).expect("recovery ID is valid");
         let sig = RecoverableSignature::from_compact(sig.as_slice(), recid)?;
 
-        let secp = Secp256k1::new();
+        // The global context is shared by all recovers instead of being created for each one.
         let msg = Message::from_digest(msg.0);
-        let public = secp.recover_ecdsa(&msg, &sig)?;
+        let public = SECP256K1.recover_ecdsa(&msg, &sig)?;
 
         let mut hash = keccak256(&public.serialize_uncompressed()[1..]);
         hash[..12].fill(0);
@@ -66,9 +72,22 @@ mod secp256k1 {
     }
 }
 
-pub fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
-    const ECRECOVER_BASE: u64 = 3_000;
+/// Recovers the addresses of a batch of signatures, such as the senders of the transactions
+/// of a block, given as `(signature, recovery id, message hash)`.
+///
+/// The address is left padded to 32 bytes like the output of [`ecrecover`], and is `None` for
+/// invalid signatures.
+pub fn ecrecover_batch(signatures: &[(B512, u8, B256)]) -> Vec<Option<B256>> {
+    signatures
+        .iter()
+        .map(|(sig, recid, msg)| ecrecover(sig, *recid, msg).ok())
+        .collect()
+}
+
+/// Gas cost of the `ecrecover` precompile.
+pub const ECRECOVER_BASE: u64 = 3_000;
 
+pub fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
     if ECRECOVER_BASE > gas_limit {
         return Err(Error::OutOfGas.into());
     }
@@ -89,3 +108,26 @@ pub fn ec_recover_run(input: &Bytes, gas_limit: u64) -> PrecompileResult {
         .unwrap_or_default();
     Ok(PrecompileOutput::new(ECRECOVER_BASE, out))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm_primitives::{address, b256, hex};
+
+    #[test]
+    fn batch_matches_precompile() {
+        let msg = b256!("456e9aea5e197a1f1af7a3e85a3212fa4049a3ba34c2289b4c860fc0b0c64ef3");
+        let sig = B512::from(hex!(
+            "9242685bf161793cc25603c231bc2f568eb630ea16aa137d2664ac8038825608"
+            "4f8ae3bd7535248d0bd448298cc2e2071e56992d0774dc340c368ae950852ada"
+        ));
+        let expected = address!("7156526fbd7a3c72969b54f64e42c10fbb768c8a").into_word();
+
+        let recovered = ecrecover_batch(&[(sig, 1, msg), (B512::ZERO, 0, msg)]);
+        assert_eq!(recovered, [Some(expected), None]);
+
+        let input = [&msg[..], &B256::with_last_byte(28)[..], &sig[..]].concat();
+        let output = ec_recover_run(&input.into(), ECRECOVER_BASE).unwrap();
+        assert_eq!(output.bytes[..], expected[..]);
+    }
+}
@@ -19,8 +19,10 @@ pub fn precompiles() -> impl Iterator<Item = PrecompileWithAddress> {
 }
 
 /// [EIP-7212](https://eips.ethereum.org/EIPS/eip-7212#specification) secp256r1 precompile.
-pub const P256VERIFY: PrecompileWithAddress =
-    PrecompileWithAddress(u64_to_address(0x100), Precompile::Standard(p256_verify));
+pub const P256VERIFY: PrecompileWithAddress = PrecompileWithAddress(
+    u64_to_address(0x100),
+    Precompile::Metered(p256_verify, |_| P256VERIFY_BASE),
+);
 
 /// secp256r1 precompile logic. It takes the input bytes sent to the precompile
 /// and the gas limit. The output represents the result of verifying the
@@ -0,0 +1,106 @@
+use crate::SpecId;
+
+/// Fork-dependent rules of transaction validation and finalization.
+///
+/// Rules are derived once from the [SpecId] with [ChainRules::new] and passed explicitly to
+/// the validation and post execution handles. Chains with custom hardforks can override
+/// individual rules instead of replacing the handles that use them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ChainRules {
+    /// EIP-3607: Reject transactions from senders with deployed code.
+    ///
+    /// The EIP was introduced after London, but as there was no collision in the past it is
+    /// enabled for all forks. It can still be disabled with `CfgEnv::disable_eip3607`.
+    pub reject_caller_with_code: bool,
+    /// EIP-1559: Check the priority fee against the max fee and the gas price against the
+    /// block base fee.
+    pub eip1559_fee_checks: bool,
+    /// Whether the gas refund counter is applied at the end of the transaction.
+    ///
+    /// Chains without refunds can disable it, see EIP-3298.
+    pub gas_refunds: bool,
+    /// Maximum refund is the gas spent divided by this quotient.
+    ///
+    /// EIP-3529 reduced the maximum refund from a half to a fifth of the gas spent.
+    pub max_refund_quotient: u64,
+    /// Base gas cost of a transaction that is not a contract creation.
+    pub tx_base_gas: u64,
+    /// Gas cost of a zero byte of transaction data.
+    pub tx_data_zero_gas: u64,
+    /// Gas cost of a non-zero byte of transaction data.
+    ///
+    /// EIP-2028 reduced it from 68 to 16 in Istanbul.
+    pub tx_data_non_zero_gas: u64,
+    /// Base gas cost of a contract creation transaction.
+    ///
+    /// EIP-2 increased it from 21000 to 53000 in Homestead.
+    pub create_tx_base_gas: u64,
+}
+
+impl ChainRules {
+    /// Returns the rules of the given spec.
+    pub const fn new(spec_id: SpecId) -> Self {
+        let is_london = spec_id.is_enabled_in(SpecId::LONDON);
+        Self {
+            reject_caller_with_code: true,
+            eip1559_fee_checks: is_london,
+            gas_refunds: true,
+            max_refund_quotient: if is_london { 5 } else { 2 },
+            tx_base_gas: 21000,
+            tx_data_zero_gas: 4,
+            tx_data_non_zero_gas: if spec_id.is_enabled_in(SpecId::ISTANBUL) {
+                16
+            } else {
+                68
+            },
+            create_tx_base_gas: if spec_id.is_enabled_in(SpecId::HOMESTEAD) {
+                53000
+            } else {
+                21000
+            },
+        }
+    }
+}
+
+impl Default for ChainRules {
+    fn default() -> Self {
+        Self::new(SpecId::LATEST)
+    }
+}
+
+impl From<SpecId> for ChainRules {
+    fn from(spec_id: SpecId) -> Self {
+        Self::new(spec_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rules_of_forks() {
+        let frontier = ChainRules::new(SpecId::FRONTIER);
+        assert!(frontier.reject_caller_with_code);
+        assert!(!frontier.eip1559_fee_checks);
+        assert!(frontier.gas_refunds);
+        assert_eq!(frontier.max_refund_quotient, 2);
+        assert_eq!(frontier.create_tx_base_gas, 21000);
+        assert_eq!(frontier.tx_base_gas, 21000);
+        assert_eq!(frontier.tx_data_zero_gas, 4);
+        assert_eq!(frontier.tx_data_non_zero_gas, 68);
+
+        let homestead = ChainRules::new(SpecId::HOMESTEAD);
+        assert_eq!(homestead.create_tx_base_gas, 53000);
+        assert_eq!(homestead.max_refund_quotient, 2);
+
+        let istanbul = ChainRules::new(SpecId::ISTANBUL);
+        assert_eq!(istanbul.tx_data_non_zero_gas, 16);
+
+        let london = ChainRules::new(SpecId::LONDON);
+        assert!(london.eip1559_fee_checks);
+        assert_eq!(london.max_refund_quotient, 5);
+        assert_eq!(ChainRules::default(), ChainRules::new(SpecId::CANCUN));
+    }
+}
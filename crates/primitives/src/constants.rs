@@ -5,6 +5,21 @@ use alloy_primitives::{address, Address};
 /// By default the limit is `0x6000` (~25kb)
 pub const MAX_CODE_SIZE: usize = 0x6000;
 
+/// EVM interpreter stack limit.
+///
+/// Default of [`crate::CfgEnv::stack_limit`].
+pub const STACK_LIMIT: usize = 1024;
+
+/// EVM call stack limit.
+///
+/// Default of [`crate::CfgEnv::call_depth_limit`].
+pub const CALL_STACK_LIMIT: u64 = 1024;
+
+/// Gas given to the callee of a call that transfers value, on top of the forwarded gas.
+///
+/// Default of [`crate::CfgEnv::call_stipend`].
+pub const CALL_STIPEND: u64 = 2300;
+
 /// Number of block hashes that EVM can access in the past (pre-Prague).
 pub const BLOCK_HASH_HISTORY: u64 = 256;
 
@@ -26,6 +41,41 @@ pub const BLOCKHASH_SERVE_WINDOW: usize = 8192;
 /// This is named `HISTORY_STORAGE_ADDRESS` in the EIP.
 pub const BLOCKHASH_STORAGE_ADDRESS: Address = address!("25a219378dad9b3503c8268c9ca836a52427a4fb");
 
+/// Address of the caller of system calls, as used by EIP-4788, EIP-2935 and EIP-7002.
+pub const SYSTEM_ADDRESS: Address = address!("fffffffffffffffffffffffffffffffffffffffe");
+
+/// Gas limit of system calls, see [SYSTEM_ADDRESS].
+pub const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// EIP-7685: General purpose execution layer requests
+///
+/// Request type of deposits, defined by EIP-6110.
+pub const DEPOSIT_REQUEST_TYPE: u8 = 0x00;
+
+/// EIP-7002: Execution layer triggerable withdrawals
+///
+/// Request type of withdrawal requests.
+pub const WITHDRAWAL_REQUEST_TYPE: u8 = 0x01;
+
+/// EIP-7002: Execution layer triggerable withdrawals
+///
+/// The address of the withdrawal request contract, which is called by the system at the
+/// end of each block.
+pub const WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS: Address =
+    address!("00000961ef480eb55e80d19ad83579a64c007002");
+
+/// EIP-7251: Increase the MAX_EFFECTIVE_BALANCE
+///
+/// Request type of consolidation requests.
+pub const CONSOLIDATION_REQUEST_TYPE: u8 = 0x02;
+
+/// EIP-7251: Increase the MAX_EFFECTIVE_BALANCE
+///
+/// The address of the consolidation request contract, which is called by the system at the
+/// end of each block.
+pub const CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS: Address =
+    address!("0000bbddc7ce488642fb579f8b00f3a590007251");
+
 /// EIP-3860: Limit and meter initcode
 ///
 /// Limit of maximum initcode size is `2 * MAX_CODE_SIZE`.
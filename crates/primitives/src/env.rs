@@ -1,7 +1,9 @@
 use crate::{
-    calc_blob_gasprice, AccessListItem, Account, Address, AuthorizationList, Block, Bytes,
-    EvmWiring, InvalidHeader, InvalidTransaction, Spec, SpecId, Transaction, TransactionValidation,
-    B256, MAX_BLOB_NUMBER_PER_BLOCK, MAX_CODE_SIZE, MAX_INITCODE_SIZE, U256,
+    calc_blob_gasprice, calc_excess_blob_gas, calc_next_block_base_fee, eip7702::SECP256K1N_HALF,
+    AccessList, AccessListItem, Account, Address, AuthorizationList, BaseFeeParams, Block, Bytes,
+    ChainRules, EvmWiring, HashSet, InvalidHeader, InvalidTransaction, SignedTransaction, Spec,
+    SpecId, Transaction, TransactionValidation, B256, CALL_STACK_LIMIT, CALL_STIPEND,
+    MAX_BLOB_NUMBER_PER_BLOCK, MAX_CODE_SIZE, MAX_INITCODE_SIZE, STACK_LIMIT, U256,
     VERSIONED_HASH_VERSION_KZG,
 };
 use alloy_primitives::TxKind;
@@ -11,6 +13,9 @@ use core::hash::Hash;
 use std::boxed::Box;
 use std::vec::Vec;
 
+mod builder;
+pub use builder::{BlockEnvBuilder, TxEnvBuilder};
+
 /// Subtype
 pub type EnvWiring<EvmWiringT> =
     Env<<EvmWiringT as EvmWiring>::Block, <EvmWiringT as EvmWiring>::Transaction>;
@@ -45,6 +50,19 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         }
     }
 
+    /// Returns the gas limit available to the execution of the transaction.
+    ///
+    /// This is the transaction gas limit, or `u64::MAX` if
+    /// [`CfgEnv::disable_gas_metering`] is set.
+    #[inline]
+    pub fn effective_gas_limit(&self) -> u64 {
+        if self.cfg.is_gas_metering_disabled() {
+            u64::MAX
+        } else {
+            self.tx.gas_limit()
+        }
+    }
+
     /// Calculates the [EIP-4844] `data_fee` of the transaction.
     ///
     /// Returns `None` if `Cancun` is not enabled. This is enforced in [`Env::validate_block_env`].
@@ -70,15 +88,57 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         })
     }
 
+    /// Returns the maximum cost of the transaction to the caller: the gas limit at the max fee
+    /// per gas, the value and, since Cancun, the blob gas at the max fee per blob gas.
+    ///
+    /// Gas is not paid for if gas metering is disabled. Fails with
+    /// [`InvalidTransaction::OverflowPaymentInTransaction`] if the cost overflows.
+    #[inline]
+    pub fn max_tx_cost<SPEC: Spec>(&self) -> Result<U256, InvalidTransaction> {
+        self.max_tx_cost_with_spec(SPEC::SPEC_ID)
+    }
+
+    /// Returns the maximum cost of the transaction to the caller in the given spec, see
+    /// [`Env::max_tx_cost`].
+    pub fn max_tx_cost_with_spec(&self, spec_id: SpecId) -> Result<U256, InvalidTransaction> {
+        let value = *self.tx.value();
+        if self.cfg.is_gas_metering_disabled() {
+            return Ok(value);
+        }
+
+        const OVERFLOW: InvalidTransaction = InvalidTransaction::OverflowPaymentInTransaction;
+        let gas_cost = U256::from(self.tx.gas_limit())
+            .checked_mul(*self.tx.gas_price())
+            .ok_or(OVERFLOW)?;
+        let mut cost = gas_cost.checked_add(value).ok_or(OVERFLOW)?;
+        if spec_id.is_enabled_in(SpecId::CANCUN) {
+            // Only blob transactions have a max fee per blob gas.
+            if let Some(max_fee_per_blob_gas) = self.tx.max_fee_per_blob_gas() {
+                let blob_cost = max_fee_per_blob_gas
+                    .checked_mul(U256::from(self.tx.get_total_blob_gas()))
+                    .ok_or(OVERFLOW)?;
+                cost = cost.checked_add(blob_cost).ok_or(OVERFLOW)?;
+            }
+        }
+        Ok(cost)
+    }
+
     /// Validate the block environment.
     #[inline]
     pub fn validate_block_env<SPEC: Spec>(&self) -> Result<(), InvalidHeader> {
+        self.validate_block_env_with_spec(SPEC::SPEC_ID)
+    }
+
+    /// Validate the block environment in the given spec.
+    #[inline]
+    pub fn validate_block_env_with_spec(&self, spec_id: SpecId) -> Result<(), InvalidHeader> {
         // `prevrandao` is required for the merge
-        if SPEC::enabled(SpecId::MERGE) && self.block.prevrandao().is_none() {
+        if spec_id.is_enabled_in(SpecId::MERGE) && self.block.prevrandao().is_none() {
             return Err(InvalidHeader::PrevrandaoNotSet);
         }
         // `excess_blob_gas` is required for Cancun
-        if SPEC::enabled(SpecId::CANCUN) && self.block.blob_excess_gas_and_price().is_none() {
+        if spec_id.is_enabled_in(SpecId::CANCUN) && self.block.blob_excess_gas_and_price().is_none()
+        {
             return Err(InvalidHeader::ExcessBlobGasNotSet);
         }
         Ok(())
@@ -89,6 +149,25 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
     /// Return initial spend gas (Gas needed to execute transaction).
     #[inline]
     pub fn validate_tx<SPEC: Spec>(&self) -> Result<(), InvalidTransaction> {
+        self.validate_tx_with_rules::<SPEC>(&ChainRules::new(SPEC::SPEC_ID))
+    }
+
+    /// Validate transaction data that is set inside ENV against the given chain rules.
+    #[inline]
+    pub fn validate_tx_with_rules<SPEC: Spec>(
+        &self,
+        rules: &ChainRules,
+    ) -> Result<(), InvalidTransaction> {
+        self.validate_tx_with_spec(SPEC::SPEC_ID, rules)
+    }
+
+    /// Validate transaction data that is set inside ENV against the given spec and chain rules.
+    #[inline]
+    pub fn validate_tx_with_spec(
+        &self,
+        spec_id: SpecId,
+        rules: &ChainRules,
+    ) -> Result<(), InvalidTransaction> {
         // Check if the transaction's chain id is correct
         if let Some(tx_chain_id) = self.tx.chain_id() {
             if tx_chain_id != self.cfg.chain_id {
@@ -97,19 +176,23 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         }
 
         // Check if gas_limit is more than block_gas_limit
+        // System transactions do not count against the block gas limit.
         if !self.cfg.is_block_gas_limit_disabled()
+            && !self.tx.is_system()
+            && !self.cfg.is_gas_metering_disabled()
             && U256::from(self.tx.gas_limit()) > *self.block.gas_limit()
         {
             return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
         }
 
         // Check that access list is empty for transactions before BERLIN
-        if !SPEC::enabled(SpecId::BERLIN) && !self.tx.access_list().is_empty() {
+        if !spec_id.is_enabled_in(SpecId::BERLIN) && !self.tx.access_list().is_empty() {
             return Err(InvalidTransaction::AccessListNotSupported);
         }
 
         // BASEFEE tx check
-        if SPEC::enabled(SpecId::LONDON) {
+        // System transactions do not pay fees.
+        if rules.eip1559_fee_checks && !self.tx.is_system() {
             if let Some(priority_fee) = self.tx.max_priority_fee_per_gas() {
                 if priority_fee > self.tx.gas_price() {
                     // or gas_max_fee for eip1559
@@ -126,7 +209,7 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         }
 
         // EIP-3860: Limit and meter initcode
-        if SPEC::enabled(SpecId::SHANGHAI) && self.tx.kind().is_create() {
+        if spec_id.is_enabled_in(SpecId::SHANGHAI) && self.tx.kind().is_create() {
             let max_initcode_size = self
                 .cfg
                 .limit_contract_code_size
@@ -138,7 +221,7 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         }
 
         // - For before CANCUN, check that `blob_hashes` and `max_fee_per_blob_gas` are empty / not set
-        if !SPEC::enabled(SpecId::CANCUN)
+        if !spec_id.is_enabled_in(SpecId::CANCUN)
             && (self.tx.max_fee_per_blob_gas().is_some() || !self.tx.blob_hashes().is_empty())
         {
             return Err(InvalidTransaction::BlobVersionedHashesNotSupported);
@@ -189,7 +272,7 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         }
 
         // check if EIP-7702 transaction is enabled.
-        if !SPEC::enabled(SpecId::PRAGUE) && self.tx.authorization_list().is_some() {
+        if !spec_id.is_enabled_in(SpecId::PRAGUE) && self.tx.authorization_list().is_some() {
             return Err(InvalidTransaction::AuthorizationListNotSupported);
         }
 
@@ -221,10 +304,44 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
         &self,
         account: &mut Account,
     ) -> Result<(), InvalidTransaction> {
+        self.validate_tx_against_state_with_rules::<SPEC>(account, &ChainRules::new(SPEC::SPEC_ID))
+    }
+
+    /// Validate transaction against state with the given chain rules.
+    ///
+    /// # Panics
+    ///
+    /// If account code is not loaded.
+    #[inline]
+    pub fn validate_tx_against_state_with_rules<SPEC: Spec>(
+        &self,
+        account: &mut Account,
+        rules: &ChainRules,
+    ) -> Result<(), InvalidTransaction> {
+        self.validate_tx_against_state_with_spec(SPEC::SPEC_ID, account, rules)
+    }
+
+    /// Validate transaction against state with the given spec and chain rules.
+    ///
+    /// # Panics
+    ///
+    /// If account code is not loaded.
+    #[inline]
+    pub fn validate_tx_against_state_with_spec(
+        &self,
+        spec_id: SpecId,
+        account: &mut Account,
+        rules: &ChainRules,
+    ) -> Result<(), InvalidTransaction> {
+        // System transactions are not signed by the caller and do not pay for gas.
+        if self.tx.is_system() {
+            return Ok(());
+        }
+
+        let is_impersonated = self.cfg.is_impersonated(self.tx.caller());
+
         // EIP-3607: Reject transactions from senders with deployed code
-        // This EIP is introduced after london but there was no collision in past
-        // so we can leave it enabled always
-        if !self.cfg.is_eip3607_disabled() {
+        if rules.reject_caller_with_code && !self.cfg.is_eip3607_disabled() && !is_impersonated {
             let bytecode = &account.info.code.as_ref().unwrap();
             // allow EOAs whose code is a valid delegation designation,
             // i.e. 0xef0100 || address, to continue to originate transactions.
@@ -233,9 +350,12 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
             }
         }
 
-        // Check that the transaction's nonce is correct
-        if !self.cfg.is_nonce_check_disabled() {
-            let tx = self.tx.nonce();
+        // Check that the transaction's nonce is correct, if it has one
+        if let Some(tx) = self
+            .tx
+            .nonce()
+            .filter(|_| !self.cfg.is_nonce_check_disabled() && !is_impersonated)
+        {
             let state = account.info.nonce;
             match tx.cmp(&state) {
                 Ordering::Greater => {
@@ -248,20 +368,10 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
             }
         }
 
-        let mut balance_check = U256::from(self.tx.gas_limit())
-            .checked_mul(*self.tx.gas_price())
-            .and_then(|gas_cost| gas_cost.checked_add(*self.tx.value()))
-            .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
+        // Gas is not paid for if gas metering is disabled.
+        let balance_check = self.max_tx_cost_with_spec(spec_id)?;
 
-        if SPEC::enabled(SpecId::CANCUN) {
-            // if the tx is not a blob tx, this will be None, so we add zero
-            let data_fee = self.calc_max_data_fee().unwrap_or_default();
-            balance_check = balance_check
-                .checked_add(U256::from(data_fee))
-                .ok_or(InvalidTransaction::OverflowPaymentInTransaction)?;
-        }
-
-        // Check if account has enough balance for gas_limit*gas_price and value transfer.
+        // Check if account has enough balance for the gas, the blob gas and the value transfer.
         // Transfer will be done inside `*_inner` functions.
         if balance_check > account.info.balance {
             if self.cfg.is_balance_check_disabled() {
@@ -279,6 +389,60 @@ impl<BlockT: Block, TxT: Transaction> Env<BlockT, TxT> {
     }
 }
 
+impl<BlockT: Block, TxT: SignedTransaction> Env<BlockT, TxT> {
+    /// Recovers the signer of the transaction and checks it against the caller.
+    ///
+    /// If the caller is not set (zero address), it is set to the recovered signer.
+    ///
+    /// The signature `s` value is checked according to EIP-2, and the chain ID of
+    /// replay-protected legacy signatures is checked according to EIP-155.
+    ///
+    /// The signature of [impersonated](CfgEnv::impersonated_accounts) callers is not checked.
+    #[inline]
+    pub fn validate_signature<SPEC: Spec>(&mut self) -> Result<(), InvalidTransaction> {
+        self.validate_signature_with_spec(SPEC::SPEC_ID)
+    }
+
+    /// Recovers the signer of the transaction and checks it against the caller in the given
+    /// spec, see [`Env::validate_signature`].
+    #[inline]
+    pub fn validate_signature_with_spec(
+        &mut self,
+        spec_id: SpecId,
+    ) -> Result<(), InvalidTransaction> {
+        if self.cfg.is_impersonated(self.tx.caller()) {
+            return Ok(());
+        }
+
+        let signature = self.tx.signature();
+
+        // EIP-2: All transaction signatures whose s-value is greater than secp256k1n/2 are
+        // considered invalid.
+        if spec_id.is_enabled_in(SpecId::HOMESTEAD) && signature.s() > SECP256K1N_HALF {
+            return Err(InvalidTransaction::InvalidSignature);
+        }
+
+        // EIP-155: Simple replay attack protection
+        if let Some(chain_id) = signature.v().chain_id() {
+            if !spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON) || chain_id != self.cfg.chain_id {
+                return Err(InvalidTransaction::InvalidChainId);
+            }
+        }
+
+        let signer = signature
+            .recover_address_from_prehash(&self.tx.signature_hash())
+            .map_err(|_| InvalidTransaction::InvalidSignature)?;
+
+        let caller = *self.tx.caller();
+        if caller == Address::ZERO {
+            self.tx.set_caller(signer);
+        } else if caller != signer {
+            return Err(InvalidTransaction::SignerMismatch);
+        }
+        Ok(())
+    }
+}
+
 impl<BlockT: Block + Default, TxT: Transaction + Default> Env<BlockT, TxT> {
     /// Resets environment to default values.
     #[inline]
@@ -311,6 +475,81 @@ pub struct CfgEnv {
     /// [`crate::InvalidTransaction::NonceTooHigh`] and
     /// [`crate::InvalidTransaction::NonceTooLow`]
     pub disable_nonce_check: bool,
+    /// Senders whose transactions are accepted as if they were signed by them.
+    ///
+    /// For these senders, EIP-3607 is not enforced, so contracts can send transactions,
+    /// the nonce of the transaction is not validated against the account's nonce, and the
+    /// signature of the transaction is not checked against the sender. Other senders are
+    /// validated as usual. By default, the set is empty.
+    pub impersonated_accounts: HashSet<Address>,
+    /// Addresses that are warm at the start of every transaction, in addition to the
+    /// precompiles, the access list and the addresses warmed by the spec.
+    ///
+    /// Chains with system contracts that are always warm, for example predeploys, list them
+    /// here so that accesses are charged as warm since EIP-2929. By default, the set is empty.
+    pub warm_addresses: HashSet<Address>,
+    /// Maximum number of words on the stack of a frame, exceeding it halts the frame with a
+    /// stack overflow.
+    ///
+    /// By default it is [`STACK_LIMIT`] (`1024`), which is also the maximum, larger values
+    /// are capped to it.
+    pub stack_limit: usize,
+    /// Maximum call depth, calls and creates beyond it fail with a call too deep error.
+    ///
+    /// By default it is [`CALL_STACK_LIMIT`] (`1024`).
+    pub call_depth_limit: u64,
+    /// Gas added to the gas limit of `CALL` and `CALLCODE` when they transfer value.
+    ///
+    /// By default it is [`CALL_STIPEND`] (`2300`). The EIP-2200 `SSTORE` sentry is not
+    /// affected and stays at `2300`.
+    pub call_stipend: u64,
+    /// Executes transactions without gas accounting.
+    ///
+    /// The transaction gas limit is ignored and the execution is given `u64::MAX` gas. No gas
+    /// fee is charged to the caller or paid to the beneficiary, and no refund is calculated.
+    /// The reported gas used is the gas the execution would have spent.
+    ///
+    /// As memory expansion is not bound by gas anymore, it is recommended to also set a
+    /// memory limit. By default, it is set to `false`.
+    pub disable_gas_metering: bool,
+    /// Maximum number of instructions executed by a transaction, across all of its frames.
+    ///
+    /// Exceeding it halts the execution with [`crate::HaltReason::ExecutionBudgetExceeded`].
+    /// This bounds the runtime of untrusted calls independently of their gas limit. By
+    /// default there is no limit.
+    pub instruction_limit: Option<u64>,
+    /// Maximum wall-clock time of the execution of a transaction.
+    ///
+    /// Exceeding it halts the execution with [`crate::HaltReason::ExecutionBudgetExceeded`].
+    /// Only enforced with the `std` feature. By default there is no limit.
+    pub execution_timeout: Option<core::time::Duration>,
+    /// Executes legacy bytecode of calls with fused instructions, which dispatch common
+    /// instruction sequences like `PUSH1 x JUMP` or `DUPn SWAPm` at once.
+    ///
+    /// Execution is unchanged otherwise, but a fused sequence counts as a single instruction
+    /// for [`Self::instruction_limit`]. Fused bytecode is cached by code hash for the lifetime
+    /// of the context. It is not used when an inspector is attached. By default, it is set to
+    /// `false`.
+    pub fuse_instructions: bool,
+    /// Starts each transaction with the accounts and storage slots accessed by the previous
+    /// transaction executed with this flag warm, as if they were in its access list but
+    /// without the intrinsic cost of the list.
+    ///
+    /// This approximates the gas of a transaction whose accesses are already warm, for
+    /// example when the same transaction is simulated repeatedly in a block-building loop.
+    /// The accesses are recorded by the context, which must be cleared before simulating a
+    /// different transaction. Disabling the flag gives the exact gas of the spec again,
+    /// without discarding the accesses. By default, it is set to `false`.
+    pub reuse_warm_accesses: bool,
+    /// Executes transactions as if they were wrapped in a `STATICCALL`, like `eth_call`.
+    ///
+    /// Any attempt to change the state, with `SSTORE`, `TSTORE`, `LOG`, `CREATE`,
+    /// `SELFDESTRUCT` or a call with value, at any depth, fails the transaction with
+    /// [`crate::EVMError::StateChangeInReadOnly`]. Contract creation transactions and
+    /// transactions with value are rejected the same way. The state of a successful read-only
+    /// transaction is not returned, so committing it is a no-op. By default, it is set to
+    /// `false`.
+    pub read_only: bool,
     /// A hard memory limit in bytes beyond which [crate::result::OutOfGasError::Memory] cannot be resized.
     ///
     /// In cases where the gas limit may be extraordinarily high, it is recommended to set this to
@@ -319,22 +558,19 @@ pub struct CfgEnv {
     #[cfg(feature = "memory_limit")]
     pub memory_limit: u64,
     /// Skip balance checks if true. Adds transaction cost to balance to ensure execution doesn't fail.
-    #[cfg(feature = "optional_balance_check")]
+    /// By default, it is set to `false`.
     pub disable_balance_check: bool,
     /// There are use cases where it's allowed to provide a gas limit that's higher than a block's gas limit. To that
     /// end, you can disable the block gas limit validation.
     /// By default, it is set to `false`.
-    #[cfg(feature = "optional_block_gas_limit")]
     pub disable_block_gas_limit: bool,
     /// EIP-3607 rejects transactions from senders with deployed code. In development, it can be desirable to simulate
     /// calls from contracts, which this setting allows.
     /// By default, it is set to `false`.
-    #[cfg(feature = "optional_eip3607")]
     pub disable_eip3607: bool,
     /// Disables all gas refunds. This is useful when using chains that have gas refunds disabled e.g. Avalanche.
     /// Reasoning behind removing gas refunds can be found in EIP-3298.
     /// By default, it is set to `false`.
-    #[cfg(feature = "optional_gas_refund")]
     pub disable_gas_refund: bool,
     /// Disables base fee checks for EIP-1559 transactions.
     /// This is useful for testing method calls with zero gas price.
@@ -359,36 +595,33 @@ impl CfgEnv {
         self
     }
 
-    #[cfg(feature = "optional_eip3607")]
-    pub fn is_eip3607_disabled(&self) -> bool {
-        self.disable_eip3607
+    /// Returns the chain ID as the word pushed by the `CHAINID` instruction.
+    #[inline]
+    pub fn chain_id_word(&self) -> U256 {
+        U256::from(self.chain_id)
     }
 
-    #[cfg(not(feature = "optional_eip3607"))]
-    pub fn is_eip3607_disabled(&self) -> bool {
-        false
+    /// Returns whether a 256-bit chain ID, for example the one of an EIP-7702 authorization,
+    /// equals the chain ID of the EVM.
+    ///
+    /// Chain IDs that do not fit in a `u64` never match instead of being truncated.
+    #[inline]
+    pub fn is_chain_id(&self, chain_id: U256) -> bool {
+        u64::try_from(chain_id).is_ok_and(|chain_id| chain_id == self.chain_id)
     }
 
-    #[cfg(feature = "optional_balance_check")]
-    pub fn is_balance_check_disabled(&self) -> bool {
-        self.disable_balance_check
+    pub const fn is_eip3607_disabled(&self) -> bool {
+        self.disable_eip3607
     }
 
-    #[cfg(not(feature = "optional_balance_check"))]
-    pub fn is_balance_check_disabled(&self) -> bool {
-        false
+    pub const fn is_balance_check_disabled(&self) -> bool {
+        self.disable_balance_check
     }
 
-    #[cfg(feature = "optional_gas_refund")]
-    pub fn is_gas_refund_disabled(&self) -> bool {
+    pub const fn is_gas_refund_disabled(&self) -> bool {
         self.disable_gas_refund
     }
 
-    #[cfg(not(feature = "optional_gas_refund"))]
-    pub fn is_gas_refund_disabled(&self) -> bool {
-        false
-    }
-
     #[cfg(feature = "optional_no_base_fee")]
     pub fn is_base_fee_check_disabled(&self) -> bool {
         self.disable_base_fee
@@ -399,16 +632,10 @@ impl CfgEnv {
         false
     }
 
-    #[cfg(feature = "optional_block_gas_limit")]
-    pub fn is_block_gas_limit_disabled(&self) -> bool {
+    pub const fn is_block_gas_limit_disabled(&self) -> bool {
         self.disable_block_gas_limit
     }
 
-    #[cfg(not(feature = "optional_block_gas_limit"))]
-    pub fn is_block_gas_limit_disabled(&self) -> bool {
-        false
-    }
-
     #[cfg(feature = "optional_beneficiary_reward")]
     pub fn is_beneficiary_reward_disabled(&self) -> bool {
         self.disable_beneficiary_reward
@@ -422,6 +649,17 @@ impl CfgEnv {
     pub const fn is_nonce_check_disabled(&self) -> bool {
         self.disable_nonce_check
     }
+
+    /// Returns whether transactions from `address` skip the EIP-3607, nonce and signature
+    /// checks, see
+    /// [`Self::impersonated_accounts`].
+    pub fn is_impersonated(&self, address: &Address) -> bool {
+        self.impersonated_accounts.contains(address)
+    }
+
+    pub const fn is_gas_metering_disabled(&self) -> bool {
+        self.disable_gas_metering
+    }
 }
 
 impl Default for CfgEnv {
@@ -431,17 +669,24 @@ impl Default for CfgEnv {
             perf_analyse_created_bytecodes: AnalysisKind::default(),
             limit_contract_code_size: None,
             disable_nonce_check: false,
+            impersonated_accounts: HashSet::default(),
+            warm_addresses: HashSet::default(),
+            stack_limit: STACK_LIMIT,
+            call_depth_limit: CALL_STACK_LIMIT,
+            call_stipend: CALL_STIPEND,
+            disable_gas_metering: false,
+            instruction_limit: None,
+            execution_timeout: None,
+            fuse_instructions: false,
+            reuse_warm_accesses: false,
+            read_only: false,
             #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
             kzg_settings: crate::kzg::EnvKzgSettings::Default,
             #[cfg(feature = "memory_limit")]
             memory_limit: (1 << 32) - 1,
-            #[cfg(feature = "optional_balance_check")]
             disable_balance_check: false,
-            #[cfg(feature = "optional_block_gas_limit")]
             disable_block_gas_limit: false,
-            #[cfg(feature = "optional_eip3607")]
             disable_eip3607: false,
-            #[cfg(feature = "optional_gas_refund")]
             disable_gas_refund: false,
             #[cfg(feature = "optional_no_base_fee")]
             disable_base_fee: false,
@@ -493,6 +738,89 @@ pub struct BlockEnv {
 }
 
 impl BlockEnv {
+    /// Calculates the [EIP-1559] base fee of a block from its parent.
+    ///
+    /// See [calc_next_block_base_fee].
+    ///
+    /// [EIP-1559]: https://eips.ethereum.org/EIPS/eip-1559
+    pub fn next_base_fee(
+        parent_gas_used: u64,
+        parent_gas_limit: u64,
+        parent_base_fee: u64,
+        params: BaseFeeParams,
+    ) -> u64 {
+        calc_next_block_base_fee(parent_gas_used, parent_gas_limit, parent_base_fee, params)
+    }
+
+    /// Returns the environment of the block that follows this one, given the gas and blob
+    /// gas used by this block.
+    ///
+    /// The number is incremented, the timestamp is advanced by `block_time` seconds, and the
+    /// base fee and blob gas price are derived from this block. The other fields are kept,
+    /// and can be changed with the `with_*` methods.
+    pub fn next_block(
+        &self,
+        gas_used: u64,
+        blob_gas_used: u64,
+        block_time: u64,
+        params: BaseFeeParams,
+    ) -> Self {
+        let basefee = Self::next_base_fee(
+            gas_used,
+            self.gas_limit.saturating_to(),
+            self.basefee.saturating_to(),
+            params,
+        );
+        Self {
+            number: self.number.saturating_add(U256::from(1)),
+            timestamp: self.timestamp.saturating_add(U256::from(block_time)),
+            basefee: U256::from(basefee),
+            blob_excess_gas_and_price: self.blob_excess_gas_and_price.as_ref().map(|parent| {
+                BlobExcessGasAndPrice::new(calc_excess_blob_gas(
+                    parent.excess_blob_gas,
+                    blob_gas_used,
+                ))
+            }),
+            ..self.clone()
+        }
+    }
+
+    /// Sets the block number.
+    pub fn with_number(mut self, number: u64) -> Self {
+        self.number = U256::from(number);
+        self
+    }
+
+    /// Sets the timestamp of the block.
+    pub fn with_timestamp(mut self, timestamp: u64) -> Self {
+        self.timestamp = U256::from(timestamp);
+        self
+    }
+
+    /// Sets the coinbase of the block.
+    pub fn with_coinbase(mut self, coinbase: Address) -> Self {
+        self.coinbase = coinbase;
+        self
+    }
+
+    /// Sets the gas limit of the block.
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = U256::from(gas_limit);
+        self
+    }
+
+    /// Sets the base fee per gas of the block.
+    pub fn with_basefee(mut self, basefee: u64) -> Self {
+        self.basefee = U256::from(basefee);
+        self
+    }
+
+    /// Sets the randomness of the block, which replaces the difficulty after the merge.
+    pub fn with_prevrandao(mut self, prevrandao: B256) -> Self {
+        self.prevrandao = Some(prevrandao);
+        self
+    }
+
     /// Takes `blob_excess_gas` saves it inside env
     /// and calculates `blob_fee` with [`BlobExcessGasAndPrice`].
     pub fn set_blob_excess_gas_and_price(&mut self, excess_blob_gas: u64) {
@@ -575,7 +903,12 @@ pub struct TxEnv {
     pub data: Bytes,
 
     /// The nonce of the transaction.
-    pub nonce: u64,
+    ///
+    /// If set, it must equal the nonce of the caller, otherwise the transaction fails with
+    /// [`InvalidTransaction::NonceTooLow`] or [`InvalidTransaction::NonceTooHigh`]. If `None`,
+    /// the current nonce of the caller is used, which is convenient for simulations.
+    /// By default, it is `None`.
+    pub nonce: Option<u64>,
 
     /// The chain ID of the transaction. If set to `None`, no checks are performed.
     ///
@@ -620,6 +953,68 @@ pub struct TxEnv {
     ///
     /// [EIP-Set EOA account code for one transaction](https://eips.ethereum.org/EIPS/eip-7702)
     pub authorization_list: Option<AuthorizationList>,
+
+    /// Whether this is a system transaction, see [Transaction::is_system].
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub is_system: bool,
+}
+
+impl TxEnv {
+    /// Sets the access list of the transaction.
+    ///
+    /// [AccessList] can be decoded from RLP or deserialized from the JSON format of the
+    /// Ethereum RPC, with the `serde` feature.
+    pub fn with_access_list(mut self, access_list: impl Into<AccessList>) -> Self {
+        self.access_list = access_list.into().0;
+        self
+    }
+
+    /// Adds `address` and its `storage_keys` to the access list.
+    ///
+    /// The keys are merged into the existing item of the address, if any, and keys that
+    /// are already listed are skipped.
+    pub fn with_access_list_item(
+        mut self,
+        address: Address,
+        storage_keys: impl IntoIterator<Item = B256>,
+    ) -> Self {
+        let index = match self
+            .access_list
+            .iter()
+            .position(|item| item.address == address)
+        {
+            Some(index) => index,
+            None => {
+                self.access_list.push(AccessListItem {
+                    address,
+                    storage_keys: Vec::new(),
+                });
+                self.access_list.len() - 1
+            }
+        };
+        let item = &mut self.access_list[index];
+        for key in storage_keys {
+            if !item.storage_keys.contains(&key) {
+                item.storage_keys.push(key);
+            }
+        }
+        self
+    }
+
+    /// Like [TxEnv::with_access_list_item], with the storage keys given as slot indices,
+    /// which are encoded as big-endian words.
+    pub fn with_access_list_slots(
+        self,
+        address: Address,
+        slots: impl IntoIterator<Item = U256>,
+    ) -> Self {
+        self.with_access_list_item(address, slots.into_iter().map(B256::from))
+    }
+
+    /// Returns the access list of the transaction as an [AccessList].
+    pub fn typed_access_list(&self) -> AccessList {
+        AccessList(self.access_list.clone())
+    }
 }
 
 impl Transaction for TxEnv {
@@ -654,7 +1049,7 @@ impl Transaction for TxEnv {
     }
 
     #[inline]
-    fn nonce(&self) -> u64 {
+    fn nonce(&self) -> Option<u64> {
         self.nonce
     }
 
@@ -687,6 +1082,11 @@ impl Transaction for TxEnv {
     fn authorization_list(&self) -> Option<&AuthorizationList> {
         self.authorization_list.as_ref()
     }
+
+    #[inline]
+    fn is_system(&self) -> bool {
+        self.is_system
+    }
 }
 
 impl TransactionValidation for TxEnv {
@@ -711,11 +1111,12 @@ impl Default for TxEnv {
             value: U256::ZERO,
             data: Bytes::new(),
             chain_id: None,
-            nonce: 0,
+            nonce: None,
             access_list: Vec::new(),
             blob_hashes: Vec::new(),
             max_fee_per_blob_gas: None,
             authorization_list: None,
+            is_system: false,
         }
     }
 }
@@ -775,6 +1176,7 @@ pub enum AnalysisKind {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::GAS_PER_BLOB;
 
     #[test]
     fn test_validate_tx_chain_id() {
@@ -785,6 +1187,34 @@ mod tests {
             env.validate_tx::<crate::LatestSpec>(),
             Err(InvalidTransaction::InvalidChainId)
         );
+
+        env.tx.chain_id = Some(2);
+        assert_eq!(env.validate_tx::<crate::LatestSpec>(), Ok(()));
+        env.tx.chain_id = None;
+        assert_eq!(env.validate_tx::<crate::LatestSpec>(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_tx_block_gas_limit_flag() {
+        let mut env = Env::<BlockEnv, TxEnv>::default();
+        env.block.gas_limit = U256::from(100_000);
+        env.tx.gas_limit = 100_001;
+        assert_eq!(
+            env.validate_tx::<crate::LatestSpec>(),
+            Err(InvalidTransaction::CallerGasLimitMoreThanBlock)
+        );
+
+        env.cfg.disable_block_gas_limit = true;
+        assert_eq!(env.validate_tx::<crate::LatestSpec>(), Ok(()));
+    }
+
+    #[test]
+    fn test_cfg_chain_id_conversions() {
+        let cfg = CfgEnv::default().with_chain_id(10);
+        assert_eq!(cfg.chain_id_word(), U256::from(10));
+        assert!(cfg.is_chain_id(U256::from(10)));
+        assert!(!cfg.is_chain_id(U256::from(1)));
+        assert!(!cfg.is_chain_id((U256::from(1) << 64) + U256::from(10)));
     }
 
     #[test]
@@ -799,4 +1229,312 @@ mod tests {
             Err(InvalidTransaction::AccessListNotSupported)
         );
     }
+
+    #[test]
+    fn test_access_list_builders() {
+        let (a, b) = (Address::with_last_byte(1), Address::with_last_byte(2));
+        let tx = TxEnv::default()
+            .with_access_list_slots(a, [U256::from(1)])
+            .with_access_list_item(b, [])
+            .with_access_list_item(a, [B256::with_last_byte(1), B256::with_last_byte(2)]);
+        let expected = AccessList(vec![
+            AccessListItem {
+                address: a,
+                storage_keys: vec![B256::with_last_byte(1), B256::with_last_byte(2)],
+            },
+            AccessListItem {
+                address: b,
+                storage_keys: vec![],
+            },
+        ]);
+        assert_eq!(tx.typed_access_list(), expected);
+        assert_eq!(
+            TxEnv::default()
+                .with_access_list(expected.clone())
+                .typed_access_list(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_next_block() {
+        let block = BlockEnv::default()
+            .with_number(10)
+            .with_timestamp(100)
+            .with_gas_limit(30_000_000)
+            .with_basefee(1_000_000_000);
+        let next = block.next_block(30_000_000, 6 * GAS_PER_BLOB, 12, BaseFeeParams::ETHEREUM);
+        assert_eq!(next.number, U256::from(11));
+        assert_eq!(next.timestamp, U256::from(112));
+        assert_eq!(next.gas_limit, block.gas_limit);
+        assert_eq!(next.basefee, U256::from(1_125_000_000));
+        assert_eq!(
+            next.blob_excess_gas_and_price,
+            Some(BlobExcessGasAndPrice::new(3 * GAS_PER_BLOB))
+        );
+    }
+
+    #[test]
+    fn test_validate_tx_with_rules() {
+        let mut env = Env::<BlockEnv, TxEnv>::default();
+        env.block.basefee = U256::from(10);
+        env.tx.gas_price = U256::from(1);
+        assert_eq!(
+            env.validate_tx::<crate::LatestSpec>(),
+            Err(InvalidTransaction::GasPriceLessThanBasefee)
+        );
+
+        let rules = ChainRules {
+            eip1559_fee_checks: false,
+            ..ChainRules::new(SpecId::LATEST)
+        };
+        assert_eq!(
+            env.validate_tx_with_rules::<crate::LatestSpec>(&rules),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_tx_against_state_impersonated() {
+        let mut env = Env::<BlockEnv, TxEnv>::default();
+        env.tx.caller = Address::with_last_byte(1);
+        env.tx.gas_price = U256::ZERO;
+        env.tx.nonce = Some(5);
+        let code = crate::Bytecode::new_raw(Bytes::from_static(&[0x00]));
+        let mut account = Account::from(crate::AccountInfo::new(
+            U256::ZERO,
+            0,
+            code.hash_slow(),
+            code,
+        ));
+        assert_eq!(
+            env.validate_tx_against_state::<crate::LatestSpec>(&mut account),
+            Err(InvalidTransaction::RejectCallerWithCode)
+        );
+
+        env.cfg.impersonated_accounts.insert(env.tx.caller);
+        assert_eq!(
+            env.validate_tx_against_state::<crate::LatestSpec>(&mut account),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_validate_tx_against_state_max_cost() {
+        let mut env = Env::<BlockEnv, TxEnv>::default();
+        env.tx.gas_limit = 100_000;
+        env.tx.gas_price = U256::from(10);
+        env.tx.value = U256::from(1);
+        env.tx.blob_hashes = vec![B256::ZERO; 2];
+        env.tx.max_fee_per_blob_gas = Some(U256::from(3));
+        let cost = U256::from(100_000 * 10 + 1 + 2 * GAS_PER_BLOB * 3);
+        assert_eq!(env.max_tx_cost::<crate::CancunSpec>(), Ok(cost));
+        // Blob gas is only paid for since Cancun.
+        assert_eq!(
+            env.max_tx_cost::<crate::ShanghaiSpec>(),
+            Ok(cost - U256::from(2 * GAS_PER_BLOB * 3))
+        );
+
+        let mut account = Account::from(crate::AccountInfo::from_balance(cost - U256::from(1)));
+        assert_eq!(
+            env.validate_tx_against_state::<crate::CancunSpec>(&mut account),
+            Err(InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(cost),
+                balance: Box::new(cost - U256::from(1)),
+            })
+        );
+
+        // Simulations can skip the check, the caller is given the missing balance.
+        env.cfg.disable_balance_check = true;
+        assert_eq!(
+            env.validate_tx_against_state::<crate::CancunSpec>(&mut account),
+            Ok(())
+        );
+        assert_eq!(account.info.balance, cost);
+
+        env.tx.max_fee_per_blob_gas = Some(U256::MAX);
+        assert_eq!(
+            env.validate_tx_against_state::<crate::CancunSpec>(&mut account),
+            Err(InvalidTransaction::OverflowPaymentInTransaction)
+        );
+    }
+
+    #[test]
+    fn test_validate_tx_against_state_nonce() {
+        let mut env = Env::<BlockEnv, TxEnv>::default();
+        env.tx.gas_price = U256::ZERO;
+        let mut account = Account::from(crate::AccountInfo {
+            nonce: 3,
+            ..Default::default()
+        });
+        // Without a nonce, the nonce of the caller is used.
+        assert_eq!(
+            env.validate_tx_against_state::<crate::LatestSpec>(&mut account),
+            Ok(())
+        );
+
+        env.tx.nonce = Some(2);
+        assert_eq!(
+            env.validate_tx_against_state::<crate::LatestSpec>(&mut account),
+            Err(InvalidTransaction::NonceTooLow { tx: 2, state: 3 })
+        );
+        env.tx.nonce = Some(4);
+        assert_eq!(
+            env.validate_tx_against_state::<crate::LatestSpec>(&mut account),
+            Err(InvalidTransaction::NonceTooHigh { tx: 4, state: 3 })
+        );
+        env.tx.nonce = Some(3);
+        assert_eq!(
+            env.validate_tx_against_state::<crate::LatestSpec>(&mut account),
+            Ok(())
+        );
+    }
+
+    /// Legacy transaction with EIP-155 signature.
+    struct SignedTxEnv {
+        tx: TxEnv,
+        signature: crate::Signature,
+        signature_hash: B256,
+    }
+
+    impl Transaction for SignedTxEnv {
+        fn caller(&self) -> &Address {
+            self.tx.caller()
+        }
+        fn gas_limit(&self) -> u64 {
+            self.tx.gas_limit()
+        }
+        fn gas_price(&self) -> &U256 {
+            self.tx.gas_price()
+        }
+        fn kind(&self) -> TxKind {
+            self.tx.kind()
+        }
+        fn value(&self) -> &U256 {
+            self.tx.value()
+        }
+        fn data(&self) -> &Bytes {
+            self.tx.data()
+        }
+        fn nonce(&self) -> Option<u64> {
+            self.tx.nonce()
+        }
+        fn chain_id(&self) -> Option<u64> {
+            self.tx.chain_id()
+        }
+        fn access_list(&self) -> &[AccessListItem] {
+            self.tx.access_list()
+        }
+        fn max_priority_fee_per_gas(&self) -> Option<&U256> {
+            self.tx.max_priority_fee_per_gas()
+        }
+        fn blob_hashes(&self) -> &[B256] {
+            self.tx.blob_hashes()
+        }
+        fn max_fee_per_blob_gas(&self) -> Option<&U256> {
+            self.tx.max_fee_per_blob_gas()
+        }
+        fn authorization_list(&self) -> Option<&AuthorizationList> {
+            self.tx.authorization_list()
+        }
+    }
+
+    impl SignedTransaction for SignedTxEnv {
+        fn signature(&self) -> &crate::Signature {
+            &self.signature
+        }
+        fn signature_hash(&self) -> B256 {
+            self.signature_hash
+        }
+        fn set_caller(&mut self, caller: Address) {
+            self.tx.caller = caller;
+        }
+    }
+
+    #[test]
+    fn test_validate_signature() {
+        // Example transaction of EIP-155.
+        let signature = crate::Signature::from_rs_and_parity(
+            U256::from_str_radix(
+                "18515461264373351373200002665853028612451056578545711640558177340181847433846",
+                10,
+            )
+            .unwrap(),
+            U256::from_str_radix(
+                "46948507304638947509940763649030358759909902576025900602547168820602576006531",
+                10,
+            )
+            .unwrap(),
+            37u64,
+        )
+        .unwrap();
+        let signer = crate::address!("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f");
+        let mut env = Env {
+            cfg: CfgEnv::default(),
+            block: BlockEnv::default(),
+            tx: SignedTxEnv {
+                tx: TxEnv::default(),
+                signature,
+                signature_hash: crate::b256!(
+                    "daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53"
+                ),
+            },
+        };
+
+        assert_eq!(env.validate_signature::<crate::LatestSpec>(), Ok(()));
+        assert_eq!(env.tx.tx.caller, signer);
+        assert_eq!(env.validate_signature::<crate::LatestSpec>(), Ok(()));
+
+        env.tx.tx.caller = Address::with_last_byte(1);
+        assert_eq!(
+            env.validate_signature::<crate::LatestSpec>(),
+            Err(InvalidTransaction::SignerMismatch)
+        );
+
+        env.tx.tx.caller = signer;
+        env.cfg.chain_id = 2;
+        assert_eq!(
+            env.validate_signature::<crate::LatestSpec>(),
+            Err(InvalidTransaction::InvalidChainId)
+        );
+
+        // Impersonated callers are accepted whatever the signature.
+        let caller = Address::with_last_byte(1);
+        env.tx.tx.caller = caller;
+        env.cfg.impersonated_accounts.insert(caller);
+        assert_eq!(env.validate_signature::<crate::LatestSpec>(), Ok(()));
+        assert_eq!(env.tx.tx.caller, caller);
+    }
+
+    #[test]
+    fn test_validate_signature_impersonated() {
+        // The s-value is above secp256k1n/2, which EIP-2 rejects.
+        let signature = crate::Signature::from_rs_and_parity(
+            U256::from(1),
+            SECP256K1N_HALF + U256::from(1),
+            27u64,
+        )
+        .unwrap();
+        let caller = Address::with_last_byte(1);
+        let mut env = Env {
+            cfg: CfgEnv::default(),
+            block: BlockEnv::default(),
+            tx: SignedTxEnv {
+                tx: TxEnv {
+                    caller,
+                    ..Default::default()
+                },
+                signature,
+                signature_hash: B256::ZERO,
+            },
+        };
+        assert_eq!(
+            env.validate_signature::<crate::LatestSpec>(),
+            Err(InvalidTransaction::InvalidSignature)
+        );
+
+        env.cfg.impersonated_accounts.insert(caller);
+        assert_eq!(env.validate_signature::<crate::LatestSpec>(), Ok(()));
+        assert_eq!(env.tx.tx.caller, caller);
+    }
 }
@@ -0,0 +1,342 @@
+use super::{BlobExcessGasAndPrice, BlockEnv, TxEnv};
+use crate::{
+    AccessList, Address, AuthorizationList, Block, Bytes, ChainRules, InvalidHeader,
+    InvalidTransaction, SpecId, TxKind, B256, U256,
+};
+use std::vec::Vec;
+
+/// Builder of a [TxEnv] that checks the invariants of the transaction when it is built.
+///
+/// Unlike setting the fields of [TxEnv], which are only validated when the transaction is
+/// executed, [TxEnvBuilder::build] reports inconsistent fees, gas limits and blob fields
+/// right away. The checks that depend on the block or the state are done by
+/// [TxEnvBuilder::build_for] and the execution.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TxEnvBuilder {
+    tx: TxEnv,
+}
+
+impl Default for TxEnvBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TxEnvBuilder {
+    /// Creates a builder of a call to the zero address, with the gas limit of a block of
+    /// mainnet and no fees.
+    pub fn new() -> Self {
+        Self {
+            tx: TxEnv {
+                gas_limit: 30_000_000,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Sets the sender of the transaction.
+    pub fn caller(mut self, caller: Address) -> Self {
+        self.tx.caller = caller;
+        self
+    }
+
+    /// Makes the transaction a call to `to`.
+    pub fn call(mut self, to: Address) -> Self {
+        self.tx.transact_to = TxKind::Call(to);
+        self
+    }
+
+    /// Makes the transaction a contract creation, whose init code is the data.
+    pub fn create(mut self) -> Self {
+        self.tx.transact_to = TxKind::Create;
+        self
+    }
+
+    /// Sets the value transferred by the transaction.
+    pub fn value(mut self, value: U256) -> Self {
+        self.tx.value = value;
+        self
+    }
+
+    /// Sets the input of a call, or the init code of a creation.
+    pub fn data(mut self, data: Bytes) -> Self {
+        self.tx.data = data;
+        self
+    }
+
+    /// Sets the nonce of the transaction, by default the current nonce of the caller is used.
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.tx.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the chain ID the transaction is signed for.
+    pub fn chain_id(mut self, chain_id: u64) -> Self {
+        self.tx.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Sets the gas limit of the transaction.
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.tx.gas_limit = gas_limit;
+        self
+    }
+
+    /// Sets the gas price of a legacy or EIP-2930 transaction.
+    pub fn gas_price(mut self, gas_price: U256) -> Self {
+        self.tx.gas_price = gas_price;
+        self.tx.gas_priority_fee = None;
+        self
+    }
+
+    /// Sets the fees of an EIP-1559 transaction.
+    pub fn max_fee_per_gas(mut self, max_fee: U256, max_priority_fee: U256) -> Self {
+        self.tx.gas_price = max_fee;
+        self.tx.gas_priority_fee = Some(max_priority_fee);
+        self
+    }
+
+    /// Sets the access list of the transaction.
+    pub fn access_list(mut self, access_list: impl Into<AccessList>) -> Self {
+        self.tx = self.tx.with_access_list(access_list);
+        self
+    }
+
+    /// Makes the transaction an EIP-4844 transaction carrying the blobs of `blob_hashes`.
+    pub fn blobs(mut self, blob_hashes: Vec<B256>, max_fee_per_blob_gas: U256) -> Self {
+        self.tx.blob_hashes = blob_hashes;
+        self.tx.max_fee_per_blob_gas = Some(max_fee_per_blob_gas);
+        self
+    }
+
+    /// Makes the transaction an EIP-7702 transaction with the authorizations of
+    /// `authorization_list`.
+    pub fn authorization_list(mut self, authorization_list: impl Into<AuthorizationList>) -> Self {
+        self.tx.authorization_list = Some(authorization_list.into());
+        self
+    }
+
+    /// Returns the transaction if its fields are consistent.
+    ///
+    /// The priority fee must not exceed the max fee, and the gas limit must cover the base
+    /// and data costs of the transaction. Blob transactions must carry at least one blob and
+    /// can not create a contract, and the authorization list must not be empty.
+    pub fn build(self) -> Result<TxEnv, InvalidTransaction> {
+        let tx = self.tx;
+        if tx
+            .gas_priority_fee
+            .is_some_and(|priority_fee| priority_fee > tx.gas_price)
+        {
+            return Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee);
+        }
+
+        let rules = ChainRules::default();
+        let base_gas = if tx.transact_to.is_create() {
+            rules.create_tx_base_gas
+        } else {
+            rules.tx_base_gas
+        };
+        let zero_bytes = tx.data.iter().filter(|byte| **byte == 0).count() as u64;
+        let non_zero_bytes = tx.data.len() as u64 - zero_bytes;
+        let data_gas = zero_bytes
+            .saturating_mul(rules.tx_data_zero_gas)
+            .saturating_add(non_zero_bytes.saturating_mul(rules.tx_data_non_zero_gas));
+        if tx.gas_limit < base_gas.saturating_add(data_gas) {
+            return Err(InvalidTransaction::CallGasCostMoreThanGasLimit);
+        }
+
+        if tx.max_fee_per_blob_gas.is_some() {
+            if tx.blob_hashes.is_empty() {
+                return Err(InvalidTransaction::EmptyBlobs);
+            }
+            if tx.transact_to.is_create() {
+                return Err(InvalidTransaction::BlobCreateTransaction);
+            }
+        }
+
+        if tx
+            .authorization_list
+            .as_ref()
+            .is_some_and(|list| list.is_empty())
+        {
+            return Err(InvalidTransaction::EmptyAuthorizationList);
+        }
+        Ok(tx)
+    }
+
+    /// Like [TxEnvBuilder::build], also checking that the transaction fits in the gas limit
+    /// of `block` and pays at least its base fee.
+    pub fn build_for(self, block: &impl Block) -> Result<TxEnv, InvalidTransaction> {
+        let tx = self.build()?;
+        if U256::from(tx.gas_limit) > *block.gas_limit() {
+            return Err(InvalidTransaction::CallerGasLimitMoreThanBlock);
+        }
+        if tx.gas_price < *block.basefee() {
+            return Err(InvalidTransaction::GasPriceLessThanBasefee);
+        }
+        Ok(tx)
+    }
+}
+
+/// Builder of a [BlockEnv] that checks the fields required by the spec of the block.
+///
+/// Unlike [BlockEnv::default], the builder does not set `prevrandao` and the excess blob
+/// gas, so [BlockEnvBuilder::build] fails if a spec that needs them is built without them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockEnvBuilder {
+    block: BlockEnv,
+}
+
+impl Default for BlockEnvBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BlockEnvBuilder {
+    /// Creates a builder of block 0, with the gas limit of a block of mainnet and no base fee.
+    pub fn new() -> Self {
+        Self {
+            block: BlockEnv {
+                gas_limit: U256::from(30_000_000),
+                prevrandao: None,
+                blob_excess_gas_and_price: None,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Sets the block number.
+    pub fn number(mut self, number: u64) -> Self {
+        self.block.number = U256::from(number);
+        self
+    }
+
+    /// Sets the timestamp of the block.
+    pub fn timestamp(mut self, timestamp: u64) -> Self {
+        self.block.timestamp = U256::from(timestamp);
+        self
+    }
+
+    /// Sets the coinbase of the block.
+    pub fn coinbase(mut self, coinbase: Address) -> Self {
+        self.block.coinbase = coinbase;
+        self
+    }
+
+    /// Sets the gas limit of the block.
+    pub fn gas_limit(mut self, gas_limit: u64) -> Self {
+        self.block.gas_limit = U256::from(gas_limit);
+        self
+    }
+
+    /// Sets the base fee per gas of the block.
+    pub fn basefee(mut self, basefee: u64) -> Self {
+        self.block.basefee = U256::from(basefee);
+        self
+    }
+
+    /// Sets the difficulty of the block, which is replaced by `prevrandao` after the merge.
+    pub fn difficulty(mut self, difficulty: U256) -> Self {
+        self.block.difficulty = difficulty;
+        self
+    }
+
+    /// Sets the randomness of the block, required since the merge.
+    pub fn prevrandao(mut self, prevrandao: B256) -> Self {
+        self.block.prevrandao = Some(prevrandao);
+        self
+    }
+
+    /// Sets the excess blob gas of the block, required since Cancun.
+    pub fn excess_blob_gas(mut self, excess_blob_gas: u64) -> Self {
+        self.block.blob_excess_gas_and_price = Some(BlobExcessGasAndPrice::new(excess_blob_gas));
+        self
+    }
+
+    /// Returns the block if it has the fields required by `spec_id`.
+    pub fn build(self, spec_id: SpecId) -> Result<BlockEnv, InvalidHeader> {
+        let block = self.block;
+        if spec_id.is_enabled_in(SpecId::MERGE) && block.prevrandao.is_none() {
+            return Err(InvalidHeader::PrevrandaoNotSet);
+        }
+        if spec_id.is_enabled_in(SpecId::CANCUN) && block.blob_excess_gas_and_price.is_none() {
+            return Err(InvalidHeader::ExcessBlobGasNotSet);
+        }
+        Ok(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tx_builder_checks_invariants() {
+        let tx = TxEnvBuilder::new()
+            .call(Address::with_last_byte(1))
+            .max_fee_per_gas(U256::from(10), U256::from(2))
+            .build()
+            .unwrap();
+        assert_eq!(tx.gas_priority_fee, Some(U256::from(2)));
+
+        assert_eq!(
+            TxEnvBuilder::new()
+                .max_fee_per_gas(U256::from(1), U256::from(2))
+                .build(),
+            Err(InvalidTransaction::PriorityFeeGreaterThanMaxFee)
+        );
+        // A creation costs more than a call.
+        assert_eq!(
+            TxEnvBuilder::new().create().gas_limit(21_000).build(),
+            Err(InvalidTransaction::CallGasCostMoreThanGasLimit)
+        );
+        assert_eq!(
+            TxEnvBuilder::new()
+                .gas_limit(21_000)
+                .data(Bytes::from_static(&[1]))
+                .build(),
+            Err(InvalidTransaction::CallGasCostMoreThanGasLimit)
+        );
+        assert_eq!(
+            TxEnvBuilder::new().blobs(Vec::new(), U256::from(1)).build(),
+            Err(InvalidTransaction::EmptyBlobs)
+        );
+
+        let block = BlockEnvBuilder::new()
+            .gas_limit(1_000_000)
+            .basefee(7)
+            .build(SpecId::LONDON)
+            .unwrap();
+        assert_eq!(
+            TxEnvBuilder::new().build_for(&block),
+            Err(InvalidTransaction::CallerGasLimitMoreThanBlock)
+        );
+        assert_eq!(
+            TxEnvBuilder::new().gas_limit(100_000).build_for(&block),
+            Err(InvalidTransaction::GasPriceLessThanBasefee)
+        );
+        assert!(TxEnvBuilder::new()
+            .gas_limit(100_000)
+            .gas_price(U256::from(7))
+            .build_for(&block)
+            .is_ok());
+    }
+
+    #[test]
+    fn block_builder_requires_spec_fields() {
+        assert!(BlockEnvBuilder::new().build(SpecId::LONDON).is_ok());
+        assert_eq!(
+            BlockEnvBuilder::new().build(SpecId::MERGE),
+            Err(InvalidHeader::PrevrandaoNotSet)
+        );
+        let merge = BlockEnvBuilder::new().prevrandao(B256::ZERO);
+        assert!(merge.clone().build(SpecId::SHANGHAI).is_ok());
+        assert_eq!(
+            merge.clone().build(SpecId::CANCUN),
+            Err(InvalidHeader::ExcessBlobGasNotSet)
+        );
+        let block = merge.excess_blob_gas(0).build(SpecId::CANCUN).unwrap();
+        assert_eq!(block.get_blob_gasprice(), Some(&1));
+    }
+}
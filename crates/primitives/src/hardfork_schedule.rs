@@ -0,0 +1,146 @@
+use crate::{Block, SpecId};
+use std::vec::Vec;
+
+/// Condition under which a hardfork is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ForkCondition {
+    /// Active from the block with this number.
+    Block(u64),
+    /// Active from the first block with at least this timestamp.
+    Timestamp(u64),
+}
+
+impl ForkCondition {
+    /// Returns whether the condition is met by the block with `number` and `timestamp`.
+    #[inline]
+    pub const fn is_active(&self, number: u64, timestamp: u64) -> bool {
+        match *self {
+            Self::Block(block) => number >= block,
+            Self::Timestamp(time) => timestamp >= time,
+        }
+    }
+}
+
+/// Activations of the hardforks of a chain, to resolve the [`SpecId`] of its blocks.
+///
+/// The spec of a block is the latest hardfork whose condition it meets, or [`SpecId::FRONTIER`]
+/// if it meets none. Resolving it is a scan of at most one entry per [`SpecId`], cheap enough
+/// to do for every block of a replay spanning several hardforks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HardforkSchedule {
+    /// Hardforks and their conditions, ordered by [`SpecId`].
+    forks: Vec<(SpecId, ForkCondition)>,
+}
+
+impl HardforkSchedule {
+    /// Creates a schedule without hardforks, every block is [`SpecId::FRONTIER`].
+    pub const fn new() -> Self {
+        Self { forks: Vec::new() }
+    }
+
+    /// Returns the schedule of Ethereum mainnet.
+    pub fn mainnet() -> Self {
+        use ForkCondition::{Block, Timestamp};
+        use SpecId::*;
+        Self::new()
+            .with_fork(FRONTIER_THAWING, Block(200_000))
+            .with_fork(HOMESTEAD, Block(1_150_000))
+            .with_fork(DAO_FORK, Block(1_920_000))
+            .with_fork(TANGERINE, Block(2_463_000))
+            .with_fork(SPURIOUS_DRAGON, Block(2_675_000))
+            .with_fork(BYZANTIUM, Block(4_370_000))
+            .with_fork(PETERSBURG, Block(7_280_000))
+            .with_fork(ISTANBUL, Block(9_069_000))
+            .with_fork(MUIR_GLACIER, Block(9_200_000))
+            .with_fork(BERLIN, Block(12_244_000))
+            .with_fork(LONDON, Block(12_965_000))
+            .with_fork(ARROW_GLACIER, Block(13_773_000))
+            .with_fork(GRAY_GLACIER, Block(15_050_000))
+            .with_fork(MERGE, Block(15_537_394))
+            .with_fork(SHANGHAI, Timestamp(1_681_338_455))
+            .with_fork(CANCUN, Timestamp(1_710_338_135))
+    }
+
+    /// Activates `spec_id` under `condition`, replacing its previous condition.
+    pub fn with_fork(mut self, spec_id: SpecId, condition: ForkCondition) -> Self {
+        self.set_fork(spec_id, condition);
+        self
+    }
+
+    /// Activates `spec_id` under `condition`, replacing its previous condition.
+    pub fn set_fork(&mut self, spec_id: SpecId, condition: ForkCondition) {
+        match self
+            .forks
+            .binary_search_by_key(&spec_id, |(spec_id, _)| *spec_id)
+        {
+            Ok(index) => self.forks[index].1 = condition,
+            Err(index) => self.forks.insert(index, (spec_id, condition)),
+        }
+    }
+
+    /// Returns the hardforks and their conditions, ordered by [`SpecId`].
+    pub fn forks(&self) -> &[(SpecId, ForkCondition)] {
+        &self.forks
+    }
+
+    /// Returns the spec of the block with `number` and `timestamp`.
+    pub fn spec_at(&self, number: u64, timestamp: u64) -> SpecId {
+        self.forks
+            .iter()
+            .rev()
+            .find(|(_, condition)| condition.is_active(number, timestamp))
+            .map_or(SpecId::FRONTIER, |(spec_id, _)| *spec_id)
+    }
+
+    /// Returns the spec of `block`.
+    pub fn spec_for(&self, block: &impl Block) -> SpecId {
+        self.spec_at(
+            block.number().saturating_to(),
+            block.timestamp().saturating_to(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlockEnv, U256};
+
+    #[test]
+    fn mainnet_spec_for_block() {
+        let schedule = HardforkSchedule::mainnet();
+        let spec_for = |number: u64, timestamp: u64| {
+            schedule.spec_for(&BlockEnv {
+                number: U256::from(number),
+                timestamp: U256::from(timestamp),
+                ..Default::default()
+            })
+        };
+        assert_eq!(spec_for(0, 0), SpecId::FRONTIER);
+        assert_eq!(spec_for(1_150_000, 0), SpecId::HOMESTEAD);
+        assert_eq!(spec_for(7_280_000, 0), SpecId::PETERSBURG);
+        assert_eq!(spec_for(15_537_393, 1_663_224_162), SpecId::GRAY_GLACIER);
+        assert_eq!(spec_for(15_537_394, 1_663_224_179), SpecId::MERGE);
+        assert_eq!(spec_for(17_034_870, 1_681_338_455), SpecId::SHANGHAI);
+        assert_eq!(spec_for(19_426_587, 1_710_338_135), SpecId::CANCUN);
+    }
+
+    #[test]
+    fn forks_are_replaced() {
+        let schedule = HardforkSchedule::new()
+            .with_fork(SpecId::LONDON, ForkCondition::Block(10))
+            .with_fork(SpecId::BERLIN, ForkCondition::Block(5))
+            .with_fork(SpecId::LONDON, ForkCondition::Block(20));
+        assert_eq!(
+            schedule.forks(),
+            [
+                (SpecId::BERLIN, ForkCondition::Block(5)),
+                (SpecId::LONDON, ForkCondition::Block(20))
+            ]
+        );
+        assert_eq!(schedule.spec_at(19, 0), SpecId::BERLIN);
+        assert_eq!(schedule.spec_at(20, 0), SpecId::LONDON);
+    }
+}
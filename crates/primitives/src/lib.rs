@@ -13,10 +13,13 @@ pub mod eip7702;
 pub mod env;
 
 mod bytecode;
+mod chain_rules;
 mod constants;
 mod evm_wiring;
+mod hardfork_schedule;
 #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
 pub mod kzg;
+mod log;
 pub mod precompile;
 pub mod result;
 pub mod specification;
@@ -30,6 +33,7 @@ pub use alloy_primitives::{
 };
 pub use bitvec;
 pub use bytecode::*;
+pub use chain_rules::ChainRules;
 pub use constants::*;
 pub use eip7702::{
     Authorization, AuthorizationList, Eip7702Bytecode, Eip7702DecodeError, InvalidAuthorization,
@@ -37,6 +41,7 @@ pub use eip7702::{
 };
 pub use env::*;
 pub use evm_wiring::*;
+pub use hardfork_schedule::{ForkCondition, HardforkSchedule};
 
 cfg_if::cfg_if! {
     if #[cfg(all(not(feature = "hashbrown"), feature = "std"))] {
@@ -50,11 +55,12 @@ cfg_if::cfg_if! {
 pub use block::Block;
 #[cfg(any(feature = "c-kzg", feature = "kzg-rs"))]
 pub use kzg::{EnvKzgSettings, KzgSettings};
+pub use log::{EvmLog, LogTopics};
 pub use precompile::*;
 pub use result::*;
 pub use specification::*;
 pub use state::*;
-pub use transaction::Transaction;
+pub use transaction::{SignedTransaction, Transaction};
 pub use utilities::*;
 
 #[cfg(all(feature = "c-kzg", feature = "kzg-rs"))]
@@ -0,0 +1,93 @@
+use crate::{Address, Bytes, Log, LogData, B256};
+use smallvec::SmallVec;
+
+/// Topics of an [EvmLog].
+///
+/// A log has at most four topics, so they are always stored inline.
+pub type LogTopics = SmallVec<[B256; 4]>;
+
+/// Log emitted by a contract during execution.
+///
+/// Unlike [Log], the topics are stored inline, so emitting a log only allocates for its data.
+/// Logs are converted to [Log] at the boundary of the EVM, e.g. in the execution result.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EvmLog {
+    /// Address of the contract that emitted the log.
+    pub address: Address,
+    /// Topics of the log, at most four.
+    pub topics: LogTopics,
+    /// Data of the log.
+    pub data: Bytes,
+}
+
+impl EvmLog {
+    /// Creates a new log.
+    #[inline]
+    pub fn new(address: Address, topics: LogTopics, data: Bytes) -> Self {
+        debug_assert!(topics.len() <= 4, "a log has at most four topics");
+        Self {
+            address,
+            topics,
+            data,
+        }
+    }
+
+    /// Returns the log as a [Log].
+    #[inline]
+    pub fn to_log(&self) -> Log {
+        Log {
+            address: self.address,
+            data: LogData::new_unchecked(self.topics.to_vec(), self.data.clone()),
+        }
+    }
+}
+
+impl From<EvmLog> for Log {
+    #[inline]
+    fn from(log: EvmLog) -> Self {
+        Log {
+            address: log.address,
+            data: LogData::new_unchecked(log.topics.into_vec(), log.data),
+        }
+    }
+}
+
+impl From<Log> for EvmLog {
+    #[inline]
+    fn from(log: Log) -> Self {
+        let (topics, data) = log.data.split();
+        Self {
+            address: log.address,
+            topics: topics.into(),
+            data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{address, b256, bytes};
+
+    #[test]
+    fn log_conversion() {
+        let log = EvmLog::new(
+            address!("0000000000000000000000000000000000000001"),
+            [
+                b256!("0000000000000000000000000000000000000000000000000000000000000002"),
+                b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+            ]
+            .into_iter()
+            .collect(),
+            bytes!("0405"),
+        );
+        assert!(!log.topics.spilled());
+
+        let converted = log.to_log();
+        assert_eq!(converted.topics(), &log.topics[..]);
+        assert_eq!(converted.data.data, log.data);
+        assert_eq!(Log::from(log.clone()), converted);
+        assert_eq!(EvmLog::from(converted), log);
+    }
+}
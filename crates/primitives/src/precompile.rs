@@ -1,4 +1,4 @@
-use crate::{Bytes, CfgEnv};
+use crate::{Address, Bytes, CfgEnv};
 use core::fmt;
 use dyn_clone::DynClone;
 use std::{boxed::Box, string::String, sync::Arc};
@@ -27,16 +27,36 @@ impl PrecompileOutput {
 pub type StandardPrecompileFn = fn(&Bytes, u64) -> PrecompileResult;
 pub type EnvPrecompileFn = fn(&Bytes, u64, env: &CfgEnv) -> PrecompileResult;
 
+/// Returns the gas a precompile requires for the given input, without executing it.
+///
+/// Calls with a lower gas limit fail with [PrecompileError::OutOfGas]. Calls that fail with
+/// another error, like an input of the wrong length, may require less.
+pub type PrecompileGasFn = fn(&[u8]) -> u64;
+
 /// Stateful precompile trait. It is used to create
 /// a arc precompile Precompile::Stateful.
 pub trait StatefulPrecompile: Sync + Send {
     fn call(&self, bytes: &Bytes, gas_limit: u64, env: &CfgEnv) -> PrecompileResult;
+
+    /// Returns the gas required for `bytes`, if it is known without executing, see
+    /// [PrecompileGasFn].
+    fn required_gas(&self, bytes: &[u8]) -> Option<u64> {
+        let _ = bytes;
+        None
+    }
 }
 
 /// Mutable stateful precompile trait. It is used to create
 /// a boxed precompile in Precompile::StatefulMut.
 pub trait StatefulPrecompileMut: DynClone + Send + Sync {
     fn call_mut(&mut self, bytes: &Bytes, gas_limit: u64, env: &CfgEnv) -> PrecompileResult;
+
+    /// Returns the gas required for `bytes`, if it is known without executing, see
+    /// [PrecompileGasFn].
+    fn required_gas(&self, bytes: &[u8]) -> Option<u64> {
+        let _ = bytes;
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(StatefulPrecompileMut);
@@ -52,6 +72,8 @@ pub type StatefulPrecompileBox = Box<dyn StatefulPrecompileMut>;
 pub enum Precompile {
     /// Standard simple precompile that takes input and gas limit.
     Standard(StandardPrecompileFn),
+    /// Standard precompile whose gas can be computed without executing it.
+    Metered(StandardPrecompileFn, PrecompileGasFn),
     /// Similar to Standard but takes reference to [`CfgEnv`].
     Env(EnvPrecompileFn),
     /// Stateful precompile that is Arc over [`StatefulPrecompile`] trait.
@@ -90,6 +112,7 @@ impl fmt::Debug for Precompile {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Precompile::Standard(_) => f.write_str("Standard"),
+            Precompile::Metered(..) => f.write_str("Metered"),
             Precompile::Env(_) => f.write_str("Env"),
             Precompile::Stateful(_) => f.write_str("Stateful"),
             Precompile::StatefulMut(_) => f.write_str("StatefulMut"),
@@ -111,7 +134,7 @@ impl Precompile {
     /// Call the precompile with the given input and gas limit and return the result.
     pub fn call(&mut self, bytes: &Bytes, gas_limit: u64, env: &CfgEnv) -> PrecompileResult {
         match *self {
-            Precompile::Standard(p) => p(bytes, gas_limit),
+            Precompile::Standard(p) | Precompile::Metered(p, _) => p(bytes, gas_limit),
             Precompile::Env(p) => p(bytes, gas_limit, env),
             Precompile::Stateful(ref p) => p.call(bytes, gas_limit, env),
             Precompile::StatefulMut(ref mut p) => p.call_mut(bytes, gas_limit, env),
@@ -123,7 +146,7 @@ impl Precompile {
     /// Returns an error if the precompile is mutable.
     pub fn call_ref(&self, bytes: &Bytes, gas_limit: u64, env: &CfgEnv) -> PrecompileResult {
         match *self {
-            Precompile::Standard(p) => p(bytes, gas_limit),
+            Precompile::Standard(p) | Precompile::Metered(p, _) => p(bytes, gas_limit),
             Precompile::Env(p) => p(bytes, gas_limit, env),
             Precompile::Stateful(ref p) => p.call(bytes, gas_limit, env),
             Precompile::StatefulMut(_) => Err(PrecompileErrors::Fatal {
@@ -131,6 +154,20 @@ impl Precompile {
             }),
         }
     }
+
+    /// Returns the gas required for `bytes`, if it is known without executing the
+    /// precompile, see [PrecompileGasFn].
+    ///
+    /// Gas estimators can use it to price calls, and callers to reject calls with a lower gas
+    /// limit without executing them.
+    pub fn required_gas(&self, bytes: &[u8]) -> Option<u64> {
+        match *self {
+            Precompile::Metered(_, gas) => Some(gas(bytes)),
+            Precompile::Standard(_) | Precompile::Env(_) => None,
+            Precompile::Stateful(ref p) => p.required_gas(bytes),
+            Precompile::StatefulMut(ref p) => p.required_gas(bytes),
+        }
+    }
 }
 
 /// Precompile errors.
@@ -218,6 +255,23 @@ impl fmt::Display for PrecompileError {
     }
 }
 
+/// Error of a failed precompile call, with the address of the precompile.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PrecompileFailure {
+    /// Address of the precompile.
+    pub address: Address,
+    /// Error returned by the precompile.
+    pub error: PrecompileError,
+}
+
+impl core::error::Error for PrecompileFailure {}
+
+impl fmt::Display for PrecompileFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "precompile {} failed: {}", self.address, self.error)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -46,6 +46,11 @@ pub enum ExecutionResult<HaltReasonT: HaltReasonTrait> {
         reason: HaltReasonT,
         /// Halting will spend all the gas, and will be equal to gas_limit.
         gas_used: u64,
+        /// Instruction that halted the execution.
+        ///
+        /// `None` if the halt was not caused by an instruction, for example when the
+        /// transaction calls a failing precompile or the created contract is too large.
+        location: Option<HaltLocation>,
     },
 }
 
@@ -100,6 +105,14 @@ impl<HaltReasonT: HaltReasonTrait> ExecutionResult<HaltReasonT> {
         }
     }
 
+    /// Returns the location of the instruction that halted the execution, if any.
+    pub fn halt_location(&self) -> Option<&HaltLocation> {
+        match self {
+            Self::Halt { location, .. } => location.as_ref(),
+            _ => None,
+        }
+    }
+
     /// Returns the gas used.
     pub fn gas_used(&self) -> u64 {
         match *self {
@@ -159,6 +172,19 @@ pub enum EVMError<DBError, TransactionValidationErrorT> {
     Header(InvalidHeader),
     /// Database error.
     Database(DBError),
+    /// Database error of an instruction, for example of `SLOAD` or `BALANCE`.
+    InstructionDatabase {
+        /// Error returned by the database.
+        error: DBError,
+        /// Location of the instruction that accessed the database.
+        location: HaltLocation,
+    },
+    /// State change attempted by a read-only execution, see `CfgEnv::read_only`.
+    StateChangeInReadOnly {
+        /// Location of the instruction that attempted the state change, or `None` if the
+        /// transaction itself creates a contract or transfers value.
+        location: Option<HaltLocation>,
+    },
     /// Custom error.
     ///
     /// Useful for handler registers where custom logic would want to return their own custom error.
@@ -168,6 +194,14 @@ pub enum EVMError<DBError, TransactionValidationErrorT> {
 }
 
 impl<DBError, TransactionValidationErrorT> EVMError<DBError, TransactionValidationErrorT> {
+    /// Returns the database error, whether it was returned to an instruction or not.
+    pub fn database_error(&self) -> Option<&DBError> {
+        match self {
+            Self::Database(error) | Self::InstructionDatabase { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
     /// Maps a `DBError` to a new error type using the provided closure, leaving other variants unchanged.
     pub fn map_db_err<F, E>(self, op: F) -> EVMError<E, TransactionValidationErrorT>
     where
@@ -177,6 +211,13 @@ impl<DBError, TransactionValidationErrorT> EVMError<DBError, TransactionValidati
             Self::Transaction(e) => EVMError::Transaction(e),
             Self::Header(e) => EVMError::Header(e),
             Self::Database(e) => EVMError::Database(op(e)),
+            Self::InstructionDatabase { error, location } => EVMError::InstructionDatabase {
+                error: op(error),
+                location,
+            },
+            Self::StateChangeInReadOnly { location } => {
+                EVMError::StateChangeInReadOnly { location }
+            }
             Self::Precompile(e) => EVMError::Precompile(e),
             Self::Custom(e) => EVMError::Custom(e),
         }
@@ -194,8 +235,8 @@ where
         match self {
             Self::Transaction(e) => Some(e),
             Self::Header(e) => Some(e),
-            Self::Database(e) => Some(e),
-            Self::Precompile(_) | Self::Custom(_) => None,
+            Self::Database(e) | Self::InstructionDatabase { error: e, .. } => Some(e),
+            Self::StateChangeInReadOnly { .. } | Self::Precompile(_) | Self::Custom(_) => None,
         }
     }
 }
@@ -211,6 +252,21 @@ where
             Self::Transaction(e) => write!(f, "transaction validation error: {e}"),
             Self::Header(e) => write!(f, "header validation error: {e}"),
             Self::Database(e) => write!(f, "database error: {e}"),
+            Self::InstructionDatabase { error, location } => write!(
+                f,
+                "database error at {} pc {} depth {}: {error}",
+                location.address, location.pc, location.depth
+            ),
+            Self::StateChangeInReadOnly {
+                location: Some(location),
+            } => write!(
+                f,
+                "state change in read-only execution at {} pc {} depth {}",
+                location.address, location.pc, location.depth
+            ),
+            Self::StateChangeInReadOnly { location: None } => {
+                f.write_str("state change in read-only execution by the transaction")
+            }
             Self::Precompile(e) | Self::Custom(e) => f.write_str(e),
         }
     }
@@ -303,6 +359,11 @@ pub enum InvalidTransaction {
     EmptyAuthorizationList,
     /// Invalid EIP-7702 Authorization List
     InvalidAuthorizationList(InvalidAuthorization),
+    /// Signer of the transaction could not be recovered, or the signature `s` value is
+    /// invalid according to EIP-2.
+    InvalidSignature,
+    /// Recovered signer of the transaction does not match the caller.
+    SignerMismatch,
 }
 
 impl From<InvalidAuthorization> for InvalidTransaction {
@@ -373,6 +434,8 @@ impl fmt::Display for InvalidTransaction {
             }
             Self::EmptyAuthorizationList => write!(f, "empty authorization list"),
             Self::InvalidAuthorizationList(i) => fmt::Display::fmt(i, f),
+            Self::InvalidSignature => write!(f, "invalid transaction signature"),
+            Self::SignerMismatch => write!(f, "transaction signer does not match the caller"),
         }
     }
 }
@@ -446,6 +509,22 @@ pub enum HaltReason {
     EOFFunctionStackOverflow,
     /// Check for target address validity is only done inside subcall.
     InvalidEXTCALLTarget,
+    /// Execution budget set in [`crate::CfgEnv`] has been exhausted.
+    ExecutionBudgetExceeded,
+}
+
+/// Location of the instruction that exceptionally halted an execution.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HaltLocation {
+    /// Address of the account whose code was executed.
+    pub address: Address,
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Opcode of the instruction.
+    pub opcode: u8,
+    /// Call depth of the frame that executed the instruction, as counted by the journal.
+    pub depth: u64,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
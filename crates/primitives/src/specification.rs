@@ -139,6 +139,12 @@ pub trait Spec: Sized + 'static {
     /// The specification ID.
     const SPEC_ID: SpecId;
 
+    /// The specification ID if it is known at compile time, `None` for [`RuntimeSpec`].
+    ///
+    /// Code that is also compiled for [`RuntimeSpec`] must use this instead of
+    /// [`Spec::SPEC_ID`] and [`Spec::enabled`].
+    const STATIC_SPEC_ID: Option<SpecId> = Some(Self::SPEC_ID);
+
     /// Returns `true` if the given specification ID is enabled in this spec.
     #[inline]
     fn enabled(spec_id: SpecId) -> bool {
@@ -180,6 +186,24 @@ spec!(PRAGUE_EOF, PragueEofSpec);
 
 spec!(LATEST, LatestSpec);
 
+/// Spec that is resolved at runtime instead of being a type parameter.
+///
+/// Code generic over [`Spec`] is compiled once per spec it is used with. Instructions compiled
+/// for this spec read the spec of the host instead, so one instruction table serves every
+/// spec at the cost of a branch in the instructions that depend on it.
+///
+/// It has no [`Spec::SPEC_ID`]: code compiled for it that uses [`Spec::SPEC_ID`] or
+/// [`Spec::enabled`] fails to build, instead of silently executing with some fixed spec. Such
+/// code has to check [`Spec::STATIC_SPEC_ID`] and read the spec at runtime when it is `None`.
+/// The handles of revm are not compiled for it, they are given the spec as a value instead.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuntimeSpec;
+
+impl Spec for RuntimeSpec {
+    const SPEC_ID: SpecId = panic!("`RuntimeSpec` has no compile-time `SPEC_ID`");
+    const STATIC_SPEC_ID: Option<SpecId> = None;
+}
+
 #[macro_export]
 macro_rules! spec_to_generic {
     ($spec_id:expr, $e:expr) => {{
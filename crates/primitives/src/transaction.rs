@@ -1,4 +1,6 @@
-use crate::{AccessListItem, Address, AuthorizationList, Bytes, TxKind, B256, GAS_PER_BLOB, U256};
+use crate::{
+    AccessListItem, Address, AuthorizationList, Bytes, Signature, TxKind, B256, GAS_PER_BLOB, U256,
+};
 
 /// Trait for retrieving transaction information required for execution.
 pub trait Transaction {
@@ -14,8 +16,11 @@ pub trait Transaction {
     fn value(&self) -> &U256;
     /// Returns the input data of the transaction.
     fn data(&self) -> &Bytes;
-    /// The nonce of the transaction.
-    fn nonce(&self) -> u64;
+    /// The nonce of the transaction, or `None` to use the current nonce of the caller.
+    ///
+    /// A set nonce is validated against the nonce of the caller, unless the nonce check is
+    /// disabled in the configuration.
+    fn nonce(&self) -> Option<u64>;
     /// The chain ID of the transaction. If set to `None`, no checks are performed.
     ///
     /// Incorporated as part of the Spurious Dragon upgrade via [EIP-155].
@@ -55,6 +60,16 @@ pub trait Transaction {
     /// [EIP-Set EOA account code for one transaction](https://eips.ethereum.org/EIPS/eip-7702)
     fn authorization_list(&self) -> Option<&AuthorizationList>;
 
+    /// Whether this is a system transaction, executed on behalf of the consensus layer or for
+    /// maintenance operations.
+    ///
+    /// System transactions skip the fee, nonce, balance and EIP-3607 checks. The caller is
+    /// not charged for gas, its nonce is not bumped and no fee is paid to the beneficiary.
+    /// Gas is still metered, and the transaction is still limited by its gas limit.
+    fn is_system(&self) -> bool {
+        false
+    }
+
     /// See [EIP-4844], [`crate::Env::calc_data_fee`], and [`crate::Env::calc_max_data_fee`].
     ///
     /// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
@@ -62,3 +77,21 @@ pub trait Transaction {
         GAS_PER_BLOB * self.blob_hashes().len() as u64
     }
 }
+
+/// Transaction that carries the signature of its sender.
+///
+/// Used to recover the caller of transactions that are executed from their raw form,
+/// see [`crate::Env::validate_signature`].
+pub trait SignedTransaction: Transaction {
+    /// Signature of the transaction.
+    ///
+    /// For legacy transactions with [EIP-155] replay protection, the parity of the signature
+    /// contains the chain ID.
+    ///
+    /// [EIP-155]: https://eips.ethereum.org/EIPS/eip-155
+    fn signature(&self) -> &Signature;
+    /// Hash of the transaction payload the signature was created over.
+    fn signature_hash(&self) -> B256;
+    /// Sets the caller to the recovered signer.
+    fn set_caller(&mut self, caller: Address);
+}
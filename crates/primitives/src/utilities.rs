@@ -1,5 +1,6 @@
 use crate::{
-    b256, B256, BLOB_GASPRICE_UPDATE_FRACTION, MIN_BLOB_GASPRICE, TARGET_BLOB_GAS_PER_BLOCK,
+    b256, Address, B256, BLOB_GASPRICE_UPDATE_FRACTION, MIN_BLOB_GASPRICE,
+    TARGET_BLOB_GAS_PER_BLOCK,
 };
 pub use alloy_primitives::keccak256;
 
@@ -7,6 +8,102 @@ pub use alloy_primitives::keccak256;
 pub const KECCAK_EMPTY: B256 =
     b256!("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
 
+/// Returns the address of a contract created with `CREATE` by `caller`, whose nonce was
+/// `nonce` before the creation.
+///
+/// This is `keccak256(rlp([caller, nonce]))[12..]`. Contract creation transactions, including
+/// the ones with EOF initcode, use the nonce of the transaction.
+#[inline]
+pub fn create_address(caller: Address, nonce: u64) -> Address {
+    caller.create(nonce)
+}
+
+/// Returns the address of a contract created with `CREATE2` by `caller`, from the `salt` and
+/// the hash of the init code.
+///
+/// This is `keccak256(0xff ++ caller ++ salt ++ init_code_hash)[12..]`. The salt is the 32
+/// bytes popped from the stack, convert a `U256` stack value with `B256::from`.
+#[inline]
+pub fn create2_address(caller: Address, salt: B256, init_code_hash: B256) -> Address {
+    caller.create2(salt, init_code_hash)
+}
+
+/// Returns the address of a contract created with `CREATE2` by `caller`, from the `salt` and
+/// the init code.
+///
+/// See [create2_address].
+#[inline]
+pub fn create2_address_from_code(caller: Address, salt: B256, init_code: &[u8]) -> Address {
+    create2_address(caller, salt, keccak256(init_code))
+}
+
+/// Returns the address of a contract created with EOF `EOFCREATE` by `caller`, from the `salt`
+/// and the init container.
+///
+/// The derivation is currently the one of `CREATE2`, with the hash of the init container in
+/// place of the hash of the init code. Use this function instead of [create2_address] so that
+/// code creating EOF contracts follows future changes of the EOF specification.
+#[inline]
+pub fn eofcreate_address(caller: Address, salt: B256, init_container: &[u8]) -> Address {
+    create2_address_from_code(caller, salt, init_container)
+}
+
+/// Parameters of the [EIP-1559] base fee adjustment.
+///
+/// [EIP-1559]: https://eips.ethereum.org/EIPS/eip-1559
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BaseFeeParams {
+    /// Bounds the change of the base fee between two blocks to `1 / max_change_denominator`.
+    pub max_change_denominator: u64,
+    /// Ratio between the gas limit and the gas target of a block.
+    pub elasticity_multiplier: u64,
+}
+
+impl BaseFeeParams {
+    /// Parameters of Ethereum mainnet.
+    pub const ETHEREUM: Self = Self::new(8, 2);
+
+    /// Creates base fee parameters, for example for L2s with a different elasticity.
+    pub const fn new(max_change_denominator: u64, elasticity_multiplier: u64) -> Self {
+        Self {
+            max_change_denominator,
+            elasticity_multiplier,
+        }
+    }
+}
+
+impl Default for BaseFeeParams {
+    fn default() -> Self {
+        Self::ETHEREUM
+    }
+}
+
+/// Calculates the base fee of a block from the gas used, gas limit and base fee of its parent.
+///
+/// See also [the EIP-1559 specification](https://eips.ethereum.org/EIPS/eip-1559#specification).
+pub fn calc_next_block_base_fee(
+    parent_gas_used: u64,
+    parent_gas_limit: u64,
+    parent_base_fee: u64,
+    params: BaseFeeParams,
+) -> u64 {
+    let gas_target = parent_gas_limit / params.elasticity_multiplier.max(1);
+    if gas_target == 0 || parent_gas_used == gas_target {
+        return parent_base_fee;
+    }
+    let denominator = gas_target as u128 * params.max_change_denominator.max(1) as u128;
+    let base_fee = parent_base_fee as u128;
+    if parent_gas_used > gas_target {
+        let delta = base_fee * (parent_gas_used - gas_target) as u128 / denominator;
+        parent_base_fee.saturating_add(delta.max(1).try_into().unwrap_or(u64::MAX))
+    } else {
+        let delta = base_fee * (gas_target - parent_gas_used) as u128 / denominator;
+        // The delta is lower than the base fee, as the gas target is greater than the difference.
+        parent_base_fee - delta as u64
+    }
+}
+
 /// Calculates the `excess_blob_gas` from the parent header's `blob_gas_used` and `excess_blob_gas`.
 ///
 /// See also [the EIP-4844 helpers]<https://eips.ethereum.org/EIPS/eip-4844#helpers>
@@ -62,7 +159,58 @@ pub fn fake_exponential(factor: u64, numerator: u64, denominator: u64) -> u128 {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::GAS_PER_BLOB;
+    use crate::{address, GAS_PER_BLOB, U256};
+
+    #[test]
+    fn test_create_addresses() {
+        // Deployer of the beacon chain deposit contract.
+        let caller = address!("b20a608c624ca5003905aa834de7156c68b2e1d0");
+        assert_eq!(
+            create_address(caller, 0),
+            address!("00000000219ab540356cBB839Cbe05303d7705Fa")
+        );
+
+        // EIP-1014 example 5.
+        let caller = address!("00000000000000000000000000000000deadbeef");
+        let salt = B256::from(U256::from(0xcafebabe_u64));
+        assert_eq!(
+            create2_address_from_code(caller, salt, &[0xde, 0xad, 0xbe, 0xef]),
+            address!("60f3f640a8508fC6a86d45DF051962668E1e8AC7")
+        );
+        assert_eq!(
+            eofcreate_address(caller, salt, &[0xde, 0xad, 0xbe, 0xef]),
+            create2_address(caller, salt, keccak256([0xde, 0xad, 0xbe, 0xef]))
+        );
+    }
+
+    #[test]
+    fn test_calc_next_block_base_fee() {
+        let params = BaseFeeParams::ETHEREUM;
+        for (gas_used, expected) in [
+            (10_000_000, 1_000_000_000),
+            (9_000_000, 987_500_000),
+            (11_000_000, 1_012_500_000),
+            (0, 875_000_000),
+            (20_000_000, 1_125_000_000),
+        ] {
+            assert_eq!(
+                calc_next_block_base_fee(gas_used, 20_000_000, 1_000_000_000, params),
+                expected
+            );
+        }
+        // The base fee increases by at least one above the target.
+        assert_eq!(
+            calc_next_block_base_fee(10_000_001, 20_000_000, 1, params),
+            2
+        );
+
+        // Elasticity and denominator of an L2.
+        let params = BaseFeeParams::new(250, 6);
+        assert_eq!(
+            calc_next_block_base_fee(30_000_000, 30_000_000, 1_000_000_000, params),
+            1_020_000_000
+        );
+    }
 
     // https://github.com/ethereum/go-ethereum/blob/28857080d732857030eda80c69b9ba2c8926f221/consensus/misc/eip4844/eip4844_test.go#L27
     #[test]
@@ -3,15 +3,23 @@ use criterion::{
 };
 use revm::{
     db::BenchmarkDB,
-    interpreter::{analysis::to_analysed, Contract, DummyHost, Interpreter},
-    primitives::{address, bytes, hex, BerlinSpec, Bytecode, Bytes, EthereumWiring, TxKind, U256},
+    interpreter::{analysis::to_analysed, opcode::*, Contract, DummyHost, Interpreter},
+    primitives::{
+        address, bytes, hex, Address, BerlinSpec, Bytecode, Bytes, EthereumWiring, RuntimeSpec,
+        SpecId, TxKind, U256,
+    },
     Evm,
 };
-use revm_interpreter::{opcode::make_instruction_table, SharedMemory, EMPTY_SHARED_MEMORY};
-use std::time::Duration;
+use revm_interpreter::{
+    opcode::{make_instruction_table, InstructionTable},
+    SharedMemory, Stack, EMPTY_SHARED_MEMORY, STACK_LIMIT,
+};
+use std::{hint::black_box, time::Duration};
 
 fn analysis(c: &mut Criterion) {
     let evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        .with_db(BenchmarkDB::default())
+        .with_default_ext_ctx()
         .modify_tx_env(|tx| {
             tx.caller = address!("0000000000000000000000000000000000000002");
             tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
@@ -45,6 +53,7 @@ fn analysis(c: &mut Criterion) {
 fn snailtracer(c: &mut Criterion) {
     let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
         .with_db(BenchmarkDB::new_bytecode(bytecode(SNAILTRACER)))
+        .with_default_ext_ctx()
         .modify_tx_env(|tx| {
             tx.caller = address!("1000000000000000000000000000000000000000");
             tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
@@ -59,12 +68,27 @@ fn snailtracer(c: &mut Criterion) {
         .sample_size(10);
     bench_transact(&mut g, &mut evm);
     bench_eval(&mut g, &mut evm);
+
+    evm.cfg_mut().fuse_instructions = true;
+    g.bench_function("transact/fused", |b| b.iter(|| evm.transact().unwrap()));
+    evm.cfg_mut().fuse_instructions = false;
+
+    #[cfg(feature = "compiler")]
+    {
+        use revm::{compiler::CompiledContracts, interpreter::compiler::BlockBackend};
+        use std::{cell::RefCell, rc::Rc};
+
+        let contracts = Rc::new(RefCell::new(CompiledContracts::new(BlockBackend, 1)));
+        let mut evm = evm.modify().with_compiler(contracts).build();
+        g.bench_function("transact/compiled", |b| b.iter(|| evm.transact().unwrap()));
+    }
     g.finish();
 }
 
 fn transfer(c: &mut Criterion) {
     let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
         .with_db(BenchmarkDB::new_bytecode(Bytecode::new()))
+        .with_default_ext_ctx()
         .modify_tx_env(|tx| {
             tx.caller = address!("0000000000000000000000000000000000000001");
             tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
@@ -78,6 +102,197 @@ fn transfer(c: &mut Criterion) {
     g.finish();
 }
 
+fn storage(c: &mut Criterion) {
+    // for n in (1..=500).rev() { sstore(n, n); sload(n) }
+    let code = [
+        PUSH2, 0x01, 0xF4, JUMPDEST, DUP1, DUP1, SSTORE, DUP1, SLOAD, POP, PUSH1, 0x01, SWAP1, SUB,
+        DUP1, PUSH1, 0x03, JUMPI, STOP,
+    ];
+    // Half of the slots are already set, so both fresh and dirty writes are measured.
+    let db = (1..=250).fold(
+        BenchmarkDB::new_bytecode(raw_bytecode(&code)),
+        |db, slot| db.with_storage(Address::ZERO, U256::from(slot), U256::from(1)),
+    );
+    let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        .with_db(db)
+        .with_default_ext_ctx()
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TxKind::Call(Address::ZERO);
+            tx.gas_limit = 30_000_000;
+        })
+        .build();
+    assert!(evm.transact().unwrap().result.is_success());
+
+    let mut g = c.benchmark_group("storage");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+    bench_transact(&mut g, &mut evm);
+    g.finish();
+}
+
+fn log_spam(c: &mut Criterion) {
+    // for n in (1..=500).rev() { log4(0, 64, n, n, n, n) }
+    let code = [
+        PUSH2, 0x01, 0xF4, JUMPDEST, DUP1, DUP1, DUP1, DUP1, PUSH1, 0x40, PUSH1, 0x00, LOG4, PUSH1,
+        0x01, SWAP1, SUB, DUP1, PUSH1, 0x03, JUMPI, STOP,
+    ];
+    let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        .with_db(BenchmarkDB::new_bytecode(raw_bytecode(&code)))
+        .with_default_ext_ctx()
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TxKind::Call(Address::ZERO);
+            tx.gas_limit = 30_000_000;
+        })
+        .build();
+    let result = evm.transact().unwrap().result;
+    assert_eq!(result.logs().len(), 500);
+
+    let mut g = c.benchmark_group("log_spam");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+    bench_transact(&mut g, &mut evm);
+    g.finish();
+}
+
+fn deep_calls(c: &mut Criterion) {
+    // The contract calls itself with all its gas, until the gas or the depth limit is reached.
+    let code = [
+        PUSH1, 0x00, DUP1, DUP1, DUP1, DUP1, ADDRESS, GAS, CALL, STOP,
+    ];
+    let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        .with_db(BenchmarkDB::new_bytecode(raw_bytecode(&code)))
+        .with_default_ext_ctx()
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TxKind::Call(Address::ZERO);
+            tx.gas_limit = 30_000_000;
+        })
+        .build();
+    assert!(evm.transact().unwrap().result.is_success());
+
+    let mut g = c.benchmark_group("deep_calls");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+    bench_transact(&mut g, &mut evm);
+    g.finish();
+}
+
+fn calldata_hashing(c: &mut Criterion) {
+    // keccak256(calldata)
+    let code = [
+        CALLDATASIZE,
+        PUSH1,
+        0x00,
+        PUSH1,
+        0x00,
+        CALLDATACOPY,
+        CALLDATASIZE,
+        PUSH1,
+        0x00,
+        KECCAK256,
+        POP,
+        STOP,
+    ];
+    let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        .with_db(BenchmarkDB::new_bytecode(raw_bytecode(&code)))
+        .with_default_ext_ctx()
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TxKind::Call(Address::ZERO);
+            tx.data = vec![0xab; 128 * 1024].into();
+            tx.gas_limit = 30_000_000;
+        })
+        .build();
+    assert!(evm.transact().unwrap().result.is_success());
+
+    let mut g = c.benchmark_group("calldata_hashing");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+    bench_transact(&mut g, &mut evm);
+    g.finish();
+}
+
+fn create2(c: &mut Criterion) {
+    // for salt in (1..=100).rev() { create2(0, init_code, salt) }, where the init code deploys
+    // the runtime code 0x42.
+    let code = [
+        PUSH10, PUSH1, 0x42, PUSH1, 0x00, MSTORE8, PUSH1, 0x01, PUSH1, 0x00, RETURN, PUSH1, 0x00,
+        MSTORE, PUSH1, 0x64, JUMPDEST, DUP1, PUSH1, 0x0A, PUSH1, 0x16, PUSH1, 0x00, CREATE2, POP,
+        PUSH1, 0x01, SWAP1, SUB, DUP1, PUSH1, 0x10, JUMPI, STOP,
+    ];
+    let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        .with_db(BenchmarkDB::new_bytecode(raw_bytecode(&code)))
+        .with_default_ext_ctx()
+        .modify_tx_env(|tx| {
+            tx.caller = address!("0000000000000000000000000000000000000001");
+            tx.transact_to = TxKind::Call(Address::ZERO);
+            tx.gas_limit = 30_000_000;
+        })
+        .build();
+    let result = evm.transact().unwrap();
+    assert!(result.result.is_success());
+    assert_eq!(result.state[&Address::ZERO].info.nonce, 101);
+
+    let mut g = c.benchmark_group("create2");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+    bench_transact(&mut g, &mut evm);
+    g.finish();
+}
+
+fn stack(c: &mut Criterion) {
+    let mut g = c.benchmark_group("stack");
+    g.noise_threshold(0.03).warm_up_time(Duration::from_secs(1));
+
+    // Fills the stack and empties it again.
+    g.bench_function("push_pop/checked", |b| {
+        let mut stack = Stack::new();
+        b.iter(|| {
+            for i in 0..STACK_LIMIT {
+                stack.push(black_box(U256::from(i))).unwrap();
+            }
+            while let Ok(value) = stack.pop() {
+                black_box(value);
+            }
+        })
+    });
+    g.bench_function("push_pop/unchecked", |b| {
+        let mut stack = Stack::new();
+        b.iter(|| unsafe {
+            for i in 0..STACK_LIMIT {
+                stack.push_unchecked(black_box(U256::from(i)));
+            }
+            for _ in 0..STACK_LIMIT {
+                black_box(stack.pop_unsafe());
+            }
+        })
+    });
+
+    // `DUPn SWAPn POP` sequences on a stack of 16 words.
+    let mut stack = Stack::new();
+    for i in 0..16 {
+        stack.push(U256::from(i)).unwrap();
+    }
+    g.bench_function("dup_swap/checked", |b| {
+        b.iter(|| {
+            for n in 1..=16 {
+                let n = black_box(n);
+                stack.dup(n).unwrap();
+                stack.swap(n).unwrap();
+                black_box(stack.pop().unwrap());
+            }
+        })
+    });
+    g.bench_function("dup_swap/unchecked", |b| {
+        b.iter(|| unsafe {
+            for n in 1..=16 {
+                let n = black_box(n);
+                stack.dup_unchecked(n);
+                stack.swap_unchecked(n);
+                black_box(stack.pop_unsafe());
+            }
+        })
+    });
+    g.finish();
+}
+
 fn bench_transact(
     g: &mut BenchmarkGroup<'_, WallTime>,
     evm: &mut Evm<'_, EthereumWiring<BenchmarkDB, ()>>,
@@ -96,7 +311,51 @@ fn bench_eval(
     g: &mut BenchmarkGroup<'_, WallTime>,
     evm: &mut Evm<'static, EthereumWiring<BenchmarkDB, ()>>,
 ) {
-    g.bench_function("eval", |b| {
+    type Host = DummyHost<EthereumWiring<BenchmarkDB, ()>>;
+    bench_eval_with(g, evm, "eval", make_instruction_table::<Host, BerlinSpec>());
+    // Same spec read from the host, the difference is the overhead of the runtime dispatch.
+    bench_eval_with(
+        g,
+        evm,
+        "eval/runtime-spec",
+        make_instruction_table::<Host, RuntimeSpec>(),
+    );
+    // Same spec dispatched with a `match` on the opcode instead of the instruction table.
+    bench_eval_match(g, evm);
+}
+
+fn bench_eval_match(
+    g: &mut BenchmarkGroup<'_, WallTime>,
+    evm: &mut Evm<'static, EthereumWiring<BenchmarkDB, ()>>,
+) {
+    g.bench_function("eval/match", |b| {
+        let contract = Contract {
+            input: evm.context.evm.env.tx.data.clone(),
+            bytecode: to_analysed(evm.context.evm.db.0.clone()),
+            ..Default::default()
+        };
+        let mut shared_memory = SharedMemory::new();
+        let mut host =
+            DummyHost::<EthereumWiring<BenchmarkDB, ()>>::new(*evm.context.evm.env.clone());
+        host.spec_id = SpecId::BERLIN;
+        b.iter(move || {
+            let temp = core::mem::replace(&mut shared_memory, EMPTY_SHARED_MEMORY);
+            let mut interpreter = Interpreter::new(contract.clone(), u64::MAX, false);
+            let res = interpreter.run_match::<_, BerlinSpec>(temp, &mut host);
+            shared_memory = interpreter.take_memory();
+            host.clear();
+            res
+        })
+    });
+}
+
+fn bench_eval_with(
+    g: &mut BenchmarkGroup<'_, WallTime>,
+    evm: &mut Evm<'static, EthereumWiring<BenchmarkDB, ()>>,
+    id: &str,
+    instruction_table: InstructionTable<DummyHost<EthereumWiring<BenchmarkDB, ()>>>,
+) {
+    g.bench_function(id, |b| {
         let contract = Contract {
             input: evm.context.evm.env.tx.data.clone(),
             bytecode: to_analysed(evm.context.evm.db.0.clone()),
@@ -104,8 +363,7 @@ fn bench_eval(
         };
         let mut shared_memory = SharedMemory::new();
         let mut host = DummyHost::new(*evm.context.evm.env.clone());
-        let instruction_table =
-            make_instruction_table::<DummyHost<EthereumWiring<BenchmarkDB, ()>>, BerlinSpec>();
+        host.spec_id = SpecId::BERLIN;
         b.iter(move || {
             // replace memory with empty memory to use it inside interpreter.
             // Later return memory back.
@@ -123,12 +381,22 @@ fn bytecode(s: &str) -> Bytecode {
     to_analysed(Bytecode::new_raw(hex::decode(s).unwrap().into()))
 }
 
+fn raw_bytecode(code: &[u8]) -> Bytecode {
+    to_analysed(Bytecode::new_raw(Bytes::copy_from_slice(code)))
+}
+
 #[rustfmt::skip]
 criterion_group!(
     benches,
     analysis,
     snailtracer,
     transfer,
+    storage,
+    log_spam,
+    deep_calls,
+    calldata_hashing,
+    create2,
+    stack,
 );
 criterion_main!(benches);
 
@@ -0,0 +1,443 @@
+//! Minimal Solidity ABI encoding and decoding, enabled by the `abi` feature.
+//!
+//! [`encode_call`] builds the [`TxEnv::data`](crate::primitives::TxEnv::data) of a call from
+//! the signature of a function and its arguments, and [`decode`] reads the output of the call
+//! from [`ExecutionResult::output`](crate::primitives::ExecutionResult::output). Only the types
+//! needed to call functions are supported, events and errors are not.
+
+use crate::primitives::{keccak256, Address, Bytes, B256, I256, U256};
+use core::fmt;
+use std::{boxed::Box, string::String, vec::Vec};
+
+/// Type of an ABI value, used to decode it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiType {
+    /// `uintN`, with the number of bits.
+    Uint(usize),
+    /// `intN`, with the number of bits.
+    Int(usize),
+    /// `address`.
+    Address,
+    /// `bool`.
+    Bool,
+    /// `bytesN`, with the number of bytes.
+    FixedBytes(usize),
+    /// `bytes`.
+    Bytes,
+    /// `string`.
+    String,
+    /// `T[]`.
+    Array(Box<AbiType>),
+    /// `T[N]`.
+    FixedArray(Box<AbiType>, usize),
+    /// `(T1, T2, ...)`.
+    Tuple(Vec<AbiType>),
+}
+
+impl AbiType {
+    /// Returns whether values of this type are encoded after the head of their tuple.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Self::Bytes | Self::String | Self::Array(_) => true,
+            Self::FixedArray(ty, _) => ty.is_dynamic(),
+            Self::Tuple(types) => types.iter().any(Self::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Returns the size of the value in the head of its tuple.
+    fn head_len(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => 32,
+            Self::FixedArray(ty, len) => ty.head_len().saturating_mul(*len),
+            Self::Tuple(types) => types.iter().map(Self::head_len).sum(),
+            _ => 32,
+        }
+    }
+}
+
+/// ABI value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AbiValue {
+    /// `uintN`.
+    Uint(U256),
+    /// `intN`.
+    Int(I256),
+    /// `address`.
+    Address(Address),
+    /// `bool`.
+    Bool(bool),
+    /// `bytesN`, left aligned.
+    FixedBytes(B256),
+    /// `bytes`.
+    Bytes(Bytes),
+    /// `string`.
+    String(String),
+    /// `T[]`.
+    Array(Vec<AbiValue>),
+    /// `T[N]`.
+    FixedArray(Vec<AbiValue>),
+    /// `(T1, T2, ...)`.
+    Tuple(Vec<AbiValue>),
+}
+
+impl AbiValue {
+    /// Returns whether the value is encoded after the head of its tuple.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Self::Bytes(_) | Self::String(_) | Self::Array(_) => true,
+            Self::FixedArray(values) | Self::Tuple(values) => values.iter().any(Self::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Returns the size of the value in the head of its tuple.
+    fn head_len(&self) -> usize {
+        match self {
+            _ if self.is_dynamic() => 32,
+            Self::FixedArray(values) | Self::Tuple(values) => {
+                values.iter().map(Self::head_len).sum()
+            }
+            _ => 32,
+        }
+    }
+
+    /// Appends the encoding of the value, its head if it is static or its tail otherwise.
+    fn encode_to(&self, out: &mut Vec<u8>) {
+        match self {
+            Self::Uint(value) => out.extend_from_slice(&value.to_be_bytes::<32>()),
+            Self::Int(value) => out.extend_from_slice(&value.to_be_bytes::<32>()),
+            Self::Address(address) => out.extend_from_slice(address.into_word().as_slice()),
+            Self::Bool(value) => {
+                out.extend_from_slice(B256::with_last_byte(*value as u8).as_slice())
+            }
+            Self::FixedBytes(bytes) => out.extend_from_slice(bytes.as_slice()),
+            Self::Bytes(bytes) => encode_bytes(bytes, out),
+            Self::String(string) => encode_bytes(string.as_bytes(), out),
+            Self::Array(values) => {
+                out.extend_from_slice(&U256::from(values.len()).to_be_bytes::<32>());
+                encode_sequence(values, out);
+            }
+            Self::FixedArray(values) | Self::Tuple(values) => encode_sequence(values, out),
+        }
+    }
+}
+
+impl From<U256> for AbiValue {
+    fn from(value: U256) -> Self {
+        Self::Uint(value)
+    }
+}
+
+impl From<I256> for AbiValue {
+    fn from(value: I256) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<Address> for AbiValue {
+    fn from(address: Address) -> Self {
+        Self::Address(address)
+    }
+}
+
+impl From<bool> for AbiValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<Bytes> for AbiValue {
+    fn from(bytes: Bytes) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl From<&str> for AbiValue {
+    fn from(string: &str) -> Self {
+        Self::String(string.into())
+    }
+}
+
+/// Error decoding ABI values.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AbiError {
+    /// The data ends before a value, or an offset or length points past its end.
+    OutOfBounds,
+    /// A value has bits set outside of its type, such as a `bool` other than 0 or 1.
+    InvalidValue,
+    /// A `string` is not valid UTF-8.
+    InvalidUtf8,
+}
+
+impl fmt::Display for AbiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfBounds => f.write_str("ABI data is out of bounds"),
+            Self::InvalidValue => f.write_str("invalid ABI value"),
+            Self::InvalidUtf8 => f.write_str("ABI string is not valid UTF-8"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AbiError {}
+
+/// Returns the selector of the function with the canonical `signature`, such as
+/// `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    keccak256(signature)[..4].try_into().unwrap()
+}
+
+/// Encodes `values` as a tuple, like the arguments of a function.
+pub fn encode(values: &[AbiValue]) -> Vec<u8> {
+    let mut out = Vec::new();
+    encode_sequence(values, &mut out);
+    out
+}
+
+/// Encodes a call to the function with the canonical `signature` with `args`.
+pub fn encode_call(signature: &str, args: &[AbiValue]) -> Bytes {
+    let mut out = selector(signature).to_vec();
+    encode_sequence(args, &mut out);
+    out.into()
+}
+
+/// Decodes a tuple of `types`, like the output of a function.
+pub fn decode(types: &[AbiType], data: &[u8]) -> Result<Vec<AbiValue>, AbiError> {
+    decode_sequence(types.iter(), data)
+}
+
+/// Encodes the heads of `values`, followed by the tails of the dynamic ones.
+fn encode_sequence(values: &[AbiValue], out: &mut Vec<u8>) {
+    let head_len: usize = values.iter().map(AbiValue::head_len).sum();
+    let mut tail = Vec::new();
+    for value in values {
+        if value.is_dynamic() {
+            out.extend_from_slice(&U256::from(head_len + tail.len()).to_be_bytes::<32>());
+            value.encode_to(&mut tail);
+        } else {
+            value.encode_to(out);
+        }
+    }
+    out.extend_from_slice(&tail);
+}
+
+/// Encodes the length of `bytes`, followed by `bytes` padded to a multiple of 32 bytes.
+fn encode_bytes(bytes: &[u8], out: &mut Vec<u8>) {
+    out.extend_from_slice(&U256::from(bytes.len()).to_be_bytes::<32>());
+    out.extend_from_slice(bytes);
+    out.resize(out.len() + (32 - bytes.len() % 32) % 32, 0);
+}
+
+/// Decodes a sequence of values whose heads start at the beginning of `data`, and whose
+/// offsets are relative to it.
+fn decode_sequence<'a>(
+    types: impl ExactSizeIterator<Item = &'a AbiType>,
+    data: &[u8],
+) -> Result<Vec<AbiValue>, AbiError> {
+    let mut values = Vec::with_capacity(types.len());
+    let mut head = 0;
+    for ty in types {
+        if ty.is_dynamic() {
+            let offset = read_usize(data, head)?;
+            values.push(decode_value(
+                ty,
+                data.get(offset..).ok_or(AbiError::OutOfBounds)?,
+            )?);
+            head += 32;
+        } else {
+            values.push(decode_value(
+                ty,
+                data.get(head..).ok_or(AbiError::OutOfBounds)?,
+            )?);
+            head += ty.head_len();
+        }
+    }
+    Ok(values)
+}
+
+/// Decodes the value of type `ty` at the beginning of `data`.
+fn decode_value(ty: &AbiType, data: &[u8]) -> Result<AbiValue, AbiError> {
+    let value = match ty {
+        AbiType::Uint(bits) => {
+            let value = U256::from_be_bytes(read_word(data, 0)?.0);
+            if value.bit_len() > *bits {
+                return Err(AbiError::InvalidValue);
+            }
+            AbiValue::Uint(value)
+        }
+        AbiType::Int(bits) => {
+            let value = I256::from_be_bytes(read_word(data, 0)?.0);
+            // The value must be the sign extension of its lowest `bits` bits.
+            let shift = 256usize.saturating_sub(*bits);
+            if shift != 0 && value.wrapping_shl(shift).asr(shift) != value {
+                return Err(AbiError::InvalidValue);
+            }
+            AbiValue::Int(value)
+        }
+        AbiType::Address => {
+            let word = read_word(data, 0)?;
+            if word[..12].iter().any(|&byte| byte != 0) {
+                return Err(AbiError::InvalidValue);
+            }
+            AbiValue::Address(Address::from_word(word))
+        }
+        AbiType::Bool => match U256::from_be_bytes(read_word(data, 0)?.0) {
+            value if value == U256::from(1) => AbiValue::Bool(true),
+            value if value.is_zero() => AbiValue::Bool(false),
+            _ => return Err(AbiError::InvalidValue),
+        },
+        AbiType::FixedBytes(len) => {
+            let word = read_word(data, 0)?;
+            if *len > 32 || word[*len..].iter().any(|&byte| byte != 0) {
+                return Err(AbiError::InvalidValue);
+            }
+            AbiValue::FixedBytes(word)
+        }
+        AbiType::Bytes => AbiValue::Bytes(Bytes::copy_from_slice(read_bytes(data)?)),
+        AbiType::String => AbiValue::String(
+            String::from_utf8(read_bytes(data)?.to_vec()).map_err(|_| AbiError::InvalidUtf8)?,
+        ),
+        AbiType::Array(ty) => {
+            let len = read_usize(data, 0)?;
+            let data = &data[32..];
+            // Every element has a head, bound the length before allocating the values.
+            if len.saturating_mul(ty.head_len().max(1)) > data.len() {
+                return Err(AbiError::OutOfBounds);
+            }
+            AbiValue::Array(decode_sequence(core::iter::repeat_n(&**ty, len), data)?)
+        }
+        AbiType::FixedArray(ty, len) => {
+            if len.saturating_mul(ty.head_len().max(1)) > data.len() {
+                return Err(AbiError::OutOfBounds);
+            }
+            AbiValue::FixedArray(decode_sequence(core::iter::repeat_n(&**ty, *len), data)?)
+        }
+        AbiType::Tuple(types) => AbiValue::Tuple(decode_sequence(types.iter(), data)?),
+    };
+    Ok(value)
+}
+
+/// Reads the word at `offset` of `data`.
+fn read_word(data: &[u8], offset: usize) -> Result<B256, AbiError> {
+    data.get(offset..offset.saturating_add(32))
+        .map(B256::from_slice)
+        .ok_or(AbiError::OutOfBounds)
+}
+
+/// Reads the word at `offset` of `data` as an offset or a length.
+fn read_usize(data: &[u8], offset: usize) -> Result<usize, AbiError> {
+    U256::from_be_bytes(read_word(data, offset)?.0)
+        .try_into()
+        .map_err(|_| AbiError::OutOfBounds)
+}
+
+/// Reads the bytes of a `bytes` or `string` value at the beginning of `data`.
+fn read_bytes(data: &[u8]) -> Result<&[u8], AbiError> {
+    let len = read_usize(data, 0)?;
+    data.get(32..32usize.saturating_add(len))
+        .ok_or(AbiError::OutOfBounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::hex;
+
+    #[test]
+    fn static_call() {
+        // Example of the Solidity documentation.
+        let data = encode_call("baz(uint32,bool)", &[U256::from(69).into(), true.into()]);
+        assert_eq!(
+            data[..],
+            hex!(
+                "cdcd77c0"
+                "0000000000000000000000000000000000000000000000000000000000000045"
+                "0000000000000000000000000000000000000000000000000000000000000001"
+            )
+        );
+        assert_eq!(
+            decode(&[AbiType::Uint(32), AbiType::Bool], &data[4..]),
+            Ok(vec![U256::from(69).into(), true.into()])
+        );
+    }
+
+    #[test]
+    fn dynamic_call() {
+        // Example of the Solidity documentation.
+        let args = [
+            Bytes::from_static(b"dave").into(),
+            true.into(),
+            AbiValue::Array(vec![
+                U256::from(1).into(),
+                U256::from(2).into(),
+                U256::from(3).into(),
+            ]),
+        ];
+        let data = encode_call("sam(bytes,bool,uint256[])", &args);
+        assert_eq!(
+            data[..],
+            hex!(
+                "a5643bf2"
+                "0000000000000000000000000000000000000000000000000000000000000060"
+                "0000000000000000000000000000000000000000000000000000000000000001"
+                "00000000000000000000000000000000000000000000000000000000000000a0"
+                "0000000000000000000000000000000000000000000000000000000000000004"
+                "6461766500000000000000000000000000000000000000000000000000000000"
+                "0000000000000000000000000000000000000000000000000000000000000003"
+                "0000000000000000000000000000000000000000000000000000000000000001"
+                "0000000000000000000000000000000000000000000000000000000000000002"
+                "0000000000000000000000000000000000000000000000000000000000000003"
+            )
+        );
+        let types = [
+            AbiType::Bytes,
+            AbiType::Bool,
+            AbiType::Array(Box::new(AbiType::Uint(256))),
+        ];
+        assert_eq!(decode(&types, &data[4..]), Ok(args.to_vec()));
+
+        // Nested dynamic values round trip.
+        let values = [
+            AbiValue::Tuple(vec!["a".into(), I256::MINUS_ONE.into()]),
+            AbiValue::FixedArray(vec!["bc".into(), "".into()]),
+            Address::with_last_byte(1).into(),
+        ];
+        let types = [
+            AbiType::Tuple(vec![AbiType::String, AbiType::Int(8)]),
+            AbiType::FixedArray(Box::new(AbiType::String), 2),
+            AbiType::Address,
+        ];
+        assert_eq!(decode(&types, &encode(&values)), Ok(values.to_vec()));
+    }
+
+    #[test]
+    fn invalid_data() {
+        let word = |byte: u8| B256::with_last_byte(byte).0;
+        assert_eq!(
+            decode(&[AbiType::Bool], &word(2)),
+            Err(AbiError::InvalidValue)
+        );
+        assert_eq!(
+            decode(&[AbiType::Uint(8)], &U256::from(256).to_be_bytes::<32>()),
+            Err(AbiError::InvalidValue)
+        );
+        assert_eq!(
+            decode(&[AbiType::Int(8)], &U256::from(128).to_be_bytes::<32>()),
+            Err(AbiError::InvalidValue)
+        );
+        assert_eq!(
+            decode(&[AbiType::Bool], &[0; 31]),
+            Err(AbiError::OutOfBounds)
+        );
+        // Offset, then a length larger than the data.
+        let data = [word(32), U256::MAX.to_be_bytes::<32>()].concat();
+        assert_eq!(decode(&[AbiType::Bytes], &data), Err(AbiError::OutOfBounds));
+        let data = [word(32), word(255)].concat();
+        assert_eq!(
+            decode(&[AbiType::Array(Box::new(AbiType::Bool))], &data),
+            Err(AbiError::OutOfBounds)
+        );
+    }
+}
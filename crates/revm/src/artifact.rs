@@ -0,0 +1,244 @@
+//! Loader of the contract artifacts of Foundry and Hardhat, enabled by the `serde-json` feature.
+//!
+//! [`Artifact::from_json`] reads the creation and deployed code of a compiled contract, with
+//! the positions of the addresses of the libraries it links to. [`Artifact::init_code`] and
+//! [`Artifact::deployed_code`] replace their placeholders with the addresses of the deployed
+//! libraries, so the code can be deployed or inserted in a database.
+
+use crate::primitives::{hex, Address, Bytecode, Bytes, HashMap};
+use core::fmt;
+use serde::Deserialize;
+use std::{collections::BTreeMap, string::String, vec::Vec};
+
+/// Positions of the library addresses in code, by source file and library name.
+pub type LinkReferences = BTreeMap<String, BTreeMap<String, Vec<LinkReference>>>;
+
+/// Position of a library address in code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct LinkReference {
+    /// Offset of the address in the code, in bytes.
+    pub start: usize,
+    /// Length of the address, in bytes.
+    pub length: usize,
+}
+
+/// Code of an artifact, which may contain placeholders for library addresses.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ArtifactCode {
+    /// Hex encoded code, with placeholders in place of the library addresses.
+    pub object: String,
+    /// Positions of the placeholders.
+    pub link_references: LinkReferences,
+}
+
+impl ArtifactCode {
+    /// Returns the code with the placeholders replaced by the addresses of `libraries`.
+    ///
+    /// Libraries are looked up by `<source file>:<name>`, then by name.
+    pub fn link(&self, libraries: &HashMap<String, Address>) -> Result<Bytes, ArtifactError> {
+        let object = self.object.strip_prefix("0x").unwrap_or(&self.object);
+        let mut object = object.as_bytes().to_vec();
+        for (file, references) in &self.link_references {
+            for (name, references) in references {
+                let library = std::format!("{file}:{name}");
+                let Some(address) = libraries.get(&library).or_else(|| libraries.get(name)) else {
+                    return Err(ArtifactError::UnlinkedLibrary(library));
+                };
+                let address = hex::encode(address);
+                for reference in references {
+                    let range = reference.start * 2..(reference.start + reference.length) * 2;
+                    if reference.length != 20 || range.end > object.len() {
+                        return Err(ArtifactError::InvalidLinkReference(*reference));
+                    }
+                    object[range].copy_from_slice(address.as_bytes());
+                }
+            }
+        }
+        hex::decode(object)
+            .map(Into::into)
+            .map_err(|_| ArtifactError::InvalidHex)
+    }
+}
+
+/// Compiled contract, as found in the artifacts of Foundry and Hardhat.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Artifact {
+    /// Creation code, deployed by a create transaction.
+    pub bytecode: ArtifactCode,
+    /// Runtime code, returned by the creation code.
+    pub deployed_bytecode: ArtifactCode,
+}
+
+impl Artifact {
+    /// Parses an artifact of Foundry, whose codes are objects with their link references, or
+    /// of Hardhat, whose codes are strings next to their link references.
+    pub fn from_json(json: &str) -> Result<Self, ArtifactError> {
+        let raw: RawArtifact = serde_json::from_str(json).map_err(ArtifactError::Json)?;
+        Ok(Self {
+            bytecode: raw.bytecode.into_code(raw.link_references),
+            deployed_bytecode: raw
+                .deployed_bytecode
+                .into_code(raw.deployed_link_references),
+        })
+    }
+
+    /// Returns the linked creation code.
+    pub fn init_code(&self, libraries: &HashMap<String, Address>) -> Result<Bytes, ArtifactError> {
+        self.bytecode.link(libraries)
+    }
+
+    /// Returns the linked runtime code, to insert in a database with the account of the
+    /// contract.
+    pub fn deployed_code(
+        &self,
+        libraries: &HashMap<String, Address>,
+    ) -> Result<Bytecode, ArtifactError> {
+        self.deployed_bytecode
+            .link(libraries)
+            .map(Bytecode::new_raw)
+    }
+}
+
+/// Error loading an [`Artifact`].
+#[derive(Debug)]
+pub enum ArtifactError {
+    /// The artifact is not valid JSON, or misses the codes.
+    Json(serde_json::Error),
+    /// The code is not valid hex once linked.
+    InvalidHex,
+    /// No address was given for the library `<source file>:<name>`.
+    UnlinkedLibrary(String),
+    /// The link reference is not an address within the code.
+    InvalidLinkReference(LinkReference),
+}
+
+impl fmt::Display for ArtifactError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "invalid artifact: {e}"),
+            Self::InvalidHex => f.write_str("artifact code is not valid hex"),
+            Self::UnlinkedLibrary(library) => write!(f, "no address for library {library}"),
+            Self::InvalidLinkReference(reference) => write!(
+                f,
+                "invalid link reference of {} bytes at {}",
+                reference.length, reference.start
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ArtifactError {}
+
+/// Artifact as found in the JSON files.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawArtifact {
+    bytecode: RawCode,
+    deployed_bytecode: RawCode,
+    /// Link references of the creation code of Hardhat.
+    #[serde(default)]
+    link_references: LinkReferences,
+    /// Link references of the runtime code of Hardhat.
+    #[serde(default)]
+    deployed_link_references: LinkReferences,
+}
+
+/// Code of Hardhat, or of Foundry with its link references.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawCode {
+    Hex(String),
+    #[serde(rename_all = "camelCase")]
+    Object {
+        object: String,
+        #[serde(default)]
+        link_references: LinkReferences,
+    },
+}
+
+impl RawCode {
+    fn into_code(self, link_references: LinkReferences) -> ArtifactCode {
+        match self {
+            Self::Hex(object) => ArtifactCode {
+                object,
+                link_references,
+            },
+            Self::Object {
+                object,
+                link_references,
+            } => ArtifactCode {
+                object,
+                link_references,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::address;
+
+    /// `PUSH20 <library>`, with the placeholder of solc.
+    const LINKED: &str = "0x73__$0123456789abcdef0123456789abcdef01$__00";
+
+    #[test]
+    fn foundry_artifact() {
+        let json = std::format!(
+            r#"{{
+                "abi": [],
+                "bytecode": {{
+                    "object": "{LINKED}",
+                    "sourceMap": "",
+                    "linkReferences": {{
+                        "src/Lib.sol": {{ "Lib": [{{ "start": 1, "length": 20 }}] }}
+                    }}
+                }},
+                "deployedBytecode": {{ "object": "0x00", "linkReferences": {{}} }}
+            }}"#
+        );
+        let artifact = Artifact::from_json(&json).unwrap();
+        let library = address!("1000000000000000000000000000000000000001");
+
+        let mut libraries = HashMap::default();
+        assert!(matches!(
+            artifact.init_code(&libraries),
+            Err(ArtifactError::UnlinkedLibrary(library)) if library == "src/Lib.sol:Lib"
+        ));
+        libraries.insert(String::from("src/Lib.sol:Lib"), library);
+        assert_eq!(
+            artifact.init_code(&libraries).unwrap(),
+            [&[0x73], library.as_slice(), &[0x00]].concat()
+        );
+        assert_eq!(
+            artifact.deployed_code(&libraries).unwrap(),
+            Bytecode::new_raw(Bytes::from_static(&[0x00]))
+        );
+    }
+
+    #[test]
+    fn hardhat_artifact() {
+        let json = std::format!(
+            r#"{{
+                "_format": "hh-sol-artifact-1",
+                "bytecode": "0x6000",
+                "deployedBytecode": "{LINKED}",
+                "linkReferences": {{}},
+                "deployedLinkReferences": {{
+                    "contracts/Lib.sol": {{ "Lib": [{{ "start": 1, "length": 20 }}] }}
+                }}
+            }}"#
+        );
+        let artifact = Artifact::from_json(&json).unwrap();
+        let library = address!("2000000000000000000000000000000000000002");
+        // Libraries can also be given by name only.
+        let libraries = HashMap::from_iter([(String::from("Lib"), library)]);
+
+        assert_eq!(artifact.init_code(&libraries).unwrap()[..], [0x60, 0x00]);
+        assert_eq!(
+            artifact.deployed_code(&libraries).unwrap().original_bytes(),
+            [&[0x73], library.as_slice(), &[0x00]].concat()
+        );
+    }
+}
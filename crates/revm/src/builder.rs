@@ -1,11 +1,14 @@
 use crate::{
     db::EmptyDB,
     handler::register,
-    primitives::{CfgEnv, EnvWiring, EthereumWiring, InvalidTransaction, TransactionValidation},
+    log_sink::{log_sink_register, LogEvent},
+    primitives::{
+        Address, CfgEnv, EnvWiring, EthereumWiring, InvalidTransaction, TransactionValidation, U256,
+    },
     Context, Evm, EvmContext, EvmWiring, Handler,
 };
 use core::marker::PhantomData;
-use std::boxed::Box;
+use std::{boxed::Box, sync::Arc, vec::Vec};
 
 /// Evm Builder allows building or modifying EVM.
 /// Note that some of the methods that changes underlying structures
@@ -320,6 +323,9 @@ impl<'a, BuilderStage, EvmWiringT: EvmWiring> EvmBuilder<'a, BuilderStage, EvmWi
     /// Register Handler that modifies the behavior of EVM.
     /// Check [`Handler`] for more information.
     ///
+    /// Registers are applied in the order they are appended, see
+    /// [`decorate`](register::decorate) to wrap the handles of the previous registers.
+    ///
     /// When called, EvmBuilder will transition from SetGenericStage to HandlerStage.
     pub fn append_handler_register(
         mut self,
@@ -343,6 +349,79 @@ impl<'a, BuilderStage, EvmWiringT: EvmWiring> EvmBuilder<'a, BuilderStage, EvmWi
         self
     }
 
+    /// Marks `addresses` warm at the start of every transaction, in addition to the
+    /// addresses of the access list.
+    ///
+    /// See [`JournaledState::preload_accounts`](crate::JournaledState::preload_accounts).
+    pub fn with_preloaded_accounts(self, addresses: impl IntoIterator<Item = Address>) -> Self {
+        let addresses: Vec<Address> = addresses.into_iter().collect();
+        self.append_handler_register_box(Box::new(move |handler| {
+            let addresses = addresses.clone();
+            let load_accounts = handler.pre_execution.load_accounts.clone();
+            handler.pre_execution.load_accounts = Arc::new(move |context| {
+                load_accounts(context)?;
+                context
+                    .evm
+                    .journaled_state
+                    .preload_accounts(addresses.iter().copied());
+                Ok(())
+            });
+        }))
+    }
+
+    /// Marks storage slots warm at the start of every transaction, in addition to the slots
+    /// of the access list.
+    ///
+    /// See [`JournaledState::preload_storage`](crate::JournaledState::preload_storage).
+    pub fn with_preloaded_storage(self, slots: impl IntoIterator<Item = (Address, U256)>) -> Self {
+        let slots: Vec<(Address, U256)> = slots.into_iter().collect();
+        self.append_handler_register_box(Box::new(move |handler| {
+            let slots = slots.clone();
+            let load_accounts = handler.pre_execution.load_accounts.clone();
+            handler.pre_execution.load_accounts = Arc::new(move |context| {
+                load_accounts(context)?;
+                context
+                    .evm
+                    .journaled_state
+                    .preload_storage(slots.iter().copied());
+                Ok(())
+            });
+        }))
+    }
+
+    /// Passes every log to `sink` as soon as it is emitted, for example to send it to a
+    /// channel, instead of waiting for the logs of the [`ExecutionResult`].
+    ///
+    /// Logs of calls that revert later are passed as well, while they are not part of the
+    /// result. Consumers that need the final logs only should use the result.
+    ///
+    /// [`ExecutionResult`]: crate::primitives::ExecutionResult
+    pub fn with_log_sink(self, sink: impl FnMut(LogEvent) + 'static) -> Self {
+        self.append_handler_register_box(log_sink_register(sink))
+    }
+
+    /// Passes the [`TxMetrics`](crate::TxMetrics) of every executed transaction to
+    /// `callback`, including the transactions that failed with an error.
+    ///
+    /// Instructions are only counted when this is set, the other counters are always
+    /// maintained by the journaled state with the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics(self, callback: impl FnMut(&crate::TxMetrics) + 'static) -> Self {
+        self.append_handler_register_box(crate::metrics::metrics_register(callback))
+    }
+
+    /// Starts the call frames of contracts with their code compiled by `contracts`, see
+    /// [`compiler_handle_register`](crate::compiler::compiler_handle_register).
+    #[cfg(feature = "compiler")]
+    pub fn with_compiler(
+        self,
+        contracts: std::rc::Rc<
+            core::cell::RefCell<crate::compiler::CompiledContracts<Context<EvmWiringT>>>,
+        >,
+    ) -> Self {
+        self.append_handler_register_box(crate::compiler::compiler_handle_register(contracts))
+    }
+
     /// Allows modification of Evm Database.
     pub fn modify_db(mut self, f: impl FnOnce(&mut EvmWiringT::Database)) -> Self {
         f(self.database.as_mut().unwrap());
@@ -461,7 +540,10 @@ where
 #[cfg(test)]
 mod test {
     use crate::{
-        interpreter::Interpreter,
+        interpreter::{
+            gas::{COLD_ACCOUNT_ACCESS_COST, COLD_SLOAD_COST, WARM_STORAGE_READ_COST},
+            opcode, Interpreter,
+        },
         primitives::{address, AccountInfo, Bytecode, EthereumWiring, TxKind, U256},
         Context, Evm, InMemoryDB,
     };
@@ -473,6 +555,53 @@ mod test {
         pub(crate) inner: Rc<RefCell<u8>>,
     }
 
+    #[test]
+    fn preloaded_accounts_and_storage() {
+        // BALANCE(0x1000), SLOAD(1)
+        let code = Bytecode::new_raw(
+            [
+                opcode::PUSH2,
+                0x10,
+                0x00,
+                opcode::BALANCE,
+                opcode::PUSH1,
+                0x01,
+                opcode::SLOAD,
+            ]
+            .into(),
+        );
+        let code_hash = code.hash_slow();
+        let to_addr = address!("ffffffffffffffffffffffffffffffffffffffff");
+        let other = address!("0000000000000000000000000000000000001000");
+        let builder = || {
+            Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+                .with_default_db()
+                .with_default_ext_ctx()
+                .modify_db(|db| {
+                    db.insert_account_info(
+                        to_addr,
+                        AccountInfo::new(U256::ZERO, 0, code_hash, code.clone()),
+                    )
+                })
+                .modify_tx_env(|tx| tx.transact_to = TxKind::Call(to_addr))
+        };
+
+        let cold = builder().build().transact().unwrap().result.gas_used();
+        let mut evm = builder()
+            .with_preloaded_accounts([other])
+            .with_preloaded_storage([(to_addr, U256::from(1))])
+            .build();
+        // Preloads apply to every transaction.
+        for _ in 0..2 {
+            let warm = evm.transact().unwrap().result.gas_used();
+            assert_eq!(
+                cold - warm,
+                (COLD_ACCOUNT_ACCESS_COST - WARM_STORAGE_READ_COST)
+                    + (COLD_SLOAD_COST - WARM_STORAGE_READ_COST)
+            );
+        }
+    }
+
     #[test]
     fn simple_add_stateful_instruction() {
         let code = Bytecode::new_raw([0xED, 0x00].into());
@@ -0,0 +1,186 @@
+//! Execution of compiled contracts, enabled by the `compiler` feature.
+//!
+//! Contracts are compiled by a [Backend] once they are hot, and their call frames start with
+//! the compiled code. The interpreter resumes the frames at the instructions the compiled
+//! code does not execute. See [compiler](crate::interpreter::compiler) for the backends.
+
+use crate::{
+    handler::register::{EvmHandler, HandleRegisterBox},
+    interpreter::{
+        compiler::{Backend, CompiledCode},
+        opcode::InstructionTables,
+        EMPTY_SHARED_MEMORY,
+    },
+    primitives::{Bytecode, HashMap, SpecId, B256},
+    Context, EvmWiring, Frame,
+};
+use core::{cell::RefCell, mem};
+use std::{boxed::Box, rc::Rc, sync::Arc};
+
+/// Compiled contracts by code hash and spec.
+pub struct CompiledContracts<H: ?Sized> {
+    backend: Box<dyn Backend<H>>,
+    threshold: u64,
+    contracts: HashMap<(B256, SpecId), Entry<H>>,
+}
+
+enum Entry<H: ?Sized> {
+    /// Number of calls of a contract that is not compiled yet.
+    Cold(u64),
+    Compiled(Arc<dyn CompiledCode<H>>),
+    /// The backend does not support the contract.
+    Unsupported,
+}
+
+impl<H: ?Sized> CompiledContracts<H> {
+    /// Creates an empty cache, where contracts are compiled by `backend` on their
+    /// `threshold`-th call.
+    pub fn new(backend: impl Backend<H> + 'static, threshold: u64) -> Self {
+        Self {
+            backend: Box::new(backend),
+            threshold,
+            contracts: HashMap::default(),
+        }
+    }
+
+    /// Returns the compiled code of the contract with `code_hash` for `spec_id`.
+    ///
+    /// Counts a call of the contract, and compiles `bytecode` on the call that makes it hot.
+    pub fn get(
+        &mut self,
+        code_hash: B256,
+        spec_id: SpecId,
+        bytecode: &Bytecode,
+    ) -> Option<Arc<dyn CompiledCode<H>>> {
+        let entry = self
+            .contracts
+            .entry((code_hash, spec_id))
+            .or_insert(Entry::Cold(0));
+        if let Entry::Cold(calls) = entry {
+            *calls += 1;
+            if *calls >= self.threshold {
+                *entry = match self.backend.compile(bytecode, spec_id) {
+                    Some(code) => Entry::Compiled(code),
+                    None => Entry::Unsupported,
+                };
+            }
+        }
+        match entry {
+            Entry::Compiled(code) => Some(code.clone()),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of compiled contracts.
+    pub fn compiled(&self) -> usize {
+        self.contracts
+            .values()
+            .filter(|entry| matches!(entry, Entry::Compiled(_)))
+            .count()
+    }
+}
+
+/// Returns a register that starts the call frames of contracts with their code compiled by
+/// `contracts`.
+///
+/// Compiled code does not step through the instruction table, so it is only executed with a
+/// plain instruction table and without an instruction limit. With an inspector, or other
+/// registers that wrap the instructions, frames are interpreted. Create frames are always
+/// interpreted. The register must be appended after the registers that replace the execution
+/// of frames.
+pub fn compiler_handle_register<'a, EvmWiringT: EvmWiring + 'a>(
+    contracts: Rc<RefCell<CompiledContracts<Context<EvmWiringT>>>>,
+) -> HandleRegisterBox<'a, EvmWiringT> {
+    Box::new(move |handler: &mut EvmHandler<'_, EvmWiringT>| {
+        let contracts = contracts.clone();
+        let prev = handler.execution.execute_frame.clone();
+        handler.execution.execute_frame =
+            Arc::new(move |frame, shared_memory, instruction_tables, context| {
+                let code = match (&*frame, instruction_tables) {
+                    (Frame::Call(call), InstructionTables::Plain(_))
+                        if context.evm.budget.is_unlimited() =>
+                    {
+                        let interpreter = &call.frame_data.interpreter;
+                        // The frame is compiled only before its first instruction.
+                        let starts =
+                            interpreter.program_counter() == 0 && interpreter.gas.spent() == 0;
+                        let contract = &interpreter.contract;
+                        contract.hash.filter(|_| starts).and_then(|code_hash| {
+                            contracts.borrow_mut().get(
+                                code_hash,
+                                context.evm.spec_id(),
+                                &contract.bytecode,
+                            )
+                        })
+                    }
+                    _ => None,
+                };
+                if let Some(code) = code {
+                    let interpreter = frame.interpreter_mut();
+                    let memory = mem::replace(shared_memory, EMPTY_SHARED_MEMORY);
+                    let action = interpreter.run_compiled(&*code, memory, context);
+                    *shared_memory = interpreter.take_memory();
+                    if let Some(action) = action {
+                        return Ok(action);
+                    }
+                }
+                prev(frame, shared_memory, instruction_tables, context)
+            });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::SetGenericStage,
+        db::BenchmarkDB,
+        inspector_handle_register,
+        inspectors::NoOpInspector,
+        interpreter::{compiler::BlockBackend, opcode::*},
+        primitives::{address, Address, EthereumWiring, TxKind},
+        Evm, EvmBuilder,
+    };
+    use core::fmt::Debug;
+
+    fn builder<EXT: Debug + Default>(
+    ) -> EvmBuilder<'static, SetGenericStage, EthereumWiring<BenchmarkDB, EXT>> {
+        // Sums 1 to 5 with a loop, stores the sum, then returns it.
+        let code = [
+            PUSH1, 0x00, PUSH1, 0x05, JUMPDEST, DUP1, SWAP2, ADD, SWAP1, PUSH1, 0x01, SWAP1, SUB,
+            DUP1, PUSH1, 0x04, JUMPI, POP, DUP1, PUSH1, 0x00, SSTORE, PUSH1, 0x00, MSTORE, PUSH1,
+            0x20, PUSH1, 0x00, RETURN,
+        ];
+        Evm::<EthereumWiring<BenchmarkDB, EXT>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code.into())))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+    }
+
+    #[test]
+    fn compiled_contracts() {
+        let expected = builder::<()>().build().transact().unwrap();
+        assert!(expected.result.is_success());
+
+        let contracts = Rc::new(RefCell::new(CompiledContracts::new(BlockBackend, 2)));
+        let mut evm = builder::<()>().with_compiler(contracts.clone()).build();
+        assert_eq!(evm.transact().unwrap(), expected);
+        assert_eq!(contracts.borrow().compiled(), 0);
+        assert_eq!(evm.transact().unwrap(), expected);
+        assert_eq!(contracts.borrow().compiled(), 1);
+        assert_eq!(evm.transact().unwrap(), expected);
+
+        // Frames are interpreted with an inspector.
+        let contracts = Rc::new(RefCell::new(CompiledContracts::new(BlockBackend, 1)));
+        let mut evm = builder::<NoOpInspector>()
+            .append_handler_register(inspector_handle_register)
+            .with_compiler(contracts.clone())
+            .build();
+        assert_eq!(evm.transact().unwrap(), expected);
+        assert_eq!(contracts.borrow().compiled(), 0);
+    }
+}
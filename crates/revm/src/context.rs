@@ -13,9 +13,10 @@ use revm_interpreter::{as_u64_saturated, Eip7702CodeLoad, StateLoad};
 
 use crate::{
     db::{Database, EmptyDB},
-    interpreter::{AccountLoad, Host, SStoreResult, SelfDestructResult},
+    interpreter::{AccountLoad, HostEnvMut, HostRead, HostWrite, SStoreResult, SelfDestructResult},
     primitives::{
-        Address, Block, Bytes, EnvWiring, EthereumWiring, Log, B256, BLOCK_HASH_HISTORY, U256,
+        Address, Block, Bytes, EnvWiring, EthereumWiring, EvmLog, SpecId, B256, BLOCK_HASH_HISTORY,
+        U256,
     },
     EvmWiring,
 };
@@ -79,7 +80,7 @@ impl<EvmWiringT: EvmWiring> ContextWithEvmWiring<EvmWiringT> {
     }
 }
 
-impl<EvmWiringT: EvmWiring> Host for Context<EvmWiringT> {
+impl<EvmWiringT: EvmWiring> HostRead for Context<EvmWiringT> {
     type EvmWiringT = EvmWiringT;
 
     /// Returns reference to Environment.
@@ -88,8 +89,9 @@ impl<EvmWiringT: EvmWiring> Host for Context<EvmWiringT> {
         &self.evm.env
     }
 
-    fn env_mut(&mut self) -> &mut EnvWiring<EvmWiringT> {
-        &mut self.evm.env
+    #[inline]
+    fn spec_id(&self) -> SpecId {
+        self.evm.spec_id()
     }
 
     fn block_hash(&mut self, requested_number: u64) -> Option<B256> {
@@ -150,6 +152,12 @@ impl<EvmWiringT: EvmWiring> Host for Context<EvmWiringT> {
             .ok()
     }
 
+    fn tload(&mut self, address: Address, index: U256) -> U256 {
+        self.evm.tload(address, index)
+    }
+}
+
+impl<EvmWiringT: EvmWiring> HostWrite for Context<EvmWiringT> {
     fn sstore(
         &mut self,
         address: Address,
@@ -162,15 +170,11 @@ impl<EvmWiringT: EvmWiring> Host for Context<EvmWiringT> {
             .ok()
     }
 
-    fn tload(&mut self, address: Address, index: U256) -> U256 {
-        self.evm.tload(address, index)
-    }
-
     fn tstore(&mut self, address: Address, index: U256, value: U256) {
         self.evm.tstore(address, index, value)
     }
 
-    fn log(&mut self, log: Log) {
+    fn log(&mut self, log: EvmLog) {
         self.evm.journaled_state.log(log);
     }
 
@@ -187,3 +191,10 @@ impl<EvmWiringT: EvmWiring> Host for Context<EvmWiringT> {
             .ok()
     }
 }
+
+impl<EvmWiringT: EvmWiring> HostEnvMut for Context<EvmWiringT> {
+    #[inline]
+    fn env_mut(&mut self) -> &mut EnvWiring<EvmWiringT> {
+        &mut self.evm.env
+    }
+}
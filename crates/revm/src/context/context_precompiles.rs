@@ -123,6 +123,20 @@ impl<EvmWiringT: EvmWiring> ContextPrecompiles<EvmWiringT> {
         })
     }
 
+    /// Returns the gas the precompile at `address` requires for `bytes`, if it is a
+    /// precompile and the gas is known without executing it.
+    #[inline]
+    pub fn required_gas(&self, address: &Address, bytes: &[u8]) -> Option<u64> {
+        match self.inner {
+            PrecompilesCow::StaticRef(p) => p.get(address)?.required_gas(bytes),
+            PrecompilesCow::Owned(ref owned) => match owned.get(address)? {
+                ContextPrecompile::Ordinary(p) => p.required_gas(bytes),
+                ContextPrecompile::ContextStateful(p) => p.required_gas(bytes),
+                ContextPrecompile::ContextStatefulMut(p) => p.required_gas(bytes),
+            },
+        }
+    }
+
     /// Returns a mutable reference to the precompiles map.
     ///
     /// Clones the precompiles map if it is shared.
@@ -190,6 +204,13 @@ pub trait ContextStatefulPrecompile<EvmWiringT: EvmWiring>: Sync + Send {
         gas_limit: u64,
         evmctx: &mut InnerEvmContext<EvmWiringT>,
     ) -> PrecompileResult;
+
+    /// Returns the gas required for `bytes`, if it is known without executing, see
+    /// [`PrecompileGasFn`](crate::primitives::PrecompileGasFn).
+    fn required_gas(&self, bytes: &[u8]) -> Option<u64> {
+        let _ = bytes;
+        None
+    }
 }
 
 /// Context aware mutable stateful precompile trait. It is used to create
@@ -201,6 +222,13 @@ pub trait ContextStatefulPrecompileMut<EvmWiringT: EvmWiring>: DynClone + Send +
         gas_limit: u64,
         evmctx: &mut InnerEvmContext<EvmWiringT>,
     ) -> PrecompileResult;
+
+    /// Returns the gas required for `bytes`, if it is known without executing, see
+    /// [`PrecompileGasFn`](crate::primitives::PrecompileGasFn).
+    fn required_gas(&self, bytes: &[u8]) -> Option<u64> {
+        let _ = bytes;
+        None
+    }
 }
 
 dyn_clone::clone_trait_object!(<EvmWiringT> ContextStatefulPrecompileMut<EvmWiringT>);
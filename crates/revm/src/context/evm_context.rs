@@ -1,21 +1,21 @@
 use derive_where::derive_where;
 use revm_interpreter::CallValue;
-use revm_precompile::PrecompileErrors;
+use revm_precompile::{PrecompileError, PrecompileErrors};
 
 use super::inner_evm_context::InnerEvmContext;
 use crate::{
     db::Database,
     interpreter::{
-        analysis::validate_eof, return_ok, CallInputs, Contract, CreateInputs, EOFCreateInputs,
-        EOFCreateKind, Gas, InstructionResult, Interpreter, InterpreterResult,
+        analysis::validate_eof, fusion, return_ok, CallInputs, Contract, CreateInputs,
+        EOFCreateInputs, EOFCreateKind, Gas, InstructionResult, Interpreter, InterpreterResult,
     },
     primitives::{
-        keccak256, Address, Bytecode, Bytes, CreateScheme, EVMError, EVMResultGeneric, EnvWiring,
-        Eof,
+        create2_address, create_address, keccak256, Address, Bytecode, Bytes, CreateScheme,
+        EVMError, EVMResultGeneric, EnvWiring, Eof, PrecompileFailure,
         SpecId::{self, *},
-        Transaction, B256, EOF_MAGIC_BYTES,
+        B256, EOF_MAGIC_BYTES,
     },
-    ContextPrecompiles, EvmWiring, FrameOrResult, CALL_STACK_LIMIT,
+    ContextPrecompiles, EvmWiring, FrameOrResult,
 };
 use core::ops::{Deref, DerefMut};
 use std::{boxed::Box, sync::Arc};
@@ -91,10 +91,25 @@ where
         // set warm loaded addresses.
         self.journaled_state
             .warm_preloaded_addresses
-            .extend(precompiles.addresses_set());
+            .extend(precompiles.addresses().copied());
         self.precompiles = precompiles;
     }
 
+    /// Returns the bytecode with fused instructions of `bytecode`, if it is legacy bytecode.
+    ///
+    /// Fused bytecode is cached by `code_hash`.
+    fn fused_bytecode(&mut self, code_hash: B256, bytecode: &Bytecode) -> Option<Bytes> {
+        if let Some(fused) = self.fused_bytecodes.get(&code_hash) {
+            // The length is checked in case different code was loaded with the same hash.
+            if fused.len() == bytecode.bytecode().len() {
+                return Some(fused.clone());
+            }
+        }
+        let fused = fusion::fuse(bytecode)?;
+        self.fused_bytecodes.insert(code_hash, fused.clone());
+        Some(fused)
+    }
+
     /// Call precompile contract
     #[inline]
     fn call_precompile(
@@ -103,6 +118,23 @@ where
         input_data: &Bytes,
         gas: Gas,
     ) -> EVMResultGeneric<Option<InterpreterResult>, EvmWiringT> {
+        // Calls that can not pay for the input fail without executing the precompile.
+        if self
+            .precompiles
+            .required_gas(address, input_data)
+            .is_some_and(|required| required > gas.limit())
+        {
+            self.inner.precompile_failure = Some(PrecompileFailure {
+                address: *address,
+                error: PrecompileError::OutOfGas,
+            });
+            return Ok(Some(InterpreterResult {
+                result: InstructionResult::PrecompileOOG,
+                gas,
+                output: Bytes::new(),
+            }));
+        }
+
         let Some(outcome) =
             self.precompiles
                 .call(address, input_data, gas.limit(), &mut self.inner)
@@ -131,6 +163,10 @@ where
                 } else {
                     InstructionResult::PrecompileError
                 };
+                self.inner.precompile_failure = Some(PrecompileFailure {
+                    address: *address,
+                    error: e,
+                });
             }
             Err(PrecompileErrors::Fatal { msg }) => return Err(EVMError::Precompile(msg)),
         }
@@ -157,7 +193,7 @@ where
         };
 
         // Check depth
-        if self.journaled_state.depth() > CALL_STACK_LIMIT {
+        if self.journaled_state.depth() > self.env.cfg.call_depth_limit {
             return return_result(InstructionResult::CallTooDeep);
         }
 
@@ -232,25 +268,35 @@ where
                 return return_result(InstructionResult::Stop);
             }
 
+            // Hash of the executed code, which is the code of the delegated account for EIP-7702.
+            let mut executed_code_hash = code_hash;
             if let Bytecode::Eip7702(eip7702_bytecode) = bytecode {
-                bytecode = self
+                let delegated = self
                     .inner
                     .journaled_state
                     .load_code(eip7702_bytecode.delegated_address, &mut self.inner.db)
-                    .map_err(EVMError::Database)?
-                    .info
-                    .code
-                    .clone()
-                    .unwrap_or_default();
+                    .map_err(EVMError::Database)?;
+                executed_code_hash = delegated.info.code_hash();
+                bytecode = delegated.info.code.clone().unwrap_or_default();
             }
 
             let contract =
                 Contract::new_with_context(inputs.input.clone(), bytecode, Some(code_hash), inputs);
+            let fused = if self.env.cfg.fuse_instructions {
+                self.fused_bytecode(executed_code_hash, &contract.bytecode)
+            } else {
+                None
+            };
             // Create interpreter and executes call and push new CallStackFrame.
+            let mut interpreter = Interpreter::new(contract, gas.limit(), inputs.is_static)
+                .with_stack_limit(self.env.cfg.stack_limit);
+            if let Some(fused) = fused {
+                interpreter.set_fused_bytecode(fused);
+            }
             Ok(FrameOrResult::new_call_frame(
                 inputs.return_memory_offset.clone(),
                 checkpoint,
-                Interpreter::new(contract, gas.limit(), inputs.is_static),
+                interpreter,
             ))
         }
     }
@@ -261,6 +307,43 @@ where
         &mut self,
         spec_id: SpecId,
         inputs: &CreateInputs,
+    ) -> Result<FrameOrResult, <EvmWiringT::Database as Database>::Error> {
+        self.make_create_frame_inner(spec_id, inputs, |old_nonce| match inputs.scheme {
+            CreateScheme::Create => (create_address(inputs.caller, old_nonce), B256::ZERO),
+            CreateScheme::Create2 { salt } => {
+                let init_code_hash = keccak256(&inputs.init_code);
+                (
+                    create2_address(inputs.caller, salt.into(), init_code_hash),
+                    init_code_hash,
+                )
+            }
+        })
+    }
+
+    /// Make create frame, deploying the contract at the address returned by
+    /// `created_address` instead of the address of the create scheme.
+    ///
+    /// `created_address` is called with the nonce of the caller before it is incremented,
+    /// after the depth, balance and nonce checks. It allows chains with other address
+    /// derivation rules to reuse the frame creation in their `create` handle.
+    #[inline]
+    pub fn make_create_frame_with_address(
+        &mut self,
+        spec_id: SpecId,
+        inputs: &CreateInputs,
+        created_address: impl FnOnce(u64) -> Address,
+    ) -> Result<FrameOrResult, <EvmWiringT::Database as Database>::Error> {
+        self.make_create_frame_inner(spec_id, inputs, |old_nonce| {
+            (created_address(old_nonce), B256::ZERO)
+        })
+    }
+
+    /// Make create frame with the address and init code hash returned by `created_address`.
+    fn make_create_frame_inner(
+        &mut self,
+        spec_id: SpecId,
+        inputs: &CreateInputs,
+        created_address: impl FnOnce(u64) -> (Address, B256),
     ) -> Result<FrameOrResult, <EvmWiringT::Database as Database>::Error> {
         let return_error = |e| {
             Ok(FrameOrResult::new_create_result(
@@ -274,7 +357,7 @@ where
         };
 
         // Check depth
-        if self.journaled_state.depth() > CALL_STACK_LIMIT {
+        if self.journaled_state.depth() > self.env.cfg.call_depth_limit {
             return return_error(InstructionResult::CallTooDeep);
         }
 
@@ -300,14 +383,7 @@ where
         }
 
         // Create address
-        let mut init_code_hash = B256::ZERO;
-        let created_address = match inputs.scheme {
-            CreateScheme::Create => inputs.caller.create(old_nonce),
-            CreateScheme::Create2 { salt } => {
-                init_code_hash = keccak256(&inputs.init_code);
-                inputs.caller.create2(salt.to_be_bytes(), init_code_hash)
-            }
-        };
+        let (created_address, init_code_hash) = created_address(old_nonce);
 
         // created address is not allowed to be a precompile.
         if self.precompiles.contains(&created_address) {
@@ -345,7 +421,8 @@ where
         Ok(FrameOrResult::new_create_frame(
             created_address,
             checkpoint,
-            Interpreter::new(contract, inputs.gas_limit, false),
+            Interpreter::new(contract, inputs.gas_limit, false)
+                .with_stack_limit(self.env.cfg.stack_limit),
         ))
     }
 
@@ -387,15 +464,14 @@ where
                     return return_error(InstructionResult::InvalidEOFInitCode);
                 }
 
-                // Use nonce from tx to calculate address.
-                let nonce = self.env.tx.nonce();
-
-                (input, eof, Some(self.env.tx.caller().create(nonce)))
+                // The address is derived from the nonce of the caller, like for legacy
+                // creates, which equals the nonce of the transaction once it is validated.
+                (input, eof, None)
             }
         };
 
         // Check depth
-        if self.journaled_state.depth() > CALL_STACK_LIMIT {
+        if self.journaled_state.depth() > self.env.cfg.call_depth_limit {
             return return_error(InstructionResult::CallTooDeep);
         }
 
@@ -414,7 +490,8 @@ where
         };
         let old_nonce = nonce - 1;
 
-        let created_address = created_address.unwrap_or_else(|| inputs.caller.create(old_nonce));
+        let created_address =
+            created_address.unwrap_or_else(|| create_address(inputs.caller, old_nonce));
 
         // created address is not allowed to be a precompile.
         if self.precompiles.contains(&created_address) {
@@ -448,7 +525,8 @@ where
             inputs.value,
         );
 
-        let mut interpreter = Interpreter::new(contract, inputs.gas_limit, false);
+        let mut interpreter = Interpreter::new(contract, inputs.gas_limit, false)
+            .with_stack_limit(self.env.cfg.stack_limit);
         // EOF init will enable RETURNCONTRACT opcode.
         interpreter.set_is_eof_init();
 
@@ -468,7 +546,7 @@ pub(crate) mod test_utils {
     use crate::{
         db::{CacheDB, EmptyDB},
         journaled_state::JournaledState,
-        primitives::{address, HashSet, SpecId, B256},
+        primitives::{address, HashMap, HashSet, SpecId, B256},
     };
 
     /// Mock caller address.
@@ -524,6 +602,11 @@ pub(crate) mod test_utils {
                 db,
                 chain: Default::default(),
                 error: Ok(()),
+                budget: Default::default(),
+                halt_location: None,
+                precompile_failure: None,
+                fused_bytecodes: HashMap::default(),
+                warm_accesses: Default::default(),
             },
             precompiles: ContextPrecompiles::default(),
         }
@@ -541,6 +624,11 @@ pub(crate) mod test_utils {
                 db,
                 chain: Default::default(),
                 error: Ok(()),
+                budget: Default::default(),
+                halt_location: None,
+                precompile_failure: None,
+                fused_bytecodes: HashMap::default(),
+                warm_accesses: Default::default(),
             },
             precompiles: ContextPrecompiles::default(),
         }
@@ -554,7 +642,7 @@ mod tests {
     use crate::{
         db::{CacheDB, EmptyDB},
         primitives::{address, Bytecode, DefaultEthereumWiring, EthereumWiring},
-        Frame, JournalEntry,
+        Frame, JournalEntry, CALL_STACK_LIMIT,
     };
     use std::boxed::Box;
     use test_utils::*;
@@ -580,6 +668,27 @@ mod tests {
         );
     }
 
+    // Tests that the call depth limit is read from the config.
+    #[test]
+    fn test_make_call_frame_configured_depth_limit() {
+        let mut env = EnvWiring::<DefaultEthereumWiring>::default();
+        env.cfg.call_depth_limit = 2;
+        let db = EmptyDB::default();
+        let mut context =
+            test_utils::create_empty_evm_context::<DefaultEthereumWiring>(Box::new(env), db);
+        context.journaled_state.depth = 3;
+        let contract = address!("dead10000000000000000000000000000001dead");
+        let call_inputs = test_utils::create_mock_call_inputs(contract);
+        let res = context.make_call_frame(&call_inputs);
+        let Ok(FrameOrResult::Result(err)) = res else {
+            panic!("Expected FrameOrResult::Result");
+        };
+        assert_eq!(
+            err.interpreter_result().result,
+            InstructionResult::CallTooDeep
+        );
+    }
+
     // Tests that the `EVMContext::make_call_frame` function returns an error if the
     // transfer fails on the journaled state. It also verifies that the revert was
     // checkpointed on the journaled state correctly.
@@ -600,8 +709,11 @@ mod tests {
             result.interpreter_result().result,
             InstructionResult::OutOfFunds
         );
-        let checkpointed = vec![vec![JournalEntry::AccountWarmed { address: contract }]];
-        assert_eq!(evm_context.journaled_state.journal, checkpointed);
+        assert_eq!(evm_context.journaled_state.journal.len(), 1);
+        assert_eq!(
+            evm_context.journaled_state.journal[0][..],
+            [JournalEntry::AccountWarmed { address: contract }]
+        );
         assert_eq!(evm_context.journaled_state.depth, 0);
     }
 
@@ -648,4 +760,126 @@ mod tests {
         };
         assert_eq!(call_frame.return_memory_range, 0..0,);
     }
+
+    #[test]
+    fn test_make_call_frame_shares_analysed_code() {
+        type CacheEthWiring = EthereumWiring<CacheDB<EmptyDB>, ()>;
+        let env = EnvWiring::<CacheEthWiring>::default();
+        let mut cdb = CacheDB::new(EmptyDB::default());
+        let bal = U256::from(3_000_000_000_u128);
+        let by = Bytecode::new_raw(Bytes::from(vec![0x60, 0x00, 0x60, 0x00]));
+        let contract = address!("dead10000000000000000000000000000001dead");
+        cdb.insert_account_info(
+            contract,
+            crate::primitives::AccountInfo {
+                nonce: 0,
+                balance: bal,
+                code_hash: by.clone().hash_slow(),
+                code: Some(by),
+            },
+        );
+        let mut evm_context =
+            create_cache_db_evm_context_with_balance::<CacheEthWiring>(Box::new(env), cdb, bal);
+        let call_inputs = test_utils::create_mock_call_inputs(contract);
+        let res = evm_context.make_call_frame(&call_inputs);
+        let Ok(FrameOrResult::Frame(Frame::Call(call_frame))) = res else {
+            panic!("Expected FrameOrResult::Frame(Frame::Call(..))");
+        };
+
+        let code = evm_context.journaled_state.state[&contract]
+            .info
+            .code
+            .as_ref()
+            .unwrap();
+        assert!(matches!(code, Bytecode::LegacyAnalyzed(_)));
+        assert_eq!(
+            code.bytes_slice().as_ptr(),
+            call_frame
+                .frame_data
+                .interpreter
+                .contract
+                .bytecode
+                .bytes_slice()
+                .as_ptr()
+        );
+    }
+
+    // Tests that the stack limit of the config is applied to created frames.
+    #[test]
+    fn test_make_call_frame_configured_stack_limit() {
+        type CacheEthWiring = EthereumWiring<CacheDB<EmptyDB>, ()>;
+        let mut env = EnvWiring::<CacheEthWiring>::default();
+        env.cfg.stack_limit = 16;
+        let mut cdb = CacheDB::new(EmptyDB::default());
+        let bal = U256::from(3_000_000_000_u128);
+        let by = Bytecode::new_raw(Bytes::from(vec![0x60, 0x00]));
+        let contract = address!("dead10000000000000000000000000000001dead");
+        cdb.insert_account_info(
+            contract,
+            crate::primitives::AccountInfo {
+                nonce: 0,
+                balance: bal,
+                code_hash: by.clone().hash_slow(),
+                code: Some(by),
+            },
+        );
+        let mut evm_context =
+            create_cache_db_evm_context_with_balance::<CacheEthWiring>(Box::new(env), cdb, bal);
+        let call_inputs = test_utils::create_mock_call_inputs(contract);
+        let res = evm_context.make_call_frame(&call_inputs);
+        let Ok(FrameOrResult::Frame(Frame::Call(call_frame))) = res else {
+            panic!("Expected FrameOrResult::Frame(Frame::Call(..))");
+        };
+        assert_eq!(call_frame.frame_data.interpreter.stack.limit(), 16);
+    }
+
+    #[test]
+    fn test_set_account_state_reverts() {
+        type CacheEthWiring = EthereumWiring<CacheDB<EmptyDB>, ()>;
+        let contract = address!("dead10000000000000000000000000000001dead");
+        let mut cdb = CacheDB::new(EmptyDB::default());
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x00]));
+        cdb.insert_account_info(
+            contract,
+            crate::primitives::AccountInfo::new(U256::from(10), 1, code.hash_slow(), code.clone()),
+        );
+        cdb.insert_account_storage(contract, U256::ZERO, U256::from(7))
+            .unwrap();
+        let mut context = test_utils::create_cache_db_evm_context::<CacheEthWiring>(
+            Box::new(EnvWiring::<CacheEthWiring>::default()),
+            cdb,
+        );
+
+        let checkpoint = context.journaled_state.checkpoint();
+        let new_code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        context.set_balance(contract, U256::from(20)).unwrap();
+        context.set_nonce(contract, 5).unwrap();
+        context
+            .set_account_code(contract, new_code.clone())
+            .unwrap();
+        context
+            .set_storage(contract, U256::ZERO, U256::from(8))
+            .unwrap();
+
+        let account = context.journaled_state.account(contract);
+        assert!(account.is_touched());
+        assert_eq!(account.info.balance, U256::from(20));
+        assert_eq!(account.info.nonce, 5);
+        assert_eq!(account.info.code_hash, new_code.hash_slow());
+        assert_eq!(
+            context.sload(contract, U256::ZERO).unwrap().data,
+            U256::from(8)
+        );
+
+        context.journaled_state.checkpoint_revert(checkpoint);
+        let account = context.journaled_state.account(contract);
+        assert!(!account.is_touched());
+        assert_eq!(account.info.balance, U256::from(10));
+        assert_eq!(account.info.nonce, 1);
+        assert_eq!(account.info.code_hash, code.hash_slow());
+        assert_eq!(
+            context.sload(contract, U256::ZERO).unwrap().data,
+            U256::from(7)
+        );
+    }
 }
@@ -3,13 +3,13 @@ use derive_where::derive_where;
 use crate::{
     db::Database,
     interpreter::{
-        analysis::to_analysed, gas, return_ok, AccountLoad, Eip7702CodeLoad, InstructionResult,
-        InterpreterResult, SStoreResult, SelfDestructResult, StateLoad,
+        analysis::to_analysed, gas, return_ok, AccountLoad, Eip7702CodeLoad, ExecutionBudget,
+        InstructionResult, InterpreterResult, SStoreResult, SelfDestructResult, StateLoad,
     },
-    journaled_state::JournaledState,
+    journaled_state::{JournaledState, WarmAccesses},
     primitives::{
         AccessListItem, Account, Address, AnalysisKind, Bytecode, Bytes, CfgEnv, EnvWiring, Eof,
-        EvmWiring, HashSet, Spec,
+        EvmWiring, HaltLocation, HashMap, HashSet, PrecompileFailure, Spec,
         SpecId::{self, *},
         Transaction, B256, EOF_MAGIC_BYTES, EOF_MAGIC_HASH, U256,
     },
@@ -31,6 +31,22 @@ pub struct InnerEvmContext<EvmWiringT: EvmWiring> {
     pub chain: EvmWiringT::ChainContext,
     /// Error that happened during execution.
     pub error: Result<(), <EvmWiringT::Database as Database>::Error>,
+    /// Execution budget of the current transaction, set from [`CfgEnv::instruction_limit`]
+    /// and [`CfgEnv::execution_timeout`] when the execution starts.
+    pub budget: ExecutionBudget,
+    /// Location of the instruction that halted the last returned frame, if it halted.
+    pub halt_location: Option<HaltLocation>,
+    /// Failure of the last precompile call of the transaction that returned an error.
+    ///
+    /// The halted call returns no data, as required by consensus, so this is the only place
+    /// where the reason of the failure can be found, for example by an inspector.
+    pub precompile_failure: Option<PrecompileFailure>,
+    /// Bytecode with fused instructions by code hash, used when
+    /// [`CfgEnv::fuse_instructions`] is enabled.
+    pub fused_bytecodes: HashMap<B256, Bytes>,
+    /// Accesses of the last transaction executed with [`CfgEnv::reuse_warm_accesses`],
+    /// preloaded warm in the next ones.
+    pub warm_accesses: WarmAccesses,
 }
 
 impl<EvmWiringT> InnerEvmContext<EvmWiringT>
@@ -44,6 +60,11 @@ where
             db,
             chain: Default::default(),
             error: Ok(()),
+            budget: ExecutionBudget::default(),
+            halt_location: None,
+            precompile_failure: None,
+            fused_bytecodes: HashMap::default(),
+            warm_accesses: WarmAccesses::default(),
         }
     }
 }
@@ -58,6 +79,11 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
             db,
             chain: Default::default(),
             error: Ok(()),
+            budget: ExecutionBudget::default(),
+            halt_location: None,
+            precompile_failure: None,
+            fused_bytecodes: HashMap::default(),
+            warm_accesses: WarmAccesses::default(),
         }
     }
 
@@ -77,6 +103,11 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
             db,
             chain: Default::default(),
             error: Ok(()),
+            budget: ExecutionBudget::default(),
+            halt_location: None,
+            precompile_failure: None,
+            fused_bytecodes: HashMap::default(),
+            warm_accesses: self.warm_accesses,
         }
     }
 
@@ -128,6 +159,10 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
         &mut self,
         number: u64,
     ) -> Result<B256, <EvmWiringT::Database as Database>::Error> {
+        #[cfg(feature = "metrics")]
+        {
+            self.journaled_state.metrics.db_block_hash_reads += 1;
+        }
         self.db.block_hash(number)
     }
 
@@ -295,6 +330,53 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
         self.journaled_state.tstore(address, index, value)
     }
 
+    /// Sets the balance of an account, see [JournaledState::set_balance].
+    ///
+    /// Like the other setters below, it can be used by inspectors to implement cheatcodes, the
+    /// change is reverted if the call it was made in reverts.
+    #[inline]
+    pub fn set_balance(
+        &mut self,
+        address: Address,
+        balance: U256,
+    ) -> Result<(), <EvmWiringT::Database as Database>::Error> {
+        self.journaled_state
+            .set_balance(address, balance, &mut self.db)
+    }
+
+    /// Sets the nonce of an account, see [JournaledState::set_nonce].
+    #[inline]
+    pub fn set_nonce(
+        &mut self,
+        address: Address,
+        nonce: u64,
+    ) -> Result<(), <EvmWiringT::Database as Database>::Error> {
+        self.journaled_state.set_nonce(address, nonce, &mut self.db)
+    }
+
+    /// Replaces the code of an account, see [JournaledState::set_account_code].
+    #[inline]
+    pub fn set_account_code(
+        &mut self,
+        address: Address,
+        code: Bytecode,
+    ) -> Result<(), <EvmWiringT::Database as Database>::Error> {
+        self.journaled_state
+            .set_account_code(address, code, &mut self.db)
+    }
+
+    /// Sets a storage slot of an account, see [JournaledState::set_storage].
+    #[inline]
+    pub fn set_storage(
+        &mut self,
+        address: Address,
+        index: U256,
+        value: U256,
+    ) -> Result<(), <EvmWiringT::Database as Database>::Error> {
+        self.journaled_state
+            .set_storage(address, index, value, &mut self.db)
+    }
+
     /// Selfdestructs the account.
     #[inline]
     pub fn selfdestruct(
@@ -373,6 +455,23 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
         interpreter_result: &mut InterpreterResult,
         address: Address,
         journal_checkpoint: JournalCheckpoint,
+    ) {
+        self.create_return_with_spec(
+            SPEC::SPEC_ID,
+            interpreter_result,
+            address,
+            journal_checkpoint,
+        )
+    }
+
+    /// Handles create return in the given spec.
+    #[inline]
+    pub fn create_return_with_spec(
+        &mut self,
+        spec_id: SpecId,
+        interpreter_result: &mut InterpreterResult,
+        address: Address,
+        journal_checkpoint: JournalCheckpoint,
     ) {
         // if return is not ok revert and return.
         if !matches!(interpreter_result.result, return_ok!()) {
@@ -383,7 +482,7 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
         // if ok, check contract creation limit and calculate gas deduction on output len.
         //
         // EIP-3541: Reject new contract code starting with the 0xEF byte
-        if SPEC::enabled(LONDON) && interpreter_result.output.first() == Some(&0xEF) {
+        if spec_id.is_enabled_in(LONDON) && interpreter_result.output.first() == Some(&0xEF) {
             self.journaled_state.checkpoint_revert(journal_checkpoint);
             interpreter_result.result = InstructionResult::CreateContractStartingWithEF;
             return;
@@ -391,7 +490,7 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
 
         // EIP-170: Contract code size limit
         // By default limit is 0x6000 (~25kb)
-        if SPEC::enabled(SPURIOUS_DRAGON)
+        if spec_id.is_enabled_in(SPURIOUS_DRAGON)
             && interpreter_result.output.len() > self.cfg().max_code_size()
         {
             self.journaled_state.checkpoint_revert(journal_checkpoint);
@@ -404,7 +503,7 @@ impl<EvmWiringT: EvmWiring> InnerEvmContext<EvmWiringT> {
             // EIP-2 point 3: If contract creation does not have enough gas to pay for the
             // final gas fee for adding the contract code to the state, the contract
             //  creation fails (i.e. goes out-of-gas) rather than leaving an empty contract.
-            if SPEC::enabled(HOMESTEAD) {
+            if spec_id.is_enabled_in(HOMESTEAD) {
                 self.journaled_state.checkpoint_revert(journal_checkpoint);
                 interpreter_result.result = InstructionResult::OutOfGas;
                 return;
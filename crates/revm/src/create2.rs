@@ -0,0 +1,324 @@
+//! Utilities for deterministic deployments with `CREATE2`.
+//!
+//! [`SaltGrinder`] searches for a salt giving a contract address that matches an
+//! [`AddressPattern`], on several threads and until cancelled. The addresses are computed
+//! with [`create2_address`], so the salts it finds are the ones the EVM uses.
+//!
+//! The [deterministic deployment proxy] is deployed at [`DETERMINISTIC_DEPLOYER`] on most
+//! chains, and deploys the init code following the salt in its call data with `CREATE2`.
+//! [`deterministic_deployment_input`] builds the call data of such a deployment, and
+//! [`Evm::simulate_deterministic_deployment`](crate::Evm::simulate_deterministic_deployment)
+//! simulates it.
+//!
+//! [deterministic deployment proxy]: https://github.com/Arachnid/deterministic-deployment-proxy
+
+use crate::primitives::{address, create2_address, hex, keccak256, Address, Bytes, B256};
+use core::fmt;
+#[cfg(feature = "std")]
+use core::sync::atomic::{AtomicBool, Ordering};
+use std::vec::Vec;
+
+/// Address of the deterministic deployment proxy.
+pub const DETERMINISTIC_DEPLOYER: Address = address!("4e59b44847b379578588920ca78fbf26c0b4956c");
+
+/// Runtime code of the deterministic deployment proxy.
+///
+/// It deploys the call data after the first 32 bytes with `CREATE2`, the first 32 bytes
+/// being the salt, and returns the address of the created contract. It reverts if the
+/// creation fails.
+pub const DETERMINISTIC_DEPLOYER_CODE: [u8; 69] = hex!(
+    "7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffe03601600081602082378035828234f58015156039578182fd5b8082525050506014600cf3"
+);
+
+/// Returns the call data of a deployment of `init_code` with `salt` through the deterministic
+/// deployment proxy.
+pub fn deterministic_deployment_input(salt: B256, init_code: &[u8]) -> Bytes {
+    [salt.as_slice(), init_code].concat().into()
+}
+
+/// Returns the address of the contract deployed from `init_code` with `salt` by the
+/// deterministic deployment proxy.
+pub fn deterministic_deployment_address(salt: B256, init_code: &[u8]) -> Address {
+    create2_address(DETERMINISTIC_DEPLOYER, salt, keccak256(init_code))
+}
+
+/// Hex digits that the start and the end of an address must have.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddressPattern {
+    /// Nibbles the address must start with.
+    prefix: Vec<u8>,
+    /// Nibbles the address must end with.
+    suffix: Vec<u8>,
+}
+
+impl AddressPattern {
+    /// Creates a pattern of addresses starting with the hex digits of `prefix` and ending with
+    /// the hex digits of `suffix`.
+    ///
+    /// The digits are case insensitive, and can be of odd length, e.g. `"dead"` and `"0"`.
+    pub fn new(prefix: &str, suffix: &str) -> Result<Self, AddressPatternError> {
+        let prefix = nibbles(prefix)?;
+        let suffix = nibbles(suffix)?;
+        if prefix.len() + suffix.len() > 40 {
+            return Err(AddressPatternError::TooLong);
+        }
+        Ok(Self { prefix, suffix })
+    }
+
+    /// Returns whether `address` matches the pattern.
+    #[inline]
+    pub fn matches(&self, address: &Address) -> bool {
+        let nibble = |index: usize| {
+            let shift = if index & 1 == 0 { 4 } else { 0 };
+            (address[index / 2] >> shift) & 0x0f
+        };
+        self.prefix
+            .iter()
+            .enumerate()
+            .all(|(index, digit)| nibble(index) == *digit)
+            && self
+                .suffix
+                .iter()
+                .enumerate()
+                .all(|(index, digit)| nibble(40 - self.suffix.len() + index) == *digit)
+    }
+
+    /// Returns the expected number of salts to try before finding a match.
+    pub fn difficulty(&self) -> f64 {
+        // `f64::powi` requires `std`.
+        (0..self.prefix.len() + self.suffix.len()).fold(1.0, |difficulty, _| difficulty * 16.0)
+    }
+}
+
+/// Parses hex digits into nibbles.
+fn nibbles(digits: &str) -> Result<Vec<u8>, AddressPatternError> {
+    let digits = digits.strip_prefix("0x").unwrap_or(digits);
+    digits
+        .chars()
+        .map(|digit| {
+            digit
+                .to_digit(16)
+                .map(|nibble| nibble as u8)
+                .ok_or(AddressPatternError::InvalidDigit(digit))
+        })
+        .collect()
+}
+
+/// Error creating an [`AddressPattern`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AddressPatternError {
+    /// The pattern contains a character that is not a hex digit.
+    InvalidDigit(char),
+    /// The prefix and the suffix have more digits than an address.
+    TooLong,
+}
+
+impl fmt::Display for AddressPatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidDigit(digit) => write!(f, "invalid hex digit {digit:?} in pattern"),
+            Self::TooLong => f.write_str("pattern has more than 40 hex digits"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AddressPatternError {}
+
+/// Salt and address found by a [`SaltGrinder`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Create2Match {
+    /// Salt giving the address.
+    pub salt: B256,
+    /// Address of the contract deployed with the salt.
+    pub address: Address,
+}
+
+/// Searches for a `CREATE2` salt giving an address that matches a pattern.
+///
+/// The salts tried are the base salt with its last 8 bytes replaced by a counter, so the
+/// first bytes of the salt can be fixed, as required by deployers that check them against
+/// the sender. The counter is split between the threads.
+#[cfg(feature = "std")]
+#[derive(Clone, Debug)]
+pub struct SaltGrinder {
+    deployer: Address,
+    init_code_hash: B256,
+    pattern: AddressPattern,
+    base_salt: B256,
+    threads: usize,
+}
+
+#[cfg(feature = "std")]
+impl SaltGrinder {
+    /// Number of salts tried by a thread between two checks of the cancellation flag.
+    const CANCEL_CHECK_INTERVAL: u64 = 1024;
+
+    /// Creates a grinder of the salts of contracts with `init_code_hash` deployed by
+    /// `deployer`, running on all available threads.
+    pub fn new(deployer: Address, init_code_hash: B256, pattern: AddressPattern) -> Self {
+        Self {
+            deployer,
+            init_code_hash,
+            pattern,
+            base_salt: B256::ZERO,
+            threads: std::thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    /// Sets the salt whose first 24 bytes are kept.
+    pub fn with_base_salt(mut self, base_salt: B256) -> Self {
+        self.base_salt = base_salt;
+        self
+    }
+
+    /// Sets the number of threads, at least one.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Returns the salt with the counter `counter`.
+    #[inline]
+    fn salt(&self, counter: u64) -> B256 {
+        let mut salt = self.base_salt;
+        salt[24..].copy_from_slice(&counter.to_be_bytes());
+        salt
+    }
+
+    /// Searches for a matching salt until one is found or `cancel` is set.
+    ///
+    /// Returns `None` if cancelled, or if all the counters were tried. When several threads
+    /// find a match, any of them is returned.
+    pub fn grind(&self, cancel: &AtomicBool) -> Option<Create2Match> {
+        let found = AtomicBool::new(false);
+        let threads = self.threads as u64;
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..threads)
+                .map(|thread| {
+                    let found = &found;
+                    scope.spawn(move || {
+                        let mut counter = thread;
+                        loop {
+                            for _ in 0..Self::CANCEL_CHECK_INTERVAL {
+                                let salt = self.salt(counter);
+                                let address =
+                                    create2_address(self.deployer, salt, self.init_code_hash);
+                                if self.pattern.matches(&address) {
+                                    found.store(true, Ordering::Relaxed);
+                                    return Some(Create2Match { salt, address });
+                                }
+                                counter = counter.checked_add(threads)?;
+                            }
+                            if found.load(Ordering::Relaxed) || cancel.load(Ordering::Relaxed) {
+                                return None;
+                            }
+                        }
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().expect("salt grinder thread panicked"))
+                .next()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        interpreter::opcode::{MSTORE8, PUSH1, RETURN},
+        primitives::{AccountInfo, Bytecode, EthereumWiring, TxKind},
+        Evm, InMemoryDB,
+    };
+
+    #[test]
+    fn deterministic_deployment() {
+        // Returns the runtime code 0x42.
+        let init_code = [
+            PUSH1, 0x42, PUSH1, 0x00, MSTORE8, PUSH1, 0x01, PUSH1, 0x00, RETURN,
+        ];
+        let salt = B256::with_last_byte(7);
+        let expected = deterministic_deployment_address(salt, &init_code);
+
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            DETERMINISTIC_DEPLOYER,
+            AccountInfo::from_bytecode(Bytecode::new_raw(DETERMINISTIC_DEPLOYER_CODE.into())),
+        );
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(DETERMINISTIC_DEPLOYER);
+                tx.data = deterministic_deployment_input(salt, &init_code);
+                tx.gas_limit = 1_000_000;
+            })
+            .build();
+
+        let simulation = evm
+            .simulate_deterministic_deployment(salt, init_code.into(), 100_000)
+            .unwrap();
+        assert_eq!(simulation.address, expected);
+        assert_eq!(simulation.code[..], [0x42]);
+
+        // The proxy returns the address of the contract it deployed.
+        let result = evm.transact().unwrap();
+        assert_eq!(result.result.output().unwrap()[..], expected[..]);
+        assert_eq!(
+            result.state[&expected]
+                .info
+                .code
+                .as_ref()
+                .unwrap()
+                .original_byte_slice(),
+            [0x42]
+        );
+    }
+
+    #[test]
+    fn address_pattern() {
+        let address = address!("dead00000000000000000000000000000000beef");
+        assert!(AddressPattern::new("0xdEaD", "beef")
+            .unwrap()
+            .matches(&address));
+        assert!(AddressPattern::new("dea", "eef").unwrap().matches(&address));
+        assert!(AddressPattern::new("", "").unwrap().matches(&address));
+        assert!(!AddressPattern::new("deaf", "").unwrap().matches(&address));
+        assert!(!AddressPattern::new("", "bee").unwrap().matches(&address));
+        assert_eq!(
+            AddressPattern::new("xyz", ""),
+            Err(AddressPatternError::InvalidDigit('x'))
+        );
+        assert_eq!(
+            AddressPattern::new(&"0".repeat(21), &"0".repeat(20)),
+            Err(AddressPatternError::TooLong)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn grind_salt() {
+        let init_code_hash = keccak256([0x00]);
+        let base_salt = B256::repeat_byte(0xaa);
+        let pattern = AddressPattern::new("00", "0").unwrap();
+        let found = SaltGrinder::new(DETERMINISTIC_DEPLOYER, init_code_hash, pattern.clone())
+            .with_base_salt(base_salt)
+            .with_threads(4)
+            .grind(&AtomicBool::new(false))
+            .unwrap();
+        assert!(pattern.matches(&found.address));
+        assert_eq!(found.salt[..24], base_salt[..24]);
+        assert_eq!(
+            found.address,
+            create2_address(DETERMINISTIC_DEPLOYER, found.salt, init_code_hash)
+        );
+
+        // An impossible pattern is only stopped by the cancellation.
+        let pattern = AddressPattern::new(&"0".repeat(40), "").unwrap();
+        let grinder = SaltGrinder::new(DETERMINISTIC_DEPLOYER, init_code_hash, pattern);
+        assert_eq!(grinder.grind(&AtomicBool::new(true)), None);
+    }
+}
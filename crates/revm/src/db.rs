@@ -5,20 +5,32 @@ mod utils;
 
 #[cfg(feature = "alloydb")]
 mod alloydb;
+#[cfg(all(feature = "std", feature = "serde-json"))]
+mod commit_log;
 #[cfg(feature = "ethersdb")]
 mod ethersdb;
+#[cfg(feature = "serde-json")]
+pub mod genesis;
 pub mod in_memory_db;
+pub mod namespaced_db;
+pub mod prestate;
 pub mod states;
 
 pub use crate::primitives::db::*;
 pub use crate::primitives::db::{EmptyDB, EmptyDBTyped};
 #[cfg(feature = "alloydb")]
 pub use alloydb::AlloyDB;
+#[cfg(all(feature = "std", feature = "serde-json"))]
+pub use commit_log::{recover_commit_log, CommitLogDB, CommitRecord};
 #[cfg(feature = "ethersdb")]
 pub use ethersdb::EthersDB;
+#[cfg(feature = "serde-json")]
+pub use genesis::{ChainConfig, Genesis, GenesisAccount};
 pub use in_memory_db::*;
+pub use namespaced_db::{NamespacedDB, NamespacedDBError};
+pub use prestate::{PoststateAccount, Prestate, PrestateAccount, PrestateTracer, StateDiff};
 pub use states::{
-    AccountRevert, AccountStatus, BundleAccount, BundleState, CacheState, DBBox,
-    OriginalValuesKnown, PlainAccount, RevertToSlot, State, StateBuilder, StateDBBox,
-    StorageWithOriginalValues, TransitionAccount, TransitionState,
+    AccountRevert, AccountStatus, BundleAccount, BundleState, CacheBounds, CacheMetrics,
+    CacheState, DBBox, OriginalValuesKnown, PlainAccount, RevertToSlot, State, StateBuilder,
+    StateDBBox, StorageWithOriginalValues, TransitionAccount, TransitionState,
 };
@@ -0,0 +1,240 @@
+//! Database that persists committed changes, so that they can be recovered after a crash.
+
+use crate::primitives::{
+    db::{Database, DatabaseCommit, DatabaseRef},
+    Account, AccountInfo, Address, Bytecode, EvmState, HashMap, B256, U256,
+};
+use serde::{Deserialize, Serialize};
+use std::io::{self, BufRead, Write};
+
+/// Changes of a single [DatabaseCommit::commit], as stored in the commit log.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommitRecord {
+    /// Position of the commit in the log, starting at zero.
+    pub sequence: u64,
+    /// Committed changes.
+    pub changes: EvmState,
+}
+
+/// Borrowed [CommitRecord], to serialize changes without cloning them.
+#[derive(Serialize)]
+struct CommitRecordRef<'a> {
+    sequence: u64,
+    changes: &'a EvmState,
+}
+
+/// A [Database] that appends every commit to a log before applying it to the inner `db`.
+///
+/// Each commit is written as a single line of JSON and flushed, which makes the log a
+/// sequence of checkpoints of the state. After a crash, [recover_commit_log] restores the
+/// state up to the last commit that was fully written, and a new log can continue from the
+/// returned sequence with [CommitLogDB::with_sequence].
+///
+/// If writing to the log fails, the changes are still applied to `db`, the error is kept
+/// and no further commits are logged. Check [CommitLogDB::error] to find out if the log is
+/// complete.
+#[derive(Debug)]
+pub struct CommitLogDB<DB, W> {
+    /// Inner database.
+    pub db: DB,
+    writer: W,
+    sequence: u64,
+    error: Option<io::Error>,
+}
+
+impl<DB, W: Write> CommitLogDB<DB, W> {
+    /// Creates a new database that logs commits to `writer`, starting at sequence zero.
+    pub fn new(db: DB, writer: W) -> Self {
+        Self {
+            db,
+            writer,
+            sequence: 0,
+            error: None,
+        }
+    }
+
+    /// Sets the sequence of the next commit, used to continue a recovered log.
+    pub fn with_sequence(mut self, sequence: u64) -> Self {
+        self.sequence = sequence;
+        self
+    }
+
+    /// Returns the sequence of the next commit.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Returns the error that stopped the logging, if any.
+    pub fn error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    /// Consumes the database and returns the inner database and the log writer.
+    pub fn into_parts(self) -> (DB, W) {
+        (self.db, self.writer)
+    }
+
+    /// Writes the record of `changes` and flushes the writer.
+    fn write_record(&mut self, changes: &EvmState) -> io::Result<()> {
+        let mut line = serde_json::to_vec(&CommitRecordRef {
+            sequence: self.sequence,
+            changes,
+        })?;
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.writer.flush()?;
+        self.sequence += 1;
+        Ok(())
+    }
+}
+
+impl<DB: DatabaseCommit, W: Write> DatabaseCommit for CommitLogDB<DB, W> {
+    fn commit(&mut self, changes: HashMap<Address, Account>) {
+        if self.error.is_none() {
+            self.error = self.write_record(&changes).err();
+        }
+        self.db.commit(changes);
+    }
+}
+
+impl<DB: Database, W> Database for CommitLogDB<DB, W> {
+    type Error = DB::Error;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address)
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash(code_hash)
+    }
+
+    #[inline]
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage(address, index)
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number)
+    }
+}
+
+impl<DB: DatabaseRef, W> DatabaseRef for CommitLogDB<DB, W> {
+    type Error = DB::Error;
+
+    #[inline]
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic_ref(address)
+    }
+
+    #[inline]
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db.code_by_hash_ref(code_hash)
+    }
+
+    #[inline]
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.db.storage_ref(address, index)
+    }
+
+    #[inline]
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash_ref(number)
+    }
+}
+
+/// Applies the records of a commit log written by [CommitLogDB] to `db`, in order.
+///
+/// Returns the number of applied records, which is the sequence to continue the log with.
+/// A last line that was not fully written, as left by a crash, is ignored. Records that are
+/// corrupted or out of order result in an [io::ErrorKind::InvalidData] error, in which case
+/// the records before them have already been applied.
+pub fn recover_commit_log<DB: DatabaseCommit>(
+    db: &mut DB,
+    mut reader: impl BufRead,
+) -> io::Result<u64> {
+    let mut sequence = 0;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 || line.last() != Some(&b'\n') {
+            return Ok(sequence);
+        }
+        let record: CommitRecord = serde_json::from_slice(&line)?;
+        if record.sequence != sequence {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "expected commit {sequence}, found commit {}",
+                    record.sequence
+                ),
+            ));
+        }
+        db.commit(record.changes);
+        sequence += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        primitives::{AccountStatus, EvmStorageSlot},
+    };
+
+    fn changes(address: Address, balance: u64, slot: u64) -> EvmState {
+        let mut account = Account::from(AccountInfo::from_balance(U256::from(balance)));
+        account.status = AccountStatus::Touched;
+        account.storage.insert(
+            U256::from(slot),
+            EvmStorageSlot::new_changed(U256::ZERO, U256::from(slot)),
+        );
+        HashMap::from_iter([(address, account)])
+    }
+
+    #[test]
+    fn recovers_committed_changes() {
+        let address = Address::with_last_byte(1);
+        let mut db = CommitLogDB::new(CacheDB::new(EmptyDB::default()), Vec::new());
+        db.commit(changes(address, 1, 1));
+        db.commit(changes(address, 2, 2));
+        assert_eq!(db.sequence(), 2);
+        assert!(db.error().is_none());
+
+        let (expected, mut log) = db.into_parts();
+        // Simulate a crash in the middle of writing the third commit.
+        log.extend_from_slice(b"{\"sequence\":2,\"chan");
+
+        let mut recovered = CacheDB::new(EmptyDB::default());
+        assert_eq!(
+            recover_commit_log(&mut recovered, log.as_slice()).unwrap(),
+            2
+        );
+        assert_eq!(
+            recovered.basic(address).unwrap(),
+            expected.basic_ref(address).unwrap()
+        );
+        assert_eq!(
+            recovered.basic(address).unwrap().unwrap().balance,
+            U256::from(2)
+        );
+        assert_eq!(recovered.storage(address, U256::from(1)), Ok(U256::from(1)));
+        assert_eq!(recovered.storage(address, U256::from(2)), Ok(U256::from(2)));
+    }
+
+    #[test]
+    fn rejects_out_of_order_records() {
+        let address = Address::with_last_byte(1);
+        let mut db =
+            CommitLogDB::new(CacheDB::new(EmptyDB::default()), Vec::new()).with_sequence(1);
+        db.commit(changes(address, 1, 1));
+
+        let (_, log) = db.into_parts();
+        let mut recovered = CacheDB::new(EmptyDB::default());
+        let error = recover_commit_log(&mut recovered, log.as_slice()).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -0,0 +1,395 @@
+//! Importer of geth genesis files, enabled by the `serde-json` feature.
+//!
+//! [`Genesis::from_json`] parses the allocation and chain config of a `genesis.json`, and
+//! [`CacheState::from_genesis`] loads the allocation in a cache. The activation blocks and
+//! timestamps of the chain config are mapped to a [`HardforkSchedule`] by
+//! [`ChainConfig::hardfork_schedule`].
+
+use crate::{
+    db::states::{plain_account::PlainStorage, CacheState},
+    primitives::{
+        AccountInfo, Address, BlockEnv, Bytecode, Bytes, ForkCondition, HardforkSchedule, SpecId,
+        B256, U256,
+    },
+};
+use serde::{Deserialize, Deserializer};
+use std::{collections::BTreeMap, string::String};
+
+/// Genesis of a chain, as in the `genesis.json` of geth.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Genesis {
+    /// Chain id and activation of the forks.
+    #[serde(default)]
+    pub config: ChainConfig,
+    /// Nonce of the genesis block.
+    #[serde(default, deserialize_with = "quantity_u64")]
+    pub nonce: u64,
+    /// Timestamp of the genesis block.
+    #[serde(default, deserialize_with = "quantity_u64")]
+    pub timestamp: u64,
+    /// Extra data of the genesis block.
+    #[serde(default)]
+    pub extra_data: Bytes,
+    /// Gas limit of the genesis block.
+    #[serde(default, deserialize_with = "quantity_u64")]
+    pub gas_limit: u64,
+    /// Difficulty of the genesis block.
+    #[serde(default, deserialize_with = "quantity")]
+    pub difficulty: U256,
+    /// Mix hash of the genesis block, the `prevrandao` after the merge.
+    #[serde(default)]
+    pub mix_hash: B256,
+    /// Beneficiary of the genesis block.
+    #[serde(default)]
+    pub coinbase: Address,
+    /// Base fee of the genesis block, if London is active.
+    #[serde(default, deserialize_with = "optional_quantity")]
+    pub base_fee_per_gas: Option<U256>,
+    /// Number of the genesis block, zero unless the chain is a continuation of another.
+    #[serde(default, deserialize_with = "quantity_u64")]
+    pub number: u64,
+    /// Accounts of the genesis state.
+    #[serde(default)]
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+}
+
+impl Genesis {
+    /// Parses a geth genesis file.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    /// Returns the spec of the genesis block.
+    pub fn spec_id(&self) -> SpecId {
+        self.config.spec_at(self.number, self.timestamp)
+    }
+
+    /// Returns the environment of the genesis block.
+    pub fn block_env(&self) -> BlockEnv {
+        let spec_id = self.spec_id();
+        let mut block = BlockEnv {
+            number: U256::from(self.number),
+            coinbase: self.coinbase,
+            timestamp: U256::from(self.timestamp),
+            gas_limit: U256::from(self.gas_limit),
+            basefee: self.base_fee_per_gas.unwrap_or_default(),
+            difficulty: self.difficulty,
+            prevrandao: spec_id
+                .is_enabled_in(SpecId::MERGE)
+                .then_some(self.mix_hash),
+            blob_excess_gas_and_price: None,
+        };
+        if spec_id.is_enabled_in(SpecId::CANCUN) {
+            block.set_blob_excess_gas_and_price(0);
+        }
+        block
+    }
+
+    /// Returns a cache with the accounts of the genesis state.
+    pub fn to_cache_state(&self) -> CacheState {
+        let mut cache = CacheState::new(self.spec_id().is_enabled_in(SpecId::SPURIOUS_DRAGON));
+        for (address, account) in &self.alloc {
+            let (info, code) = account.info();
+            if let Some(code) = code {
+                cache.contracts.insert(info.code_hash, code);
+            }
+            let storage: PlainStorage = account
+                .storage
+                .iter()
+                .map(|(slot, value)| (*slot, *value))
+                .collect();
+            cache.insert_account_with_storage(*address, info, storage);
+        }
+        cache
+    }
+}
+
+impl CacheState {
+    /// Returns a cache with the accounts of the geth genesis file `json`.
+    ///
+    /// The state clear flag is set if Spurious Dragon is active in the genesis block.
+    pub fn from_genesis(json: &str) -> Result<Self, serde_json::Error> {
+        Genesis::from_json(json).map(|genesis| genesis.to_cache_state())
+    }
+}
+
+/// Account of the genesis state.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+pub struct GenesisAccount {
+    /// Balance, in wei.
+    #[serde(default, deserialize_with = "quantity")]
+    pub balance: U256,
+    /// Nonce.
+    #[serde(default, deserialize_with = "quantity_u64")]
+    pub nonce: u64,
+    /// Code, if the account is a contract.
+    #[serde(default)]
+    pub code: Option<Bytes>,
+    /// Storage slots.
+    #[serde(default, deserialize_with = "quantity_map")]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl GenesisAccount {
+    /// Returns the info of the account and its code, if it has any.
+    pub fn info(&self) -> (AccountInfo, Option<Bytecode>) {
+        let code = self
+            .code
+            .clone()
+            .filter(|code| !code.is_empty())
+            .map(Bytecode::new_raw);
+        let info = AccountInfo::new(
+            self.balance,
+            self.nonce,
+            code.as_ref()
+                .map_or(crate::primitives::KECCAK_EMPTY, Bytecode::hash_slow),
+            code.clone().unwrap_or_default(),
+        );
+        (info, code)
+    }
+}
+
+/// Chain config of a geth genesis file.
+///
+/// Forks activated by block are activated when the number of the block reaches their block,
+/// and forks activated by timestamp when the timestamp of the block reaches their timestamp.
+/// Forks without activation are never activated.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainConfig {
+    /// Chain id.
+    #[serde(default)]
+    pub chain_id: u64,
+    /// Homestead activation block.
+    pub homestead_block: Option<u64>,
+    /// DAO fork activation block.
+    pub dao_fork_block: Option<u64>,
+    /// Tangerine Whistle (EIP-150) activation block.
+    pub eip150_block: Option<u64>,
+    /// Spurious Dragon (EIP-155 and EIP-158) activation block.
+    pub eip158_block: Option<u64>,
+    /// Byzantium activation block.
+    pub byzantium_block: Option<u64>,
+    /// Constantinople activation block.
+    pub constantinople_block: Option<u64>,
+    /// Petersburg activation block.
+    pub petersburg_block: Option<u64>,
+    /// Istanbul activation block.
+    pub istanbul_block: Option<u64>,
+    /// Muir Glacier activation block.
+    pub muir_glacier_block: Option<u64>,
+    /// Berlin activation block.
+    pub berlin_block: Option<u64>,
+    /// London activation block.
+    pub london_block: Option<u64>,
+    /// Arrow Glacier activation block.
+    pub arrow_glacier_block: Option<u64>,
+    /// Gray Glacier activation block.
+    pub gray_glacier_block: Option<u64>,
+    /// Paris activation block.
+    pub merge_netsplit_block: Option<u64>,
+    /// Total difficulty at which the chain merges, Paris is active from genesis if it is zero.
+    #[serde(default, deserialize_with = "optional_quantity")]
+    pub terminal_total_difficulty: Option<U256>,
+    /// Shanghai activation timestamp.
+    pub shanghai_time: Option<u64>,
+    /// Cancun activation timestamp.
+    pub cancun_time: Option<u64>,
+    /// Prague activation timestamp.
+    pub prague_time: Option<u64>,
+}
+
+impl ChainConfig {
+    /// Returns the forks activated by block, in activation order.
+    fn block_forks(&self) -> [(SpecId, Option<u64>); 14] {
+        let merge_block = self.merge_netsplit_block.or(self
+            .terminal_total_difficulty
+            .filter(|ttd| ttd.is_zero())
+            .map(|_| 0));
+        [
+            (SpecId::HOMESTEAD, self.homestead_block),
+            (SpecId::DAO_FORK, self.dao_fork_block),
+            (SpecId::TANGERINE, self.eip150_block),
+            (SpecId::SPURIOUS_DRAGON, self.eip158_block),
+            (SpecId::BYZANTIUM, self.byzantium_block),
+            (SpecId::CONSTANTINOPLE, self.constantinople_block),
+            (SpecId::PETERSBURG, self.petersburg_block),
+            (SpecId::ISTANBUL, self.istanbul_block),
+            (SpecId::MUIR_GLACIER, self.muir_glacier_block),
+            (SpecId::BERLIN, self.berlin_block),
+            (SpecId::LONDON, self.london_block),
+            (SpecId::ARROW_GLACIER, self.arrow_glacier_block),
+            (SpecId::GRAY_GLACIER, self.gray_glacier_block),
+            (SpecId::MERGE, merge_block),
+        ]
+    }
+
+    /// Returns the forks activated by timestamp, in activation order.
+    fn timestamp_forks(&self) -> [(SpecId, Option<u64>); 3] {
+        [
+            (SpecId::SHANGHAI, self.shanghai_time),
+            (SpecId::CANCUN, self.cancun_time),
+            (SpecId::PRAGUE, self.prague_time),
+        ]
+    }
+
+    /// Returns the activations of the forks of the chain.
+    pub fn hardfork_schedule(&self) -> HardforkSchedule {
+        let block_forks = self
+            .block_forks()
+            .into_iter()
+            .filter_map(|(spec_id, block)| Some((spec_id, ForkCondition::Block(block?))));
+        let timestamp_forks = self
+            .timestamp_forks()
+            .into_iter()
+            .filter_map(|(spec_id, time)| Some((spec_id, ForkCondition::Timestamp(time?))));
+        block_forks
+            .chain(timestamp_forks)
+            .fold(HardforkSchedule::new(), |schedule, (spec_id, condition)| {
+                schedule.with_fork(spec_id, condition)
+            })
+    }
+
+    /// Returns the spec of the block with `number` and `timestamp`, the latest fork that is
+    /// active in it or Frontier.
+    pub fn spec_at(&self, number: u64, timestamp: u64) -> SpecId {
+        self.hardfork_schedule().spec_at(number, timestamp)
+    }
+}
+
+/// Quantity of a genesis file, a number or a hex or decimal string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Quantity {
+    Number(u64),
+    String(String),
+}
+
+impl Quantity {
+    fn into_u256<E: serde::de::Error>(self) -> Result<U256, E> {
+        match self {
+            Self::Number(number) => Ok(U256::from(number)),
+            Self::String(string) => string.parse().map_err(E::custom),
+        }
+    }
+}
+
+fn quantity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<U256, D::Error> {
+    Quantity::deserialize(deserializer)?.into_u256()
+}
+
+fn optional_quantity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<U256>, D::Error> {
+    Option::<Quantity>::deserialize(deserializer)?
+        .map(Quantity::into_u256)
+        .transpose()
+}
+
+fn quantity_u64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    quantity(deserializer)?
+        .try_into()
+        .map_err(serde::de::Error::custom)
+}
+
+fn quantity_map<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<U256, U256>, D::Error> {
+    BTreeMap::<String, Quantity>::deserialize(deserializer)?
+        .into_iter()
+        .map(|(slot, value)| Ok((Quantity::String(slot).into_u256()?, value.into_u256()?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{EmptyDB, State},
+        primitives::{address, Bytes, EthereumWiring, TxKind},
+        Database, Evm,
+    };
+
+    const GENESIS: &str = r#"{
+        "config": {
+            "chainId": 1337,
+            "homesteadBlock": 0,
+            "eip150Block": 0,
+            "eip155Block": 0,
+            "eip158Block": 0,
+            "byzantiumBlock": 0,
+            "constantinopleBlock": 0,
+            "petersburgBlock": 0,
+            "istanbulBlock": 0,
+            "berlinBlock": 0,
+            "londonBlock": 0,
+            "terminalTotalDifficulty": 0,
+            "shanghaiTime": 1000
+        },
+        "nonce": "0x0",
+        "timestamp": "0x3e8",
+        "gasLimit": "0x1c9c380",
+        "difficulty": "0x1",
+        "alloc": {
+            "0x1000000000000000000000000000000000000001": {
+                "balance": "1000000000000000000"
+            },
+            "2000000000000000000000000000000000000002": {
+                "balance": "0x0",
+                "nonce": "0x1",
+                "code": "0x5f5460005260205ff3",
+                "storage": {
+                    "0x00": "0x2a"
+                }
+            }
+        }
+    }"#;
+
+    #[test]
+    fn spec_schedule() {
+        let config = Genesis::from_json(GENESIS).unwrap().config;
+        assert_eq!(config.chain_id, 1337);
+        // The merge is active from genesis, as the terminal total difficulty is zero.
+        assert_eq!(config.spec_at(0, 999), SpecId::MERGE);
+        assert_eq!(config.spec_at(0, 1000), SpecId::SHANGHAI);
+
+        let config = ChainConfig {
+            homestead_block: Some(5),
+            london_block: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(config.spec_at(4, 0), SpecId::FRONTIER);
+        assert_eq!(config.spec_at(5, 0), SpecId::HOMESTEAD);
+        assert_eq!(config.spec_at(10, 0), SpecId::LONDON);
+    }
+
+    #[test]
+    fn genesis_state_is_executable() {
+        let genesis = Genesis::from_json(GENESIS).unwrap();
+        let contract = address!("2000000000000000000000000000000000000002");
+        let caller = address!("1000000000000000000000000000000000000001");
+
+        let cache = CacheState::from_genesis(GENESIS).unwrap();
+        assert!(cache.has_state_clear);
+        let mut state = State::builder().with_cached_prestate(cache).build();
+        let account = state.basic(caller).unwrap().unwrap();
+        assert_eq!(account.balance, U256::from(10).pow(U256::from(18)));
+
+        assert_eq!(genesis.spec_id(), SpecId::SHANGHAI);
+        let mut evm = Evm::<EthereumWiring<State<EmptyDB>, ()>>::builder()
+            .with_db(state)
+            .with_default_ext_ctx()
+            .with_spec_id(genesis.spec_id())
+            .modify_block_env(|block| *block = genesis.block_env())
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(contract);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+        let result = evm.transact().unwrap().result;
+        assert_eq!(
+            result.output(),
+            Some(&Bytes::from(U256::from(42).to_be_bytes_vec()))
+        );
+    }
+}
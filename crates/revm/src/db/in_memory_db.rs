@@ -136,7 +136,7 @@ impl<ExtDB> DatabaseCommit for CacheDB<ExtDB> {
             if account.is_selfdestructed() {
                 let db_account = self.accounts.entry(address).or_default();
                 db_account.storage.clear();
-                db_account.account_state = AccountState::NotExisting;
+                db_account.account_state = AccountState::Destroyed;
                 db_account.info = AccountInfo::default();
                 continue;
             }
@@ -146,14 +146,18 @@ impl<ExtDB> DatabaseCommit for CacheDB<ExtDB> {
             let db_account = self.accounts.entry(address).or_default();
             db_account.info = account.info;
 
-            db_account.account_state = if is_newly_created {
+            if is_newly_created {
                 db_account.storage.clear();
-                AccountState::StorageCleared
-            } else if db_account.account_state.is_storage_cleared() {
+            }
+            db_account.account_state = match db_account.account_state {
+                // The storage of the destroyed account must still be wiped when persisted.
+                AccountState::Destroyed | AccountState::DestroyedThenCreated => {
+                    AccountState::DestroyedThenCreated
+                }
+                _ if is_newly_created => AccountState::StorageCleared,
                 // Preserve old account state if it already exists
-                AccountState::StorageCleared
-            } else {
-                AccountState::Touched
+                AccountState::StorageCleared => AccountState::StorageCleared,
+                _ => AccountState::Touched,
             };
             db_account.storage.extend(
                 account
@@ -204,10 +208,9 @@ impl<ExtDB: DatabaseRef> Database for CacheDB<ExtDB> {
                 match acc_entry.storage.entry(index) {
                     Entry::Occupied(entry) => Ok(*entry.get()),
                     Entry::Vacant(entry) => {
-                        if matches!(
-                            acc_entry.account_state,
-                            AccountState::StorageCleared | AccountState::NotExisting
-                        ) {
+                        if acc_entry.account_state.is_storage_cleared()
+                            || acc_entry.account_state == AccountState::NotExisting
+                        {
                             Ok(U256::ZERO)
                         } else {
                             let slot = self.db.storage_ref(address, index)?;
@@ -268,10 +271,9 @@ impl<ExtDB: DatabaseRef> DatabaseRef for CacheDB<ExtDB> {
             Some(acc_entry) => match acc_entry.storage.get(&index) {
                 Some(entry) => Ok(*entry),
                 None => {
-                    if matches!(
-                        acc_entry.account_state,
-                        AccountState::StorageCleared | AccountState::NotExisting
-                    ) {
+                    if acc_entry.account_state.is_storage_cleared()
+                        || acc_entry.account_state == AccountState::NotExisting
+                    {
                         Ok(U256::ZERO)
                     } else {
                         self.db.storage_ref(address, index)
@@ -309,7 +311,10 @@ impl DbAccount {
     }
 
     pub fn info(&self) -> Option<AccountInfo> {
-        if matches!(self.account_state, AccountState::NotExisting) {
+        if matches!(
+            self.account_state,
+            AccountState::NotExisting | AccountState::Destroyed
+        ) {
             None
         } else {
             Some(self.info.clone())
@@ -341,9 +346,17 @@ pub enum AccountState {
     NotExisting,
     /// EVM touched this account. For newer hardfork this means it can be cleared/removed from state.
     Touched,
-    /// EVM cleared storage of this account, mostly by selfdestruct, we don't ask database for storage slots
+    /// EVM cleared storage of this account, mostly by creating it, we don't ask database for storage slots
     /// and assume they are U256::ZERO
     StorageCleared,
+    /// Account was selfdestructed. It does not exist anymore and its storage is cleared.
+    Destroyed,
+    /// Account was selfdestructed, then created or funded again by a later transaction, for
+    /// example when CREATE2 redeploys a contract at the same address.
+    ///
+    /// Like [AccountState::StorageCleared], but the storage the account had before it was
+    /// destroyed must be wiped when the account is persisted.
+    DestroyedThenCreated,
     /// EVM didn't interacted with this account
     #[default]
     None,
@@ -352,23 +365,82 @@ pub enum AccountState {
 impl AccountState {
     /// Returns `true` if EVM cleared storage of this account
     pub fn is_storage_cleared(&self) -> bool {
-        matches!(self, AccountState::StorageCleared)
+        matches!(
+            self,
+            AccountState::StorageCleared
+                | AccountState::Destroyed
+                | AccountState::DestroyedThenCreated
+        )
+    }
+
+    /// Returns `true` if the account was selfdestructed, whether or not it was created again.
+    pub fn was_destroyed(&self) -> bool {
+        matches!(
+            self,
+            AccountState::Destroyed | AccountState::DestroyedThenCreated
+        )
     }
 }
 
 /// Ethereum benchmark wiring
 pub type EthereumBenchmarkWiring = EthereumWiring<BenchmarkDB, ()>;
 
-/// Custom benchmarking DB that only has account info for the zero address.
+/// Custom benchmarking DB that has the bytecode at the zero address and a funded account at
+/// address `0x..01`.
 ///
-/// Any other address will return an empty account.
+/// More accounts, contracts and storage can be registered with [BenchmarkDB::with_account]
+/// and [BenchmarkDB::with_storage]. Any other address will return an empty account.
 #[derive(Debug, Default, Clone)]
-pub struct BenchmarkDB(pub Bytecode, B256);
+pub struct BenchmarkDB(pub Bytecode, B256, BenchmarkFixtures);
+
+/// Accounts and storage registered in a [BenchmarkDB].
+#[derive(Debug, Default, Clone)]
+struct BenchmarkFixtures {
+    accounts: HashMap<Address, AccountInfo>,
+    contracts: HashMap<B256, Bytecode>,
+    storage: HashMap<(Address, U256), U256>,
+    block_hash: Option<fn(u64) -> B256>,
+}
 
 impl BenchmarkDB {
     pub fn new_bytecode(bytecode: Bytecode) -> Self {
         let hash = bytecode.hash_slow();
-        Self(bytecode, hash)
+        Self(bytecode, hash, BenchmarkFixtures::default())
+    }
+
+    /// Registers an account, replacing the default accounts if `address` is one of them.
+    pub fn with_account(mut self, address: Address, mut info: AccountInfo) -> Self {
+        if let Some(code) = info.code.take() {
+            info.code_hash = code.hash_slow();
+            self.2.contracts.insert(info.code_hash, code.clone());
+            info.code = Some(code);
+        }
+        self.2.accounts.insert(address, info);
+        self
+    }
+
+    /// Registers accounts without code that have `balance`.
+    pub fn with_funded_accounts(
+        mut self,
+        addresses: impl IntoIterator<Item = Address>,
+        balance: U256,
+    ) -> Self {
+        for address in addresses {
+            self = self.with_account(address, AccountInfo::from_balance(balance));
+        }
+        self
+    }
+
+    /// Sets the value of the storage `slot` of `address`. Other slots are zero.
+    pub fn with_storage(mut self, address: Address, slot: U256, value: U256) -> Self {
+        self.2.storage.insert((address, slot), value);
+        self
+    }
+
+    /// Sets the function that returns the hash of a block, instead of the zero hash.
+    pub fn with_block_hash(mut self, block_hash: fn(u64) -> B256) -> Self {
+        self.2.block_hash = Some(block_hash);
+        self
     }
 }
 
@@ -376,6 +448,9 @@ impl Database for BenchmarkDB {
     type Error = Infallible;
     /// Get basic account information.
     fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        if let Some(info) = self.2.accounts.get(&address) {
+            return Ok(Some(info.clone()));
+        }
         if address == Address::ZERO {
             return Ok(Some(AccountInfo {
                 nonce: 1,
@@ -396,25 +471,111 @@ impl Database for BenchmarkDB {
     }
 
     /// Get account code by its hash
-    fn code_by_hash(&mut self, _code_hash: B256) -> Result<Bytecode, Self::Error> {
-        Ok(Bytecode::default())
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self
+            .2
+            .contracts
+            .get(&code_hash)
+            .cloned()
+            .unwrap_or_default())
     }
 
     /// Get storage value of address at index.
-    fn storage(&mut self, _address: Address, _index: U256) -> Result<U256, Self::Error> {
-        Ok(U256::default())
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Ok(self
+            .2
+            .storage
+            .get(&(address, index))
+            .copied()
+            .unwrap_or_default())
     }
 
     // History related
-    fn block_hash(&mut self, _number: u64) -> Result<B256, Self::Error> {
-        Ok(B256::default())
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        Ok(self
+            .2
+            .block_hash
+            .map_or(B256::default(), |block_hash| block_hash(number)))
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{CacheDB, EmptyDB};
-    use crate::primitives::{db::Database, AccountInfo, Address, U256};
+    use super::{AccountState, BenchmarkDB, CacheDB, EmptyDB};
+    use crate::primitives::{
+        db::{Database, DatabaseCommit, DatabaseRef},
+        Account, AccountInfo, Address, Bytecode, Bytes, HashMap, B256, U256,
+    };
+
+    #[test]
+    fn test_benchmark_db_fixtures() {
+        let contract = Address::with_last_byte(42);
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00]));
+        let funded = [Address::with_last_byte(1), Address::with_last_byte(2)];
+        let mut db = BenchmarkDB::new_bytecode(Bytecode::new())
+            .with_account(contract, AccountInfo::from_bytecode(code.clone()))
+            .with_storage(contract, U256::from(1), U256::from(2))
+            .with_funded_accounts(funded, U256::from(100))
+            .with_block_hash(|number| B256::with_last_byte(number as u8));
+
+        let info = db.basic(contract).unwrap().unwrap();
+        assert_eq!(info.code_hash, code.hash_slow());
+        assert_eq!(db.code_by_hash(info.code_hash), Ok(code));
+        assert_eq!(db.storage(contract, U256::from(1)), Ok(U256::from(2)));
+        assert_eq!(db.storage(contract, U256::from(2)), Ok(U256::ZERO));
+        for address in funded {
+            assert_eq!(db.basic(address).unwrap().unwrap().balance, U256::from(100));
+        }
+        assert_eq!(db.block_hash(7), Ok(B256::with_last_byte(7)));
+
+        // The bytecode is still at the zero address.
+        assert!(db.basic(Address::ZERO).unwrap().unwrap().code.is_some());
+    }
+
+    #[test]
+    fn test_destroyed_then_created() {
+        let account = Address::with_last_byte(42);
+        let slot = U256::from(1);
+        let mut init_state = CacheDB::new(EmptyDB::default());
+        init_state.insert_account_info(account, AccountInfo::from_balance(U256::from(1)));
+        init_state
+            .insert_account_storage(account, slot, U256::from(2))
+            .unwrap();
+        let mut state = CacheDB::new(init_state);
+        let info = state.basic(account).unwrap().unwrap();
+        assert_eq!(state.storage(account, slot), Ok(U256::from(2)));
+
+        let mut destroyed = Account::from(info.clone());
+        destroyed.mark_touch();
+        destroyed.mark_selfdestruct();
+        state.commit(HashMap::from([(account, destroyed)]));
+        assert_eq!(
+            state.accounts[&account].account_state,
+            AccountState::Destroyed
+        );
+        assert_eq!(state.basic(account), Ok(None));
+        assert_eq!(state.storage(account, slot), Ok(U256::ZERO));
+
+        // Funding the account again must not expose its storage from before the destruction.
+        let mut funded = Account::from(info);
+        funded.mark_touch();
+        state.commit(HashMap::from([(account, funded)]));
+        let db_account = &state.accounts[&account];
+        assert_eq!(db_account.account_state, AccountState::DestroyedThenCreated);
+        assert!(db_account.account_state.was_destroyed());
+        assert_eq!(state.storage_ref(account, slot), Ok(U256::ZERO));
+        assert_eq!(state.storage(account, slot), Ok(U256::ZERO));
+
+        // Creating it again keeps the destruction.
+        let mut created = Account::from(AccountInfo::from_balance(U256::from(3)));
+        created.mark_touch();
+        created.mark_created();
+        state.commit(HashMap::from([(account, created)]));
+        assert_eq!(
+            state.accounts[&account].account_state,
+            AccountState::DestroyedThenCreated
+        );
+    }
 
     #[test]
     fn test_insert_account_storage() {
@@ -0,0 +1,203 @@
+//! Database that routes storage of configured address ranges to a separate backend.
+
+use crate::primitives::{
+    db::{Database, DatabaseCommit, DatabaseRef},
+    Account, AccountInfo, Address, Bytecode, HashMap, B256, U256,
+};
+use core::{fmt, ops::RangeInclusive};
+use std::vec::Vec;
+
+/// A [Database] that serves storage of the configured address ranges from a separate
+/// `namespace` database, and everything else from the primary `db`.
+///
+/// Account info, code and block hashes are always served by the primary database.
+///
+/// On [DatabaseCommit::commit] storage changes of namespaced accounts are committed to the
+/// namespace database only, while the primary database receives the same accounts with
+/// their storage stripped. Both databases observe account creation and selfdestruct, so
+/// storage clearing is applied consistently.
+#[derive(Clone, Debug, Default)]
+pub struct NamespacedDB<DB, NS> {
+    /// Primary database.
+    pub db: DB,
+    /// Database that holds storage of the namespaced addresses.
+    pub namespace: NS,
+    /// Address ranges whose storage is routed to the namespace database.
+    pub ranges: Vec<RangeInclusive<Address>>,
+}
+
+/// Error of the [NamespacedDB], tagged with the database that returned it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NamespacedDBError<E, NSE> {
+    Database(E),
+    Namespace(NSE),
+}
+
+impl<E: fmt::Display, NSE: fmt::Display> fmt::Display for NamespacedDBError<E, NSE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Database(e) => e.fmt(f),
+            Self::Namespace(e) => write!(f, "namespace: {e}"),
+        }
+    }
+}
+
+impl<DB, NS> NamespacedDB<DB, NS> {
+    /// Creates a new namespaced database without any namespaced addresses.
+    pub fn new(db: DB, namespace: NS) -> Self {
+        Self {
+            db,
+            namespace,
+            ranges: Vec::new(),
+        }
+    }
+
+    /// Routes storage of all addresses in `range` to the namespace database.
+    pub fn with_range(mut self, range: RangeInclusive<Address>) -> Self {
+        self.ranges.push(range);
+        self
+    }
+
+    /// Routes storage of `address` to the namespace database.
+    pub fn with_address(self, address: Address) -> Self {
+        self.with_range(address..=address)
+    }
+
+    /// Returns `true` if storage of `address` is served by the namespace database.
+    #[inline]
+    pub fn is_namespaced(&self, address: &Address) -> bool {
+        self.ranges.iter().any(|range| range.contains(address))
+    }
+}
+
+impl<DB: Database, NS: Database> Database for NamespacedDB<DB, NS> {
+    type Error = NamespacedDBError<DB::Error, NS::Error>;
+
+    #[inline]
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic(address).map_err(Self::Error::Database)
+    }
+
+    #[inline]
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db
+            .code_by_hash(code_hash)
+            .map_err(Self::Error::Database)
+    }
+
+    #[inline]
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if self.is_namespaced(&address) {
+            self.namespace
+                .storage(address, index)
+                .map_err(Self::Error::Namespace)
+        } else {
+            self.db
+                .storage(address, index)
+                .map_err(Self::Error::Database)
+        }
+    }
+
+    #[inline]
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.db.block_hash(number).map_err(Self::Error::Database)
+    }
+}
+
+impl<DB: DatabaseRef, NS: DatabaseRef> DatabaseRef for NamespacedDB<DB, NS> {
+    type Error = NamespacedDBError<DB::Error, NS::Error>;
+
+    #[inline]
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.db.basic_ref(address).map_err(Self::Error::Database)
+    }
+
+    #[inline]
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.db
+            .code_by_hash_ref(code_hash)
+            .map_err(Self::Error::Database)
+    }
+
+    #[inline]
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        if self.is_namespaced(&address) {
+            self.namespace
+                .storage_ref(address, index)
+                .map_err(Self::Error::Namespace)
+        } else {
+            self.db
+                .storage_ref(address, index)
+                .map_err(Self::Error::Database)
+        }
+    }
+
+    #[inline]
+    fn block_hash_ref(&self, number: u64) -> Result<B256, Self::Error> {
+        self.db
+            .block_hash_ref(number)
+            .map_err(Self::Error::Database)
+    }
+}
+
+impl<DB: DatabaseCommit, NS: DatabaseCommit> DatabaseCommit for NamespacedDB<DB, NS> {
+    fn commit(&mut self, mut changes: HashMap<Address, Account>) {
+        let mut namespaced = HashMap::new();
+        for (address, account) in changes.iter_mut() {
+            if self.is_namespaced(address) {
+                let mut stripped = account.clone();
+                stripped.storage.clear();
+                namespaced.insert(*address, core::mem::replace(account, stripped));
+            }
+        }
+        if !namespaced.is_empty() {
+            self.namespace.commit(namespaced);
+        }
+        self.db.commit(changes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NamespacedDB;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        primitives::{
+            db::{Database, DatabaseCommit},
+            Account, AccountStatus, Address, EvmStorageSlot, HashMap, U256,
+        },
+    };
+
+    #[test]
+    fn routes_storage_of_namespaced_addresses() {
+        let system = Address::with_last_byte(1);
+        let user = Address::with_last_byte(42);
+        let key = U256::from(7);
+
+        let mut db = NamespacedDB::new(
+            CacheDB::new(EmptyDB::default()),
+            CacheDB::new(EmptyDB::default()),
+        )
+        .with_range(Address::ZERO..=Address::with_last_byte(9));
+        assert!(db.is_namespaced(&system));
+        assert!(!db.is_namespaced(&user));
+
+        let mut changes = HashMap::new();
+        for (address, value) in [(system, U256::from(1)), (user, U256::from(2))] {
+            let mut account = Account {
+                status: AccountStatus::Touched,
+                ..Default::default()
+            };
+            account
+                .storage
+                .insert(key, EvmStorageSlot::new_changed(U256::ZERO, value));
+            changes.insert(address, account);
+        }
+        db.commit(changes);
+
+        assert_eq!(db.storage(system, key), Ok(U256::from(1)));
+        assert_eq!(db.storage(user, key), Ok(U256::from(2)));
+        assert_eq!(db.db.storage(system, key), Ok(U256::ZERO));
+        assert_eq!(db.namespace.storage(user, key), Ok(U256::ZERO));
+    }
+}
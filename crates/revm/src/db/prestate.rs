@@ -0,0 +1,405 @@
+//! Recording of the state read by transactions, like the `prestateTracer` of geth.
+//!
+//! The [Prestate] recorded by a [PrestateTracer] can be diffed with the state changed by the
+//! transaction with [Prestate::diff], like the `diffMode` of the tracer.
+
+use crate::{
+    db::{AccountState, CacheDB, CacheState, DbAccount},
+    primitives::{
+        db::Database, AccountInfo, Address, Bytecode, EvmState, HashMap, B256, KECCAK_EMPTY, U256,
+    },
+};
+use std::collections::BTreeMap;
+
+/// Account read by a transaction, as it was before the transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrestateAccount {
+    /// Balance of the account.
+    pub balance: U256,
+    /// Nonce of the account.
+    pub nonce: u64,
+    /// Hash of the code of the account.
+    pub code_hash: B256,
+    /// Code of the account, if it was read.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub code: Option<Bytecode>,
+    /// Storage slots read from the account.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl PrestateAccount {
+    /// Returns the account info, with the code if it was read.
+    pub fn info(&self) -> AccountInfo {
+        AccountInfo {
+            balance: self.balance,
+            nonce: self.nonce,
+            code_hash: self.code_hash,
+            code: self.code.clone(),
+        }
+    }
+}
+
+/// State read by transactions, sufficient to execute them again without the database they
+/// were executed on.
+///
+/// Accounts that did not exist are omitted, as are their storage slots.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Prestate {
+    /// Read accounts.
+    pub accounts: BTreeMap<Address, PrestateAccount>,
+    /// Read block hashes, by block number.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub block_hashes: BTreeMap<u64, B256>,
+}
+
+/// Account changed by a transaction, with the fields that were changed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PoststateAccount {
+    /// Balance of the account, if it was changed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub balance: Option<U256>,
+    /// Nonce of the account, if it was changed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub nonce: Option<u64>,
+    /// Code of the account, if it was changed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub code: Option<Bytecode>,
+    /// Changed storage slots with a non-zero value.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "BTreeMap::is_empty")
+    )]
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Changes made by a transaction, in the `diffMode` format of the `prestateTracer` of geth.
+///
+/// `pre` holds the changed accounts that existed before the transaction, with the previous
+/// values of their changed slots. `post` holds the accounts that exist after the
+/// transaction with their changed fields, and slots set to zero are omitted. Accounts that
+/// were destroyed are only in `pre`, and accounts that were created are only in `post`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDiff {
+    /// Changed accounts before the transaction.
+    pub pre: BTreeMap<Address, PrestateAccount>,
+    /// Changed accounts after the transaction.
+    pub post: BTreeMap<Address, PoststateAccount>,
+}
+
+impl Prestate {
+    /// Returns the changes of `state`, the state changed by a transaction, with the accounts
+    /// of the prestate as their previous values.
+    ///
+    /// The prestate should be recorded for that transaction only, see
+    /// [PrestateTracer::take_prestate].
+    pub fn diff(&self, state: &EvmState) -> StateDiff {
+        let mut diff = StateDiff::default();
+        for (address, account) in state {
+            if !account.is_touched() {
+                continue;
+            }
+            let pre = self.accounts.get(address);
+            let changed_slots: BTreeMap<_, _> = account
+                .changed_storage_slots()
+                .map(|(key, slot)| (*key, (slot.original_value(), slot.present_value())))
+                .collect();
+
+            if account.is_selfdestructed() {
+                if let Some(pre) = pre {
+                    diff.pre.insert(*address, pre.clone());
+                }
+                continue;
+            }
+
+            let (pre_balance, pre_nonce, pre_code_hash) = pre
+                .map_or((U256::ZERO, 0, KECCAK_EMPTY), |pre| {
+                    (pre.balance, pre.nonce, pre.code_hash)
+                });
+            let info = &account.info;
+            let post = PoststateAccount {
+                balance: (info.balance != pre_balance).then_some(info.balance),
+                nonce: (info.nonce != pre_nonce).then_some(info.nonce),
+                code: (info.code_hash != pre_code_hash)
+                    .then(|| info.code.clone())
+                    .flatten(),
+                storage: changed_slots
+                    .iter()
+                    .filter(|(_, (_, present))| !present.is_zero())
+                    .map(|(key, (_, present))| (*key, *present))
+                    .collect(),
+            };
+            if post == PoststateAccount::default() && changed_slots.is_empty() {
+                continue;
+            }
+            if let Some(pre) = pre {
+                diff.pre.insert(
+                    *address,
+                    PrestateAccount {
+                        storage: changed_slots
+                            .iter()
+                            .filter(|(_, (original, _))| !original.is_zero())
+                            .map(|(key, (original, _))| (*key, *original))
+                            .collect(),
+                        ..pre.clone()
+                    },
+                );
+            }
+            diff.post.insert(*address, post);
+        }
+        diff
+    }
+
+    /// Returns a cache state holding the accounts, their code and storage.
+    ///
+    /// Block hashes are not part of the cache state, see [Prestate::into_cache_db].
+    pub fn into_cache_state(self, has_state_clear: bool) -> CacheState {
+        let mut cache = CacheState::new(has_state_clear);
+        for (address, account) in self.accounts {
+            let mut info = account.info();
+            if let Some(code) = info.code.take() {
+                cache.contracts.insert(info.code_hash, code);
+            }
+            let storage = account.storage.into_iter().collect::<HashMap<_, _>>();
+            cache.insert_account_with_storage(address, info, storage);
+        }
+        cache
+    }
+
+    /// Returns a cache database holding the accounts, their code and storage and the block
+    /// hashes, in front of `db`.
+    pub fn into_cache_db<ExtDB>(self, db: ExtDB) -> CacheDB<ExtDB> {
+        let mut cache = CacheDB::new(db);
+        for (address, account) in self.accounts {
+            let mut info = account.info();
+            cache.insert_contract(&mut info);
+            info.code = None;
+            cache.accounts.insert(
+                address,
+                DbAccount {
+                    info,
+                    account_state: AccountState::None,
+                    storage: account.storage.into_iter().collect(),
+                },
+            );
+        }
+        cache.block_hashes.extend(
+            self.block_hashes
+                .into_iter()
+                .map(|(number, hash)| (U256::from(number), hash)),
+        );
+        cache
+    }
+}
+
+/// [Database] that records the accounts, code, storage slots and block hashes read from the
+/// wrapped database into a [Prestate].
+///
+/// Only the first read of a value is recorded, so the prestate holds the values from before
+/// the transactions even if the database is written to in between. Code is attached to the
+/// accounts it was read for.
+#[derive(Clone, Debug, Default)]
+pub struct PrestateTracer<DB> {
+    /// Wrapped database.
+    pub db: DB,
+    prestate: Prestate,
+}
+
+impl<DB> PrestateTracer<DB> {
+    /// Creates a tracer with an empty prestate.
+    pub fn new(db: DB) -> Self {
+        Self {
+            db,
+            prestate: Prestate::default(),
+        }
+    }
+
+    /// Returns the recorded prestate.
+    pub fn prestate(&self) -> &Prestate {
+        &self.prestate
+    }
+
+    /// Returns the recorded prestate and starts recording a new one.
+    pub fn take_prestate(&mut self) -> Prestate {
+        core::mem::take(&mut self.prestate)
+    }
+
+    /// Consumes the tracer and returns the wrapped database and the recorded prestate.
+    pub fn into_parts(self) -> (DB, Prestate) {
+        (self.db, self.prestate)
+    }
+}
+
+impl<DB: Database> Database for PrestateTracer<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let info = self.db.basic(address)?;
+        if let Some(info) = &info {
+            self.prestate
+                .accounts
+                .entry(address)
+                .or_insert_with(|| PrestateAccount {
+                    balance: info.balance,
+                    nonce: info.nonce,
+                    code_hash: info.code_hash,
+                    code: info.code.clone().filter(|_| info.code_hash != KECCAK_EMPTY),
+                    storage: BTreeMap::new(),
+                });
+        }
+        Ok(info)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.db.code_by_hash(code_hash)?;
+        for account in self.prestate.accounts.values_mut() {
+            if account.code_hash == code_hash && account.code.is_none() {
+                account.code = Some(code.clone());
+            }
+        }
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self.db.storage(address, index)?;
+        if let Some(account) = self.prestate.accounts.get_mut(&address) {
+            account.storage.entry(index).or_insert(value);
+        }
+        Ok(value)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.db.block_hash(number)?;
+        self.prestate.block_hashes.entry(number).or_insert(hash);
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{EmptyDB, State},
+        interpreter::opcode,
+        primitives::{address, Bytes, EthereumWiring, EvmState, TxKind},
+        Evm,
+    };
+
+    const CALLER: Address = address!("1000000000000000000000000000000000000000");
+    const CONTRACT: Address = address!("0000000000000000000000000000000000001000");
+
+    /// Calls the contract in block 1 and returns the database and the changed state.
+    fn transact<DB: Database>(db: DB) -> (DB, EvmState)
+    where
+        DB::Error: core::fmt::Debug,
+    {
+        let mut evm = Evm::<EthereumWiring<DB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_block_env(|block| block.number = U256::from(1))
+            .modify_tx_env(|tx| {
+                tx.caller = CALLER;
+                tx.transact_to = TxKind::Call(CONTRACT);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+        let state = evm.transact().unwrap().state;
+        (evm.into_context().evm.inner.db, state)
+    }
+
+    #[test]
+    fn prestate_reexecutes_transaction() {
+        // SSTORE(0, SLOAD(0) + 1), SSTORE(1, BLOCKHASH(0))
+        let code = Bytecode::new_raw(Bytes::from(
+            [
+                opcode::PUSH0,
+                opcode::SLOAD,
+                opcode::PUSH1,
+                0x01,
+                opcode::ADD,
+                opcode::PUSH0,
+                opcode::SSTORE,
+                opcode::PUSH0,
+                opcode::BLOCKHASH,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        ));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            CALLER,
+            AccountInfo::from_balance(U256::from(10).pow(U256::from(18))),
+        );
+        db.insert_account_info(CONTRACT, AccountInfo::from_bytecode(code.clone()));
+        db.insert_account_storage(CONTRACT, U256::ZERO, U256::from(41))
+            .unwrap();
+        db.block_hashes.insert(U256::ZERO, B256::with_last_byte(7));
+
+        let (tracer, state) = transact(PrestateTracer::new(db));
+        let (_, prestate) = tracer.into_parts();
+        assert_eq!(prestate.block_hashes, [(0, B256::with_last_byte(7))].into());
+        let account = &prestate.accounts[&CONTRACT];
+        assert_eq!(account.code, Some(code));
+        assert_eq!(
+            account.storage,
+            [(U256::ZERO, U256::from(41)), (U256::from(1), U256::ZERO)].into()
+        );
+        assert!(prestate.accounts.contains_key(&CALLER));
+
+        // Executing on the prestate alone gives the same changes.
+        let (_, replayed) = transact(prestate.clone().into_cache_db(EmptyDB::default()));
+        assert_eq!(replayed, state);
+        // Only the caller nonce and the slots of the contract are changed.
+        let diff = prestate.diff(&state);
+        assert_eq!(diff.pre.keys().collect::<Vec<_>>(), [&CONTRACT, &CALLER]);
+        assert_eq!(diff.pre[&CALLER].nonce, 0);
+        assert_eq!(
+            diff.pre[&CONTRACT].storage,
+            [(U256::ZERO, U256::from(41))].into()
+        );
+        assert_eq!(
+            diff.post[&CALLER],
+            PoststateAccount {
+                nonce: Some(1),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            diff.post[&CONTRACT].storage,
+            [(U256::ZERO, U256::from(42)), (U256::from(1), U256::from(7))].into()
+        );
+        assert_eq!(diff.post.len(), 2);
+
+        let block_hashes = prestate.block_hashes.clone();
+        let cache = prestate.into_cache_state(true);
+        let mut state_db = State::builder().with_cached_prestate(cache).build();
+        state_db.block_hashes = block_hashes;
+        let (_, replayed) = transact(state_db);
+        assert_eq!(replayed, state);
+    }
+}
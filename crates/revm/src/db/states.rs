@@ -15,7 +15,7 @@ pub mod transition_state;
 pub use account_status::AccountStatus;
 pub use bundle_account::BundleAccount;
 pub use bundle_state::{BundleBuilder, BundleState, OriginalValuesKnown};
-pub use cache::CacheState;
+pub use cache::{CacheBounds, CacheMetrics, CacheState};
 pub use cache_account::CacheAccount;
 pub use changes::{PlainStateReverts, PlainStorageChangeset, PlainStorageRevert, StateChangeset};
 pub use plain_account::{PlainAccount, StorageSlot, StorageWithOriginalValues};
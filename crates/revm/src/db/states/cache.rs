@@ -6,6 +6,45 @@ use revm_interpreter::primitives::{
 };
 use std::vec::Vec;
 
+/// Optional size bounds of the [CacheState].
+///
+/// When a bound is exceeded, [CacheState::evict] removes the least recently used entries
+/// that were only loaded from the database. Changed accounts, their storage and contracts
+/// that were not loaded from the database are never evicted, so the bounds can be
+/// exceeded if the cache holds too many of them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheBounds {
+    /// Maximum number of accounts.
+    pub max_accounts: Option<usize>,
+    /// Maximum number of storage slots, summed over all accounts.
+    pub max_storage_slots: Option<usize>,
+    /// Maximum number of contracts.
+    pub max_contracts: Option<usize>,
+}
+
+impl CacheBounds {
+    /// Returns whether any bound is set.
+    pub fn is_bounded(&self) -> bool {
+        self.max_accounts.is_some()
+            || self.max_storage_slots.is_some()
+            || self.max_contracts.is_some()
+    }
+}
+
+/// Hit and miss counts of the [CacheState], and the number of evicted entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    pub account_hits: u64,
+    pub account_misses: u64,
+    pub storage_hits: u64,
+    pub storage_misses: u64,
+    pub contract_hits: u64,
+    pub contract_misses: u64,
+    pub evicted_accounts: u64,
+    pub evicted_storage_slots: u64,
+    pub evicted_contracts: u64,
+}
+
 /// Cache state contains both modified and original values.
 ///
 /// Cache state is main state that revm uses to access state.
@@ -21,6 +60,17 @@ pub struct CacheState {
     pub contracts: HashMap<B256, Bytecode>,
     /// Has EIP-161 state clear enabled (Spurious Dragon hardfork).
     pub has_state_clear: bool,
+    /// Size bounds enforced by [CacheState::evict].
+    pub bounds: CacheBounds,
+    /// Hit and miss counts of the cache.
+    pub metrics: CacheMetrics,
+    /// Last use of the accounts, only tracked if the cache is bounded.
+    account_uses: HashMap<Address, u64>,
+    /// Last use of the contracts loaded from the database, only tracked if the cache is
+    /// bounded. Other contracts are never evicted.
+    contract_uses: HashMap<B256, u64>,
+    /// Counter used to order the uses.
+    clock: u64,
 }
 
 impl Default for CacheState {
@@ -36,9 +86,110 @@ impl CacheState {
             accounts: HashMap::default(),
             contracts: HashMap::default(),
             has_state_clear,
+            bounds: CacheBounds::default(),
+            metrics: CacheMetrics::default(),
+            account_uses: HashMap::default(),
+            contract_uses: HashMap::default(),
+            clock: 0,
         }
     }
 
+    /// Sets the size bounds of the cache.
+    pub fn set_bounds(&mut self, bounds: CacheBounds) {
+        self.bounds = bounds;
+    }
+
+    /// Records a use of the account for the eviction order.
+    #[inline]
+    pub(crate) fn touch_account(&mut self, address: Address) {
+        if self.bounds.is_bounded() {
+            self.clock += 1;
+            self.account_uses.insert(address, self.clock);
+        }
+    }
+
+    /// Records a use of the contract for the eviction order, `loaded` if it was just loaded
+    /// from the database. Only the uses of loaded contracts are recorded.
+    #[inline]
+    pub(crate) fn touch_contract(&mut self, code_hash: B256, loaded: bool) {
+        if self.bounds.is_bounded() && (loaded || self.contract_uses.contains_key(&code_hash)) {
+            self.clock += 1;
+            self.contract_uses.insert(code_hash, self.clock);
+        }
+    }
+
+    /// Returns the number of cached storage slots, summed over all accounts.
+    pub fn storage_slots(&self) -> usize {
+        self.accounts
+            .values()
+            .filter_map(|account| account.account.as_ref())
+            .map(|account| account.storage.len())
+            .sum()
+    }
+
+    /// Evicts the least recently used entries that were only loaded from the database until
+    /// the cache is within its [CacheBounds].
+    ///
+    /// Storage bounds are enforced by dropping the cached storage of unchanged accounts.
+    ///
+    /// This must not be called while an execution is in progress, as the accounts of its
+    /// state are expected to be inside the cache when the state is committed.
+    pub fn evict(&mut self) {
+        let bounds = self.bounds;
+        if let Some(max_accounts) = bounds.max_accounts {
+            let excess = self.accounts.len().saturating_sub(max_accounts);
+            for address in self.clean_accounts_by_use().into_iter().take(excess) {
+                self.accounts.remove(&address);
+                self.account_uses.remove(&address);
+                self.metrics.evicted_accounts += 1;
+            }
+        }
+        if let Some(max_storage_slots) = bounds.max_storage_slots {
+            let mut slots = self.storage_slots();
+            for address in self.clean_accounts_by_use() {
+                if slots <= max_storage_slots {
+                    break;
+                }
+                if let Some(account) = self
+                    .accounts
+                    .get_mut(&address)
+                    .and_then(|account| account.account.as_mut())
+                {
+                    let evicted = account.storage.len();
+                    account.storage.clear();
+                    slots -= evicted;
+                    self.metrics.evicted_storage_slots += evicted as u64;
+                }
+            }
+        }
+        if let Some(max_contracts) = bounds.max_contracts {
+            let excess = self.contracts.len().saturating_sub(max_contracts);
+            let mut loaded: Vec<_> = self.contract_uses.iter().map(|(k, v)| (*v, *k)).collect();
+            loaded.sort_unstable();
+            for (_, code_hash) in loaded.into_iter().take(excess) {
+                self.contracts.remove(&code_hash);
+                self.contract_uses.remove(&code_hash);
+                self.metrics.evicted_contracts += 1;
+            }
+        }
+    }
+
+    /// Returns the accounts that are unchanged since they were loaded, least recently used
+    /// first.
+    fn clean_accounts_by_use(&self) -> Vec<Address> {
+        let mut clean: Vec<_> = self
+            .accounts
+            .iter()
+            .filter(|(_, account)| account.status.is_not_modified())
+            .map(|(address, _)| {
+                let last_use = self.account_uses.get(address).copied().unwrap_or_default();
+                (last_use, *address)
+            })
+            .collect();
+        clean.sort_unstable();
+        clean.into_iter().map(|(_, address)| address).collect()
+    }
+
     /// Set state clear flag. EIP-161.
     pub fn set_state_clear_flag(&mut self, has_state_clear: bool) {
         self.has_state_clear = has_state_clear;
@@ -3,9 +3,13 @@ use super::{
     CacheAccount, StateBuilder, TransitionAccount, TransitionState,
 };
 use crate::db::EmptyDB;
-use revm_interpreter::primitives::{
-    db::{Database, DatabaseCommit},
-    hash_map, Account, AccountInfo, Address, Bytecode, HashMap, B256, BLOCK_HASH_HISTORY, U256,
+use revm_interpreter::{
+    analysis::to_analysed,
+    primitives::{
+        db::{Database, DatabaseCommit},
+        hash_map, Account, AccountInfo, Address, Bytecode, HashMap, HashSet, B256,
+        BLOCK_HASH_HISTORY, U256,
+    },
 };
 use std::{
     boxed::Box,
@@ -160,6 +164,22 @@ impl<DB: Database> State<DB> {
         }
     }
 
+    /// Applies the output of a transaction like [DatabaseCommit::commit], and returns the
+    /// transitions of the changed accounts.
+    ///
+    /// Unlike the cache, that only keeps the present values, the storage of the transitions
+    /// holds both the value of every changed slot before the transaction and its present
+    /// value, which is needed to build state diffs or audit gas refunds after the commit.
+    pub fn commit_with_transitions(
+        &mut self,
+        evm_state: HashMap<Address, Account>,
+    ) -> Vec<(Address, TransitionAccount)> {
+        let transitions = self.cache.apply_evm_state(evm_state);
+        self.apply_transition(transitions.clone());
+        self.cache.evict();
+        transitions
+    }
+
     /// Take all transitions and merge them inside bundle state.
     /// This action will create final post state and all reverts so that
     /// we at any time revert state of bundle to the state before transition
@@ -175,8 +195,10 @@ impl<DB: Database> State<DB> {
     /// If the account is not found in the cache, it will be loaded from the
     /// database and inserted into the cache.
     pub fn load_cache_account(&mut self, address: Address) -> Result<&mut CacheAccount, DB::Error> {
+        self.cache.touch_account(address);
         match self.cache.accounts.entry(address) {
             hash_map::Entry::Vacant(entry) => {
+                self.cache.metrics.account_misses += 1;
                 if self.use_preloaded_bundle {
                     // load account from bundle state
                     if let Some(account) =
@@ -196,10 +218,61 @@ impl<DB: Database> State<DB> {
                 };
                 Ok(entry.insert(account))
             }
-            hash_map::Entry::Occupied(entry) => Ok(entry.into_mut()),
+            hash_map::Entry::Occupied(entry) => {
+                self.cache.metrics.account_hits += 1;
+                Ok(entry.into_mut())
+            }
         }
     }
 
+    /// Evicts unchanged entries of the cache until it is within its bounds, see
+    /// [CacheState::evict].
+    ///
+    /// This is done automatically when the state is committed.
+    pub fn evict_cache(&mut self) {
+        self.cache.evict();
+    }
+
+    /// Reverts the state changes of the latest `num_blocks` blocks merged into the bundle,
+    /// for example to handle a reorg. Returns the number of reverted blocks, which is lower
+    /// than `num_blocks` if the bundle does not contain enough reverts.
+    ///
+    /// Reverts are only recorded if transitions are merged with [BundleRetention::Reverts].
+    /// Pending transitions that were not merged are discarded.
+    ///
+    /// The cached accounts changed by the discarded transitions or reverted blocks are
+    /// replaced by their reverted bundle account, or removed from the cache if the bundle
+    /// no longer contains them, so that they are loaded again from the database.
+    pub fn revert_blocks(&mut self, num_blocks: usize) -> usize {
+        let mut addresses: HashSet<Address> = self
+            .transition_state
+            .as_mut()
+            .map(|transition_state| transition_state.take().transitions.into_keys().collect())
+            .unwrap_or_default();
+
+        let reverts = &self.bundle_state.reverts;
+        let num_blocks = num_blocks.min(reverts.len());
+        addresses.extend(
+            reverts[reverts.len() - num_blocks..]
+                .iter()
+                .flatten()
+                .map(|(address, _)| *address),
+        );
+        self.bundle_state.revert(num_blocks);
+
+        for address in addresses {
+            match self.bundle_state.account(&address) {
+                Some(account) => {
+                    self.cache.accounts.insert(address, account.clone().into());
+                }
+                None => {
+                    self.cache.accounts.remove(&address);
+                }
+            }
+        }
+        num_blocks
+    }
+
     // TODO make cache aware of transitions dropping by having global transition counter.
     /// Takes the [`BundleState`] changeset from the [`State`], replacing it
     /// with an empty one.
@@ -224,17 +297,27 @@ impl<DB: Database> Database for State<DB> {
 
     fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
         let res = match self.cache.contracts.entry(code_hash) {
-            hash_map::Entry::Occupied(entry) => Ok(entry.get().clone()),
+            hash_map::Entry::Occupied(entry) => {
+                self.cache.metrics.contract_hits += 1;
+                let code = entry.get().clone();
+                self.cache.touch_contract(code_hash, false);
+                Ok(code)
+            }
             hash_map::Entry::Vacant(entry) => {
+                self.cache.metrics.contract_misses += 1;
                 if self.use_preloaded_bundle {
                     if let Some(code) = self.bundle_state.contracts.get(&code_hash) {
                         entry.insert(code.clone());
+                        self.cache.touch_contract(code_hash, true);
                         return Ok(code.clone());
                     }
                 }
-                // if not found in bundle ask database
-                let code = self.database.code_by_hash(code_hash)?;
+                // if not found in bundle ask database. The code is analysed before it is
+                // cached, so the transactions loading it share the padded bytes and jump
+                // table instead of each analysing its own copy.
+                let code = to_analysed(self.database.code_by_hash(code_hash)?);
                 entry.insert(code.clone());
+                self.cache.touch_contract(code_hash, true);
                 Ok(code)
             }
         };
@@ -244,6 +327,7 @@ impl<DB: Database> Database for State<DB> {
     fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
         // Account is guaranteed to be loaded.
         // Note that storage from bundle is already loaded with account.
+        self.cache.touch_account(address);
         if let Some(account) = self.cache.accounts.get_mut(&address) {
             // account will always be some, but if it is not, U256::ZERO will be returned.
             let is_storage_known = account.status.is_storage_known();
@@ -251,8 +335,12 @@ impl<DB: Database> Database for State<DB> {
                 .account
                 .as_mut()
                 .map(|account| match account.storage.entry(index) {
-                    hash_map::Entry::Occupied(entry) => Ok(*entry.get()),
+                    hash_map::Entry::Occupied(entry) => {
+                        self.cache.metrics.storage_hits += 1;
+                        Ok(*entry.get())
+                    }
                     hash_map::Entry::Vacant(entry) => {
+                        self.cache.metrics.storage_misses += 1;
                         // if account was destroyed or account is newly built
                         // we return zero and don't ask database.
                         let value = if is_storage_known {
@@ -297,6 +385,7 @@ impl<DB: Database> DatabaseCommit for State<DB> {
     fn commit(&mut self, evm_state: HashMap<Address, Account>) {
         let transitions = self.cache.apply_evm_state(evm_state);
         self.apply_transition(transitions);
+        self.cache.evict();
     }
 }
 
@@ -792,4 +881,200 @@ mod tests {
             )])])
         )
     }
+
+    #[test]
+    fn bounded_cache_evicts_unchanged_entries() {
+        use crate::db::{CacheBounds, CacheDB, EmptyDB};
+        use revm_interpreter::primitives::{address, Bytecode};
+
+        let (a1, a2, a3) = (
+            address!("0000000000000000000000000000000000000001"),
+            address!("0000000000000000000000000000000000000002"),
+            address!("0000000000000000000000000000000000000003"),
+        );
+        let code = Bytecode::new_raw([0x00].into());
+        let code_hash = code.hash_slow();
+        let mut db = CacheDB::new(EmptyDB::default());
+        for address in [a1, a2, a3] {
+            db.insert_account_info(
+                address,
+                AccountInfo::new(U256::from(1), 0, code_hash, code.clone()),
+            );
+            db.insert_account_storage(address, U256::from(1), U256::from(7))
+                .unwrap();
+        }
+        let mut state = State::builder()
+            .with_database(db)
+            .with_cache_bounds(CacheBounds {
+                max_accounts: Some(2),
+                max_storage_slots: Some(1),
+                max_contracts: Some(0),
+            })
+            .build();
+
+        let info = state.basic(a1).unwrap().unwrap();
+        state.basic(a2).unwrap();
+        state.basic(a1).unwrap();
+        state.storage(a1, U256::from(1)).unwrap();
+        state.storage(a1, U256::from(1)).unwrap();
+        state.storage(a2, U256::from(1)).unwrap();
+        state.basic(a3).unwrap();
+        state.storage(a3, U256::from(1)).unwrap();
+        assert_eq!(
+            state.code_by_hash(code_hash).unwrap().original_bytes(),
+            code.original_bytes()
+        );
+        assert_eq!(
+            state.code_by_hash(code_hash).unwrap().original_bytes(),
+            code.original_bytes()
+        );
+
+        // a2 is changed and can not be evicted anymore.
+        let mut account = Account::from(AccountInfo {
+            balance: U256::from(2),
+            ..info
+        });
+        account.mark_touch();
+        state.commit(HashMap::from([(a2, account)]));
+
+        // a1 is the least recently used unchanged account, and the storage of a3 is the only
+        // unchanged storage left.
+        assert_eq!(state.cache.accounts.len(), 2);
+        assert!(!state.cache.accounts.contains_key(&a1));
+        let storage_len = |address| {
+            state.cache.accounts[&address]
+                .account
+                .as_ref()
+                .unwrap()
+                .storage
+                .len()
+        };
+        assert_eq!(storage_len(a2), 1);
+        assert_eq!(storage_len(a3), 0);
+        assert!(state.cache.contracts.is_empty());
+
+        let metrics = state.cache.metrics;
+        assert_eq!((metrics.account_hits, metrics.account_misses), (1, 3));
+        assert_eq!((metrics.storage_hits, metrics.storage_misses), (1, 3));
+        assert_eq!((metrics.contract_hits, metrics.contract_misses), (1, 1));
+        assert_eq!(
+            (
+                metrics.evicted_accounts,
+                metrics.evicted_storage_slots,
+                metrics.evicted_contracts
+            ),
+            (1, 1, 1)
+        );
+
+        // Evicted entries are loaded again from the database.
+        assert_eq!(state.storage(a3, U256::from(1)).unwrap(), U256::from(7));
+        assert_eq!(state.basic(a1).unwrap().unwrap().balance, U256::from(1));
+    }
+
+    #[test]
+    fn revert_blocks() {
+        use crate::db::{CacheDB, EmptyDB};
+
+        let address = Address::with_last_byte(1);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            address,
+            AccountInfo {
+                balance: U256::from(1),
+                ..Default::default()
+            },
+        );
+        let mut state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+
+        let set_balance = |state: &mut State<_>, balance: u64| {
+            let mut info = state.basic(address).unwrap().unwrap();
+            info.balance = U256::from(balance);
+            let mut account = Account::from(info);
+            account.mark_touch();
+            state.commit(HashMap::from([(address, account)]));
+        };
+        let balance = |state: &mut State<_>| state.basic(address).unwrap().unwrap().balance;
+
+        set_balance(&mut state, 2);
+        state.merge_transitions(BundleRetention::Reverts);
+        set_balance(&mut state, 3);
+        state.merge_transitions(BundleRetention::Reverts);
+
+        assert_eq!(state.revert_blocks(1), 1);
+        assert_eq!(balance(&mut state), U256::from(2));
+        assert_eq!(state.bundle_state.reverts.len(), 1);
+
+        // Pending transitions are discarded as well.
+        set_balance(&mut state, 4);
+        assert_eq!(state.revert_blocks(2), 1);
+        assert_eq!(balance(&mut state), U256::from(1));
+        assert!(state.bundle_state.reverts.is_empty());
+        assert!(state.transition_state.unwrap().transitions.is_empty());
+    }
+
+    #[test]
+    fn commit_with_transitions_keeps_original_values() {
+        use crate::db::{CacheDB, EmptyDB};
+        use revm_interpreter::primitives::EvmStorageSlot;
+
+        let address = Address::with_last_byte(1);
+        let slot = U256::from(1);
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(address, AccountInfo::from_balance(U256::from(1)));
+        db.insert_account_storage(address, slot, U256::from(7))
+            .unwrap();
+        let mut state = State::builder()
+            .with_database(db)
+            .with_bundle_update()
+            .build();
+
+        let commit = |state: &mut State<_>, original: u64, present: u64| {
+            let info = state.basic(address).unwrap().unwrap();
+            assert_eq!(state.storage(address, slot).unwrap(), U256::from(original));
+            let mut account = Account::from(info);
+            account.mark_touch();
+            account.storage.insert(
+                slot,
+                EvmStorageSlot::new_changed(U256::from(original), U256::from(present)),
+            );
+            state.commit_with_transitions(HashMap::from([(address, account)]))
+        };
+
+        commit(&mut state, 7, 8);
+        let transitions = commit(&mut state, 8, 9);
+        assert_eq!(transitions.len(), 1);
+        assert_eq!(
+            transitions[0].1.storage,
+            HashMap::from([(slot, StorageSlot::new_changed(U256::from(8), U256::from(9)))])
+        );
+
+        // The transition of the block keeps the value from before the block.
+        assert_eq!(
+            state.transition_state.unwrap().transitions[&address].storage[&slot],
+            StorageSlot::new_changed(U256::from(7), U256::from(9))
+        );
+    }
+
+    #[test]
+    fn loaded_code_is_analysed_once() {
+        use crate::db::{CacheDB, EmptyDB};
+
+        let code = Bytecode::new_raw([0x60, 0x00, 0x56].into());
+        let code_hash = code.hash_slow();
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(Address::with_last_byte(1), AccountInfo::from_bytecode(code));
+        let mut state = State::builder().with_database(db).build();
+
+        let first = state.code_by_hash(code_hash).unwrap();
+        let second = state.code_by_hash(code_hash).unwrap();
+        let (Bytecode::LegacyAnalyzed(first), Bytecode::LegacyAnalyzed(second)) = (first, second)
+        else {
+            panic!("expected analysed bytecode");
+        };
+        assert_eq!(first.bytecode().as_ptr(), second.bytecode().as_ptr());
+        assert_eq!(first.original_byte_slice(), [0x60, 0x00, 0x56]);
+    }
 }
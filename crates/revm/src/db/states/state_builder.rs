@@ -1,4 +1,8 @@
-use super::{cache::CacheState, state::DBBox, BundleState, State, TransitionState};
+use super::{
+    cache::{CacheBounds, CacheState},
+    state::DBBox,
+    BundleState, State, TransitionState,
+};
 use crate::db::EmptyDB;
 use revm_interpreter::primitives::{
     db::{Database, DatabaseRef, WrapDatabaseRef},
@@ -7,6 +11,12 @@ use revm_interpreter::primitives::{
 use std::collections::BTreeMap;
 
 /// Allows building of State and initializing it with different options.
+///
+/// The built [State] stacks three layers: the cache, that holds the loaded and changed
+/// accounts, the optional [BundleState], that accumulates the changes and reverts of the
+/// executed blocks, and the underlying database. With
+/// [`with_bundle_update`](Self::with_bundle_update), blocks can be taken with
+/// [State::take_bundle] to be persisted, or reverted with [State::revert_blocks].
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct StateBuilder<DB> {
     /// Database that we use to fetch data from.
@@ -28,6 +38,8 @@ pub struct StateBuilder<DB> {
     with_background_transition_merge: bool,
     /// If we want to set different block hashes
     with_block_hashes: BTreeMap<u64, B256>,
+    /// Size bounds of the cache. Default is unbounded.
+    with_cache_bounds: CacheBounds,
 }
 
 impl StateBuilder<EmptyDB> {
@@ -57,6 +69,7 @@ impl<DB: Database> StateBuilder<DB> {
             with_bundle_update: false,
             with_background_transition_merge: false,
             with_block_hashes: BTreeMap::new(),
+            with_cache_bounds: CacheBounds::default(),
         }
     }
 
@@ -72,6 +85,7 @@ impl<DB: Database> StateBuilder<DB> {
             with_bundle_update: self.with_bundle_update,
             with_background_transition_merge: self.with_background_transition_merge,
             with_block_hashes: self.with_block_hashes,
+            with_cache_bounds: self.with_cache_bounds,
         }
     }
 
@@ -150,6 +164,17 @@ impl<DB: Database> StateBuilder<DB> {
         }
     }
 
+    /// Bounds the size of the cache, see [CacheBounds].
+    ///
+    /// If set, it overrides the bounds of the cache set by
+    /// [`with_cached_prestate`](Self::with_cached_prestate).
+    pub fn with_cache_bounds(self, bounds: CacheBounds) -> Self {
+        Self {
+            with_cache_bounds: bounds,
+            ..self
+        }
+    }
+
     pub fn build(mut self) -> State<DB> {
         let use_preloaded_bundle = if self.with_cache_prestate.is_some() {
             self.with_bundle_prestate = None;
@@ -157,10 +182,14 @@ impl<DB: Database> StateBuilder<DB> {
         } else {
             self.with_bundle_prestate.is_some()
         };
+        let mut cache = self
+            .with_cache_prestate
+            .unwrap_or_else(|| CacheState::new(self.with_state_clear));
+        if self.with_cache_bounds.is_bounded() {
+            cache.set_bounds(self.with_cache_bounds);
+        }
         State {
-            cache: self
-                .with_cache_prestate
-                .unwrap_or_else(|| CacheState::new(self.with_state_clear)),
+            cache,
             database: self.database,
             transition_state: self.with_bundle_update.then(TransitionState::default),
             bundle_state: self.with_bundle_prestate.unwrap_or_default(),
@@ -0,0 +1,323 @@
+//! Differential execution against a reference EVM.
+//!
+//! [run_differential] executes a transaction with revm and with a [ReferenceEvm], and reports
+//! the first [Divergence] in the outcome, gas, output, logs or state. Chains that modify the
+//! handler can plug in an unmodified implementation, for example evmone through FFI or
+//! geth's `t8n` tool through a subprocess, to validate their changes.
+
+use crate::{
+    db::{CacheDB, InMemoryDB},
+    primitives::{
+        AccountInfo, Address, BlockEnv, Bytes, EVMError, Env, EthereumWiring, EvmState,
+        ExecutionResult, HaltReason, InvalidTransaction, Log, ResultAndState, SpecId, TxEnv, U256,
+    },
+    Evm,
+};
+use core::convert::Infallible;
+use std::{
+    boxed::Box,
+    collections::{BTreeMap, BTreeSet},
+};
+
+/// EVM implementation that revm is compared with.
+pub trait ReferenceEvm {
+    /// Error of the reference implementation.
+    type Error;
+
+    /// Executes the transaction of `env` with the rules of `spec_id` on top of `db`.
+    ///
+    /// Returns the result and the changed state in the format of revm, only the accounts
+    /// touched by the transaction and their changed storage slots are compared.
+    fn transact(
+        &mut self,
+        db: &InMemoryDB,
+        env: &Env<BlockEnv, TxEnv>,
+        spec_id: SpecId,
+    ) -> Result<ResultAndState<HaltReason>, Self::Error>;
+}
+
+/// First difference between the executions of revm and of the reference.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Divergence {
+    /// The executions ended differently, for example one succeeded and the other halted.
+    Outcome {
+        revm: ExecutionResult<HaltReason>,
+        reference: ExecutionResult<HaltReason>,
+    },
+    /// The executions used a different amount of gas.
+    GasUsed { revm: u64, reference: u64 },
+    /// The executions returned different data.
+    Output { revm: Bytes, reference: Bytes },
+    /// The log at `index` differs, `None` if the execution emitted fewer logs.
+    Log {
+        index: usize,
+        revm: Option<Log>,
+        reference: Option<Log>,
+    },
+    /// The account at `address` differs, `None` if the account does not exist.
+    Account {
+        address: Address,
+        revm: Option<AccountInfo>,
+        reference: Option<AccountInfo>,
+    },
+    /// The storage slot `slot` of `address` differs, `None` if the slot was not changed.
+    Storage {
+        address: Address,
+        slot: U256,
+        revm: Option<U256>,
+        reference: Option<U256>,
+    },
+}
+
+/// Error of a differential execution.
+#[derive(Debug)]
+pub enum DifferentialError<E> {
+    /// revm failed to execute the transaction.
+    Revm(EVMError<Infallible, InvalidTransaction>),
+    /// The reference failed to execute the transaction.
+    Reference(E),
+}
+
+/// Executes the transaction of `env` with revm and with `reference`, and returns the first
+/// divergence between them, if any.
+///
+/// The configuration of `env` is used by revm only, the reference is expected to follow the
+/// same rules.
+pub fn run_differential<R: ReferenceEvm>(
+    reference: &mut R,
+    db: &InMemoryDB,
+    env: &Env<BlockEnv, TxEnv>,
+    spec_id: SpecId,
+) -> Result<Option<Divergence>, DifferentialError<R::Error>> {
+    let revm = Evm::<EthereumWiring<CacheDB<&InMemoryDB>, ()>>::builder()
+        .with_db(CacheDB::new(db))
+        .with_default_ext_ctx()
+        .with_env(Box::new(env.clone()))
+        .with_spec_id(spec_id)
+        .build()
+        .transact()
+        .map_err(|error| DifferentialError::Revm(error.map_db_err(|error| match error {})))?;
+    let reference = reference
+        .transact(db, env, spec_id)
+        .map_err(DifferentialError::Reference)?;
+    Ok(compare(&revm, &reference, spec_id))
+}
+
+/// Returns the first divergence between two executions of the same transaction.
+pub fn compare(
+    revm: &ResultAndState<HaltReason>,
+    reference: &ResultAndState<HaltReason>,
+    spec_id: SpecId,
+) -> Option<Divergence> {
+    let same_outcome = match (&revm.result, &reference.result) {
+        (
+            ExecutionResult::Success { reason: a, .. },
+            ExecutionResult::Success { reason: b, .. },
+        ) => a == b,
+        (ExecutionResult::Revert { .. }, ExecutionResult::Revert { .. }) => true,
+        (ExecutionResult::Halt { reason: a, .. }, ExecutionResult::Halt { reason: b, .. }) => {
+            a == b
+        }
+        _ => false,
+    };
+    if !same_outcome {
+        return Some(Divergence::Outcome {
+            revm: revm.result.clone(),
+            reference: reference.result.clone(),
+        });
+    }
+    if revm.result.gas_used() != reference.result.gas_used() {
+        return Some(Divergence::GasUsed {
+            revm: revm.result.gas_used(),
+            reference: reference.result.gas_used(),
+        });
+    }
+    if revm.result.output() != reference.result.output() {
+        return Some(Divergence::Output {
+            revm: revm.result.output().cloned().unwrap_or_default(),
+            reference: reference.result.output().cloned().unwrap_or_default(),
+        });
+    }
+
+    let (revm_logs, reference_logs) = (revm.result.logs(), reference.result.logs());
+    for index in 0..revm_logs.len().max(reference_logs.len()) {
+        let (a, b) = (revm_logs.get(index), reference_logs.get(index));
+        if a != b {
+            return Some(Divergence::Log {
+                index,
+                revm: a.cloned(),
+                reference: b.cloned(),
+            });
+        }
+    }
+
+    let revm_state = post_state(&revm.state, spec_id);
+    let mut reference_state = post_state(&reference.state, spec_id);
+    for (address, revm_account) in revm_state {
+        let reference_account = reference_state.remove(&address).flatten();
+        if let Some(divergence) = compare_account(address, revm_account, reference_account) {
+            return Some(divergence);
+        }
+    }
+    reference_state
+        .into_iter()
+        .find_map(|(address, account)| compare_account(address, None, account))
+}
+
+/// Account after the transaction, with its changed storage slots.
+type PostAccount = (AccountInfo, BTreeMap<U256, U256>);
+
+/// Returns the touched accounts of `state`, `None` for the ones that were destroyed.
+fn post_state(state: &EvmState, spec_id: SpecId) -> BTreeMap<Address, Option<PostAccount>> {
+    state
+        .iter()
+        .filter(|(_, account)| account.is_touched())
+        .map(|(address, account)| {
+            let destroyed = account.is_selfdestructed()
+                || (account.is_empty() && spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON));
+            let account = (!destroyed).then(|| {
+                let storage = account
+                    .changed_storage_slots()
+                    .map(|(slot, value)| (*slot, value.present_value))
+                    .collect();
+                (account.info.clone().without_code(), storage)
+            });
+            (*address, account)
+        })
+        .collect()
+}
+
+fn compare_account(
+    address: Address,
+    revm: Option<PostAccount>,
+    reference: Option<PostAccount>,
+) -> Option<Divergence> {
+    let (revm_info, revm_storage) = revm.unzip();
+    let (reference_info, reference_storage) = reference.unzip();
+    if revm_info != reference_info {
+        return Some(Divergence::Account {
+            address,
+            revm: revm_info,
+            reference: reference_info,
+        });
+    }
+    let (mut revm_storage, mut reference_storage) = (
+        revm_storage.unwrap_or_default(),
+        reference_storage.unwrap_or_default(),
+    );
+    let slots: BTreeSet<U256> = revm_storage
+        .keys()
+        .chain(reference_storage.keys())
+        .copied()
+        .collect();
+    slots.into_iter().find_map(|slot| {
+        let (a, b) = (revm_storage.remove(&slot), reference_storage.remove(&slot));
+        (a != b).then_some(Divergence::Storage {
+            address,
+            slot,
+            revm: a,
+            reference: b,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        interpreter::opcode,
+        primitives::{address, Bytecode, HaltReason, OutOfGasError, TxKind},
+    };
+
+    /// revm itself, executing with the rules of another spec.
+    struct OtherSpec(SpecId);
+
+    impl ReferenceEvm for OtherSpec {
+        type Error = EVMError<Infallible, InvalidTransaction>;
+
+        fn transact(
+            &mut self,
+            db: &InMemoryDB,
+            env: &Env<BlockEnv, TxEnv>,
+            _spec_id: SpecId,
+        ) -> Result<ResultAndState<HaltReason>, Self::Error> {
+            Evm::<EthereumWiring<CacheDB<&InMemoryDB>, ()>>::builder()
+                .with_db(CacheDB::new(db))
+                .with_default_ext_ctx()
+                .with_env(Box::new(env.clone()))
+                .with_spec_id(self.0)
+                .build()
+                .transact()
+                .map_err(|error| error.map_db_err(|error| match error {}))
+        }
+    }
+
+    fn setup(code: &[u8]) -> (InMemoryDB, Env<BlockEnv, TxEnv>) {
+        let contract = address!("0000000000000000000000000000000000001000");
+        let mut db = InMemoryDB::default();
+        let code = Bytecode::new_raw(code.to_vec().into());
+        db.insert_account_info(
+            contract,
+            AccountInfo::new(U256::ZERO, 0, code.hash_slow(), code),
+        );
+        let mut env = Env::<BlockEnv, TxEnv>::default();
+        env.tx.caller = address!("1000000000000000000000000000000000000000");
+        env.tx.transact_to = TxKind::Call(contract);
+        env.tx.gas_limit = 100_000;
+        (db, env)
+    }
+
+    #[test]
+    fn same_execution() {
+        // SSTORE(0, 1)
+        let (db, env) = setup(&[opcode::PUSH1, 0x01, opcode::PUSH1, 0x00, opcode::SSTORE]);
+        let mut reference = OtherSpec(SpecId::CANCUN);
+        assert_eq!(
+            run_differential(&mut reference, &db, &env, SpecId::CANCUN).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn divergences() {
+        // PUSH0 is not enabled before Shanghai.
+        let (db, env) = setup(&[opcode::PUSH0, opcode::STOP]);
+        let mut reference = OtherSpec(SpecId::MERGE);
+        let divergence = run_differential(&mut reference, &db, &env, SpecId::SHANGHAI).unwrap();
+        assert!(matches!(
+            divergence,
+            Some(Divergence::Outcome {
+                reference: ExecutionResult::Halt {
+                    reason: HaltReason::NotActivated,
+                    ..
+                },
+                ..
+            })
+        ));
+
+        // SSTORE(0, 1) costs more since Berlin, when the slot is cold.
+        let (db, env) = setup(&[opcode::PUSH1, 0x01, opcode::PUSH1, 0x00, opcode::SSTORE]);
+        let mut reference = OtherSpec(SpecId::ISTANBUL);
+        let divergence = run_differential(&mut reference, &db, &env, SpecId::BERLIN).unwrap();
+        assert!(
+            matches!(divergence, Some(Divergence::GasUsed { revm, reference }) if revm > reference),
+            "{divergence:?}"
+        );
+
+        // Enough gas for the store at Frontier, but not for the cold store at Berlin.
+        let mut reference = OtherSpec(SpecId::FRONTIER);
+        let mut env = env;
+        env.tx.gas_limit = 21_000 + 6 + 20_000;
+        let divergence = run_differential(&mut reference, &db, &env, SpecId::BERLIN).unwrap();
+        assert!(matches!(
+            divergence,
+            Some(Divergence::Outcome {
+                revm: ExecutionResult::Halt {
+                    reason: HaltReason::OutOfGas(OutOfGasError::Basic),
+                    ..
+                },
+                ..
+            })
+        ));
+    }
+}
@@ -1,22 +1,35 @@
-use revm_interpreter::Host as _;
+use revm_interpreter::HostRead as _;
 
 use crate::{
     builder::{EvmBuilder, SetGenericStage},
     db::{Database, DatabaseCommit},
     handler::Handler,
-    interpreter::{CallInputs, CreateInputs, EOFCreateInputs, InterpreterAction, SharedMemory},
+    interpreter::{
+        CallInputs, CallScheme, CallValue, CreateInputs, CreateScheme, EOFCreateInputs,
+        ExecutionBudget, InstructionResult, InterpreterAction, SharedMemory,
+    },
     primitives::{
-        CfgEnv, EVMError, EVMResult, EVMResultGeneric, EnvWiring, ExecutionResult, ResultAndState,
-        SpecId, Transaction, TxKind, EOF_MAGIC_BYTES,
+        Address, Bytes, CfgEnv, EVMError, EVMResult, EVMResultGeneric, EnvWiring, ExecutionResult,
+        HaltReasonTrait, ResultAndState, SpecId, Transaction, TxKind, B256, EOF_MAGIC_BYTES,
+        SYSTEM_CALL_GAS_LIMIT, U256,
     },
     Context, ContextWithEvmWiring, EvmContext, EvmWiring, Frame, FrameOrResult, FrameResult,
     InnerEvmContext,
 };
 use core::fmt::{self, Debug};
 use std::{boxed::Box, vec::Vec};
+#[cfg(feature = "tracing")]
+use {crate::spans, std::vec};
+#[cfg(feature = "std")]
+use {
+    crate::{
+        handler::register::{instruction_counter_register, HandleRegisters},
+        stats::{EvmStats, StatsCollector, INSTRUCTION_COUNTER_REGISTER},
+    },
+    std::time::Instant,
+};
 
-/// EVM call stack limit.
-pub const CALL_STACK_LIMIT: u64 = 1024;
+pub use crate::primitives::CALL_STACK_LIMIT;
 
 /// EVM instance containing both internal EVM context and external context
 /// and the handler that dictates the logic of EVM (or hardfork specification).
@@ -26,6 +39,9 @@ pub struct Evm<'a, EvmWiringT: EvmWiring> {
     /// Handler is a component of the of EVM that contains all the logic. Handler contains specification id
     /// and it different depending on the specified fork.
     pub handler: Handler<'a, EvmWiringT, Context<EvmWiringT>>,
+    /// Optional statistics collector, see [`Evm::enable_stats`].
+    #[cfg(feature = "std")]
+    stats: Option<Box<StatsCollector>>,
 }
 
 impl<EvmWiringT> Debug for Evm<'_, EvmWiringT>
@@ -41,6 +57,17 @@ where
     }
 }
 
+/// Deployment simulated by [`Evm::simulate_create`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreateSimulation<HaltReasonT: HaltReasonTrait> {
+    /// Address of the contract, computed before the init code is executed.
+    pub address: Address,
+    /// Runtime code returned by the init code, empty if the deployment failed.
+    pub code: Bytes,
+    /// Result of the execution of the init code, including the gas it used.
+    pub result: ExecutionResult<HaltReasonT>,
+}
+
 impl<EvmWiringT: EvmWiring<Database: DatabaseCommit>> Evm<'_, EvmWiringT> {
     /// Commit the changes to the database.
     pub fn transact_commit(
@@ -50,6 +77,20 @@ impl<EvmWiringT: EvmWiring<Database: DatabaseCommit>> Evm<'_, EvmWiringT> {
         self.context.evm.db.commit(state);
         Ok(result)
     }
+
+    /// Executes a system call and commits the changes to the database.
+    ///
+    /// See [`Evm::transact_system_call`].
+    pub fn transact_system_call_commit(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> EVMResultGeneric<ExecutionResult<EvmWiringT::HaltReason>, EvmWiringT> {
+        let ResultAndState { result, state } = self.transact_system_call(caller, contract, data)?;
+        self.context.evm.db.commit(state);
+        Ok(result)
+    }
 }
 
 impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT>
@@ -73,12 +114,22 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
             .evm
             .journaled_state
             .set_spec_id(handler.spec_id.into());
-        Evm { context, handler }
+        Evm {
+            context,
+            handler,
+            #[cfg(feature = "std")]
+            stats: None,
+        }
     }
 
     /// Allow for evm setting to be modified by feeding current evm
     /// into the builder for modifications.
-    pub fn modify(self) -> EvmBuilder<'a, SetGenericStage, EvmWiringT> {
+    ///
+    /// Statistics are dropped, and their instruction counter is removed from the handler.
+    #[cfg_attr(not(feature = "std"), allow(unused_mut))]
+    pub fn modify(mut self) -> EvmBuilder<'a, SetGenericStage, EvmWiringT> {
+        #[cfg(feature = "std")]
+        self.disable_stats();
         let Evm {
             context:
                 Context {
@@ -90,6 +141,7 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
                     external,
                 },
             handler,
+            ..
         } = self;
         EvmBuilder::<'a>::new_with(db, external, env, handler)
     }
@@ -100,6 +152,12 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
         &mut self,
         first_frame: Frame,
     ) -> EVMResultGeneric<FrameResult, EvmWiringT> {
+        // Entered spans of the frames on the call stack.
+        #[cfg(feature = "tracing")]
+        let mut spans =
+            vec![
+                spans::frame_span(&first_frame, self.context.evm.journaled_state.depth()).entered(),
+            ];
         let mut call_stack: Vec<Frame> = Vec::with_capacity(1025);
         call_stack.push(first_frame);
 
@@ -111,6 +169,10 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
 
         shared_memory.new_context();
 
+        let cfg = &self.context.evm.env.cfg;
+        self.context.evm.budget =
+            ExecutionBudget::new(cfg.instruction_limit, cfg.execution_timeout);
+
         // Peek the last stack frame.
         let mut stack_frame = call_stack.last_mut().unwrap();
 
@@ -122,7 +184,28 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
 
             // Take error and break the loop, if any.
             // This error can be set in the Interpreter when it interacts with the context.
-            self.context.evm.take_error().map_err(EVMError::Database)?;
+            if let Err(error) = self.context.evm.take_error() {
+                let depth = self.context.evm.journaled_state.depth();
+                return Err(EVMError::InstructionDatabase {
+                    error,
+                    location: stack_frame.interpreter().halt_location(depth),
+                });
+            }
+
+            // Span of the frame about to be created, entered while it is created.
+            #[cfg(feature = "tracing")]
+            let span = {
+                let depth = self.context.evm.journaled_state.depth() + 1;
+                match &next_action {
+                    InterpreterAction::Call { inputs } => Some(spans::call_span(inputs, depth)),
+                    InterpreterAction::Create { inputs } => Some(spans::create_span(inputs, depth)),
+                    InterpreterAction::EOFCreate { inputs } => {
+                        Some(spans::eofcreate_span(inputs, depth))
+                    }
+                    _ => None,
+                }
+                .map(tracing::Span::entered)
+            };
 
             let exec = &mut self.handler.execution;
             let frame_or_result = match next_action {
@@ -132,7 +215,8 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
                     exec.eofcreate(&mut self.context, inputs)?
                 }
                 InterpreterAction::Return { result } => {
-                    // free memory context.
+                    // free memory context, keeping what its expansion cost for the gas summary.
+                    let memory_expansion = shared_memory.current_expansion_cost();
                     shared_memory.free_context();
 
                     // pop last frame from the stack and consume it to create FrameResult.
@@ -140,8 +224,25 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
                         .pop()
                         .expect("We just returned from Interpreter frame");
 
+                    let interpreter = returned_frame.interpreter();
+                    self.context.evm.halt_location =
+                        interpreter.instruction_result.is_error().then(|| {
+                            interpreter.halt_location(self.context.evm.journaled_state.depth())
+                        });
+                    if self.context.evm.env.cfg.read_only
+                        && matches!(
+                            result.result,
+                            InstructionResult::StateChangeDuringStaticCall
+                                | InstructionResult::CallNotAllowedInsideStatic
+                        )
+                    {
+                        return Err(EVMError::StateChangeInReadOnly {
+                            location: self.context.evm.halt_location,
+                        });
+                    }
+
                     let ctx = &mut self.context;
-                    FrameOrResult::Result(match returned_frame {
+                    let mut frame_result = match returned_frame {
                         Frame::Call(frame) => {
                             // return_call
                             FrameResult::Call(exec.call_return(ctx, frame, result)?)
@@ -154,18 +255,27 @@ impl<'a, EvmWiringT: EvmWiring> Evm<'a, EvmWiringT> {
                             // return_eofcreate
                             FrameResult::EOFCreate(exec.eofcreate_return(ctx, frame, result)?)
                         }
-                    })
+                    };
+                    frame_result.gas_summary_mut().memory_expansion = memory_expansion;
+                    FrameOrResult::Result(frame_result)
                 }
                 InterpreterAction::None => unreachable!("InterpreterAction::None is not expected"),
             };
             // handle result
             match frame_or_result {
                 FrameOrResult::Frame(frame) => {
+                    #[cfg(feature = "tracing")]
+                    spans.extend(span);
                     shared_memory.new_context();
                     call_stack.push(frame);
                     stack_frame = call_stack.last_mut().unwrap();
                 }
                 FrameOrResult::Result(result) => {
+                    // Either a frame returned, or the new frame returned without being executed.
+                    #[cfg(feature = "tracing")]
+                    if let Some(span) = span.or_else(|| spans.pop()) {
+                        spans::record_frame(&span, &result);
+                    }
                     let Some(top_frame) = call_stack.last_mut() else {
                         // Break the loop if there are no more frames.
                         return Ok(result);
@@ -220,15 +330,27 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
     /// This function will not validate the transaction.
     #[inline]
     pub fn transact_preverified(&mut self) -> EVMResult<EvmWiringT> {
-        let initial_gas_spend = self
+        #[cfg(feature = "tracing")]
+        let span = spans::transact_span(&self.context.evm.env.tx).entered();
+        #[cfg(feature = "std")]
+        let started = self.stats.is_some().then(Instant::now);
+        let output = self
             .handler
-            .validation()
-            .initial_tx_gas(&self.context.evm.env)
-            .inspect_err(|_| {
-                self.clear();
-            })?;
-        let output = self.transact_preverified_inner(initial_gas_spend);
-        let output = self.handler.post_execution().end(&mut self.context, output);
+            .pre_execution()
+            .start(&mut self.context)
+            .and_then(|()| {
+                self.handler
+                    .validation()
+                    .initial_tx_gas(&self.context.evm.env)
+            })
+            .and_then(|initial_gas_spend| {
+                let output = self.transact_preverified_inner(initial_gas_spend);
+                self.handler.post_execution().end(&mut self.context, output)
+            });
+        #[cfg(feature = "std")]
+        self.record_stats(started, &output);
+        #[cfg(feature = "tracing")]
+        spans::record_transact(&span, &output);
         self.clear();
         output
     }
@@ -236,6 +358,9 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
     /// Pre verify transaction inner.
     #[inline]
     fn preverify_transaction_inner(&mut self) -> EVMResultGeneric<u64, EvmWiringT> {
+        self.handler
+            .validation()
+            .signature(&mut self.context.evm.env)?;
         self.handler.validation().env(&self.context.evm.env)?;
         let initial_gas_spend = self
             .handler
@@ -252,16 +377,146 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
     /// This function will validate the transaction.
     #[inline]
     pub fn transact(&mut self) -> EVMResult<EvmWiringT> {
-        let initial_gas_spend = self.preverify_transaction_inner().inspect_err(|_| {
-            self.clear();
-        })?;
+        #[cfg(feature = "tracing")]
+        let span = spans::transact_span(&self.context.evm.env.tx).entered();
+        #[cfg(feature = "std")]
+        let started = self.stats.is_some().then(Instant::now);
+        let output = self
+            .handler
+            .pre_execution()
+            .start(&mut self.context)
+            .and_then(|()| self.preverify_transaction_inner())
+            .and_then(|initial_gas_spend| {
+                let output = self.transact_preverified_inner(initial_gas_spend);
+                self.handler.post_execution().end(&mut self.context, output)
+            });
+        #[cfg(feature = "std")]
+        self.record_stats(started, &output);
+        #[cfg(feature = "tracing")]
+        spans::record_transact(&span, &output);
+        self.clear();
+        output
+    }
 
-        let output = self.transact_preverified_inner(initial_gas_spend);
+    /// Executes a system call, as used by EIP-4788, EIP-2935 and EIP-7002 block processing.
+    ///
+    /// Calls `contract` from `caller` (usually [`SYSTEM_ADDRESS`](crate::primitives::SYSTEM_ADDRESS)) with `data` and a fixed gas
+    /// limit of [`SYSTEM_CALL_GAS_LIMIT`]. Transaction validation, nonce, fee and EIP-3607
+    /// logic are skipped, no gas is refunded and the gas used must not be counted toward the
+    /// block gas. Changes of the called contracts are returned in the state as for a regular
+    /// transaction, while changes of the caller account are discarded.
+    ///
+    /// The transaction environment is left unchanged, so `ORIGIN` and `GASPRICE` are read
+    /// from the current transaction.
+    pub fn transact_system_call(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> EVMResult<EvmWiringT> {
+        let output = self
+            .handler
+            .pre_execution()
+            .start(&mut self.context)
+            .and_then(|()| self.transact_system_call_inner(caller, contract, data));
         let output = self.handler.post_execution().end(&mut self.context, output);
         self.clear();
         output
     }
 
+    /// Simulates the deployment of `init_code` by `caller` with the given `scheme`.
+    ///
+    /// The deployment address is computed from the current nonce of the caller, or from the
+    /// salt, before the init code is executed as a `CREATE` or `CREATE2` frame with
+    /// `gas_limit`. Like [`Evm::transact_system_call`], transaction validation and fees are
+    /// skipped, and gas is not refunded. The reported gas does not include the intrinsic
+    /// gas of a transaction.
+    ///
+    /// The changed state is discarded, so the database is never modified. An address
+    /// collision is reported as a [`HaltReason::CreateCollision`](crate::primitives::HaltReason::CreateCollision).
+    pub fn simulate_create(
+        &mut self,
+        caller: Address,
+        scheme: CreateScheme,
+        value: U256,
+        init_code: Bytes,
+        gas_limit: u64,
+    ) -> EVMResultGeneric<CreateSimulation<EvmWiringT::HaltReason>, EvmWiringT> {
+        let output = self.simulate_create_inner(caller, scheme, value, init_code, gas_limit);
+        self.clear();
+        output
+    }
+
+    /// Simulates the deployment of `init_code` with `salt` through the deterministic
+    /// deployment proxy, see [`create2`](crate::create2).
+    ///
+    /// The init code is executed as a `CREATE2` frame of the
+    /// [`DETERMINISTIC_DEPLOYER`](crate::create2::DETERMINISTIC_DEPLOYER), which does not need to
+    /// be in the database. See [`Evm::simulate_create`].
+    pub fn simulate_deterministic_deployment(
+        &mut self,
+        salt: B256,
+        init_code: Bytes,
+        gas_limit: u64,
+    ) -> EVMResultGeneric<CreateSimulation<EvmWiringT::HaltReason>, EvmWiringT> {
+        self.simulate_create(
+            crate::create2::DETERMINISTIC_DEPLOYER,
+            CreateScheme::Create2 { salt: salt.into() },
+            U256::ZERO,
+            init_code,
+            gas_limit,
+        )
+    }
+
+    /// Enables collection of execution statistics, keeping at most `max_errors` of the
+    /// most recent errors.
+    ///
+    /// If statistics are already enabled, the gathered statistics are reset.
+    ///
+    /// Statistics are not carried over by [`Evm::modify`].
+    #[cfg(feature = "std")]
+    pub fn enable_stats(&mut self, max_errors: usize) {
+        if let Some(stats) = &mut self.stats {
+            stats.reset(max_errors);
+            return;
+        }
+        let stats = StatsCollector::new(max_errors);
+        self.handler
+            .append_handler_register(HandleRegisters::Identified(
+                INSTRUCTION_COUNTER_REGISTER,
+                instruction_counter_register(stats.instruction_counter()),
+            ));
+        self.stats = Some(Box::new(stats));
+    }
+
+    /// Disables collection of execution statistics, discarding the gathered statistics and
+    /// removing the instruction counter from the handler.
+    #[cfg(feature = "std")]
+    pub fn disable_stats(&mut self) {
+        if self.stats.take().is_some() {
+            self.handler
+                .remove_handle_register(INSTRUCTION_COUNTER_REGISTER);
+        }
+    }
+
+    /// Returns a snapshot of the execution statistics, or `None` if they are not enabled.
+    #[cfg(feature = "std")]
+    pub fn stats(&self) -> Option<EvmStats> {
+        self.stats.as_ref().map(|stats| stats.snapshot())
+    }
+
+    /// Records the outcome of a transaction if statistics are enabled.
+    #[cfg(feature = "std")]
+    fn record_stats(&mut self, started: Option<Instant>, output: &EVMResult<EvmWiringT>) {
+        if let (Some(stats), Some(started)) = (&mut self.stats, started) {
+            stats.record(
+                output.as_ref().map(|output| &output.result),
+                &self.context.evm.journaled_state.load_stats,
+                started.elapsed(),
+            );
+        }
+    }
+
     /// Returns the reference of Env configuration
     #[inline]
     pub fn cfg(&self) -> &CfgEnv {
@@ -344,12 +599,119 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
         ContextWithEvmWiring::new(self.context, self.handler.spec_id)
     }
 
+    /// Executes a system call, see [`Evm::transact_system_call`].
+    fn transact_system_call_inner(
+        &mut self,
+        caller: Address,
+        contract: Address,
+        data: Bytes,
+    ) -> EVMResult<EvmWiringT> {
+        let ctx = &mut self.context;
+        let precompiles = self.handler.pre_execution().load_precompiles();
+        ctx.evm.set_precompiles(precompiles);
+
+        let inputs = Box::new(CallInputs {
+            input: data,
+            return_memory_offset: 0..0,
+            gas_limit: SYSTEM_CALL_GAS_LIMIT,
+            bytecode_address: contract,
+            target_address: contract,
+            caller,
+            value: CallValue::Transfer(U256::ZERO),
+            scheme: CallScheme::Call,
+            is_static: false,
+            is_eof: false,
+        });
+        let mut result = match self.handler.execution().call(ctx, inputs)? {
+            FrameOrResult::Frame(first_frame) => self.run_the_loop(first_frame)?,
+            FrameOrResult::Result(result) => result,
+        };
+
+        // System calls are not refunded.
+        result.gas_mut().set_refund(0);
+
+        let mut output = self
+            .handler
+            .post_execution()
+            .output(&mut self.context, result)?;
+        output.state.remove(&caller);
+        Ok(output)
+    }
+
+    /// Simulates a deployment, see [`Evm::simulate_create`].
+    fn simulate_create_inner(
+        &mut self,
+        caller: Address,
+        scheme: CreateScheme,
+        value: U256,
+        init_code: Bytes,
+        gas_limit: u64,
+    ) -> EVMResultGeneric<CreateSimulation<EvmWiringT::HaltReason>, EvmWiringT> {
+        let ctx = &mut self.context;
+        let precompiles = self.handler.pre_execution().load_precompiles();
+        ctx.evm.set_precompiles(precompiles);
+
+        let inputs = Box::new(CreateInputs {
+            caller,
+            scheme,
+            value,
+            init_code,
+            gas_limit,
+        });
+        let nonce = match scheme {
+            CreateScheme::Create => {
+                ctx.evm
+                    .inner
+                    .journaled_state
+                    .load_account(caller, &mut ctx.evm.inner.db)
+                    .map_err(EVMError::Database)?
+                    .info
+                    .nonce
+            }
+            // The nonce is not part of the address.
+            CreateScheme::Create2 { .. } => 0,
+        };
+        let address = inputs.created_address(nonce);
+
+        let mut result = match self.handler.execution().create(ctx, inputs)? {
+            FrameOrResult::Frame(first_frame) => self.run_the_loop(first_frame)?,
+            FrameOrResult::Result(result) => result,
+        };
+
+        // Halts spend all the gas, and simulations are not refunded.
+        let instruction_result = result.interpreter_result().result;
+        if !instruction_result.is_ok() && !instruction_result.is_revert() {
+            result.gas_mut().spend_all();
+        }
+        result.gas_mut().set_refund(0);
+
+        let ResultAndState { result, .. } = self
+            .handler
+            .post_execution()
+            .output(&mut self.context, result)?;
+        let code = match &result {
+            ExecutionResult::Success { output, .. } => output.data().clone(),
+            _ => Bytes::new(),
+        };
+        Ok(CreateSimulation {
+            address,
+            code,
+            result,
+        })
+    }
+
     /// Transact pre-verified transaction.
     fn transact_preverified_inner(&mut self, initial_gas_spend: u64) -> EVMResult<EvmWiringT> {
         let spec_id = self.spec_id();
         let ctx = &mut self.context;
         let pre_exec = self.handler.pre_execution();
 
+        // Read-only transactions can only call without value.
+        let read_only = ctx.evm.env.cfg.read_only;
+        if read_only && (ctx.evm.env.tx.kind().is_create() || !ctx.evm.env.tx.value().is_zero()) {
+            return Err(EVMError::StateChangeInReadOnly { location: None });
+        }
+
         // load access list and beneficiary if needed.
         pre_exec.load_accounts(ctx)?;
 
@@ -360,7 +722,7 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
         // deduce caller balance with its limit.
         pre_exec.deduct_caller(ctx)?;
 
-        let gas_limit = ctx.evm.env.tx.gas_limit() - initial_gas_spend;
+        let gas_limit = ctx.evm.env.effective_gas_limit() - initial_gas_spend;
 
         // apply EIP-7702 auth list.
         let eip7702_gas_refund = pre_exec.apply_eip7702_auth_list(ctx)? as i64;
@@ -368,10 +730,11 @@ impl<EvmWiringT: EvmWiring> Evm<'_, EvmWiringT> {
         let exec = self.handler.execution();
         // call inner handling of call/create
         let first_frame_or_result = match ctx.evm.env.tx.kind() {
-            TxKind::Call(_) => exec.call(
-                ctx,
-                CallInputs::new_boxed(&ctx.evm.env.tx, gas_limit).unwrap(),
-            )?,
+            TxKind::Call(_) => {
+                let mut inputs = CallInputs::new_boxed(&ctx.evm.env.tx, gas_limit).unwrap();
+                inputs.is_static = read_only;
+                exec.call(ctx, inputs)?
+            }
             TxKind::Create => {
                 // if first byte of data is magic 0xEF00, then it is EOFCreate.
                 if Into::<SpecId>::into(spec_id).is_enabled_in(SpecId::PRAGUE_EOF)
@@ -425,51 +788,845 @@ mod tests {
     use super::*;
     use crate::{
         db::BenchmarkDB,
-        interpreter::opcode::{PUSH1, SSTORE},
+        handler::register::opcode_observer_handle_register,
+        inspector_handle_register,
+        interpreter::{
+            gas::{COLD_ACCOUNT_ACCESS_COST, COLD_SLOAD_COST, WARM_STORAGE_READ_COST},
+            opcode::{
+                BALANCE, CALL, CALLER, DUP1, GAS, JUMP, JUMPDEST, JUMPI, MSTORE8, PUSH1, PUSH2,
+                RETURN, SLOAD, SSTORE, STOP, SUB, SWAP1,
+            },
+            CallOutcome, FrameGasSummary, Interpreter,
+        },
         primitives::{
-            address, Authorization, Bytecode, EthereumWiring, RecoveredAuthorization, Signature,
-            U256,
+            address, create2_address_from_code, create_address, AccountInfo, Authorization,
+            Bytecode, EthereumWiring, HaltLocation, HaltReason, InvalidTransaction, Output,
+            PrecompileError, PrecompileFailure, RecoveredAuthorization, Signature, B256,
+            KECCAK_EMPTY, SYSTEM_ADDRESS, U256,
         },
+        InMemoryDB, Inspector,
     };
 
+    #[test]
+    fn runtime_spec_handler() {
+        let contract = address!("0000000000000000000000000000000000000100");
+        let mut db = InMemoryDB::default();
+        // PUSH0, activated in Shanghai.
+        db.insert_account_info(
+            contract,
+            AccountInfo::from_bytecode(Bytecode::new_legacy([0x5f].into())),
+        );
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .with_handler(Handler::mainnet_with_runtime_spec(SpecId::LONDON))
+            .modify_tx_env(|tx| tx.transact_to = TxKind::Call(contract))
+            .build();
+
+        assert!(matches!(
+            evm.transact().unwrap().result,
+            ExecutionResult::Halt {
+                reason: HaltReason::NotActivated,
+                ..
+            }
+        ));
+
+        // Blob transactions are rejected by the validation handles before Cancun.
+        evm.context.evm.env.tx.max_fee_per_blob_gas = Some(U256::from(1));
+        assert!(matches!(
+            evm.transact(),
+            Err(EVMError::Transaction(
+                InvalidTransaction::BlobVersionedHashesNotSupported
+            ))
+        ));
+        evm.context.evm.env.tx.max_fee_per_blob_gas = None;
+
+        let mut evm = evm
+            .modify()
+            .with_handler(Handler::mainnet_with_runtime_spec(SpecId::SHANGHAI))
+            .build();
+        assert!(evm.transact().unwrap().result.is_success());
+    }
+
+    #[test]
+    fn opcode_observer_counts_frames() {
+        let callee = address!("0000000000000000000000000000000000000100");
+        let contract = address!("0000000000000000000000000000000000000200");
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(
+            callee,
+            AccountInfo::from_bytecode(Bytecode::new_legacy([CALLER].into())),
+        );
+        // CALL(gas, callee, 0, 0, 0, 0, 0)
+        let code = [
+            PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH2, 0x01, 0x00, GAS, CALL,
+        ];
+        db.insert_account_info(
+            contract,
+            AccountInfo::from_bytecode(Bytecode::new_legacy(code.into())),
+        );
+        let mut evm =
+            Evm::<EthereumWiring<InMemoryDB, crate::interpreter::OpcodeCounter>>::builder()
+                .with_db(db)
+                .with_default_ext_ctx()
+                .append_handler_register(opcode_observer_handle_register)
+                .modify_tx_env(|tx| tx.transact_to = TxKind::Call(contract))
+                .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        let counter = &evm.context.external;
+        assert_eq!(counter.count(PUSH1), 5);
+        assert_eq!(counter.count(CALL), 1);
+        // Instructions of the called frame are counted too, including the padding STOP.
+        assert_eq!(counter.count(CALLER), 1);
+        assert_eq!(counter.total(), 11);
+    }
+
     #[test]
     fn sanity_eip7702_tx() {
-        let delegate = address!("0000000000000000000000000000000000000000");
+        let delegate = address!("0000000000000000000000000000000000000200");
         let caller = address!("0000000000000000000000000000000000000001");
         let auth = address!("0000000000000000000000000000000000000100");
 
         let bytecode = Bytecode::new_legacy([PUSH1, 0x01, PUSH1, 0x01, SSTORE].into());
+        let mut db = InMemoryDB::default();
+        db.insert_account_info(delegate, AccountInfo::from_bytecode(bytecode));
 
-        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+        let authorization = |address, nonce| {
+            Some(
+                vec![RecoveredAuthorization::new_unchecked(
+                    Authorization {
+                        chain_id: U256::from(1),
+                        address,
+                        nonce,
+                    }
+                    .into_signed(Signature::test_signature()),
+                    Some(auth),
+                )]
+                .into(),
+            )
+        };
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
             .with_spec_id(SpecId::PRAGUE)
-            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_db(db)
             .with_default_ext_ctx()
             .modify_tx_env(|tx| {
-                tx.authorization_list = Some(
-                    vec![RecoveredAuthorization::new_unchecked(
-                        Authorization {
-                            chain_id: U256::from(1),
-                            address: delegate,
-                            nonce: 0,
-                        }
-                        .into_signed(Signature::test_signature()),
-                        Some(auth),
-                    )]
-                    .into(),
-                );
+                tx.authorization_list = authorization(delegate, 0);
                 tx.caller = caller;
                 tx.transact_to = TxKind::Call(auth);
             })
             .build();
 
-        let ok = evm.transact().unwrap();
+        let ok = evm.transact_commit().unwrap();
+        assert!(ok.is_success());
+        let auth_acc = &evm.db().accounts[&auth];
+        assert_eq!(
+            auth_acc.info.code_hash,
+            Bytecode::new_eip7702(delegate).hash_slow()
+        );
+        assert_eq!(auth_acc.info.nonce, 1);
+        assert_eq!(auth_acc.storage[&U256::from(1)], U256::from(1));
 
+        // Delegating to the zero address clears the code of the authority.
+        evm.context.evm.env.tx.authorization_list = authorization(Address::ZERO, 1);
+        evm.context.evm.env.tx.nonce = Some(1);
+        let ok = evm.transact().unwrap();
         let auth_acc = ok.state.get(&auth).unwrap();
-        assert_eq!(auth_acc.info.code, Some(Bytecode::new_eip7702(delegate)));
-        assert_eq!(auth_acc.info.nonce, 1);
+        assert_eq!(auth_acc.info.code_hash, KECCAK_EMPTY);
+        assert_eq!(auth_acc.info.nonce, 2);
+    }
+
+    #[test]
+    fn system_call() {
+        let contract = Address::ZERO;
+        let bytecode = Bytecode::new_legacy([CALLER, PUSH1, 0x00, SSTORE].into());
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_default_ext_ctx()
+            .build();
+
+        let ResultAndState { result, state } = evm
+            .transact_system_call(SYSTEM_ADDRESS, contract, Bytes::new())
+            .unwrap();
+
+        assert!(result.is_success());
+        assert!(!state.contains_key(&SYSTEM_ADDRESS));
+        let contract_acc = state.get(&contract).unwrap();
+        assert_eq!(contract_acc.info.nonce, 1);
+        assert_eq!(
+            contract_acc.storage.get(&U256::ZERO).unwrap().present_value,
+            SYSTEM_ADDRESS.into_word().into()
+        );
+    }
+
+    #[test]
+    fn instruction_limit() {
+        // Infinite loop: JUMPDEST, PUSH1 0, JUMP
+        let bytecode = Bytecode::new_legacy([JUMPDEST, PUSH1, 0x00, JUMP].into());
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_default_ext_ctx()
+            .modify_cfg_env(|cfg| cfg.instruction_limit = Some(300))
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 1_000_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap().result;
         assert_eq!(
-            auth_acc.storage.get(&U256::from(1)).unwrap().present_value,
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::ExecutionBudgetExceeded,
+                gas_used: 1_000_000,
+                location: Some(HaltLocation {
+                    address: Address::ZERO,
+                    pc: 0,
+                    opcode: JUMPDEST,
+                    depth: 1,
+                }),
+            }
+        );
+
+        // The budget is renewed for every transaction.
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_halt());
+        assert_eq!(evm.context.evm.budget.remaining_instructions(), Some(0));
+    }
+
+    #[test]
+    fn halt_location() {
+        // PUSH1 1, PUSH1 0, JUMPI: the destination is not a JUMPDEST.
+        let bytecode = Bytecode::new_legacy([PUSH1, 0x01, PUSH1, 0x00, JUMPI].into());
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::InvalidJump,
+                ..
+            }
+        ));
+        assert_eq!(
+            result.halt_location(),
+            Some(&HaltLocation {
+                address: Address::ZERO,
+                pc: 4,
+                opcode: JUMPI,
+                depth: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn reuse_warm_accesses() {
+        // Loads storage slot 0 and the balance of 0x42.
+        let code = [PUSH1, 0x00, SLOAD, PUSH1, 0x42, BALANCE, STOP];
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code.into())))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+        let mut gas_used = |reuse_warm_accesses| {
+            evm.cfg_mut().reuse_warm_accesses = reuse_warm_accesses;
+            evm.transact().unwrap().result.gas_used()
+        };
+
+        let exact = gas_used(false);
+        // Nothing is recorded without the flag.
+        assert_eq!(gas_used(true), exact);
+        let warm = exact
+            - (COLD_SLOAD_COST - WARM_STORAGE_READ_COST)
+            - (COLD_ACCOUNT_ACCESS_COST - WARM_STORAGE_READ_COST);
+        assert_eq!(gas_used(true), warm);
+        assert_eq!(gas_used(true), warm);
+        // The exact gas is estimated by the same engine, without discarding the accesses.
+        assert_eq!(gas_used(false), exact);
+        assert_eq!(gas_used(true), warm);
+
+        let accesses = &evm.context.evm.warm_accesses;
+        assert!(accesses
+            .addresses
+            .contains(&address!("0000000000000000000000000000000000000042")));
+        assert!(accesses.storage.contains(&(Address::ZERO, U256::ZERO)));
+
+        evm.context.evm.warm_accesses.clear();
+        evm.cfg_mut().reuse_warm_accesses = true;
+        assert_eq!(evm.transact().unwrap().result.gas_used(), exact);
+    }
+
+    #[test]
+    fn fused_instructions() {
+        #[derive(Debug, Default)]
+        struct StepInspector(Vec<u8>);
+
+        impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for StepInspector {
+            fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+                self.0.push(interp.current_opcode());
+            }
+        }
+
+        // Counts down from 3 to 0, stores the counter, then jumps to an invalid destination.
+        let code = [
+            PUSH1, 0x03, JUMPDEST, PUSH1, 0x01, SWAP1, SUB, DUP1, DUP1, SWAP1, PUSH1, 0x00, SSTORE,
+            PUSH1, 0x02, JUMPI, PUSH1, 0x01, PUSH1, 0x00, JUMPI,
+        ];
+        let bytecode = Bytecode::new_legacy(code.into());
+        let transact = |fuse_instructions| {
+            let mut evm = Evm::<EthereumWiring<BenchmarkDB, StepInspector>>::builder()
+                .with_db(BenchmarkDB::new_bytecode(bytecode.clone()))
+                .with_default_ext_ctx()
+                .modify_cfg_env(|cfg| cfg.fuse_instructions = fuse_instructions)
+                .modify_tx_env(|tx| {
+                    tx.caller = address!("1000000000000000000000000000000000000000");
+                    tx.transact_to = TxKind::Call(Address::ZERO);
+                    tx.gas_limit = 100_000;
+                })
+                .build();
+            let result = evm.transact().unwrap();
+            assert_eq!(
+                evm.context.evm.fused_bytecodes.len(),
+                fuse_instructions as usize
+            );
+            result
+        };
+
+        let fused = transact(true);
+        assert_eq!(
+            fused.result.halt_location(),
+            Some(&HaltLocation {
+                address: Address::ZERO,
+                pc: 20,
+                opcode: JUMPI,
+                depth: 1,
+            })
+        );
+        assert_eq!(fused, transact(false));
+
+        // Inspectors step through the original instructions.
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, StepInspector>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_default_ext_ctx()
+            .modify_cfg_env(|cfg| cfg.fuse_instructions = true)
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        assert_eq!(evm.transact().unwrap().result, fused.result);
+        let steps = &evm.context.external.0;
+        assert_eq!(steps[..4], [PUSH1, JUMPDEST, PUSH1, SWAP1]);
+        assert_eq!(steps[steps.len() - 3..], [PUSH1, PUSH1, JUMPI]);
+    }
+
+    #[test]
+    fn simulate_create() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        // Returns the runtime code 0x42.
+        let init_code = Bytes::from_static(&[
+            PUSH1, 0x42, PUSH1, 0x00, MSTORE8, PUSH1, 0x01, PUSH1, 0x00, RETURN,
+        ]);
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_default_db()
+            .with_default_ext_ctx()
+            .build();
+
+        let simulation = evm
+            .simulate_create(
+                caller,
+                CreateScheme::Create,
+                U256::ZERO,
+                init_code.clone(),
+                100_000,
+            )
+            .unwrap();
+        assert_eq!(simulation.address, create_address(caller, 0));
+        assert_eq!(simulation.code, Bytes::from_static(&[0x42]));
+        assert!(matches!(
+            &simulation.result,
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                gas_used,
+                ..
+            } if *address == simulation.address && *gas_used > 0
+        ));
+
+        // Nothing was committed, so the caller nonce did not change.
+        let again = evm
+            .simulate_create(
+                caller,
+                CreateScheme::Create,
+                U256::ZERO,
+                init_code.clone(),
+                100_000,
+            )
+            .unwrap();
+        assert_eq!(again, simulation);
+
+        let salt = U256::from(7);
+        let simulation = evm
+            .simulate_create(
+                caller,
+                CreateScheme::Create2 { salt },
+                U256::ZERO,
+                init_code.clone(),
+                100_000,
+            )
+            .unwrap();
+        assert_eq!(
+            simulation.address,
+            create2_address_from_code(caller, salt.into(), &init_code)
+        );
+        assert!(simulation.result.is_success());
+    }
+
+    #[test]
+    fn call_end_gas_summary() {
+        #[derive(Debug, Default)]
+        struct SummaryInspector(Vec<FrameGasSummary>);
+
+        impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for SummaryInspector {
+            fn call_end(
+                &mut self,
+                _context: &mut EvmContext<EvmWiringT>,
+                _inputs: &CallInputs,
+                outcome: CallOutcome,
+            ) -> CallOutcome {
+                self.0.push(outcome.gas_summary);
+                outcome
+            }
+        }
+
+        let callee = address!("0000000000000000000000000000000000000100");
+        let contract = address!("0000000000000000000000000000000000000200");
+        let mut db = InMemoryDB::default();
+        // MSTORE8(32, 1), expanding the memory to two words.
+        db.insert_account_info(
+            callee,
+            AccountInfo::from_bytecode(Bytecode::new_legacy([PUSH1, 1, PUSH1, 32, MSTORE8].into())),
+        );
+        // MSTORE8(0, 1), then CALL(gas, callee, 0, 0, 0, 0, 0).
+        let code = [
+            PUSH1, 1, PUSH1, 0, MSTORE8, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH1, 0, PUSH2,
+            0x01, 0x00, GAS, CALL,
+        ];
+        db.insert_account_info(
+            contract,
+            AccountInfo::from_bytecode(Bytecode::new_legacy(code.into())),
+        );
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, SummaryInspector>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(contract);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+        let [inner, outer] = evm.context.external.0[..] else {
+            panic!("expected two calls");
+        };
+        assert_eq!(inner.memory_expansion, 6);
+        assert_eq!(inner.spent, 3 + 3 + 3 + 6);
+        assert_eq!(inner.spent + inner.remaining, inner.limit);
+        assert_eq!(outer.memory_expansion, 3);
+        assert_eq!(outer.limit, 100_000 - 21_000);
+        assert!(outer.spent > inner.spent);
+        assert_eq!(outer.spent + outer.remaining, outer.limit);
+    }
+
+    #[test]
+    fn precompile_failure() {
+        #[derive(Debug, Default)]
+        struct FailureInspector(Option<PrecompileFailure>);
+
+        impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for FailureInspector {
+            fn transact_end(
+                &mut self,
+                context: &mut EvmContext<EvmWiringT>,
+                _output: &ResultAndState<EvmWiringT::HaltReason>,
+            ) {
+                self.0 = context.precompile_failure.clone();
+            }
+        }
+
+        // bn128 addition of the point (1, 1), which is not on the curve.
+        let bn128_add = address!("0000000000000000000000000000000000000006");
+        let mut input = [0; 128];
+        input[31] = 1;
+        input[63] = 1;
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, FailureInspector>>::builder()
+            .with_default_db()
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(bn128_add);
+                tx.data = Bytes::copy_from_slice(&input);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(matches!(
+            result,
+            ExecutionResult::Halt {
+                reason: HaltReason::PrecompileError,
+                ..
+            }
+        ));
+        assert_eq!(
+            evm.context.external.0,
+            Some(PrecompileFailure {
+                address: bn128_add,
+                error: PrecompileError::Bn128AffineGFailedToCreate,
+            })
+        );
+        assert!(evm.context.evm.precompile_failure.is_none());
+    }
+
+    #[test]
+    fn custom_create_address() {
+        let deployed = address!("00000000000000000000000000000000000c0de0");
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_default_db()
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Create;
+                tx.data = Bytes::from_static(&[PUSH1, 0x01, PUSH1, 0x00, RETURN]);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register_box(Box::new(move |handler| {
+                handler.execution.create = std::sync::Arc::new(move |ctx, inputs| {
+                    let spec_id = ctx.evm.spec_id();
+                    ctx.evm
+                        .make_create_frame_with_address(spec_id, &inputs, |_| deployed)
+                        .map_err(EVMError::Database)
+                })
+            }))
+            .build();
+
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert!(matches!(
+            result,
+            ExecutionResult::Success {
+                output: Output::Create(_, Some(address)),
+                ..
+            } if address == deployed
+        ));
+        assert!(state[&deployed].info.code.is_some());
+    }
+
+    #[test]
+    fn cfg_warm_addresses() {
+        let other = address!("0000000000000000000000000000000000001000");
+        // BALANCE(0x1000)
+        let bytecode = Bytecode::new_legacy([PUSH2, 0x10, 0x00, BALANCE].into());
+        let gas_used = |warm_addresses: &[Address]| {
+            Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+                .with_db(BenchmarkDB::new_bytecode(bytecode.clone()))
+                .with_default_ext_ctx()
+                .modify_cfg_env(|cfg| cfg.warm_addresses.extend(warm_addresses))
+                .modify_tx_env(|tx| {
+                    tx.caller = address!("1000000000000000000000000000000000000000");
+                    tx.transact_to = TxKind::Call(Address::ZERO);
+                    tx.gas_limit = 100_000;
+                })
+                .build()
+                .transact()
+                .unwrap()
+                .result
+                .gas_used()
+        };
+        assert_eq!(
+            gas_used(&[]) - gas_used(&[other]),
+            COLD_ACCOUNT_ACCESS_COST - WARM_STORAGE_READ_COST
+        );
+    }
+
+    /// Database whose storage requests fail, like a fork backend whose RPC requests time out.
+    struct TimeoutDB(InMemoryDB);
+
+    impl Database for TimeoutDB {
+        type Error = &'static str;
+
+        fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+            self.0.basic(address).map_err(|error| match error {})
+        }
+
+        fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+            self.0
+                .code_by_hash(code_hash)
+                .map_err(|error| match error {})
+        }
+
+        fn storage(&mut self, _address: Address, _index: U256) -> Result<U256, Self::Error> {
+            Err("request timed out")
+        }
+
+        fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+            self.0.block_hash(number).map_err(|error| match error {})
+        }
+    }
+
+    #[test]
+    fn instruction_database_error() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let outer = address!("0000000000000000000000000000000000001000");
+        let inner = address!("0000000000000000000000000000000000002000");
+        // CALL(GAS, 0x2000, 0, 0, 0, 0, 0)
+        let outer_code = Bytecode::new_legacy(
+            [
+                PUSH1, 0x00, PUSH1, 0x00, PUSH1, 0x00, PUSH1, 0x00, PUSH1, 0x00, PUSH2, 0x20, 0x00,
+                GAS, CALL,
+            ]
+            .into(),
+        );
+        // SLOAD(1)
+        let inner_code = Bytecode::new_legacy([PUSH1, 0x01, SLOAD].into());
+
+        let mut db = InMemoryDB::default();
+        for (address, code) in [(outer, outer_code), (inner, inner_code)] {
+            db.insert_account_info(
+                address,
+                AccountInfo::new(U256::ZERO, 0, code.hash_slow(), code),
+            );
+        }
+        let mut evm = Evm::<EthereumWiring<TimeoutDB, ()>>::builder()
+            .with_db(TimeoutDB(db))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(outer);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let error = evm.transact().unwrap_err();
+        assert_eq!(error.database_error(), Some(&"request timed out"));
+        assert_eq!(
+            error,
+            EVMError::InstructionDatabase {
+                error: "request timed out",
+                location: HaltLocation {
+                    address: inner,
+                    pc: 2,
+                    opcode: SLOAD,
+                    depth: 2,
+                },
+            }
+        );
+    }
+
+    #[test]
+    fn disabled_gas_metering() {
+        let caller = Address::with_last_byte(1);
+        let coinbase = Address::with_last_byte(2);
+        let bytecode = Bytecode::new_legacy([PUSH1, 0x01, PUSH1, 0x01, SSTORE].into());
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(bytecode))
+            .with_default_ext_ctx()
+            .modify_cfg_env(|cfg| cfg.disable_gas_metering = true)
+            .modify_block_env(|block| block.coinbase = coinbase)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_price = U256::from(1_000);
+                // Not even enough for the intrinsic gas.
+                tx.gas_limit = 0;
+            })
+            .build();
+
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert!(result.is_success());
+        // Intrinsic gas, two PUSH1 and a cold SSTORE of a new value.
+        assert_eq!(result.gas_used(), 21_000 + 3 + 3 + 22_100);
+
+        let caller_acc = state.get(&caller).unwrap();
+        assert_eq!(caller_acc.info.balance, U256::from(10_000_000));
+        assert_eq!(caller_acc.info.nonce, 1);
+        assert!(!state.contains_key(&coinbase));
+    }
+
+    #[test]
+    fn call_without_value_to_missing_account() {
+        use crate::{
+            db::{CacheDB, EmptyDB, State},
+            interpreter::gas::NEWACCOUNT,
+        };
+
+        let caller = address!("1000000000000000000000000000000000000000");
+        let contract = Address::with_last_byte(0xa1);
+        let missing = Address::with_last_byte(0xde);
+        // CALL(gas, missing, 0, 0, 0, 0, 0)
+        let mut code = [PUSH1, 0x00].repeat(5);
+        code.extend([PUSH1, 0xde, GAS, CALL]);
+
+        // Returns the gas used by the transaction and whether the called account exists.
+        let run = |spec_id: SpecId| {
+            let mut db = CacheDB::new(EmptyDB::default());
+            db.insert_account_info(
+                contract,
+                AccountInfo::from_bytecode(Bytecode::new_legacy(code.clone().into())),
+            );
+            let mut state = State::builder().with_database(db).build();
+            state.set_state_clear_flag(spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON));
+            let gas_used = Evm::<EthereumWiring<&mut State<CacheDB<EmptyDB>>, ()>>::builder()
+                .with_db(&mut state)
+                .with_default_ext_ctx()
+                .with_spec_id(spec_id)
+                .modify_tx_env(|tx| {
+                    tx.caller = caller;
+                    tx.transact_to = TxKind::Call(contract);
+                    tx.gas_limit = 100_000;
+                })
+                .build()
+                .transact_commit()
+                .unwrap()
+                .gas_used();
+            (gas_used, state.basic(missing).unwrap().is_some())
+        };
+
+        // Before Spurious Dragon, the call creates the account and is charged for it.
+        let (gas_before, created_before) = run(SpecId::TANGERINE);
+        assert!(created_before);
+        // Since Spurious Dragon, the account is only touched, then removed as it is empty.
+        let (gas_after, created_after) = run(SpecId::SPURIOUS_DRAGON);
+        assert!(!created_after);
+        assert_eq!(gas_before - gas_after, NEWACCOUNT);
+    }
+
+    #[test]
+    fn system_transaction() {
+        let contract = Address::with_last_byte(0xa1);
+        let coinbase = Address::with_last_byte(0xc0);
+        let mut db = InMemoryDB::default();
+        // The system address has code and a nonce, and no balance.
+        db.insert_account_info(
+            SYSTEM_ADDRESS,
+            AccountInfo {
+                nonce: 5,
+                ..AccountInfo::from_bytecode(Bytecode::new_legacy([0x00].into()))
+            },
+        );
+        // SSTORE(0, 1)
+        db.insert_account_info(
+            contract,
+            AccountInfo::from_bytecode(Bytecode::new_legacy(
+                [PUSH1, 0x01, PUSH1, 0x00, SSTORE].into(),
+            )),
+        );
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_block_env(|block| {
+                block.coinbase = coinbase;
+                block.basefee = U256::from(10);
+                block.gas_limit = U256::from(1_000_000);
+            })
+            .modify_tx_env(|tx| {
+                tx.caller = SYSTEM_ADDRESS;
+                tx.transact_to = TxKind::Call(contract);
+                tx.gas_limit = 30_000_000;
+                tx.gas_price = U256::from(1);
+            })
+            .build();
+
+        // A regular transaction is rejected.
+        assert!(evm.transact().is_err());
+
+        evm.tx_mut().is_system = true;
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert!(result.is_success());
+        assert_eq!(
+            state[&contract].storage[&U256::ZERO].present_value,
             U256::from(1)
         );
+        let system = &state[&SYSTEM_ADDRESS].info;
+        assert_eq!((system.nonce, system.balance), (5, U256::ZERO));
+        assert!(!state.contains_key(&coinbase));
+    }
+
+    #[test]
+    fn read_only_execution() {
+        let caller = address!("1000000000000000000000000000000000000000");
+        let reader = Address::with_last_byte(0xa1);
+        let writer = Address::with_last_byte(0xa2);
+        let mut db = InMemoryDB::default();
+        // SLOAD(0)
+        db.insert_account_info(
+            reader,
+            AccountInfo::from_bytecode(Bytecode::new_legacy([PUSH1, 0x00, SLOAD].into())),
+        );
+        // SSTORE(0, 1)
+        db.insert_account_info(
+            writer,
+            AccountInfo::from_bytecode(Bytecode::new_legacy(
+                [PUSH1, 0x01, PUSH1, 0x00, SSTORE].into(),
+            )),
+        );
+
+        let mut evm = Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_cfg_env(|cfg| cfg.read_only = true)
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.transact_to = TxKind::Call(reader);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let ResultAndState { result, state } = evm.transact().unwrap();
+        assert!(result.is_success());
+        assert!(state.is_empty());
+
+        evm.tx_mut().transact_to = TxKind::Call(writer);
+        let Err(EVMError::StateChangeInReadOnly {
+            location: Some(location),
+        }) = evm.transact()
+        else {
+            panic!("SSTORE must fail the read-only transaction");
+        };
+        assert_eq!(
+            (
+                location.address,
+                location.pc,
+                location.opcode,
+                location.depth
+            ),
+            (writer, 4, SSTORE, 1)
+        );
+
+        evm.tx_mut().transact_to = TxKind::Create;
+        assert_eq!(
+            evm.transact().unwrap_err(),
+            EVMError::StateChangeInReadOnly { location: None }
+        );
     }
 }
@@ -1,7 +1,9 @@
 use crate::{
     handler::{ExecutionHandler, PostExecutionHandler, PreExecutionHandler, ValidationHandler},
     interpreter::opcode::InstructionTables,
-    primitives::{db::Database, spec_to_generic, EthereumWiring, EvmWiring as PrimitiveEvmWiring},
+    primitives::{
+        db::Database, spec_to_generic, ChainRules, EthereumWiring, EvmWiring as PrimitiveEvmWiring,
+    },
     EvmHandler,
 };
 use std::fmt::Debug;
@@ -10,6 +12,14 @@ use std::vec::Vec;
 pub trait EvmWiring: PrimitiveEvmWiring {
     /// Creates a new handler with the given hardfork.
     fn handler<'evm>(hardfork: Self::Hardfork) -> EvmHandler<'evm, Self>;
+
+    /// Returns the fork-dependent transaction rules of the given hardfork.
+    ///
+    /// Defaults to the rules of the Ethereum spec the hardfork maps to. Chains can override
+    /// individual rules of their hardforks.
+    fn chain_rules(hardfork: Self::Hardfork) -> ChainRules {
+        ChainRules::new(hardfork.into())
+    }
 }
 
 impl<DB: Database, EXT: Debug> EvmWiring for EthereumWiring<DB, EXT> {
@@ -17,15 +27,16 @@ impl<DB: Database, EXT: Debug> EvmWiring for EthereumWiring<DB, EXT> {
     where
         DB: Database,
     {
+        let rules = Self::chain_rules(hardfork);
         spec_to_generic!(
             hardfork,
             EvmHandler {
                 spec_id: hardfork,
                 instruction_table: InstructionTables::new_plain::<SPEC>(),
                 registers: Vec::new(),
-                validation: ValidationHandler::new::<SPEC>(),
+                validation: ValidationHandler::with_rules::<SPEC>(rules),
                 pre_execution: PreExecutionHandler::new::<SPEC>(),
-                post_execution: PostExecutionHandler::mainnet::<SPEC>(),
+                post_execution: PostExecutionHandler::mainnet_with_rules::<SPEC>(rules),
                 execution: ExecutionHandler::new::<SPEC>(),
             }
         )
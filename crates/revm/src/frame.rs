@@ -4,7 +4,9 @@ use crate::{
     JournalCheckpoint,
 };
 use core::ops::Range;
-use revm_interpreter::{CallOutcome, CreateOutcome, Gas, InstructionResult, InterpreterResult};
+use revm_interpreter::{
+    CallOutcome, CreateOutcome, FrameGasSummary, Gas, InstructionResult, InterpreterResult,
+};
 use std::boxed::Box;
 
 /// Call CallStackFrame.
@@ -105,6 +107,16 @@ impl FrameResult {
         }
     }
 
+    /// Returns mutable reference to the gas summary of the returned frame.
+    #[inline]
+    pub fn gas_summary_mut(&mut self) -> &mut FrameGasSummary {
+        match self {
+            FrameResult::Call(outcome) => &mut outcome.gas_summary,
+            FrameResult::Create(outcome) => &mut outcome.gas_summary,
+            FrameResult::EOFCreate(outcome) => &mut outcome.gas_summary,
+        }
+    }
+
     /// Returns reference to interpreter result.
     #[inline]
     pub fn interpreter_result(&self) -> &InterpreterResult {
@@ -269,29 +281,29 @@ impl FrameOrResult {
         interpreter_result: InterpreterResult,
         address: Option<Address>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::Create(CreateOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::Create(CreateOutcome::new(
+            interpreter_result,
             address,
-        }))
+        )))
     }
 
     pub fn new_eofcreate_result(
         interpreter_result: InterpreterResult,
         address: Option<Address>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::EOFCreate(CreateOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::EOFCreate(CreateOutcome::new(
+            interpreter_result,
             address,
-        }))
+        )))
     }
 
     pub fn new_call_result(
         interpreter_result: InterpreterResult,
         memory_offset: Range<usize>,
     ) -> Self {
-        FrameOrResult::Result(FrameResult::Call(CallOutcome {
-            result: interpreter_result,
+        FrameOrResult::Result(FrameResult::Call(CallOutcome::new(
+            interpreter_result,
             memory_offset,
-        }))
+        )))
     }
 }
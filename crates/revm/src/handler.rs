@@ -13,7 +13,7 @@ use crate::{
     Context, EvmWiring, Frame,
 };
 use core::mem;
-use register::{EvmHandler, HandleRegisters};
+use register::{EvmHandler, HandleRegisterId, HandleRegisters};
 use std::vec::Vec;
 
 use self::register::{HandleRegister, HandleRegisterBox};
@@ -45,19 +45,42 @@ where
 {
     /// Creates a base/vanilla Ethereum handler with the provided spec id.
     pub fn mainnet_with_spec(spec_id: EvmWiringT::Hardfork) -> Self {
+        let rules = EvmWiringT::chain_rules(spec_id);
         spec_to_generic!(
             spec_id.into(),
             Self {
                 spec_id,
                 instruction_table: InstructionTables::new_plain::<SPEC>(),
                 registers: Vec::new(),
-                validation: ValidationHandler::new::<SPEC>(),
+                validation: ValidationHandler::with_rules::<SPEC>(rules),
                 pre_execution: PreExecutionHandler::new::<SPEC>(),
-                post_execution: PostExecutionHandler::mainnet::<SPEC>(),
+                post_execution: PostExecutionHandler::mainnet_with_rules::<SPEC>(rules),
                 execution: ExecutionHandler::new::<SPEC>(),
             }
         )
     }
+
+    /// Creates a base/vanilla Ethereum handler with the provided spec id, whose instructions
+    /// and handles read the spec at runtime.
+    ///
+    /// Unlike [`Handler::mainnet_with_spec`], nothing is compiled for every spec: it uses the
+    /// [`InstructionTables::new_runtime`] table and the handles are given the spec as a value,
+    /// trading a branch wherever the spec is checked for a smaller binary and faster compiles.
+    /// The gain is only realized if [`Handler::mainnet_with_spec`] is never used, so
+    /// [`EvmWiring::handler`] should return this handler as well.
+    pub fn mainnet_with_runtime_spec(spec_id: EvmWiringT::Hardfork) -> Self {
+        let rules = EvmWiringT::chain_rules(spec_id);
+        let spec = spec_id.into();
+        Self {
+            spec_id,
+            instruction_table: InstructionTables::new_runtime(),
+            registers: Vec::new(),
+            validation: ValidationHandler::with_spec(spec, rules),
+            pre_execution: PreExecutionHandler::with_spec(spec),
+            post_execution: PostExecutionHandler::mainnet_with_spec(spec, rules),
+            execution: ExecutionHandler::with_spec(spec),
+        }
+    }
 }
 
 impl<'a, EvmWiringT: EvmWiring> EvmHandler<'a, EvmWiringT> {
@@ -135,17 +158,37 @@ impl<'a, EvmWiringT: EvmWiring> EvmHandler<'a, EvmWiringT> {
     pub fn pop_handle_register(&mut self) -> Option<HandleRegisters<'a, EvmWiringT>> {
         let out = self.registers.pop();
         if out.is_some() {
-            let registers = core::mem::take(&mut self.registers);
-            let mut base_handler = EvmWiringT::handler::<'a>(self.spec_id);
-            // apply all registers to default handler and raw mainnet instruction table.
-            for register in registers {
-                base_handler.append_handler_register(register)
-            }
-            *self = base_handler;
+            self.reapply_handle_registers();
         }
         out
     }
 
+    /// Removes the last handle register identified by `id` and reapplies all registers that
+    /// are left.
+    pub fn remove_handle_register(
+        &mut self,
+        id: HandleRegisterId,
+    ) -> Option<HandleRegisters<'a, EvmWiringT>> {
+        let index = self
+            .registers
+            .iter()
+            .rposition(|register| register.id() == Some(id))?;
+        let out = self.registers.remove(index);
+        self.reapply_handle_registers();
+        Some(out)
+    }
+
+    /// Reapplies all registers to the default handler of the spec.
+    fn reapply_handle_registers(&mut self) {
+        let registers = core::mem::take(&mut self.registers);
+        let mut base_handler = EvmWiringT::handler::<'a>(self.spec_id);
+        // apply all registers to default handler and raw mainnet instruction table.
+        for register in registers {
+            base_handler.append_handler_register(register)
+        }
+        *self = base_handler;
+    }
+
     /// Creates the Handler with variable SpecId, inside it will call function with Generic Spec.
     pub fn modify_spec_id(&mut self, spec_id: EvmWiringT::Hardfork) {
         if self.spec_id == spec_id {
@@ -172,7 +215,10 @@ mod test {
         db::EmptyDB,
         primitives::{self, EVMError},
     };
-    use std::{rc::Rc, sync::Arc};
+    use std::{
+        rc::Rc,
+        sync::{Arc, Mutex},
+    };
 
     use super::*;
 
@@ -204,4 +250,141 @@ mod test {
         // first handler is reapplied
         assert_eq!(*test.borrow(), 3);
     }
+
+    #[test]
+    fn test_handler_register_decorate() {
+        fn record<EvmWiringT: EvmWiring>(
+            handler: &mut EvmHandler<'_, EvmWiringT>,
+            calls: &Arc<Mutex<Vec<&'static str>>>,
+            name: &'static str,
+        ) {
+            let calls = calls.clone();
+            register::decorate(&mut handler.post_execution.reward_beneficiary, |prev| {
+                Arc::new(move |ctx, gas, recipients| {
+                    calls.lock().unwrap().push(name);
+                    prev(ctx, gas, recipients)
+                })
+            })
+        }
+
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let register = |name: &'static str| -> HandleRegisterBox<'_, TestEvmWiring> {
+            let calls = calls.clone();
+            Box::new(move |h| record(h, &calls, name))
+        };
+
+        let mut evm = crate::Evm::<TestEvmWiring>::builder()
+            .with_default_db()
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| tx.gas_limit = 21_000)
+            .append_handler_register_box(register("first"))
+            .append_handler_register_box(register("second"))
+            .build();
+        assert!(evm.transact().unwrap().result.is_success());
+        // The last register wraps the previous ones.
+        assert_eq!(*calls.lock().unwrap(), ["second", "first"]);
+    }
+
+    #[test]
+    fn test_fee_recipients() {
+        let caller = primitives::Address::with_last_byte(0xa1);
+        let coinbase = primitives::Address::with_last_byte(0xc0);
+        let vault = primitives::Address::with_last_byte(0xf0);
+        let mut db = crate::db::InMemoryDB::default();
+        db.insert_account_info(
+            caller,
+            primitives::AccountInfo::from_balance(primitives::U256::from(1_000_000_000u64)),
+        );
+
+        let mut evm = crate::Evm::<primitives::EthereumWiring<_, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_block_env(|block| {
+                block.coinbase = coinbase;
+                block.basefee = primitives::U256::from(10);
+            })
+            .modify_tx_env(|tx| {
+                tx.caller = caller;
+                tx.gas_limit = 21_000;
+                tx.gas_price = primitives::U256::from(13);
+            })
+            .append_handler_register_box(Box::new(move |handler| {
+                handler.post_execution.fee_recipients =
+                    Arc::new(move |ctx| handle_types::FeeRecipients {
+                        base_fee: Some(vault),
+                        ..mainnet::fee_recipients(ctx)
+                    })
+            }))
+            .build();
+        let state = evm.transact().unwrap().state;
+        assert_eq!(
+            state[&coinbase].info.balance,
+            primitives::U256::from(3 * 21_000)
+        );
+        assert_eq!(
+            state[&vault].info.balance,
+            primitives::U256::from(10 * 21_000)
+        );
+    }
+
+    #[test]
+    fn test_validation_with_chain_rules() {
+        let mut env = primitives::EnvWiring::<TestEvmWiring>::default();
+        env.tx.transact_to = primitives::TxKind::Create;
+        env.tx.gas_limit = 100_000;
+
+        let handler =
+            EvmHandler::<'_, TestEvmWiring>::mainnet_with_spec(primitives::SpecId::LATEST);
+        assert_eq!(handler.validation.initial_tx_gas(&env), Ok(53_000));
+
+        let rules = primitives::ChainRules {
+            create_tx_base_gas: 32_000,
+            ..primitives::ChainRules::new(primitives::SpecId::LATEST)
+        };
+        let validation =
+            ValidationHandler::<'_, TestEvmWiring>::with_rules::<primitives::LatestSpec>(rules);
+        assert_eq!(validation.initial_tx_gas(&env), Ok(32_000));
+
+        // Chain without a base cost and with cheaper calldata.
+        env.tx.transact_to = primitives::TxKind::Call(primitives::Address::ZERO);
+        env.tx.data = primitives::Bytes::from_static(&[0, 1, 2]);
+        assert_eq!(
+            handler.validation.initial_tx_gas(&env),
+            Ok(21_000 + 4 + 2 * 16)
+        );
+        let rules = primitives::ChainRules {
+            tx_base_gas: 0,
+            tx_data_non_zero_gas: 8,
+            ..primitives::ChainRules::new(primitives::SpecId::LATEST)
+        };
+        let validation =
+            ValidationHandler::<'_, TestEvmWiring>::with_rules::<primitives::LatestSpec>(rules);
+        assert_eq!(validation.initial_tx_gas(&env), Ok(4 + 2 * 8));
+    }
+
+    #[test]
+    fn test_refund_with_chain_rules() {
+        let mut context = crate::Context::<TestEvmWiring>::default();
+        let refund = |post_execution: &PostExecutionHandler<'_, TestEvmWiring>,
+                      context: &mut crate::Context<TestEvmWiring>| {
+            let mut gas = crate::interpreter::Gas::new(100_000);
+            assert!(gas.record_cost(50_000));
+            gas.record_refund(20_000);
+            post_execution.refund(context, &mut gas, 0);
+            gas.refunded()
+        };
+
+        let handler =
+            EvmHandler::<'_, TestEvmWiring>::mainnet_with_spec(primitives::SpecId::LATEST);
+        assert_eq!(refund(&handler.post_execution, &mut context), 10_000);
+
+        let rules = primitives::ChainRules {
+            gas_refunds: false,
+            ..primitives::ChainRules::new(primitives::SpecId::LATEST)
+        };
+        let post_execution = PostExecutionHandler::<'_, TestEvmWiring>::mainnet_with_rules::<
+            primitives::LatestSpec,
+        >(rules);
+        assert_eq!(refund(&post_execution, &mut context), 0);
+    }
 }
@@ -14,10 +14,11 @@ pub use execution::{
 };
 pub use generic::{GenericContextHandle, GenericContextHandleRet};
 pub use post_execution::{
-    EndHandle, OutputHandle, PostExecutionHandler, ReimburseCallerHandle, RewardBeneficiaryHandle,
+    EndHandle, FeeRecipients, FeeRecipientsHandle, OutputHandle, PostExecutionHandler,
+    ReimburseCallerHandle, RewardBeneficiaryHandle,
 };
 pub use pre_execution::{
-    DeductCallerHandle, LoadAccountsHandle, LoadPrecompilesHandle, PreExecutionHandler,
+    DeductCallerHandle, LoadAccountsHandle, LoadPrecompilesHandle, PreExecutionHandler, StartHandle,
 };
 pub use validation::{
     ValidateEnvHandle, ValidateInitialTxGasHandle, ValidateTxEnvAgainstState, ValidationHandler,
@@ -2,7 +2,7 @@ use crate::{
     frame::EOFCreateFrame,
     handler::mainnet,
     interpreter::{CallInputs, CreateInputs, SharedMemory},
-    primitives::{EVMResultGeneric, Spec},
+    primitives::{EVMResultGeneric, RuntimeSpec, Spec, SpecId},
     CallFrame, Context, CreateFrame, EvmWiring, Frame, FrameOrResult, FrameResult,
 };
 use revm_interpreter::{
@@ -148,6 +148,32 @@ impl<'a, EvmWiringT: EvmWiring + 'a> ExecutionHandler<'a, EvmWiringT> {
             insert_eofcreate_outcome: Arc::new(mainnet::insert_eofcreate_outcome),
         }
     }
+
+    /// Creates mainnet ExecutionHandler for the given spec.
+    ///
+    /// Unlike [`ExecutionHandler::new`], the handles are not compiled for every spec.
+    pub fn with_spec(spec_id: SpecId) -> Self {
+        Self {
+            // Handles that do not depend on the spec are compiled once for `RuntimeSpec`.
+            last_frame_return: Arc::new(mainnet::last_frame_return::<EvmWiringT, RuntimeSpec>),
+            execute_frame: Arc::new(mainnet::execute_frame::<EvmWiringT, RuntimeSpec>),
+            call: Arc::new(mainnet::call::<EvmWiringT, RuntimeSpec>),
+            call_return: Arc::new(mainnet::call_return::<EvmWiringT>),
+            insert_call_outcome: Arc::new(mainnet::insert_call_outcome),
+            create: Arc::new(move |context, inputs| {
+                mainnet::create_with_spec(spec_id, context, inputs)
+            }),
+            create_return: Arc::new(move |context, frame, interpreter_result| {
+                mainnet::create_return_with_spec(spec_id, context, frame, interpreter_result)
+            }),
+            insert_create_outcome: Arc::new(mainnet::insert_create_outcome),
+            eofcreate: Arc::new(move |context, inputs| {
+                mainnet::eofcreate_with_spec(spec_id, context, inputs)
+            }),
+            eofcreate_return: Arc::new(mainnet::eofcreate_return::<EvmWiringT, RuntimeSpec>),
+            insert_eofcreate_outcome: Arc::new(mainnet::insert_eofcreate_outcome),
+        }
+    }
 }
 
 impl<'a, EvmWiringT: EvmWiring> ExecutionHandler<'a, EvmWiringT> {
@@ -2,7 +2,7 @@
 use crate::{
     handler::mainnet,
     interpreter::Gas,
-    primitives::{EVMResult, EVMResultGeneric, ResultAndState, Spec},
+    primitives::{Address, ChainRules, EVMResult, EVMResultGeneric, ResultAndState, Spec, SpecId},
     Context, EvmWiring, FrameResult,
 };
 use std::sync::Arc;
@@ -11,8 +11,27 @@ use std::sync::Arc;
 pub type ReimburseCallerHandle<'a, EvmWiringT> =
     Arc<dyn Fn(&mut Context<EvmWiringT>, &Gas) -> EVMResultGeneric<(), EvmWiringT> + 'a>;
 
+/// Recipients of the fees paid by a transaction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FeeRecipients {
+    /// Recipient of the priority fee, the block coinbase on mainnet.
+    ///
+    /// Before London, it receives the whole fee.
+    pub priority_fee: Address,
+    /// Recipient of the base fee, for example the base fee vault of an L2.
+    ///
+    /// `None` burns the base fee, as on mainnet.
+    pub base_fee: Option<Address>,
+}
+
+/// Returns the recipients of the fees of the transaction.
+pub type FeeRecipientsHandle<'a, EvmWiringT> =
+    Arc<dyn Fn(&mut Context<EvmWiringT>) -> FeeRecipients + 'a>;
+
 /// Reward beneficiary with transaction rewards.
-pub type RewardBeneficiaryHandle<'a, EvmWiringT> = ReimburseCallerHandle<'a, EvmWiringT>;
+pub type RewardBeneficiaryHandle<'a, EvmWiringT> = Arc<
+    dyn Fn(&mut Context<EvmWiringT>, &Gas, &FeeRecipients) -> EVMResultGeneric<(), EvmWiringT> + 'a,
+>;
 
 /// Main return handle, takes state from journal and transforms internal result to external.
 pub type OutputHandle<'a, EvmWiringT> =
@@ -37,6 +56,8 @@ pub struct PostExecutionHandler<'a, EvmWiringT: EvmWiring> {
     pub refund: RefundHandle<'a, EvmWiringT>,
     /// Reimburse the caller with ethereum it didn't spend.
     pub reimburse_caller: ReimburseCallerHandle<'a, EvmWiringT>,
+    /// Returns the recipients of the fees, passed to the reward beneficiary handle.
+    pub fee_recipients: FeeRecipientsHandle<'a, EvmWiringT>,
     /// Reward the beneficiary with caller fee.
     pub reward_beneficiary: RewardBeneficiaryHandle<'a, EvmWiringT>,
     /// Main return handle, returns the output of the transact.
@@ -56,12 +77,43 @@ impl<'a, EvmWiringT: EvmWiring + 'a> PostExecutionHandler<'a, EvmWiringT> {
         Self {
             refund: Arc::new(mainnet::refund::<EvmWiringT, SPEC>),
             reimburse_caller: Arc::new(mainnet::reimburse_caller::<EvmWiringT>),
+            fee_recipients: Arc::new(mainnet::fee_recipients::<EvmWiringT>),
             reward_beneficiary: Arc::new(mainnet::reward_beneficiary::<EvmWiringT, SPEC>),
             output: Arc::new(mainnet::output::<EvmWiringT>),
             end: Arc::new(mainnet::end::<EvmWiringT>),
             clear: Arc::new(mainnet::clear::<EvmWiringT>),
         }
     }
+
+    /// Creates mainnet MainHandles that calculate the refund with the given chain rules.
+    pub fn mainnet_with_rules<SPEC: Spec + 'a>(rules: ChainRules) -> Self {
+        Self {
+            refund: Arc::new(move |context, gas, eip7702_refund| {
+                mainnet::refund_with_rules::<EvmWiringT>(&rules, context, gas, eip7702_refund)
+            }),
+            ..Self::mainnet::<SPEC>()
+        }
+    }
+
+    /// Creates mainnet MainHandles for the given spec and chain rules.
+    ///
+    /// Unlike [`PostExecutionHandler::mainnet_with_rules`], the handles are not compiled for
+    /// every spec.
+    pub fn mainnet_with_spec(spec_id: SpecId, rules: ChainRules) -> Self {
+        Self {
+            refund: Arc::new(move |context, gas, eip7702_refund| {
+                mainnet::refund_with_rules::<EvmWiringT>(&rules, context, gas, eip7702_refund)
+            }),
+            reimburse_caller: Arc::new(mainnet::reimburse_caller::<EvmWiringT>),
+            fee_recipients: Arc::new(mainnet::fee_recipients::<EvmWiringT>),
+            reward_beneficiary: Arc::new(move |context, gas, recipients| {
+                mainnet::reward_beneficiary_with_spec(spec_id, context, gas, recipients)
+            }),
+            output: Arc::new(mainnet::output::<EvmWiringT>),
+            end: Arc::new(mainnet::end::<EvmWiringT>),
+            clear: Arc::new(mainnet::clear::<EvmWiringT>),
+        }
+    }
 }
 
 impl<'a, EvmWiringT: EvmWiring> PostExecutionHandler<'a, EvmWiringT> {
@@ -78,13 +130,20 @@ impl<'a, EvmWiringT: EvmWiring> PostExecutionHandler<'a, EvmWiringT> {
     ) -> EVMResultGeneric<(), EvmWiringT> {
         (self.reimburse_caller)(context, gas)
     }
-    /// Reward beneficiary
+
+    /// Returns the recipients of the fees.
+    pub fn fee_recipients(&self, context: &mut Context<EvmWiringT>) -> FeeRecipients {
+        (self.fee_recipients)(context)
+    }
+
+    /// Reward the recipients of the fees.
     pub fn reward_beneficiary(
         &self,
         context: &mut Context<EvmWiringT>,
         gas: &Gas,
     ) -> EVMResultGeneric<(), EvmWiringT> {
-        (self.reward_beneficiary)(context, gas)
+        let recipients = self.fee_recipients(context);
+        (self.reward_beneficiary)(context, gas, &recipients)
     }
 
     /// Returns the output of transaction.
@@ -2,11 +2,14 @@
 use super::{GenericContextHandle, GenericContextHandleRet};
 use crate::{
     handler::mainnet,
-    primitives::{EVMResultGeneric, Spec},
+    primitives::{EVMResultGeneric, Spec, SpecId},
     Context, ContextPrecompiles, EvmWiring,
 };
 use std::sync::Arc;
 
+/// Called when a transaction starts, before it is validated.
+pub type StartHandle<'a, EvmWiringT> = GenericContextHandle<'a, EvmWiringT>;
+
 /// Loads precompiles into Evm
 pub type LoadPrecompilesHandle<'a, EvmWiringT> =
     Arc<dyn Fn() -> ContextPrecompiles<EvmWiringT> + 'a>;
@@ -24,6 +27,8 @@ pub type ApplyEIP7702AuthListHandle<'a, EvmWiringT> = GenericContextHandleRet<'a
 
 /// Handles related to pre execution before the stack loop is started.
 pub struct PreExecutionHandler<'a, EvmWiringT: EvmWiring> {
+    /// Called when the transaction starts.
+    pub start: StartHandle<'a, EvmWiringT>,
     /// Load precompiles
     pub load_precompiles: LoadPrecompilesHandle<'a, EvmWiringT>,
     /// Main load handle
@@ -38,15 +43,40 @@ impl<'a, EvmWiringT: EvmWiring + 'a> PreExecutionHandler<'a, EvmWiringT> {
     /// Creates mainnet MainHandles.
     pub fn new<SPEC: Spec + 'a>() -> Self {
         Self {
+            start: Arc::new(mainnet::start::<EvmWiringT>),
             load_precompiles: Arc::new(mainnet::load_precompiles::<EvmWiringT, SPEC>),
             load_accounts: Arc::new(mainnet::load_accounts::<EvmWiringT, SPEC>),
             deduct_caller: Arc::new(mainnet::deduct_caller::<EvmWiringT, SPEC>),
             apply_eip7702_auth_list: Arc::new(mainnet::apply_eip7702_auth_list::<EvmWiringT, SPEC>),
         }
     }
+
+    /// Creates mainnet MainHandles for the given spec.
+    ///
+    /// Unlike [`PreExecutionHandler::new`], the handles are not compiled for every spec.
+    pub fn with_spec(spec_id: SpecId) -> Self {
+        Self {
+            start: Arc::new(mainnet::start::<EvmWiringT>),
+            load_precompiles: Arc::new(move || mainnet::load_precompiles_with_spec(spec_id)),
+            load_accounts: Arc::new(move |context| {
+                mainnet::load_accounts_with_spec(spec_id, context)
+            }),
+            deduct_caller: Arc::new(move |context| {
+                mainnet::deduct_caller_with_spec(spec_id, context)
+            }),
+            apply_eip7702_auth_list: Arc::new(move |context| {
+                mainnet::apply_eip7702_auth_list_with_spec(spec_id, context)
+            }),
+        }
+    }
 }
 
 impl<'a, EvmWiringT: EvmWiring> PreExecutionHandler<'a, EvmWiringT> {
+    /// Called when the transaction starts.
+    pub fn start(&self, context: &mut Context<EvmWiringT>) -> EVMResultGeneric<(), EvmWiringT> {
+        (self.start)(context)
+    }
+
     /// Deduct caller to its limit.
     pub fn deduct_caller(
         &self,
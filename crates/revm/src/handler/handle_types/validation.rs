@@ -1,6 +1,9 @@
 use crate::{
     handler::mainnet,
-    primitives::{EVMResultGeneric, EnvWiring, InvalidTransaction, Spec, TransactionValidation},
+    primitives::{
+        ChainRules, EVMResultGeneric, EnvWiring, InvalidTransaction, Spec, SpecId,
+        TransactionValidation,
+    },
     Context, EvmWiring,
 };
 use std::sync::Arc;
@@ -18,6 +21,10 @@ pub type ValidateTxEnvAgainstState<'a, EvmWiringT> =
 pub type ValidateInitialTxGasHandle<'a, EvmWiringT> =
     Arc<dyn Fn(&EnvWiring<EvmWiringT>) -> EVMResultGeneric<u64, EvmWiringT> + 'a>;
 
+/// Handle that recovers the transaction signer and checks it against the caller.
+pub type ValidateSignatureHandle<'a, EvmWiringT> =
+    Arc<dyn Fn(&mut EnvWiring<EvmWiringT>) -> EVMResultGeneric<(), EvmWiringT> + 'a>;
+
 /// Handles related to validation.
 pub struct ValidationHandler<'a, EvmWiringT: EvmWiring> {
     /// Validate and calculate initial transaction gas.
@@ -26,6 +33,12 @@ pub struct ValidationHandler<'a, EvmWiringT: EvmWiring> {
     pub tx_against_state: ValidateTxEnvAgainstState<'a, EvmWiringT>,
     /// Validate Env.
     pub env: ValidateEnvHandle<'a, EvmWiringT>,
+    /// Recover and validate the transaction signer.
+    ///
+    /// Disabled by default, as the caller is expected to be recovered before execution.
+    /// Can be set to [`mainnet::validate_signature`] for transactions that implement
+    /// [`SignedTransaction`](crate::primitives::SignedTransaction).
+    pub signature: Option<ValidateSignatureHandle<'a, EvmWiringT>>,
 }
 
 impl<'a, EvmWiringT: EvmWiring + 'a> ValidationHandler<'a, EvmWiringT>
@@ -38,11 +51,54 @@ where
             initial_tx_gas: Arc::new(mainnet::validate_initial_tx_gas::<EvmWiringT, SPEC>),
             env: Arc::new(mainnet::validate_env::<EvmWiringT, SPEC>),
             tx_against_state: Arc::new(mainnet::validate_tx_against_state::<EvmWiringT, SPEC>),
+            signature: None,
+        }
+    }
+
+    /// Create new ValidationHandles that validate against the given chain rules.
+    pub fn with_rules<SPEC: Spec + 'a>(rules: ChainRules) -> Self {
+        Self {
+            initial_tx_gas: Arc::new(move |env| {
+                mainnet::validate_initial_tx_gas_with_rules::<EvmWiringT, SPEC>(&rules, env)
+            }),
+            env: Arc::new(move |env| {
+                mainnet::validate_env_with_rules::<EvmWiringT, SPEC>(&rules, env)
+            }),
+            tx_against_state: Arc::new(move |context| {
+                mainnet::validate_tx_against_state_with_rules::<EvmWiringT, SPEC>(&rules, context)
+            }),
+            signature: None,
+        }
+    }
+
+    /// Create new ValidationHandles that validate against the given spec and chain rules.
+    ///
+    /// Unlike [`ValidationHandler::with_rules`], the handles are not compiled for every spec.
+    pub fn with_spec(spec_id: SpecId, rules: ChainRules) -> Self {
+        Self {
+            initial_tx_gas: Arc::new(move |env| {
+                mainnet::validate_initial_tx_gas_with_spec::<EvmWiringT>(spec_id, &rules, env)
+            }),
+            env: Arc::new(move |env| {
+                mainnet::validate_env_with_spec::<EvmWiringT>(spec_id, &rules, env)
+            }),
+            tx_against_state: Arc::new(move |context| {
+                mainnet::validate_tx_against_state_with_spec::<EvmWiringT>(spec_id, &rules, context)
+            }),
+            signature: None,
         }
     }
 }
 
 impl<'a, EvmWiringT: EvmWiring> ValidationHandler<'a, EvmWiringT> {
+    /// Recover and validate the transaction signer, if enabled.
+    pub fn signature(&self, env: &mut EnvWiring<EvmWiringT>) -> EVMResultGeneric<(), EvmWiringT> {
+        match &self.signature {
+            Some(signature) => signature(env),
+            None => Ok(()),
+        }
+    }
+
     /// Validate env.
     pub fn env(&self, env: &EnvWiring<EvmWiringT>) -> EVMResultGeneric<(), EvmWiringT> {
         (self.env)(env)
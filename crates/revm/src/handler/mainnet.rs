@@ -8,11 +8,22 @@ mod validation;
 // Public exports
 
 pub use execution::{
-    call, call_return, create, create_return, eofcreate, eofcreate_return, execute_frame,
+    call, call_return, create, create_return, create_return_with_spec, create_with_spec, eofcreate,
+    eofcreate_return, eofcreate_with_spec, execute_frame, execute_frame_observed,
     insert_call_outcome, insert_create_outcome, insert_eofcreate_outcome, last_frame_return,
 };
-pub use post_execution::{clear, end, output, refund, reimburse_caller, reward_beneficiary};
+pub use post_execution::{
+    clear, end, fee_recipients, output, refund, refund_with_rules, reimburse_caller,
+    reward_beneficiary, reward_beneficiary_with_spec,
+};
 pub use pre_execution::{
-    apply_eip7702_auth_list, deduct_caller, deduct_caller_inner, load_accounts, load_precompiles,
+    apply_eip7702_auth_list, apply_eip7702_auth_list_with_spec, deduct_caller, deduct_caller_inner,
+    deduct_caller_inner_with_spec, deduct_caller_with_spec, load_accounts, load_accounts_with_spec,
+    load_precompiles, load_precompiles_with_spec, start,
+};
+pub use validation::{
+    validate_env, validate_env_with_rules, validate_env_with_spec, validate_initial_tx_gas,
+    validate_initial_tx_gas_with_rules, validate_initial_tx_gas_with_spec, validate_signature,
+    validate_signature_with_spec, validate_tx_against_state, validate_tx_against_state_with_rules,
+    validate_tx_against_state_with_spec,
 };
-pub use validation::{validate_env, validate_initial_tx_gas, validate_tx_against_state};
@@ -4,13 +4,13 @@ use crate::{
         return_ok, return_revert, CallInputs, CreateInputs, CreateOutcome, Gas, InstructionResult,
         SharedMemory,
     },
-    primitives::{EVMError, EVMResultGeneric, Spec, Transaction},
+    primitives::{EVMError, EVMResultGeneric, Spec, SpecId},
     CallFrame, Context, CreateFrame, EvmWiring, Frame, FrameOrResult, FrameResult,
 };
 use core::mem;
 use revm_interpreter::{
     opcode::InstructionTables, CallOutcome, EOFCreateInputs, InterpreterAction, InterpreterResult,
-    EMPTY_SHARED_MEMORY,
+    OpcodeObserver, EMPTY_SHARED_MEMORY,
 };
 use std::boxed::Box;
 
@@ -21,13 +21,33 @@ pub fn execute_frame<EvmWiringT: EvmWiring, SPEC: Spec>(
     shared_memory: &mut SharedMemory,
     instruction_tables: &InstructionTables<'_, Context<EvmWiringT>>,
     context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<InterpreterAction, EvmWiringT> {
+    execute_frame_observed(frame, shared_memory, instruction_tables, context, &mut ())
+}
+
+/// Execute frame, calling `observer` before every instruction.
+#[inline]
+pub fn execute_frame_observed<EvmWiringT: EvmWiring, O: OpcodeObserver + ?Sized>(
+    frame: &mut Frame,
+    shared_memory: &mut SharedMemory,
+    instruction_tables: &InstructionTables<'_, Context<EvmWiringT>>,
+    context: &mut Context<EvmWiringT>,
+    observer: &mut O,
 ) -> EVMResultGeneric<InterpreterAction, EvmWiringT> {
     let interpreter = frame.interpreter_mut();
     let memory = mem::replace(shared_memory, EMPTY_SHARED_MEMORY);
+    // The budget is taken out of the context so that it can be borrowed alongside it.
+    let mut budget = mem::take(&mut context.evm.budget);
+    let budget_ref = (!budget.is_unlimited()).then_some(&mut budget);
     let next_action = match instruction_tables {
-        InstructionTables::Plain(table) => interpreter.run(memory, table, context),
-        InstructionTables::Boxed(table) => interpreter.run(memory, table, context),
+        InstructionTables::Plain(table) => {
+            interpreter.run_observed(memory, table, context, budget_ref, observer)
+        }
+        InstructionTables::Boxed(table) => {
+            interpreter.run_observed(memory, table, context, budget_ref, observer)
+        }
     };
+    context.evm.budget = budget;
     // Take the shared memory back.
     *shared_memory = interpreter.take_memory();
 
@@ -46,7 +66,13 @@ pub fn last_frame_return<EvmWiringT: EvmWiring, SPEC: Spec>(
     let refunded = gas.refunded();
 
     // Spend the gas limit. Gas is reimbursed when the tx returns successfully.
-    *gas = Gas::new_spent(context.evm.env.tx.gas_limit());
+    *gas = Gas::new_spent(context.evm.env.effective_gas_limit());
+
+    // Without gas metering, only the gas that was spent is reported.
+    if context.evm.env.cfg.is_gas_metering_disabled() {
+        gas.erase_cost(remaining);
+        return Ok(());
+    }
 
     match instruction_result {
         return_ok!() => {
@@ -106,20 +132,41 @@ pub fn insert_call_outcome<EvmWiringT: EvmWiring>(
 pub fn create<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
     inputs: Box<CreateInputs>,
+) -> EVMResultGeneric<FrameOrResult, EvmWiringT> {
+    create_with_spec(SPEC::SPEC_ID, context, inputs)
+}
+
+/// Handle frame sub create in the given spec.
+#[inline]
+pub fn create_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    context: &mut Context<EvmWiringT>,
+    inputs: Box<CreateInputs>,
 ) -> EVMResultGeneric<FrameOrResult, EvmWiringT> {
     context
         .evm
-        .make_create_frame(SPEC::SPEC_ID, &inputs)
+        .make_create_frame(spec_id, &inputs)
         .map_err(EVMError::Database)
 }
 
 #[inline]
 pub fn create_return<EvmWiringT: EvmWiring, SPEC: Spec>(
+    context: &mut Context<EvmWiringT>,
+    frame: Box<CreateFrame>,
+    interpreter_result: InterpreterResult,
+) -> EVMResultGeneric<CreateOutcome, EvmWiringT> {
+    create_return_with_spec(SPEC::SPEC_ID, context, frame, interpreter_result)
+}
+
+#[inline]
+pub fn create_return_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
     context: &mut Context<EvmWiringT>,
     frame: Box<CreateFrame>,
     mut interpreter_result: InterpreterResult,
 ) -> EVMResultGeneric<CreateOutcome, EvmWiringT> {
-    context.evm.create_return::<SPEC>(
+    context.evm.create_return_with_spec(
+        spec_id,
         &mut interpreter_result,
         frame.created_address,
         frame.frame_data.checkpoint,
@@ -150,10 +197,20 @@ pub fn insert_create_outcome<EvmWiringT: EvmWiring>(
 pub fn eofcreate<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
     inputs: Box<EOFCreateInputs>,
+) -> EVMResultGeneric<FrameOrResult, EvmWiringT> {
+    eofcreate_with_spec(SPEC::SPEC_ID, context, inputs)
+}
+
+/// Handle frame sub create in the given spec.
+#[inline]
+pub fn eofcreate_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    context: &mut Context<EvmWiringT>,
+    inputs: Box<EOFCreateInputs>,
 ) -> EVMResultGeneric<FrameOrResult, EvmWiringT> {
     context
         .evm
-        .make_eofcreate_frame(SPEC::SPEC_ID, &inputs)
+        .make_eofcreate_frame(spec_id, &inputs)
         .map_err(EVMError::Database)
 }
 
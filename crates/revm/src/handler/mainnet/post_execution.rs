@@ -1,8 +1,11 @@
 use crate::{
+    handler::FeeRecipients,
     interpreter::{Gas, SuccessOrHalt},
     primitives::{
-        Block, EVMError, EVMResult, EVMResultGeneric, ExecutionResult, ResultAndState, Spec,
-        SpecId, SpecId::LONDON, Transaction, U256,
+        Block, ChainRules, EVMError, EVMResult, EVMResultGeneric, ExecutionResult, ResultAndState,
+        Spec,
+        SpecId::{self, LONDON},
+        Transaction, U256,
     },
     Context, EvmWiring, FrameResult,
 };
@@ -19,33 +22,63 @@ pub fn end<EvmWiringT: EvmWiring>(
 /// Clear handle clears error and journal state.
 #[inline]
 pub fn clear<EvmWiringT: EvmWiring>(context: &mut Context<EvmWiringT>) {
-    // clear error and journaled state.
+    // clear error, halt location, precompile failure and journaled state.
     let _ = context.evm.take_error();
+    context.evm.inner.halt_location = None;
+    context.evm.inner.precompile_failure = None;
     context.evm.inner.journaled_state.clear();
 }
 
+/// Mainnet fee recipients: the priority fee goes to the block coinbase and the base fee is
+/// burned.
+#[inline]
+pub fn fee_recipients<EvmWiringT: EvmWiring>(context: &mut Context<EvmWiringT>) -> FeeRecipients {
+    FeeRecipients {
+        priority_fee: *context.evm.env.block.coinbase(),
+        base_fee: None,
+    }
+}
+
 /// Reward beneficiary with gas fee.
 #[inline]
 pub fn reward_beneficiary<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
     gas: &Gas,
+    recipients: &FeeRecipients,
+) -> EVMResultGeneric<(), EvmWiringT> {
+    reward_beneficiary_with_spec(SPEC::SPEC_ID, context, gas, recipients)
+}
+
+/// Reward beneficiary with gas fee in the given spec.
+#[inline]
+pub fn reward_beneficiary_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    context: &mut Context<EvmWiringT>,
+    gas: &Gas,
+    recipients: &FeeRecipients,
 ) -> EVMResultGeneric<(), EvmWiringT> {
-    let beneficiary = *context.evm.env.block.coinbase();
+    // System transactions do not pay for gas.
+    if context.evm.env.cfg.is_gas_metering_disabled() || context.evm.env.tx.is_system() {
+        return Ok(());
+    }
     let effective_gas_price = context.evm.env.effective_gas_price();
+    let gas_used = U256::from(gas.spent() - gas.refunded() as u64);
 
     // transfer fee to coinbase/beneficiary.
-    // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded.
-    let coinbase_gas_price = if SPEC::enabled(LONDON) {
-        effective_gas_price.saturating_sub(*context.evm.env.block.basefee())
+    // EIP-1559 discard basefee for coinbase transfer. Basefee amount of gas is discarded,
+    // unless it has a recipient.
+    let (coinbase_gas_price, base_fee) = if spec_id.is_enabled_in(LONDON) {
+        let basefee = *context.evm.env.block.basefee();
+        (effective_gas_price.saturating_sub(basefee), basefee)
     } else {
-        effective_gas_price
+        (effective_gas_price, U256::ZERO)
     };
 
     let coinbase_account = context
         .evm
         .inner
         .journaled_state
-        .load_account(beneficiary, &mut context.evm.inner.db)
+        .load_account(recipients.priority_fee, &mut context.evm.inner.db)
         .map_err(EVMError::Database)?;
 
     coinbase_account.data.mark_touch();
@@ -53,22 +86,63 @@ pub fn reward_beneficiary<EvmWiringT: EvmWiring, SPEC: Spec>(
         .data
         .info
         .balance
-        .saturating_add(coinbase_gas_price * U256::from(gas.spent() - gas.refunded() as u64));
+        .saturating_add(coinbase_gas_price * gas_used);
+
+    if let Some(base_fee_recipient) = recipients.base_fee {
+        let base_fee_account = context
+            .evm
+            .inner
+            .journaled_state
+            .load_account(base_fee_recipient, &mut context.evm.inner.db)
+            .map_err(EVMError::Database)?;
+
+        base_fee_account.data.mark_touch();
+        base_fee_account.data.info.balance = base_fee_account
+            .data
+            .info
+            .balance
+            .saturating_add(base_fee * gas_used);
+    }
 
     Ok(())
 }
 
 pub fn refund<EvmWiringT: EvmWiring, SPEC: Spec>(
-    _context: &mut Context<EvmWiringT>,
+    context: &mut Context<EvmWiringT>,
+    gas: &mut Gas,
+    eip7702_refund: i64,
+) {
+    refund_with_rules(
+        &ChainRules::new(SPEC::SPEC_ID),
+        context,
+        gas,
+        eip7702_refund,
+    )
+}
+
+/// Calculates the final refund with the given chain rules.
+///
+/// No refund is given if the rules or the configuration disable gas refunds.
+pub fn refund_with_rules<EvmWiringT: EvmWiring>(
+    rules: &ChainRules,
+    context: &mut Context<EvmWiringT>,
     gas: &mut Gas,
     eip7702_refund: i64,
 ) {
+    if context.evm.env.cfg.is_gas_metering_disabled() {
+        gas.set_refund(0);
+        return;
+    }
+    if !rules.gas_refunds || context.evm.env.cfg.is_gas_refund_disabled() {
+        gas.set_refund(0);
+        return;
+    }
     gas.record_refund(eip7702_refund);
 
     // Calculate gas refund for transaction.
-    // If spec is set to london, it will decrease the maximum refund amount to 5th part of
-    // gas spend. (Before london it was 2th part of gas spend)
-    gas.set_final_refund(SPEC::SPEC_ID.is_enabled_in(SpecId::LONDON));
+    // Since london the maximum refund amount is 5th part of gas spend.
+    // (Before london it was 2th part of gas spend)
+    gas.set_final_refund_with_quotient(rules.max_refund_quotient);
 }
 
 #[inline]
@@ -76,6 +150,10 @@ pub fn reimburse_caller<EvmWiringT: EvmWiring>(
     context: &mut Context<EvmWiringT>,
     gas: &Gas,
 ) -> EVMResultGeneric<(), EvmWiringT> {
+    // System transactions do not pay for gas.
+    if context.evm.env.cfg.is_gas_metering_disabled() || context.evm.env.tx.is_system() {
+        return Ok(());
+    }
     let caller = *context.evm.env.tx.caller();
     let effective_gas_price = context.evm.env.effective_gas_price();
 
@@ -108,9 +186,17 @@ pub fn output<EvmWiringT: EvmWiring>(
     let final_gas_used = result.gas().spent() - gas_refunded;
     let output = result.output();
     let instruction_result = result.into_interpreter_result();
+    let halt_location = context.evm.halt_location.take();
 
     // reset journal and return present state.
-    let (state, logs) = context.evm.journaled_state.finalize();
+    let (mut state, logs) = context.evm.journaled_state.finalize();
+    if context.evm.env.cfg.reuse_warm_accesses {
+        context.evm.warm_accesses.record(&state);
+    }
+    // Read-only executions do not return their state, so committing it is a no-op.
+    if context.evm.env.cfg.read_only {
+        state.clear();
+    }
 
     let result = match SuccessOrHalt::<EvmWiringT>::from(instruction_result.result) {
         SuccessOrHalt::Success(reason) => ExecutionResult::Success {
@@ -127,6 +213,7 @@ pub fn output<EvmWiringT: EvmWiring>(
         SuccessOrHalt::Halt(reason) => ExecutionResult::Halt {
             reason,
             gas_used: final_gas_used,
+            location: halt_location,
         },
         // Only two internal return flags.
         flag @ (SuccessOrHalt::FatalExternalError | SuccessOrHalt::Internal(_)) => {
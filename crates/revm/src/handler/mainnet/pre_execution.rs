@@ -11,23 +11,48 @@ use crate::{
     Context, ContextPrecompiles, EvmWiring,
 };
 
+/// Mainnet start handle does nothing.
+#[inline]
+pub fn start<EvmWiringT: EvmWiring>(
+    _context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT> {
+    Ok(())
+}
+
 /// Main precompile load
 #[inline]
 pub fn load_precompiles<EvmWiringT: EvmWiring, SPEC: Spec>() -> ContextPrecompiles<EvmWiringT> {
-    ContextPrecompiles::new(PrecompileSpecId::from_spec_id(SPEC::SPEC_ID))
+    load_precompiles_with_spec(SPEC::SPEC_ID)
+}
+
+/// Main precompile load in the given spec.
+#[inline]
+pub fn load_precompiles_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+) -> ContextPrecompiles<EvmWiringT> {
+    ContextPrecompiles::new(PrecompileSpecId::from_spec_id(spec_id))
 }
 
 /// Main load handle
 #[inline]
 pub fn load_accounts<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT> {
+    load_accounts_with_spec(SPEC::SPEC_ID, context)
+}
+
+/// Main load handle in the given spec.
+#[inline]
+pub fn load_accounts_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    context: &mut Context<EvmWiringT>,
 ) -> EVMResultGeneric<(), EvmWiringT> {
     // set journaling state flag.
-    context.evm.journaled_state.set_spec_id(SPEC::SPEC_ID);
+    context.evm.journaled_state.set_spec_id(spec_id);
 
     // load coinbase
     // EIP-3651: Warm COINBASE. Starts the `COINBASE` address warm
-    if SPEC::enabled(SpecId::SHANGHAI) {
+    if spec_id.is_enabled_in(SpecId::SHANGHAI) {
         let coinbase = *context.evm.inner.env.block.coinbase();
         context
             .evm
@@ -38,7 +63,7 @@ pub fn load_accounts<EvmWiringT: EvmWiring, SPEC: Spec>(
 
     // Load blockhash storage address
     // EIP-2935: Serve historical block hashes from state
-    if SPEC::enabled(SpecId::PRAGUE) {
+    if spec_id.is_enabled_in(SpecId::PRAGUE) {
         context
             .evm
             .journaled_state
@@ -46,6 +71,27 @@ pub fn load_accounts<EvmWiringT: EvmWiring, SPEC: Spec>(
             .insert(BLOCKHASH_STORAGE_ADDRESS);
     }
 
+    // Load the addresses that the chain configures as warm.
+    let warm_addresses = context.evm.inner.env.cfg.warm_addresses.iter().copied();
+    context
+        .evm
+        .inner
+        .journaled_state
+        .warm_preloaded_addresses
+        .extend(warm_addresses);
+
+    // Load the accesses of the previous transaction.
+    if context.evm.inner.env.cfg.reuse_warm_accesses {
+        let inner = &mut context.evm.inner;
+        let accesses = &inner.warm_accesses;
+        inner
+            .journaled_state
+            .preload_accounts(accesses.addresses.iter().copied());
+        inner
+            .journaled_state
+            .preload_storage(accesses.storage.iter().copied());
+    }
+
     // Load access list
     context.evm.load_access_list().map_err(EVMError::Database)?;
     Ok(())
@@ -57,18 +103,35 @@ pub fn deduct_caller_inner<EvmWiringT: EvmWiring, SPEC: Spec>(
     caller_account: &mut Account,
     env: &EnvWiring<EvmWiringT>,
 ) {
-    // Subtract gas costs from the caller's account.
-    // We need to saturate the gas cost to prevent underflow in case that `disable_balance_check` is enabled.
-    let mut gas_cost = U256::from(env.tx.gas_limit()).saturating_mul(env.effective_gas_price());
-
-    // EIP-4844
-    if SPEC::enabled(SpecId::CANCUN) {
-        let data_fee = env.calc_data_fee().expect("already checked");
-        gas_cost = gas_cost.saturating_add(data_fee);
+    deduct_caller_inner_with_spec::<EvmWiringT>(SPEC::SPEC_ID, caller_account, env)
+}
+
+/// Helper function that deducts the caller balance in the given spec.
+#[inline]
+pub fn deduct_caller_inner_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    caller_account: &mut Account,
+    env: &EnvWiring<EvmWiringT>,
+) {
+    // System transactions leave the caller untouched.
+    if env.tx.is_system() {
+        return;
     }
 
-    // set new caller account balance.
-    caller_account.info.balance = caller_account.info.balance.saturating_sub(gas_cost);
+    // Subtract gas costs from the caller's account, unless gas is not metered.
+    if !env.cfg.is_gas_metering_disabled() {
+        // We need to saturate the gas cost to prevent underflow in case that `disable_balance_check` is enabled.
+        let mut gas_cost = U256::from(env.tx.gas_limit()).saturating_mul(env.effective_gas_price());
+
+        // EIP-4844
+        if spec_id.is_enabled_in(SpecId::CANCUN) {
+            let data_fee = env.calc_data_fee().expect("already checked");
+            gas_cost = gas_cost.saturating_add(data_fee);
+        }
+
+        // set new caller account balance.
+        caller_account.info.balance = caller_account.info.balance.saturating_sub(gas_cost);
+    }
 
     // bump the nonce for calls. Nonce for CREATE will be bumped in `handle_create`.
     if env.tx.kind().is_call() {
@@ -84,6 +147,15 @@ pub fn deduct_caller_inner<EvmWiringT: EvmWiring, SPEC: Spec>(
 #[inline]
 pub fn deduct_caller<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT> {
+    deduct_caller_with_spec(SPEC::SPEC_ID, context)
+}
+
+/// Deducts the caller balance to the transaction limit in the given spec.
+#[inline]
+pub fn deduct_caller_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    context: &mut Context<EvmWiringT>,
 ) -> EVMResultGeneric<(), EvmWiringT> {
     // load caller's account.
     let caller_account = context
@@ -97,7 +169,11 @@ pub fn deduct_caller<EvmWiringT: EvmWiring, SPEC: Spec>(
         .map_err(EVMError::Database)?;
 
     // deduct gas cost from caller's account.
-    deduct_caller_inner::<EvmWiringT, SPEC>(caller_account.data, &context.evm.inner.env);
+    deduct_caller_inner_with_spec::<EvmWiringT>(
+        spec_id,
+        caller_account.data,
+        &context.evm.inner.env,
+    );
 
     Ok(())
 }
@@ -106,9 +182,18 @@ pub fn deduct_caller<EvmWiringT: EvmWiring, SPEC: Spec>(
 #[inline]
 pub fn apply_eip7702_auth_list<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<u64, EvmWiringT> {
+    apply_eip7702_auth_list_with_spec(SPEC::SPEC_ID, context)
+}
+
+/// Apply EIP-7702 auth list in the given spec, see [`apply_eip7702_auth_list`].
+#[inline]
+pub fn apply_eip7702_auth_list_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    context: &mut Context<EvmWiringT>,
 ) -> EVMResultGeneric<u64, EvmWiringT> {
     // EIP-7702. Load bytecode to authorized accounts.
-    if !SPEC::enabled(PRAGUE) {
+    if !spec_id.is_enabled_in(PRAGUE) {
         return Ok(0);
     }
 
@@ -125,9 +210,19 @@ pub fn apply_eip7702_auth_list<EvmWiringT: EvmWiring, SPEC: Spec>(
             continue;
         };
 
+        // EIP-2681: the nonce of the authority can't be increased past its maximum.
+        if authorization.nonce() == u64::MAX {
+            continue;
+        }
+
         // 2. Verify the chain id is either 0 or the chain's current ID.
         if !authorization.chain_id().is_zero()
-            && authorization.chain_id() != U256::from(context.evm.inner.env.cfg.chain_id)
+            && !context
+                .evm
+                .inner
+                .env
+                .cfg
+                .is_chain_id(authorization.chain_id())
         {
             continue;
         }
@@ -160,7 +255,12 @@ pub fn apply_eip7702_auth_list<EvmWiringT: EvmWiring, SPEC: Spec>(
         }
 
         // 7. Set the code of authority to be 0xef0100 || address. This is a delegation designation.
-        let bytecode = Bytecode::new_eip7702(authorization.address);
+        // A delegation to the zero address clears the code of the authority instead.
+        let bytecode = if authorization.address.is_zero() {
+            Bytecode::default()
+        } else {
+            Bytecode::new_eip7702(authorization.address)
+        };
         authority_acc.info.code_hash = bytecode.hash_slow();
         authority_acc.info.code = Some(bytecode);
 
@@ -2,8 +2,8 @@ use revm_interpreter::gas;
 
 use crate::{
     primitives::{
-        EVMError, EVMResultGeneric, EnvWiring, InvalidTransaction, Spec, Transaction,
-        TransactionValidation,
+        ChainRules, EVMError, EVMResultGeneric, EnvWiring, InvalidTransaction, SignedTransaction,
+        Spec, SpecId, Transaction, TransactionValidation,
     },
     Context, EvmWiring,
 };
@@ -12,20 +12,94 @@ use crate::{
 pub fn validate_env<EvmWiringT: EvmWiring, SPEC: Spec>(
     env: &EnvWiring<EvmWiringT>,
 ) -> EVMResultGeneric<(), EvmWiringT>
+where
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_env_with_rules::<EvmWiringT, SPEC>(&ChainRules::new(SPEC::SPEC_ID), env)
+}
+
+/// Validate environment for the mainnet with the given chain rules.
+pub fn validate_env_with_rules<EvmWiringT: EvmWiring, SPEC: Spec>(
+    rules: &ChainRules,
+    env: &EnvWiring<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT>
+where
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_env_with_spec::<EvmWiringT>(SPEC::SPEC_ID, rules, env)
+}
+
+/// Validate environment for the mainnet with the given spec and chain rules.
+pub fn validate_env_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    rules: &ChainRules,
+    env: &EnvWiring<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT>
 where
     <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
 {
     // Important: validate block before tx.
-    env.validate_block_env::<SPEC>()?;
-    env.validate_tx::<SPEC>()
+    env.validate_block_env_with_spec(spec_id)?;
+    env.validate_tx_with_spec(spec_id, rules)
         .map_err(|error| EVMError::Transaction(error.into()))?;
     Ok(())
 }
 
+/// Recovers the transaction signer and checks it against the caller, setting the caller
+/// if it is not set.
+pub fn validate_signature<EvmWiringT: EvmWiring, SPEC: Spec>(
+    env: &mut EnvWiring<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT>
+where
+    EvmWiringT::Transaction: SignedTransaction,
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_signature_with_spec::<EvmWiringT>(SPEC::SPEC_ID, env)
+}
+
+/// Recovers the transaction signer in the given spec, see [`validate_signature`].
+pub fn validate_signature_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    env: &mut EnvWiring<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT>
+where
+    EvmWiringT::Transaction: SignedTransaction,
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    env.validate_signature_with_spec(spec_id)
+        .map_err(|error| EVMError::Transaction(error.into()))
+}
+
 /// Validates transaction against the state.
 pub fn validate_tx_against_state<EvmWiringT: EvmWiring, SPEC: Spec>(
     context: &mut Context<EvmWiringT>,
 ) -> EVMResultGeneric<(), EvmWiringT>
+where
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_tx_against_state_with_rules::<EvmWiringT, SPEC>(
+        &ChainRules::new(SPEC::SPEC_ID),
+        context,
+    )
+}
+
+/// Validates transaction against the state with the given chain rules.
+pub fn validate_tx_against_state_with_rules<EvmWiringT: EvmWiring, SPEC: Spec>(
+    rules: &ChainRules,
+    context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT>
+where
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_tx_against_state_with_spec::<EvmWiringT>(SPEC::SPEC_ID, rules, context)
+}
+
+/// Validates transaction against the state with the given spec and chain rules.
+pub fn validate_tx_against_state_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    rules: &ChainRules,
+    context: &mut Context<EvmWiringT>,
+) -> EVMResultGeneric<(), EvmWiringT>
 where
     <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
 {
@@ -42,7 +116,7 @@ where
         .evm
         .inner
         .env
-        .validate_tx_against_state::<SPEC>(caller_account.data)
+        .validate_tx_against_state_with_spec(spec_id, caller_account.data, rules)
         .map_err(|e| EVMError::Transaction(e.into()))?;
 
     Ok(())
@@ -52,6 +126,29 @@ where
 pub fn validate_initial_tx_gas<EvmWiringT: EvmWiring, SPEC: Spec>(
     env: &EnvWiring<EvmWiringT>,
 ) -> EVMResultGeneric<u64, EvmWiringT>
+where
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_initial_tx_gas_with_rules::<EvmWiringT, SPEC>(&ChainRules::new(SPEC::SPEC_ID), env)
+}
+
+/// Validate initial transaction gas with the given chain rules.
+pub fn validate_initial_tx_gas_with_rules<EvmWiringT: EvmWiring, SPEC: Spec>(
+    rules: &ChainRules,
+    env: &EnvWiring<EvmWiringT>,
+) -> EVMResultGeneric<u64, EvmWiringT>
+where
+    <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
+{
+    validate_initial_tx_gas_with_spec::<EvmWiringT>(SPEC::SPEC_ID, rules, env)
+}
+
+/// Validate initial transaction gas with the given spec and chain rules.
+pub fn validate_initial_tx_gas_with_spec<EvmWiringT: EvmWiring>(
+    spec_id: SpecId,
+    rules: &ChainRules,
+    env: &EnvWiring<EvmWiringT>,
+) -> EVMResultGeneric<u64, EvmWiringT>
 where
     <EvmWiringT::Transaction as TransactionValidation>::ValidationError: From<InvalidTransaction>,
 {
@@ -65,8 +162,9 @@ where
         .map(|l| l.len() as u64)
         .unwrap_or_default();
 
-    let initial_gas_spend = gas::validate_initial_tx_gas(
-        SPEC::SPEC_ID,
+    let initial_gas_spend = gas::validate_initial_tx_gas_with_rules(
+        spec_id,
+        rules,
         input,
         is_create,
         access_list,
@@ -74,7 +172,7 @@ where
     );
 
     // Additional check to see if limit is big enough to cover initial gas.
-    if initial_gas_spend > env.tx.gas_limit() {
+    if initial_gas_spend > env.effective_gas_limit() {
         return Err(EVMError::Transaction(
             InvalidTransaction::CallGasCostMoreThanGasLimit.into(),
         ));
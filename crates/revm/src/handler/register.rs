@@ -1,21 +1,39 @@
-use crate::{handler::Handler, Context, EvmWiring};
-use std::boxed::Box;
+use crate::{
+    handler::{mainnet, Handler},
+    interpreter::OpcodeObserver,
+    Context, EvmWiring,
+};
+use core::mem;
+use std::{boxed::Box, sync::Arc};
 
 /// EVM Handler
 pub type EvmHandler<'a, EvmWiringT> = Handler<'a, EvmWiringT, Context<EvmWiringT>>;
 
-// Handle register
+/// Handle register.
+///
+/// Registers are applied in the order they are appended. A register can override a stage
+/// handle by assigning a new one, or decorate it with [decorate] so that the handles of the
+/// previous registers are still called.
 pub type HandleRegister<EvmWiringT> = for<'a> fn(&mut EvmHandler<'a, EvmWiringT>);
 
-// Boxed handle register
+/// Boxed handle register, that can capture its environment.
 pub type HandleRegisterBox<'a, EvmWiringT> =
     Box<dyn for<'e> Fn(&mut EvmHandler<'e, EvmWiringT>) + 'a>;
 
+/// Identifier of a handle register, to find it among the registers of a handler.
+///
+/// See [`EvmHandler::remove_handle_register`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HandleRegisterId(pub &'static str);
+
+/// Handle register appended to the [EvmHandler].
 pub enum HandleRegisters<'a, EvmWiringT: EvmWiring> {
     /// Plain function register
     Plain(HandleRegister<EvmWiringT>),
     /// Boxed function register.
     Box(HandleRegisterBox<'a, EvmWiringT>),
+    /// Boxed function register with an identifier.
+    Identified(HandleRegisterId, HandleRegisterBox<'a, EvmWiringT>),
 }
 
 impl<'register, EvmWiringT: EvmWiring> HandleRegisters<'register, EvmWiringT> {
@@ -26,7 +44,100 @@ impl<'register, EvmWiringT: EvmWiring> HandleRegisters<'register, EvmWiringT> {
     {
         match self {
             HandleRegisters::Plain(f) => f(handler),
-            HandleRegisters::Box(f) => f(handler),
+            HandleRegisters::Box(f) | HandleRegisters::Identified(_, f) => f(handler),
+        }
+    }
+
+    /// Returns the identifier of the register, if it has one.
+    pub fn id(&self) -> Option<HandleRegisterId> {
+        match self {
+            HandleRegisters::Identified(id, _) => Some(*id),
+            _ => None,
         }
     }
 }
+
+/// Provides access to the [OpcodeObserver] of the external context.
+pub trait GetOpcodeObserver {
+    /// Observer called before every instruction.
+    type Observer: OpcodeObserver + Default;
+
+    /// Returns the observer.
+    fn opcode_observer(&mut self) -> &mut Self::Observer;
+}
+
+impl<O: OpcodeObserver + Default> GetOpcodeObserver for O {
+    type Observer = O;
+
+    #[inline]
+    fn opcode_observer(&mut self) -> &mut Self::Observer {
+        self
+    }
+}
+
+/// Replaces the execution of frames with one that calls the [OpcodeObserver] of the external
+/// context before every instruction.
+///
+/// The instruction table is left untouched, so an observer like
+/// [OpcodeCounter](crate::interpreter::OpcodeCounter) costs no dynamic dispatch per
+/// instruction, unlike an inspector. The observer is taken out of the context while a frame
+/// runs, and must be appended before the registers that decorate the execution of frames.
+pub fn opcode_observer_handle_register<
+    EvmWiringT: EvmWiring<ExternalContext: GetOpcodeObserver>,
+>(
+    handler: &mut EvmHandler<'_, EvmWiringT>,
+) {
+    handler.execution.execute_frame =
+        Arc::new(|frame, shared_memory, instruction_tables, context| {
+            // The observer is taken out of the context so that it can be borrowed alongside it.
+            let mut observer = mem::take(context.external.opcode_observer());
+            let next_action = mainnet::execute_frame_observed(
+                frame,
+                shared_memory,
+                instruction_tables,
+                context,
+                &mut observer,
+            );
+            *context.external.opcode_observer() = observer;
+            next_action
+        });
+}
+
+/// Returns a handle register that wraps every instruction so that it increments `counter`.
+#[cfg(any(feature = "std", feature = "metrics"))]
+pub(crate) fn instruction_counter_register<'a, EvmWiringT: EvmWiring>(
+    counter: std::rc::Rc<core::cell::Cell<u64>>,
+) -> HandleRegisterBox<'a, EvmWiringT> {
+    Box::new(move |handler: &mut EvmHandler<'_, EvmWiringT>| {
+        for instruction in handler.instruction_table.to_boxed().iter_mut() {
+            let counter = counter.clone();
+            crate::interpreter::opcode::update_boxed_instruction(
+                instruction,
+                move |prev, interpreter, host| {
+                    counter.set(counter.get() + 1);
+                    prev(interpreter, host)
+                },
+            );
+        }
+    })
+}
+
+/// Replaces `handle` with the handle returned by `wrap`, which is given the previous handle.
+///
+/// The handle of the last register that decorates a stage is called first, and decides if
+/// and when the handles of the previous registers are called.
+///
+/// # Example
+///
+/// ```ignore
+/// decorate(&mut handler.post_execution.reward_beneficiary, |prev| {
+///     Arc::new(move |ctx, gas, recipients| {
+///         prev(ctx, gas, recipients)?;
+///         // pay the L1 fee vault
+///         Ok(())
+///     })
+/// });
+/// ```
+pub fn decorate<H: Clone>(handle: &mut H, wrap: impl FnOnce(H) -> H) {
+    *handle = wrap(handle.clone());
+}
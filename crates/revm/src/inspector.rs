@@ -3,16 +3,20 @@ mod customprinter;
 #[cfg(all(feature = "std", feature = "serde-json"))]
 mod eip3155;
 mod gas;
+mod gas_breakdown;
 mod handler_register;
 mod noop;
+mod profiler;
+mod storage_recorder;
 
 pub use handler_register::{inspector_handle_register, GetInspector};
 
 use crate::{
     interpreter::{
         CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, Interpreter,
+        MemorySnapshotMode,
     },
-    primitives::{Address, Log, U256},
+    primitives::{Address, Log, ResultAndState, U256},
     EvmContext, EvmWiring,
 };
 use auto_impl::auto_impl;
@@ -20,16 +24,39 @@ use auto_impl::auto_impl;
 /// [Inspector] implementations.
 pub mod inspectors {
     #[cfg(feature = "std")]
-    pub use super::customprinter::CustomPrintTracer;
+    pub use super::customprinter::{AbiRegistry, CustomPrintTracer};
     #[cfg(all(feature = "std", feature = "serde-json"))]
     pub use super::eip3155::TracerEip3155;
     pub use super::gas::GasInspector;
+    pub use super::gas_breakdown::{GasBreakdown, GasBreakdownInspector};
     pub use super::noop::NoOpInspector;
+    pub use super::profiler::{Hotspot, OpcodeProfiler, SiteProfile};
+    pub use super::storage_recorder::{StorageAccess, StorageAccessKind, StorageRecorder};
 }
 
 /// EVM [Interpreter] callbacks.
 #[auto_impl(&mut, Box)]
 pub trait Inspector<EvmWiringT: EvmWiring> {
+    /// Called when a transaction starts, before it is validated.
+    #[inline]
+    fn transact_start(&mut self, context: &mut EvmContext<EvmWiringT>) {
+        let _ = context;
+    }
+
+    /// Called when a transaction ends successfully, with its result and the final state
+    /// before it is committed to the database.
+    ///
+    /// Not called if the transaction is invalid or the execution fails with an error.
+    #[inline]
+    fn transact_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        output: &ResultAndState<EvmWiringT::HaltReason>,
+    ) {
+        let _ = context;
+        let _ = output;
+    }
+
     /// Called before the interpreter is initialized.
     ///
     /// If `interp.instruction_result` is set to anything other than [crate::interpreter::InstructionResult::Continue] then the execution of the interpreter
@@ -58,6 +85,15 @@ pub trait Inspector<EvmWiringT: EvmWiring> {
         let _ = context;
     }
 
+    /// Returns the part of the memory captured in [Interpreter::memory_snapshot] before every
+    /// [step](Inspector::step), or `None` to not capture it.
+    ///
+    /// The memory is copied once per step, instead of every step cloning the whole memory.
+    #[inline]
+    fn memory_snapshot_mode(&self) -> Option<MemorySnapshotMode> {
+        None
+    }
+
     /// Called after `step` when the instruction has been executed.
     ///
     /// Setting `interp.instruction_result` to anything other than [crate::interpreter::InstructionResult::Continue] alters the execution
@@ -94,7 +130,8 @@ pub trait Inspector<EvmWiringT: EvmWiring> {
     ///
     /// The returned [CallOutcome] is used as the result of the call.
     ///
-    /// This allows the inspector to modify the given `result` before returning it.
+    /// This allows the inspector to modify the given `result` before returning it. The gas of the
+    /// returned frame, including its memory expansion, is in [CallOutcome::gas_summary].
     #[inline]
     fn call_end(
         &mut self,
@@ -126,7 +163,8 @@ pub trait Inspector<EvmWiringT: EvmWiring> {
     /// Called when a contract has been created.
     ///
     /// InstructionResulting anything other than the values passed to this function (`(ret, remaining_gas,
-    /// address, out)`) will alter the result of the create.
+    /// address, out)`) will alter the result of the create. The gas of the returned frame is in
+    /// [CreateOutcome::gas_summary].
     #[inline]
     fn create_end(
         &mut self,
@@ -8,7 +8,7 @@ use revm_interpreter::OpCode;
 use crate::{
     inspectors::GasInspector,
     interpreter::{CallInputs, CreateInputs, Interpreter},
-    primitives::{Address, U256},
+    primitives::{hex, keccak256, Address, HashMap, Log, B256, I256, U256},
     EvmContext, EvmWiring, Inspector,
 };
 
@@ -18,6 +18,201 @@ use crate::{
 #[derive(Clone, Debug, Default)]
 pub struct CustomPrintTracer {
     gas_inspector: GasInspector,
+    abi: Option<AbiRegistry>,
+}
+
+impl CustomPrintTracer {
+    /// Decodes calls and logs with the signatures of `abi` when printing them.
+    pub fn with_abi(mut self, abi: AbiRegistry) -> Self {
+        self.abi = Some(abi);
+        self
+    }
+}
+
+/// Function and event signatures used by the [CustomPrintTracer] to print calls and logs
+/// with their names and decoded arguments.
+///
+/// Arguments of type `address`, `bool`, `uintN`, `intN`, `bytesN`, `bytes` and `string` are
+/// decoded, other arguments are printed as raw words.
+#[derive(Clone, Debug, Default)]
+pub struct AbiRegistry {
+    functions: HashMap<[u8; 4], AbiSignature>,
+    events: HashMap<B256, AbiSignature>,
+}
+
+/// Parsed function or event signature.
+#[derive(Clone, Debug)]
+struct AbiSignature {
+    name: String,
+    /// Types of the parameters and whether they are indexed.
+    params: Vec<(String, bool)>,
+}
+
+impl AbiSignature {
+    /// Parses a signature such as `Transfer(address indexed from, address to, uint256)`.
+    ///
+    /// Returns the signature and its canonical form, which is hashed into the selector.
+    fn parse(signature: &str) -> Option<(Self, String)> {
+        let (name, params) = signature.trim().split_once('(')?;
+        let params = params.strip_suffix(')')?;
+        let params: Vec<_> = split_params(params)
+            .into_iter()
+            .map(|param| {
+                let mut tokens = param.split_whitespace();
+                let ty = tokens.next().unwrap_or_default().to_string();
+                (ty, tokens.any(|token| token == "indexed"))
+            })
+            .collect();
+        let types: Vec<_> = params.iter().map(|(ty, _)| ty.as_str()).collect();
+        let canonical = format!("{name}({})", types.join(","));
+        let name = name.to_string();
+        Some((Self { name, params }, canonical))
+    }
+}
+
+impl AbiRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a function, such as `transfer(address,uint256)`.
+    ///
+    /// Invalid signatures are ignored.
+    pub fn with_function(mut self, signature: &str) -> Self {
+        if let Some((function, canonical)) = AbiSignature::parse(signature) {
+            let selector = keccak256(canonical.as_bytes())[..4].try_into().unwrap();
+            self.functions.insert(selector, function);
+        }
+        self
+    }
+
+    /// Registers an event, such as `Transfer(address indexed,address indexed,uint256)`.
+    ///
+    /// Invalid signatures are ignored.
+    pub fn with_event(mut self, signature: &str) -> Self {
+        if let Some((event, canonical)) = AbiSignature::parse(signature) {
+            self.events.insert(keccak256(canonical.as_bytes()), event);
+        }
+        self
+    }
+
+    /// Returns the function call encoded in `input` with its decoded arguments, if the
+    /// selector is registered.
+    pub fn decode_call(&self, input: &[u8]) -> Option<String> {
+        let selector: [u8; 4] = input.get(..4)?.try_into().unwrap();
+        let function = self.functions.get(&selector)?;
+        let types = function.params.iter().map(|(ty, _)| ty.as_str());
+        let args = decode_params(types, &input[4..]);
+        Some(format!("{}({})", function.name, args.join(", ")))
+    }
+
+    /// Returns the event of `log` with its decoded arguments, if its first topic is
+    /// registered.
+    pub fn decode_log(&self, log: &Log) -> Option<String> {
+        let (topic0, topics) = log.topics().split_first()?;
+        let event = self.events.get(topic0)?;
+
+        let non_indexed = event
+            .params
+            .iter()
+            .filter(|(_, indexed)| !indexed)
+            .map(|(ty, _)| ty.as_str());
+        let mut data = decode_params(non_indexed, &log.data.data).into_iter();
+        let mut topics = topics.iter();
+        let args: Vec<_> = event
+            .params
+            .iter()
+            .map(|(ty, indexed)| {
+                if *indexed {
+                    // Indexed dynamic values are hashed, so only static ones are decoded.
+                    topics.next().map_or_else(String::new, |topic| {
+                        decode_word(ty, topic).unwrap_or_else(|| topic.to_string())
+                    })
+                } else {
+                    data.next().unwrap_or_default()
+                }
+            })
+            .collect();
+        Some(format!("{}({})", event.name, args.join(", ")))
+    }
+}
+
+/// Splits a parameter list at the commas that are not nested in tuples.
+fn split_params(params: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (i, c) in params.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(params[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = params[start..].trim();
+    if !last.is_empty() || !parts.is_empty() {
+        parts.push(last);
+    }
+    parts
+}
+
+/// Decodes ABI encoded parameters of the given types.
+fn decode_params<'a>(types: impl Iterator<Item = &'a str>, data: &[u8]) -> Vec<String> {
+    let word_at = |offset: usize| -> Option<B256> {
+        data.get(offset..offset.checked_add(32)?)
+            .map(B256::from_slice)
+    };
+    types
+        .enumerate()
+        .map(|(i, ty)| {
+            let Some(head) = word_at(i * 32) else {
+                return String::from("?");
+            };
+            if ty == "bytes" || ty == "string" {
+                let decoded = (|| {
+                    let offset = usize::try_from(U256::from_be_bytes(head.0)).ok()?;
+                    let len = usize::try_from(U256::from_be_bytes(word_at(offset)?.0)).ok()?;
+                    let start = offset.checked_add(32)?;
+                    let bytes = data.get(start..start.checked_add(len)?)?;
+                    Some(if ty == "string" {
+                        format!("{:?}", String::from_utf8_lossy(bytes))
+                    } else {
+                        hex::encode_prefixed(bytes)
+                    })
+                })();
+                return decoded.unwrap_or_else(|| head.to_string());
+            }
+            decode_word(ty, &head).unwrap_or_else(|| head.to_string())
+        })
+        .collect()
+}
+
+/// Decodes a single word of a static type.
+fn decode_word(ty: &str, word: &B256) -> Option<String> {
+    if ty == "address" {
+        return Some(Address::from_word(*word).to_string());
+    }
+    if ty == "bool" {
+        return Some((!word.is_zero()).to_string());
+    }
+    if ty.starts_with("uint") {
+        return Some(U256::from_be_bytes(word.0).to_string());
+    }
+    if ty.starts_with("int") {
+        return Some(I256::from_raw(U256::from_be_bytes(word.0)).to_string());
+    }
+    if let Some(size) = ty.strip_prefix("bytes") {
+        let size = size
+            .parse::<usize>()
+            .ok()
+            .filter(|size| (1..=32).contains(size))?;
+        return Some(hex::encode_prefixed(&word[..size]));
+    }
+    None
 }
 
 impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for CustomPrintTracer {
@@ -78,11 +273,30 @@ impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for CustomPrintTracer {
         self.gas_inspector.create_end(context, inputs, outcome)
     }
 
+    fn log(&mut self, _interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>, log: &Log) {
+        match self.abi.as_ref().and_then(|abi| abi.decode_log(log)) {
+            Some(event) => println!("LOG: address:{:?}, event:{event}", log.address),
+            None => println!(
+                "LOG: address:{:?}, topics:{:?}, data:{}",
+                log.address,
+                log.topics(),
+                log.data.data
+            ),
+        }
+    }
+
     fn call(
         &mut self,
         _context: &mut EvmContext<EvmWiringT>,
         inputs: &mut CallInputs,
     ) -> Option<CallOutcome> {
+        if let Some(call) = self
+            .abi
+            .as_ref()
+            .and_then(|abi| abi.decode_call(&inputs.input))
+        {
+            println!("CALL: {call}");
+        }
         println!(
             "SM Address: {:?}, caller:{:?},target:{:?} is_static:{:?}, transfer:{:?}, input_size:{:?}",
             inputs.bytecode_address,
@@ -155,4 +369,52 @@ mod test {
 
         evm.transact().expect("Transaction to work");
     }
+
+    #[test]
+    fn decode_call_and_log() {
+        use crate::{
+            inspectors::AbiRegistry,
+            primitives::{b256, hex, keccak256, Log, LogData, U256},
+        };
+
+        let registry = AbiRegistry::new()
+            .with_function("transfer(address to, uint256 amount)")
+            .with_event("Transfer(address indexed from, address indexed to, uint256 value)")
+            .with_event("Note(string)");
+
+        let to = address!("00000000000000000000000000000000000000aa");
+        let mut input = hex!("a9059cbb").to_vec();
+        input.extend_from_slice(to.into_word().as_slice());
+        input.extend_from_slice(&U256::from(100).to_be_bytes::<32>());
+        assert_eq!(
+            registry.decode_call(&input).unwrap(),
+            format!("transfer({to}, 100)")
+        );
+        assert_eq!(registry.decode_call(&hex!("deadbeef")), None);
+
+        let from = address!("00000000000000000000000000000000000000bb");
+        let transfer = b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+        let log = Log {
+            address: to,
+            data: LogData::new_unchecked(
+                vec![transfer, from.into_word(), to.into_word()],
+                U256::from(7).to_be_bytes_vec().into(),
+            ),
+        };
+        assert_eq!(
+            registry.decode_log(&log).unwrap(),
+            format!("Transfer({from}, {to}, 7)")
+        );
+
+        let note = keccak256("Note(string)");
+        let mut data = U256::from(32).to_be_bytes_vec();
+        data.extend_from_slice(&U256::from(2).to_be_bytes::<32>());
+        data.extend_from_slice(&[0; 32]);
+        data[64..66].copy_from_slice(b"hi");
+        let log = Log {
+            address: to,
+            data: LogData::new_unchecked(vec![note], data.into()),
+        };
+        assert_eq!(registry.decode_log(&log).unwrap(), "Note(\"hi\")");
+    }
 }
@@ -3,7 +3,7 @@ use crate::{
     interpreter::{
         CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter, InterpreterResult,
     },
-    primitives::{hex, HashMap, Transaction, B256, U256},
+    primitives::{hex, HashMap, B256, U256},
     EvmContext, EvmWiring, Inspector,
 };
 use derive_where::derive_where;
@@ -176,7 +176,7 @@ impl TracerEip3155 {
                 state_root: B256::ZERO.to_string(),
                 output: result.output.to_string(),
                 gas_used: hex_number(
-                    context.inner.env().tx.gas_limit() - self.gas_inspector.gas_remaining(),
+                    context.inner.env().effective_gas_limit() - self.gas_inspector.gas_remaining(),
                 ),
                 pass: result.is_ok(),
                 time: None,
@@ -198,7 +198,8 @@ impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for TracerEip3155 {
 
     fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<EvmWiringT>) {
         self.gas_inspector.step(interp, context);
-        self.stack.clone_from(interp.stack.data());
+        self.stack.clear();
+        self.stack.extend_from_slice(interp.stack.data());
         self.memory = if self.include_memory {
             Some(hex::encode_prefixed(interp.shared_memory.context_memory()))
         } else {
@@ -8,6 +8,10 @@ use crate::{
 };
 
 /// Helper [Inspector] that keeps track of gas.
+///
+/// Instructions charge their own gas when they are executed, so the cost observed between
+/// `step` and `step_end` is the exact cost of the instruction, including memory expansion
+/// and the gas forwarded to calls.
 #[allow(dead_code)]
 #[derive(Clone, Copy, Debug, Default)]
 pub struct GasInspector {
@@ -16,10 +20,12 @@ pub struct GasInspector {
 }
 
 impl GasInspector {
+    /// Returns the gas remaining in the current frame.
     pub fn gas_remaining(&self) -> u64 {
         self.gas_remaining
     }
 
+    /// Returns the gas spent by the last executed instruction.
     pub fn last_gas_cost(&self) -> u64 {
         self.last_gas_cost
     }
@@ -0,0 +1,337 @@
+//! GasBreakdownInspector. Attributes gas of a transaction to categories.
+
+use crate::{
+    interpreter::{
+        gas::{validate_initial_tx_gas, CODEDEPOSIT},
+        CallInputs, CallOutcome, CreateInputs, CreateOutcome, EOFCreateInputs, InterpreterResult,
+    },
+    primitives::{HashMap, Transaction},
+    EvmContext, EvmWiring, Inspector,
+};
+use std::vec::Vec;
+
+/// Gas of a transaction attributed to categories.
+///
+/// The sum of all categories is the gas spent by the transaction before the refund is
+/// applied, see [GasBreakdown::spent].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GasBreakdown {
+    /// Intrinsic gas without the calldata cost: base cost, access list and authorization
+    /// list.
+    pub intrinsic: u64,
+    /// Intrinsic gas of the transaction data, including the EIP-3860 initcode cost.
+    pub calldata: u64,
+    /// Execution gas per opcode, without memory expansion and without the gas forwarded
+    /// to calls and creates.
+    ///
+    /// The stipend of value transferring calls is attributed to the callee, so it is
+    /// deducted from the cost of the calling opcode.
+    pub opcodes: HashMap<u8, u64>,
+    /// Gas spent on memory expansion.
+    pub memory_expansion: u64,
+    /// Gas spent by precompiles.
+    pub precompiles: u64,
+    /// Gas spent on depositing the code of created contracts.
+    pub code_deposit: u64,
+    /// Gas burned by exceptional halts on top of the cost of the executed opcodes.
+    pub halt: u64,
+    /// Refund counter of the transaction, before it is capped at the end of execution.
+    pub refunded: i64,
+}
+
+impl GasBreakdown {
+    /// Returns the execution gas of all opcodes.
+    pub fn execution(&self) -> u64 {
+        self.opcodes.values().sum()
+    }
+
+    /// Returns the gas spent by the transaction before the refund.
+    pub fn spent(&self) -> u64 {
+        self.intrinsic
+            + self.calldata
+            + self.execution()
+            + self.memory_expansion
+            + self.precompiles
+            + self.code_deposit
+            + self.halt
+    }
+}
+
+/// [Inspector] that attributes the gas of a transaction to the categories of
+/// [GasBreakdown].
+///
+/// The breakdown is reset at the start of each transaction and can be read after
+/// `transact`.
+#[derive(Clone, Debug, Default)]
+pub struct GasBreakdownInspector {
+    breakdown: GasBreakdown,
+    /// Gas attributed to each active frame.
+    frames: Vec<u64>,
+    /// Opcode, remaining gas and memory expansion cost at the last step.
+    last_step: Option<(u8, u64, u64)>,
+}
+
+impl GasBreakdownInspector {
+    /// Returns the breakdown of the last transaction.
+    pub fn breakdown(&self) -> &GasBreakdown {
+        &self.breakdown
+    }
+
+    /// Consumes the inspector and returns the breakdown of the last transaction.
+    pub fn into_breakdown(self) -> GasBreakdown {
+        self.breakdown
+    }
+
+    /// Starts a new frame that was given `gas_limit`.
+    fn frame_start<EvmWiringT: EvmWiring>(
+        &mut self,
+        context: &EvmContext<EvmWiringT>,
+        gas_limit: u64,
+    ) {
+        if self.frames.is_empty() {
+            let tx = &context.env.tx;
+            let spec_id = context.spec_id();
+            let is_create = tx.kind().is_create();
+            let with_data = validate_initial_tx_gas(spec_id, tx.data(), is_create, &[], 0);
+            let without_data = validate_initial_tx_gas(spec_id, &[], is_create, &[], 0);
+
+            self.breakdown = GasBreakdown::default();
+            self.breakdown.calldata = with_data - without_data;
+            self.breakdown.intrinsic = context
+                .env
+                .effective_gas_limit()
+                .saturating_sub(gas_limit)
+                .saturating_sub(self.breakdown.calldata);
+        } else if let Some((opcode, ..)) = self.last_step.take() {
+            // Forwarded gas was recorded as the cost of the calling opcode.
+            if let Some(cost) = self.breakdown.opcodes.get_mut(&opcode) {
+                *cost = cost.saturating_sub(gas_limit);
+            }
+            if let Some(attributed) = self.frames.last_mut() {
+                *attributed = attributed.saturating_sub(gas_limit);
+            }
+        }
+        self.frames.push(0);
+    }
+
+    /// Ends the current frame, attributing the gas that was not spent by its opcodes.
+    fn frame_end(&mut self, result: &InterpreterResult, is_precompile: bool, deposit: u64) {
+        let attributed = self.frames.pop().unwrap_or_default();
+        // Remaining gas of a halted frame is burned after it returns.
+        let spent = if result.is_error() {
+            result.gas.limit()
+        } else {
+            result.gas.spent()
+        };
+        if is_precompile {
+            self.breakdown.precompiles += spent;
+        } else if result.is_ok() {
+            self.breakdown.code_deposit += deposit;
+        } else if result.is_error() {
+            self.breakdown.halt += spent.saturating_sub(attributed);
+        }
+
+        match self.frames.last_mut() {
+            // Gas spent by the frame is returned to the parent as spent.
+            Some(parent) => *parent += spent,
+            None if result.is_ok() => self.breakdown.refunded = result.gas.refunded(),
+            None => {}
+        }
+    }
+
+    /// Returns the code deposit cost of a create outcome.
+    fn code_deposit(outcome: &CreateOutcome) -> u64 {
+        if outcome.result.is_ok() {
+            outcome.result.output.len() as u64 * CODEDEPOSIT
+        } else {
+            0
+        }
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for GasBreakdownInspector {
+    fn step(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        _context: &mut EvmContext<EvmWiringT>,
+    ) {
+        self.last_step = Some((
+            interp.current_opcode(),
+            interp.gas.remaining(),
+            interp.shared_memory.current_expansion_cost(),
+        ));
+    }
+
+    fn step_end(
+        &mut self,
+        interp: &mut crate::interpreter::Interpreter,
+        _context: &mut EvmContext<EvmWiringT>,
+    ) {
+        let Some((opcode, remaining, memory_cost)) = self.last_step else {
+            return;
+        };
+        let cost = remaining.saturating_sub(interp.gas.remaining());
+        let memory = interp
+            .shared_memory
+            .current_expansion_cost()
+            .saturating_sub(memory_cost)
+            .min(cost);
+
+        self.breakdown.memory_expansion += memory;
+        *self.breakdown.opcodes.entry(opcode).or_default() += cost - memory;
+        if let Some(attributed) = self.frames.last_mut() {
+            *attributed += cost;
+        }
+    }
+
+    fn call(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.frame_start(context, inputs.gas_limit);
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        let is_precompile = context.precompiles.contains(&inputs.bytecode_address);
+        self.frame_end(&outcome.result, is_precompile, 0);
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.frame_start(context, inputs.gas_limit);
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        _inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.frame_end(&outcome.result, false, Self::code_deposit(&outcome));
+        outcome
+    }
+
+    fn eofcreate(
+        &mut self,
+        context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut EOFCreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.frame_start(context, inputs.gas_limit);
+        None
+    }
+
+    fn eofcreate_end(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        _inputs: &EOFCreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.frame_end(&outcome.result, false, Self::code_deposit(&outcome));
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        interpreter::opcode,
+        primitives::{address, Bytecode, Bytes, EthereumWiring, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn breakdown_of_call() {
+        // Stores a byte to memory offset 64, sends 1 wei to the identity precompile and stops.
+        let code = Bytes::from(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x40,
+                opcode::MSTORE8,
+                // CALL(gas, 0x04, 1, 0, 0, 0, 0)
+                opcode::PUSH1,
+                0x00,
+                opcode::DUP1,
+                opcode::DUP1,
+                opcode::DUP1,
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x04,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        );
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, GasBreakdownInspector>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
+                tx.data = Bytes::from_static(&[0x00, 0x01]);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let breakdown = evm.context.external.breakdown();
+        assert_eq!(breakdown.intrinsic, 21_000);
+        assert_eq!(breakdown.calldata, 4 + 16);
+        // 3 words of memory.
+        assert_eq!(breakdown.memory_expansion, 9);
+        assert_eq!(breakdown.opcodes[&opcode::MSTORE8], 3);
+        // Warm access, value transfer and new account, without the stipend of the callee.
+        assert_eq!(breakdown.opcodes[&opcode::CALL], 100 + 9000 + 25000 - 2300);
+        // Identity precompile base cost.
+        assert_eq!(breakdown.precompiles, 15);
+        assert_eq!(breakdown.code_deposit, 0);
+        assert_eq!(breakdown.halt, 0);
+        assert_eq!(breakdown.spent(), result.gas_used());
+    }
+
+    #[test]
+    fn breakdown_of_halt() {
+        let code = Bytes::from([opcode::PUSH1, 0x00, opcode::INVALID].to_vec());
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, GasBreakdownInspector>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(address!("0000000000000000000000000000000000000000"));
+                tx.gas_limit = 30_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_halt());
+
+        let breakdown = evm.context.external.breakdown();
+        assert_eq!(breakdown.opcodes[&opcode::PUSH1], 3);
+        assert_eq!(breakdown.halt, 30_000 - 21_000 - 3);
+        assert_eq!(breakdown.spent(), 30_000);
+    }
+}
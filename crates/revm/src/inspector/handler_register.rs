@@ -1,6 +1,6 @@
 use crate::{
     handler::register::EvmHandler,
-    interpreter::{opcode, InstructionResult, Interpreter},
+    interpreter::{opcode, InstructionResult, Interpreter, MemorySnapshot},
     primitives::EVMResultGeneric,
     Context, EvmWiring, FrameOrResult, FrameResult, Inspector, JournalEntry,
 };
@@ -38,6 +38,24 @@ pub fn inspector_handle_register<
 >(
     handler: &mut EvmHandler<'_, EvmWiringT>,
 ) {
+    // Transaction start and end
+    let prev_handle = handler.pre_execution.start.clone();
+    handler.pre_execution.start = Arc::new(move |ctx| {
+        prev_handle(ctx)?;
+        ctx.external.get_inspector().transact_start(&mut ctx.evm);
+        Ok(())
+    });
+    let prev_handle = handler.post_execution.end.clone();
+    handler.post_execution.end = Arc::new(move |ctx, output| {
+        let output = prev_handle(ctx, output);
+        if let Ok(output) = &output {
+            ctx.external
+                .get_inspector()
+                .transact_end(&mut ctx.evm, output);
+        }
+        output
+    });
+
     let table = &mut handler.instruction_table;
 
     // Update all instructions to call inspector step and step_end.
@@ -51,10 +69,9 @@ pub fn inspector_handle_register<
             // check if log was added. It is possible that revert happened
             // cause of gas or stack underflow.
             if host.evm.journaled_state.logs.len() == prev_log_len + 1 {
-                // clone log.
-                // TODO decide if we should remove this and leave the comment
-                // that log can be found as journaled_state.
-                let last_log = host.evm.journaled_state.logs.last().unwrap().clone();
+                // Inspectors get the log as a `Log`, the log itself stays in the journaled
+                // state.
+                let last_log = host.evm.journaled_state.logs.last().unwrap().to_log();
                 // call Inspector
                 host.external
                     .get_inspector()
@@ -132,6 +149,8 @@ pub fn inspector_handle_register<
 
         let mut frame_or_result = prev_handle(ctx, inputs);
         if let Ok(FrameOrResult::Frame(frame)) = &mut frame_or_result {
+            // Inspectors step through every instruction, so fused ones are not executed.
+            frame.interpreter_mut().unfuse();
             ctx.external
                 .get_inspector()
                 .initialize_interp(frame.interpreter_mut(), &mut ctx.evm)
@@ -239,10 +258,15 @@ fn inspector_instruction<EvmWiringT>(
     // old Inspector behavior.
     interpreter.instruction_pointer = unsafe { interpreter.instruction_pointer.sub(1) };
 
-    // Call step.
-    host.external
-        .get_inspector()
-        .step(interpreter, &mut host.evm);
+    // Capture the memory, then call step.
+    let inspector = host.external.get_inspector();
+    if let Some(mode) = inspector.memory_snapshot_mode() {
+        interpreter.memory_snapshot = Some(MemorySnapshot::capture(
+            &mut interpreter.shared_memory,
+            mode,
+        ));
+    }
+    inspector.step(interpreter, &mut host.evm);
     if interpreter.instruction_result != InstructionResult::Continue {
         return;
     }
@@ -265,7 +289,7 @@ mod tests {
     use crate::{
         inspectors::NoOpInspector,
         interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
-        primitives::{self, db::EmptyDB, EthereumWiring},
+        primitives::{self, db::EmptyDB, EthereumWiring, ResultAndState},
         Evm, EvmContext,
     };
 
@@ -278,9 +302,27 @@ mod tests {
         step_end: u32,
         call: bool,
         call_end: bool,
+        transact_start: u32,
+        transact_end: u32,
+        touched_accounts: usize,
     }
 
     impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for StackInspector {
+        fn transact_start(&mut self, context: &mut EvmContext<EvmWiringT>) {
+            assert_eq!(self.transact_end, self.transact_start);
+            assert!(context.journaled_state.state.is_empty());
+            self.transact_start += 1;
+        }
+
+        fn transact_end(
+            &mut self,
+            _context: &mut EvmContext<EvmWiringT>,
+            output: &ResultAndState<EvmWiringT::HaltReason>,
+        ) {
+            self.transact_end += 1;
+            self.touched_accounts = output.state.len();
+        }
+
         fn initialize_interp(
             &mut self,
             _interp: &mut Interpreter,
@@ -388,7 +430,8 @@ mod tests {
             .build();
 
         // run evm.
-        evm.transact().unwrap();
+        let output = evm.transact().unwrap();
+        assert_eq!(evm.context.external.touched_accounts, output.state.len());
 
         let inspector = evm.into_context().external;
 
@@ -397,6 +440,87 @@ mod tests {
         assert!(inspector.initialize_interp_called);
         assert!(inspector.call);
         assert!(inspector.call_end);
+        assert_eq!(inspector.transact_start, 1);
+        assert_eq!(inspector.transact_end, 1);
+    }
+
+    #[test]
+    fn test_memory_snapshots() {
+        use crate::{
+            db::BenchmarkDB,
+            interpreter::{opcode, MemorySnapshotMode},
+            primitives::{address, Bytecode, Bytes, TxKind},
+        };
+
+        /// Records the memory snapshots of every step.
+        #[derive(Default, Debug)]
+        struct SnapshotInspector(Vec<Option<MemorySnapshot>>);
+
+        impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for SnapshotInspector {
+            fn memory_snapshot_mode(&self) -> Option<MemorySnapshotMode> {
+                Some(MemorySnapshotMode::Touched)
+            }
+
+            fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+                self.0.push(interp.memory_snapshot.clone());
+            }
+        }
+
+        // MSTORE8(0x21, 0xff), MSTORE8(0x01, 0xee)
+        let code = Bytes::from(vec![
+            opcode::PUSH1,
+            0xff,
+            opcode::PUSH1,
+            0x21,
+            opcode::MSTORE8,
+            opcode::PUSH1,
+            0xee,
+            opcode::PUSH1,
+            0x01,
+            opcode::MSTORE8,
+            opcode::STOP,
+        ]);
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, SnapshotInspector>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_external_context(SnapshotInspector::default())
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(primitives::Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+        evm.transact().unwrap();
+
+        let snapshots: Vec<_> = evm
+            .context
+            .external
+            .0
+            .iter()
+            .map(|snapshot| {
+                let snapshot = snapshot.as_ref().unwrap();
+                (
+                    snapshot.offset,
+                    snapshot.data.to_vec(),
+                    snapshot.memory_size,
+                )
+            })
+            .collect();
+        let mut expanded = vec![0; 64];
+        expanded[0x21] = 0xff;
+        assert_eq!(
+            snapshots,
+            [
+                (0, vec![], 0),
+                (0, vec![], 0),
+                (0, vec![], 0),
+                // The first MSTORE8 expands the memory.
+                (0, expanded, 64),
+                (0, vec![], 64),
+                (0, vec![], 64),
+                (0x01, vec![0xee], 64),
+            ]
+        );
     }
 
     #[test]
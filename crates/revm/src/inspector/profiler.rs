@@ -0,0 +1,217 @@
+//! OpcodeProfiler. Counts executed opcodes and their gas per contract and program counter.
+
+use crate::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome, Interpreter},
+    primitives::{Address, HashMap},
+    EvmContext, EvmWiring, Inspector,
+};
+use std::vec::Vec;
+
+/// Execution statistics of a single instruction of a contract.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SiteProfile {
+    /// Opcode at the program counter.
+    pub opcode: u8,
+    /// Number of times the instruction was executed.
+    pub count: u64,
+    /// Gas spent by the instruction, without the gas forwarded to calls and creates.
+    pub gas: u64,
+}
+
+/// Instruction of a contract, as reported by [OpcodeProfiler::hotspots].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Hotspot {
+    /// Address of the executed bytecode.
+    pub address: Address,
+    /// Program counter of the instruction.
+    pub pc: usize,
+    /// Statistics of the instruction.
+    pub profile: SiteProfile,
+}
+
+/// [Inspector] that profiles executed bytecode.
+///
+/// Instructions are identified by the address of the executed bytecode, which for
+/// `DELEGATECALL` and `CALLCODE` is the code address and not the address of the caller.
+/// Statistics accumulate over all inspected transactions until [OpcodeProfiler::clear] is
+/// called.
+#[derive(Clone, Debug, Default)]
+pub struct OpcodeProfiler {
+    sites: HashMap<(Address, usize), SiteProfile>,
+    /// Site and remaining gas at the last step.
+    last_step: Option<((Address, usize), u64)>,
+}
+
+impl OpcodeProfiler {
+    /// Returns the statistics of all executed instructions.
+    pub fn sites(&self) -> &HashMap<(Address, usize), SiteProfile> {
+        &self.sites
+    }
+
+    /// Returns the `n` instructions that spent the most gas, the most expensive first.
+    ///
+    /// Instructions that spent the same gas are ordered by execution count.
+    pub fn hotspots(&self, n: usize) -> Vec<Hotspot> {
+        let mut hotspots: Vec<_> = self
+            .sites
+            .iter()
+            .map(|(&(address, pc), &profile)| Hotspot {
+                address,
+                pc,
+                profile,
+            })
+            .collect();
+        hotspots.sort_unstable_by(|a, b| {
+            (b.profile.gas, b.profile.count, a.address, a.pc).cmp(&(
+                a.profile.gas,
+                a.profile.count,
+                b.address,
+                b.pc,
+            ))
+        });
+        hotspots.truncate(n);
+        hotspots
+    }
+
+    /// Returns the number of executions of each opcode in the bytecode at `address`.
+    pub fn opcode_counts(&self, address: Address) -> HashMap<u8, u64> {
+        let mut counts = HashMap::new();
+        for (_, profile) in self.sites.iter().filter(|((a, _), _)| *a == address) {
+            *counts.entry(profile.opcode).or_default() += profile.count;
+        }
+        counts
+    }
+
+    /// Returns the total gas spent by the bytecode of each contract.
+    pub fn gas_per_contract(&self) -> HashMap<Address, u64> {
+        let mut gas = HashMap::new();
+        for ((address, _), profile) in &self.sites {
+            *gas.entry(*address).or_default() += profile.gas;
+        }
+        gas
+    }
+
+    /// Removes all gathered statistics.
+    pub fn clear(&mut self) {
+        self.sites.clear();
+        self.last_step = None;
+    }
+
+    /// Deducts the gas forwarded to a call or create from the instruction that made it.
+    fn forward_gas(&mut self, gas_limit: u64) {
+        if let Some((site, _)) = self.last_step.take() {
+            if let Some(profile) = self.sites.get_mut(&site) {
+                profile.gas = profile.gas.saturating_sub(gas_limit);
+            }
+        }
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for OpcodeProfiler {
+    fn step(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+        let contract = &interp.contract;
+        let address = contract.bytecode_address.unwrap_or(contract.target_address);
+        let site = (address, interp.program_counter());
+        let profile = self.sites.entry(site).or_default();
+        profile.opcode = interp.current_opcode();
+        profile.count += 1;
+        self.last_step = Some((site, interp.gas.remaining()));
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, _context: &mut EvmContext<EvmWiringT>) {
+        let Some((site, remaining)) = self.last_step else {
+            return;
+        };
+        if let Some(profile) = self.sites.get_mut(&site) {
+            profile.gas += remaining.saturating_sub(interp.gas.remaining());
+        }
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.forward_gas(inputs.gas_limit);
+        None
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<EvmWiringT>,
+        inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.forward_gas(inputs.gas_limit);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        interpreter::opcode,
+        primitives::{address, Bytecode, Bytes, EthereumWiring, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn profiles_loop() {
+        // Counts down from 3 to 0 in a loop:
+        // 0: PUSH1 3
+        // 2: JUMPDEST
+        // 3: PUSH1 1, SWAP1, SUB, DUP1, PUSH1 2, JUMPI
+        // 11: STOP
+        let code = Bytes::from(
+            [
+                opcode::PUSH1,
+                0x03,
+                opcode::JUMPDEST,
+                opcode::PUSH1,
+                0x01,
+                opcode::SWAP1,
+                opcode::SUB,
+                opcode::DUP1,
+                opcode::PUSH1,
+                0x02,
+                opcode::JUMPI,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        );
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, OpcodeProfiler>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        let result = evm.transact().unwrap().result;
+        assert!(result.is_success());
+
+        let profiler = &evm.context.external;
+        let jumpi = profiler.sites()[&(Address::ZERO, 10)];
+        assert_eq!(jumpi.opcode, opcode::JUMPI);
+        assert_eq!(jumpi.count, 3);
+        assert_eq!(jumpi.gas, 3 * 10);
+
+        let counts = profiler.opcode_counts(Address::ZERO);
+        assert_eq!(counts[&opcode::PUSH1], 1 + 3 + 3);
+        assert_eq!(counts[&opcode::STOP], 1);
+
+        let hotspots = profiler.hotspots(1);
+        assert_eq!(hotspots.len(), 1);
+        assert_eq!(hotspots[0].pc, 10);
+        assert_eq!(
+            profiler.gas_per_contract()[&Address::ZERO],
+            result.gas_used() - 21_000
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! StorageRecorder. Records storage reads and writes with their values.
+
+use crate::{
+    interpreter::{opcode, InstructionResult, Interpreter},
+    primitives::{Address, U256},
+    EvmContext, EvmWiring, Inspector, JournalEntries, JournalEntry,
+};
+use std::vec::Vec;
+
+/// Kind of a [StorageAccess].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageAccessKind {
+    /// `SLOAD`.
+    Load,
+    /// `SSTORE`.
+    Store,
+}
+
+/// Storage slot access made by `SLOAD` or `SSTORE`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StorageAccess {
+    /// Instruction that accessed the slot.
+    pub kind: StorageAccessKind,
+    /// Account whose storage was accessed.
+    pub address: Address,
+    /// Accessed slot.
+    pub slot: U256,
+    /// Value of the slot at the start of the transaction.
+    pub original_value: U256,
+    /// Value of the slot before the access.
+    pub previous_value: U256,
+    /// Value of the slot after the access, which is the loaded or written value.
+    pub value: U256,
+    /// Call depth of the access.
+    pub depth: u64,
+    /// Whether the slot was cold before the access.
+    pub is_cold: bool,
+}
+
+/// [Inspector] that records every successful `SLOAD` and `SSTORE`, in execution order.
+///
+/// Accesses made in calls that later reverted are recorded as well, the values are the
+/// ones observed at the time of the access.
+#[derive(Clone, Debug, Default)]
+pub struct StorageRecorder {
+    accesses: Vec<StorageAccess>,
+    /// Access of the current step and the length of the journal before it.
+    pending: Option<(StorageAccessKind, Address, U256, usize)>,
+}
+
+impl StorageRecorder {
+    /// Returns the recorded accesses.
+    pub fn accesses(&self) -> &[StorageAccess] {
+        &self.accesses
+    }
+
+    /// Consumes the recorder and returns the recorded accesses.
+    pub fn into_accesses(self) -> Vec<StorageAccess> {
+        self.accesses
+    }
+
+    /// Removes all recorded accesses.
+    pub fn clear(&mut self) {
+        self.accesses.clear();
+    }
+}
+
+impl<EvmWiringT: EvmWiring> Inspector<EvmWiringT> for StorageRecorder {
+    fn step(&mut self, interp: &mut Interpreter, context: &mut EvmContext<EvmWiringT>) {
+        let kind = match interp.current_opcode() {
+            opcode::SLOAD => StorageAccessKind::Load,
+            opcode::SSTORE => StorageAccessKind::Store,
+            _ => return,
+        };
+        let Ok(slot) = interp.stack.peek(0) else {
+            return;
+        };
+        let journal_len = context
+            .journaled_state
+            .journal
+            .last()
+            .map_or(0, JournalEntries::len);
+        self.pending = Some((kind, interp.contract.target_address, slot, journal_len));
+    }
+
+    fn step_end(&mut self, interp: &mut Interpreter, context: &mut EvmContext<EvmWiringT>) {
+        let Some((kind, address, slot, journal_len)) = self.pending.take() else {
+            return;
+        };
+        if interp.instruction_result != InstructionResult::Continue {
+            return;
+        }
+        let Some(storage_slot) = context
+            .journaled_state
+            .state
+            .get(&address)
+            .and_then(|account| account.storage.get(&slot))
+        else {
+            return;
+        };
+
+        let value = storage_slot.present_value;
+        let mut previous_value = value;
+        let mut is_cold = false;
+        let entries = context
+            .journaled_state
+            .journal
+            .last()
+            .map(JournalEntries::as_slice);
+        for entry in entries.unwrap_or_default().iter().skip(journal_len) {
+            match entry {
+                JournalEntry::StorageWarmed { address: a, key }
+                    if *a == address && *key == slot =>
+                {
+                    is_cold = true
+                }
+                JournalEntry::StorageChanged {
+                    address: a,
+                    key,
+                    had_value,
+                } if *a == address && *key == slot => previous_value = *had_value,
+                _ => {}
+            }
+        }
+
+        self.accesses.push(StorageAccess {
+            kind,
+            address,
+            slot,
+            original_value: storage_slot.original_value,
+            previous_value,
+            value,
+            depth: context.journaled_state.depth(),
+            is_cold,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        inspector_handle_register,
+        primitives::{address, Bytecode, Bytes, EthereumWiring, TxKind},
+        Evm,
+    };
+
+    #[test]
+    fn records_loads_and_stores() {
+        // SSTORE(1, 5), SSTORE(1, 5), SLOAD(1)
+        let code = Bytes::from(
+            [
+                opcode::PUSH1,
+                0x05,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x05,
+                opcode::PUSH1,
+                0x01,
+                opcode::SSTORE,
+                opcode::PUSH1,
+                0x01,
+                opcode::SLOAD,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        );
+
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, StorageRecorder>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .append_handler_register(inspector_handle_register)
+            .build();
+
+        assert!(evm.transact().unwrap().result.is_success());
+
+        let access = |kind, previous_value, is_cold| StorageAccess {
+            kind,
+            address: Address::ZERO,
+            slot: U256::from(1),
+            original_value: U256::ZERO,
+            previous_value,
+            value: U256::from(5),
+            depth: 1,
+            is_cold,
+        };
+        assert_eq!(
+            evm.context.external.accesses(),
+            [
+                access(StorageAccessKind::Store, U256::ZERO, true),
+                access(StorageAccessKind::Store, U256::from(5), false),
+                access(StorageAccessKind::Load, U256::from(5), false),
+            ]
+        );
+    }
+}
@@ -1,15 +1,26 @@
 use revm_interpreter::Eip7702CodeLoad;
 
 use crate::{
-    interpreter::{AccountLoad, InstructionResult, SStoreResult, SelfDestructResult, StateLoad},
+    interpreter::{
+        analysis::to_analysed, AccountLoad, InstructionResult, SStoreResult, SelfDestructResult,
+        StateLoad,
+    },
     primitives::{
-        db::Database, hash_map::Entry, Account, Address, Bytecode, EvmState, EvmStorageSlot,
-        HashMap, HashSet, Log, SpecId, SpecId::*, TransientStorage, B256, KECCAK_EMPTY,
-        PRECOMPILE3, U256,
+        db::Database, hash_map::Entry, Account, Address, Bytecode, EvmLog, EvmState,
+        EvmStorageSlot, HashMap, HashSet, Log, SpecId, SpecId::*, TransientStorage, B256,
+        KECCAK_EMPTY, PRECOMPILE3, U256,
     },
 };
-use core::mem;
-use std::vec::Vec;
+use core::{fmt, mem};
+use dyn_clone::DynClone;
+use smallvec::SmallVec;
+use std::{boxed::Box, vec::Vec};
+
+/// Journal entries of a single call.
+///
+/// Most calls only warm and touch a few accounts, so the first entries are stored inline
+/// and entering a call does not allocate.
+pub type JournalEntries = SmallVec<[JournalEntry; 4]>;
 
 /// A journal of state changes internal to the EVM.
 ///
@@ -24,11 +35,11 @@ pub struct JournaledState {
     /// See [EIP-1153](https://eips.ethereum.org/EIPS/eip-1153).
     pub transient_storage: TransientStorage,
     /// Emitted logs.
-    pub logs: Vec<Log>,
+    pub logs: Vec<EvmLog>,
     /// The current call stack depth.
     pub depth: usize,
     /// The journal of state changes, one for each call.
-    pub journal: Vec<Vec<JournalEntry>>,
+    pub journal: Vec<JournalEntries>,
     /// The spec ID for the EVM.
     ///
     /// This spec is used for two things:
@@ -46,6 +57,158 @@ pub struct JournaledState {
     /// Note that this not include newly loaded accounts, account and storage
     /// is considered warm if it is found in the `State`.
     pub warm_preloaded_addresses: HashSet<Address>,
+    /// Storage slots that are considered warm when they are first loaded.
+    ///
+    /// See [Self::preload_storage].
+    pub warm_preloaded_storage: HashSet<(Address, U256)>,
+    /// Counters of account and storage loads, split by whether they were served
+    /// from the journaled state or had to be fetched from the database.
+    ///
+    /// Reset on [Self::clear].
+    pub load_stats: JournalLoadStats,
+    /// Database reads, checkpoints and cold and warm accesses of the transaction.
+    ///
+    /// Reset on [Self::clear].
+    #[cfg(feature = "metrics")]
+    pub metrics: crate::TxMetrics,
+    /// Observer notified of the journal entries, see [Self::set_observer].
+    ///
+    /// Kept on [Self::clear] and [Self::finalize], and ignored by comparisons.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observer: Option<Box<dyn JournalObserver>>,
+    /// Number of entries of the last journal already passed to the observer.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    observed: usize,
+}
+
+/// Observer of the changes recorded by a [JournaledState].
+///
+/// Entries are passed once the change they record is applied, so the state holds the new
+/// values while the entry holds the previous ones. Entries are passed in the order they are
+/// appended, and all pending entries are passed before a checkpoint is created, committed or
+/// reverted.
+///
+/// As loaded accounts are borrowed by the caller, [JournalEntry::AccountWarmed] entries are
+/// passed with the next change instead.
+pub trait JournalObserver: DynClone + Send + Sync {
+    /// Called with an entry appended to the journal and the state after the change.
+    fn journal_entry(&mut self, entry: &JournalEntry, state: &EvmState) {
+        let _ = (entry, state);
+    }
+
+    /// Called when a checkpoint is created, with the new depth.
+    fn checkpoint(&mut self, depth: usize) {
+        let _ = depth;
+    }
+
+    /// Called when a checkpoint is committed, with the depth before the commit.
+    fn checkpoint_commit(&mut self, depth: usize) {
+        let _ = depth;
+    }
+
+    /// Called when a checkpoint is reverted, with the reverted entries in the order they were
+    /// appended and the state after the revert.
+    fn checkpoint_revert(&mut self, reverted: &[JournalEntry], state: &EvmState) {
+        let _ = (reverted, state);
+    }
+}
+
+dyn_clone::clone_trait_object!(JournalObserver);
+
+impl fmt::Debug for dyn JournalObserver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("JournalObserver")
+    }
+}
+
+impl PartialEq for dyn JournalObserver {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for dyn JournalObserver {}
+
+/// Counters of journaled state lookups.
+///
+/// A hit is a lookup answered from the in-memory state, a miss is one that
+/// required a database fetch.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JournalLoadStats {
+    /// Account loads served from the journaled state.
+    pub account_hits: u64,
+    /// Account loads fetched from the database.
+    pub account_misses: u64,
+    /// Storage loads served from the journaled state.
+    pub storage_hits: u64,
+    /// Storage loads fetched from the database.
+    pub storage_misses: u64,
+}
+
+impl JournalLoadStats {
+    /// Returns the total number of lookups.
+    #[inline]
+    pub fn total(&self) -> u64 {
+        self.account_hits + self.account_misses + self.storage_hits + self.storage_misses
+    }
+
+    /// Returns the total number of lookups served from the journaled state.
+    #[inline]
+    pub fn hits(&self) -> u64 {
+        self.account_hits + self.storage_hits
+    }
+
+    /// Accumulates counters of `other` into `self`.
+    #[inline]
+    pub fn merge(&mut self, other: &Self) {
+        self.account_hits += other.account_hits;
+        self.account_misses += other.account_misses;
+        self.storage_hits += other.storage_hits;
+        self.storage_misses += other.storage_misses;
+    }
+}
+
+/// Accounts and storage slots accessed by a transaction.
+///
+/// Recorded from the state of a transaction and preloaded warm in the next ones when
+/// [`CfgEnv::reuse_warm_accesses`] is enabled.
+///
+/// [`CfgEnv::reuse_warm_accesses`]: crate::primitives::CfgEnv::reuse_warm_accesses
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WarmAccesses {
+    /// Accessed accounts.
+    pub addresses: HashSet<Address>,
+    /// Accessed storage slots.
+    pub storage: HashSet<(Address, U256)>,
+}
+
+impl WarmAccesses {
+    /// Replaces the accesses with the accounts and storage slots loaded in `state`.
+    ///
+    /// This includes the accesses of reverted calls, as they are kept in the state.
+    pub fn record(&mut self, state: &EvmState) {
+        self.clear();
+        for (address, account) in state {
+            self.addresses.insert(*address);
+            self.storage
+                .extend(account.storage.keys().map(|key| (*address, *key)));
+        }
+    }
+
+    /// Returns whether no access is recorded.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.storage.is_empty()
+    }
+
+    /// Clears the recorded accesses.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.addresses.clear();
+        self.storage.clear();
+    }
 }
 
 impl JournaledState {
@@ -63,11 +226,47 @@ impl JournaledState {
             state: HashMap::new(),
             transient_storage: TransientStorage::default(),
             logs: Vec::new(),
-            journal: vec![vec![]],
+            journal: vec![JournalEntries::new()],
             depth: 0,
             spec,
             warm_preloaded_addresses,
+            warm_preloaded_storage: HashSet::new(),
+            load_stats: JournalLoadStats::default(),
+            #[cfg(feature = "metrics")]
+            metrics: crate::TxMetrics::default(),
+            observer: None,
+            observed: 0,
+        }
+    }
+
+    /// Sets the observer of the journal entries, returning the previous one.
+    pub fn set_observer(
+        &mut self,
+        observer: Option<Box<dyn JournalObserver>>,
+    ) -> Option<Box<dyn JournalObserver>> {
+        self.observed = self.journal.last().map_or(0, JournalEntries::len);
+        mem::replace(&mut self.observer, observer)
+    }
+
+    /// Returns the observer of the journal entries.
+    pub fn observer(&self) -> Option<&dyn JournalObserver> {
+        self.observer.as_deref()
+    }
+
+    /// Passes the entries of the last journal that were not observed yet to the observer.
+    #[inline]
+    fn notify_observer(&mut self) {
+        let Some(observer) = &mut self.observer else {
+            return;
+        };
+        let entries = self
+            .journal
+            .last()
+            .map_or(&[][..], JournalEntries::as_slice);
+        for entry in entries.get(self.observed..).unwrap_or_default() {
+            observer.journal_entry(entry, &self.state);
         }
+        self.observed = entries.len();
     }
 
     /// Return reference to state.
@@ -89,29 +288,52 @@ impl JournaledState {
     pub fn touch(&mut self, address: &Address) {
         if let Some(account) = self.state.get_mut(address) {
             Self::touch_account(self.journal.last_mut().unwrap(), address, account);
+            self.notify_observer();
         }
     }
 
     /// Mark account as touched.
     #[inline]
-    fn touch_account(journal: &mut Vec<JournalEntry>, address: &Address, account: &mut Account) {
+    fn touch_account(journal: &mut JournalEntries, address: &Address, account: &mut Account) {
         if !account.is_touched() {
             journal.push(JournalEntry::AccountTouched { address: *address });
             account.mark_touch();
         }
     }
 
+    /// Marks `addresses` warm, so that their first access in the transaction is not charged
+    /// as cold, as if they were in the access list.
+    ///
+    /// Accounts that were already loaded keep their state. Preloads are cleared with the
+    /// rest of the journal at the end of the transaction.
+    pub fn preload_accounts(&mut self, addresses: impl IntoIterator<Item = Address>) {
+        self.warm_preloaded_addresses.extend(addresses);
+    }
+
+    /// Marks storage slots warm, so that their first `SLOAD` or `SSTORE` in the transaction
+    /// is not charged as cold.
+    ///
+    /// Unlike an access list, this does not warm the accounts of the slots, see
+    /// [Self::preload_accounts].
+    pub fn preload_storage(&mut self, slots: impl IntoIterator<Item = (Address, U256)>) {
+        self.warm_preloaded_storage.extend(slots);
+    }
+
     /// Clears the JournaledState. Preserving only the spec.
     pub fn clear(&mut self) {
         let spec = self.spec;
+        let observer = self.observer.take();
         *self = Self::new(spec, HashSet::new());
+        self.observer = observer;
     }
 
     /// Does cleanup and returns modified state.
     ///
-    /// This resets the [JournaledState] to its initial state in [Self::new]
+    /// This resets the [JournaledState] to its initial state in [Self::new]. The logs are
+    /// converted to [Log]s.
     #[inline]
     pub fn finalize(&mut self) -> (EvmState, Vec<Log>) {
+        self.notify_observer();
         let Self {
             state,
             transient_storage,
@@ -121,13 +343,20 @@ impl JournaledState {
             // kept, see [Self::new]
             spec: _,
             warm_preloaded_addresses: _,
+            warm_preloaded_storage: _,
+            load_stats: _,
+            #[cfg(feature = "metrics")]
+                metrics: _,
+            observer: _,
+            observed,
         } = self;
 
         *transient_storage = TransientStorage::default();
-        *journal = vec![vec![]];
+        *journal = vec![JournalEntries::new()];
         *depth = 0;
+        *observed = 0;
         let state = mem::take(state);
-        let logs = mem::take(logs);
+        let logs = logs.drain(..).map(Log::from).collect();
 
         (state, logs)
     }
@@ -167,6 +396,7 @@ impl JournaledState {
 
         account.info.code_hash = hash;
         account.info.code = Some(code);
+        self.notify_observer();
     }
 
     /// use it only if you know that acc is warm
@@ -191,8 +421,102 @@ impl JournaledState {
             .push(JournalEntry::NonceChange { address });
 
         account.info.nonce += 1;
+        let nonce = account.info.nonce;
+        self.notify_observer();
+
+        Some(nonce)
+    }
+
+    /// Sets the balance of an account, loading it if needed.
+    ///
+    /// Unlike [JournaledState::transfer], the balance is not taken from another account. The
+    /// change is journaled and is reverted with the checkpoint it was made in.
+    #[inline]
+    pub fn set_balance<DB: Database>(
+        &mut self,
+        address: Address,
+        balance: U256,
+        db: &mut DB,
+    ) -> Result<(), DB::Error> {
+        self.load_account(address, db)?;
+        let account = self.state.get_mut(&address).unwrap();
+        let last_journal = self.journal.last_mut().unwrap();
+        Self::touch_account(last_journal, &address, account);
+        last_journal.push(JournalEntry::BalanceSet {
+            address,
+            had_balance: account.info.balance,
+        });
+        account.info.balance = balance;
+        self.notify_observer();
+        Ok(())
+    }
+
+    /// Sets the nonce of an account, loading it if needed.
+    ///
+    /// The change is journaled and is reverted with the checkpoint it was made in.
+    #[inline]
+    pub fn set_nonce<DB: Database>(
+        &mut self,
+        address: Address,
+        nonce: u64,
+        db: &mut DB,
+    ) -> Result<(), DB::Error> {
+        self.load_account(address, db)?;
+        let account = self.state.get_mut(&address).unwrap();
+        let last_journal = self.journal.last_mut().unwrap();
+        Self::touch_account(last_journal, &address, account);
+        last_journal.push(JournalEntry::NonceSet {
+            address,
+            had_nonce: account.info.nonce,
+        });
+        account.info.nonce = nonce;
+        self.notify_observer();
+        Ok(())
+    }
+
+    /// Replaces the code of an account, loading it if needed.
+    ///
+    /// Unlike [JournaledState::set_code], the account can already have code, which is
+    /// restored if the checkpoint the change was made in is reverted.
+    #[inline]
+    pub fn set_account_code<DB: Database>(
+        &mut self,
+        address: Address,
+        code: Bytecode,
+        db: &mut DB,
+    ) -> Result<(), DB::Error> {
+        let code = to_analysed(code);
+        let hash = code.hash_slow();
+        self.load_code(address, db)?;
+        let account = self.state.get_mut(&address).unwrap();
+        let last_journal = self.journal.last_mut().unwrap();
+        Self::touch_account(last_journal, &address, account);
+        last_journal.push(JournalEntry::CodeSet {
+            address,
+            had_code: account.info.code.replace(code),
+            had_code_hash: mem::replace(&mut account.info.code_hash, hash),
+        });
+        self.notify_observer();
+        Ok(())
+    }
 
-        Some(account.info.nonce)
+    /// Sets a storage slot of an account, loading both if needed.
+    ///
+    /// The slot is stored as with `SSTORE`, the change is journaled and is reverted with the
+    /// checkpoint it was made in.
+    #[inline]
+    pub fn set_storage<DB: Database>(
+        &mut self,
+        address: Address,
+        key: U256,
+        value: U256,
+        db: &mut DB,
+    ) -> Result<(), DB::Error> {
+        self.load_account(address, db)?;
+        let account = self.state.get_mut(&address).unwrap();
+        Self::touch_account(self.journal.last_mut().unwrap(), &address, account);
+        self.sstore(address, key, value, db)?;
+        Ok(())
     }
 
     /// Transfers balance from two accounts. Returns error if sender balance is not enough.
@@ -236,6 +560,7 @@ impl JournaledState {
                 to: *to,
                 balance,
             });
+        self.notify_observer();
 
         Ok(None)
     }
@@ -314,6 +639,7 @@ impl JournaledState {
             to: address,
             balance,
         });
+        self.notify_observer();
 
         Ok(checkpoint)
     }
@@ -323,7 +649,7 @@ impl JournaledState {
     fn journal_revert(
         state: &mut EvmState,
         transient_storage: &mut TransientStorage,
-        journal_entries: Vec<JournalEntry>,
+        journal_entries: JournalEntries,
         is_spurious_dragon_enabled: bool,
     ) {
         for entry in journal_entries.into_iter().rev() {
@@ -421,6 +747,24 @@ impl JournaledState {
                     acc.info.code_hash = KECCAK_EMPTY;
                     acc.info.code = None;
                 }
+                JournalEntry::BalanceSet {
+                    address,
+                    had_balance,
+                } => {
+                    state.get_mut(&address).unwrap().info.balance = had_balance;
+                }
+                JournalEntry::NonceSet { address, had_nonce } => {
+                    state.get_mut(&address).unwrap().info.nonce = had_nonce;
+                }
+                JournalEntry::CodeSet {
+                    address,
+                    had_code,
+                    had_code_hash,
+                } => {
+                    let acc = state.get_mut(&address).unwrap();
+                    acc.info.code_hash = had_code_hash;
+                    acc.info.code = had_code;
+                }
             }
         }
     }
@@ -428,24 +772,41 @@ impl JournaledState {
     /// Makes a checkpoint that in case of Revert can bring back state to this point.
     #[inline]
     pub fn checkpoint(&mut self) -> JournalCheckpoint {
+        self.notify_observer();
         let checkpoint = JournalCheckpoint {
             log_i: self.logs.len(),
             journal_i: self.journal.len(),
         };
         self.depth += 1;
         self.journal.push(Default::default());
+        self.observed = 0;
+        if let Some(observer) = &mut self.observer {
+            observer.checkpoint(self.depth);
+        }
+        #[cfg(feature = "metrics")]
+        {
+            self.metrics.checkpoints += 1;
+        }
         checkpoint
     }
 
     /// Commit the checkpoint.
     #[inline]
     pub fn checkpoint_commit(&mut self) {
+        self.notify_observer();
+        if let Some(observer) = &mut self.observer {
+            observer.checkpoint_commit(self.depth);
+        }
         self.depth -= 1;
     }
 
     /// Reverts all changes to state until given checkpoint.
     #[inline]
     pub fn checkpoint_revert(&mut self, checkpoint: JournalCheckpoint) {
+        self.notify_observer();
+        // Reverted entries are only collected for the observer.
+        let mut reverted = Vec::new();
+        let is_observed = self.observer.is_some();
         let is_spurious_dragon_enabled = SpecId::enabled(self.spec, SPURIOUS_DRAGON);
         let state = &mut self.state;
         let transient_storage = &mut self.transient_storage;
@@ -457,16 +818,25 @@ impl JournaledState {
             .rev()
             .take(leng - checkpoint.journal_i)
             .for_each(|cs| {
+                let entries = mem::take(cs);
+                if is_observed {
+                    // Journals are visited last first, and the entries of each are appended.
+                    reverted.splice(0..0, entries.iter().cloned());
+                }
                 Self::journal_revert(
                     state,
                     transient_storage,
-                    mem::take(cs),
+                    entries,
                     is_spurious_dragon_enabled,
                 )
             });
 
         self.logs.truncate(checkpoint.log_i);
         self.journal.truncate(checkpoint.journal_i);
+        self.observed = self.journal.last().map_or(0, JournalEntries::len);
+        if let Some(observer) = &mut self.observer {
+            observer.checkpoint_revert(&reverted, &self.state);
+        }
     }
 
     /// Performances selfdestruct action.
@@ -535,6 +905,7 @@ impl JournaledState {
         if let Some(entry) = journal_entry {
             self.journal.last_mut().unwrap().push(entry);
         };
+        self.notify_observer();
 
         Ok(StateLoad {
             data: SelfDestructResult {
@@ -582,6 +953,7 @@ impl JournaledState {
     ) -> Result<StateLoad<&mut Account>, DB::Error> {
         let load = match self.state.entry(address) {
             Entry::Occupied(entry) => {
+                self.load_stats.account_hits += 1;
                 let account = entry.into_mut();
                 let is_cold = account.mark_warm();
                 StateLoad {
@@ -590,6 +962,11 @@ impl JournaledState {
                 }
             }
             Entry::Vacant(vac) => {
+                self.load_stats.account_misses += 1;
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.db_basic_reads += 1;
+                }
                 let account = if let Some(account) = db.basic(address)? {
                     account.into()
                 } else {
@@ -606,6 +983,13 @@ impl JournaledState {
             }
         };
 
+        #[cfg(feature = "metrics")]
+        if load.is_cold {
+            self.metrics.cold_account_accesses += 1;
+        } else {
+            self.metrics.warm_account_accesses += 1;
+        }
+
         // journal loading of cold account.
         if load.is_cold {
             self.journal
@@ -650,8 +1034,10 @@ impl JournaledState {
         address: Address,
         db: &mut DB,
     ) -> Result<StateLoad<&mut Account>, DB::Error> {
-        let account_load = self.load_account(address, db)?;
-        let acc = &mut account_load.data.info;
+        let is_cold = self.load_account(address, db)?.is_cold;
+        // Look the account up again, so the metrics can be updated while it is borrowed.
+        let account = self.state.get_mut(&address).unwrap();
+        let acc = &mut account.info;
         if acc.code.is_none() {
             if acc.code_hash == KECCAK_EMPTY {
                 let empty = Bytecode::default();
@@ -659,9 +1045,18 @@ impl JournaledState {
             } else {
                 let code = db.code_by_hash(acc.code_hash)?;
                 acc.code = Some(code);
+                #[cfg(feature = "metrics")]
+                {
+                    self.metrics.db_code_reads += 1;
+                }
             }
         }
-        Ok(account_load)
+        // Analyse raw legacy bytecode once, so every frame and `EXTCODE*` access shares the
+        // same padded bytes and jump table instead of redoing the analysis on each call.
+        if let Some(code @ Bytecode::LegacyRaw(_)) = &mut acc.code {
+            *code = to_analysed(mem::take(code));
+        }
+        Ok(StateLoad::new(account, is_cold))
     }
 
     /// Load storage slot
@@ -682,6 +1077,7 @@ impl JournaledState {
         let is_newly_created = account.is_created();
         let (value, is_cold) = match account.storage.entry(key) {
             Entry::Occupied(occ) => {
+                self.load_stats.storage_hits += 1;
                 let slot = occ.into_mut();
                 let is_cold = slot.mark_warm();
                 (slot.present_value, is_cold)
@@ -691,21 +1087,37 @@ impl JournaledState {
                 let value = if is_newly_created {
                     U256::ZERO
                 } else {
+                    self.load_stats.storage_misses += 1;
+                    #[cfg(feature = "metrics")]
+                    {
+                        self.metrics.db_storage_reads += 1;
+                    }
                     db.storage(address, key)?
                 };
 
                 vac.insert(EvmStorageSlot::new(value));
 
-                (value, true)
+                (
+                    value,
+                    !self.warm_preloaded_storage.contains(&(address, key)),
+                )
             }
         };
 
+        #[cfg(feature = "metrics")]
+        if is_cold {
+            self.metrics.cold_storage_accesses += 1;
+        } else {
+            self.metrics.warm_storage_accesses += 1;
+        }
+
         if is_cold {
             // add it to journal as cold loaded.
             self.journal
                 .last_mut()
                 .unwrap()
                 .push(JournalEntry::StorageWarmed { address, key });
+            self.notify_observer();
         }
 
         Ok(StateLoad::new(value, is_cold))
@@ -754,14 +1166,13 @@ impl JournaledState {
             });
         // insert value into present state.
         slot.present_value = new;
-        Ok(StateLoad::new(
-            SStoreResult {
-                original_value: slot.original_value(),
-                present_value: present.data,
-                new_value: new,
-            },
-            present.is_cold,
-        ))
+        let result = SStoreResult {
+            original_value: slot.original_value(),
+            present_value: present.data,
+            new_value: new,
+        };
+        self.notify_observer();
+        Ok(StateLoad::new(result, present.is_cold))
     }
 
     /// Read transient storage tied to the account.
@@ -814,12 +1225,13 @@ impl JournaledState {
                     key,
                     had_value,
                 });
+            self.notify_observer();
         }
     }
 
     /// push log into subroutine
     #[inline]
-    pub fn log(&mut self, log: Log) {
+    pub fn log(&mut self, log: EvmLog) {
         self.logs.push(log);
     }
 }
@@ -888,6 +1300,22 @@ pub enum JournalEntry {
     /// Action: Account code changed
     /// Revert: Revert to previous bytecode.
     CodeChange { address: Address },
+    /// Balance set without a transfer, for example by a cheatcode
+    /// Action: Balance set
+    /// Revert: Revert to previous balance
+    BalanceSet { address: Address, had_balance: U256 },
+    /// Nonce set to an arbitrary value
+    /// Action: Nonce set
+    /// Revert: Revert to previous nonce
+    NonceSet { address: Address, had_nonce: u64 },
+    /// Code of an account replaced
+    /// Action: Account code replaced
+    /// Revert: Revert to previous code and code hash
+    CodeSet {
+        address: Address,
+        had_code: Option<Bytecode>,
+        had_code_hash: B256,
+    },
 }
 
 /// SubRoutine checkpoint that will help us to go back from this
@@ -897,3 +1325,89 @@ pub struct JournalCheckpoint {
     log_i: usize,
     journal_i: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::EmptyDB;
+    use std::sync::{Arc, Mutex};
+
+    type Event = (&'static str, Option<JournalEntry>);
+
+    /// Records the observed events.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<Event>>>);
+
+    impl JournalObserver for Recorder {
+        fn journal_entry(&mut self, entry: &JournalEntry, _: &EvmState) {
+            self.0.lock().unwrap().push(("entry", Some(entry.clone())));
+        }
+
+        fn checkpoint(&mut self, _: usize) {
+            self.0.lock().unwrap().push(("checkpoint", None));
+        }
+
+        fn checkpoint_commit(&mut self, _: usize) {
+            self.0.lock().unwrap().push(("commit", None));
+        }
+
+        fn checkpoint_revert(&mut self, reverted: &[JournalEntry], state: &EvmState) {
+            let mut events = self.0.lock().unwrap();
+            events.extend(
+                reverted
+                    .iter()
+                    .map(|entry| ("reverted", Some(entry.clone()))),
+            );
+            assert!(state.values().all(|account| account.info.nonce == 0));
+        }
+    }
+
+    #[test]
+    fn observer_receives_entries_and_reverts() {
+        let (a, b) = (Address::with_last_byte(0xa), Address::with_last_byte(0xb));
+        let recorder = Recorder::default();
+        let mut journal = JournaledState::new(SpecId::CANCUN, HashSet::new());
+        journal.set_observer(Some(Box::new(recorder.clone())));
+        let db = &mut EmptyDB::default();
+
+        journal.load_account(a, db).unwrap();
+        let checkpoint = journal.checkpoint();
+        journal.inc_nonce(a);
+        journal
+            .set_storage(b, U256::from(1), U256::from(2), db)
+            .unwrap();
+        journal.checkpoint_revert(checkpoint);
+        journal.checkpoint();
+        journal.checkpoint_commit();
+
+        let warmed = JournalEntry::AccountWarmed { address: a };
+        let entries = [
+            JournalEntry::AccountTouched { address: a },
+            JournalEntry::NonceChange { address: a },
+            JournalEntry::AccountWarmed { address: b },
+            JournalEntry::AccountTouched { address: b },
+            JournalEntry::StorageWarmed {
+                address: b,
+                key: U256::from(1),
+            },
+            JournalEntry::StorageChanged {
+                address: b,
+                key: U256::from(1),
+                had_value: U256::ZERO,
+            },
+        ];
+        let mut expected = vec![("entry", Some(warmed)), ("checkpoint", None)];
+        expected.extend(entries.iter().map(|entry| ("entry", Some(entry.clone()))));
+        expected.extend(
+            entries
+                .iter()
+                .map(|entry| ("reverted", Some(entry.clone()))),
+        );
+        expected.extend([("checkpoint", None), ("commit", None)]);
+        assert_eq!(*recorder.0.lock().unwrap(), expected);
+
+        // The observer is kept when the journal is cleared.
+        journal.clear();
+        assert!(journal.observer().is_some());
+    }
+}
@@ -8,8 +8,18 @@ extern crate alloc as std;
 
 // Define modules.
 
+#[cfg(feature = "abi")]
+pub mod abi;
+#[cfg(feature = "serde-json")]
+pub mod artifact;
 mod builder;
+#[cfg(feature = "compiler")]
+pub mod compiler;
 mod context;
+pub mod create2;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod differential;
 
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
@@ -21,6 +31,17 @@ mod frame;
 pub mod handler;
 mod inspector;
 mod journaled_state;
+mod log_sink;
+#[cfg(feature = "metrics")]
+mod metrics;
+mod post_block;
+mod simulator;
+#[cfg(feature = "tracing")]
+mod spans;
+#[cfg(feature = "std")]
+mod stats;
+#[cfg(feature = "trie")]
+pub mod trie;
 
 // Export items.
 
@@ -31,15 +52,26 @@ pub use context::{
     ContextWithEvmWiring, EvmContext, InnerEvmContext,
 };
 pub use db::{
-    CacheState, DBBox, State, StateBuilder, StateDBBox, TransitionAccount, TransitionState,
+    CacheBounds, CacheMetrics, CacheState, DBBox, State, StateBuilder, StateDBBox,
+    TransitionAccount, TransitionState,
 };
 pub use db::{Database, DatabaseCommit, DatabaseRef, InMemoryDB};
-pub use evm::{Evm, CALL_STACK_LIMIT};
+pub use evm::{CreateSimulation, Evm, CALL_STACK_LIMIT};
 pub use evm_wiring::EvmWiring;
 pub use frame::{CallFrame, CreateFrame, Frame, FrameData, FrameOrResult, FrameResult};
 pub use handler::{register::EvmHandler, Handler};
 pub use inspector::{inspector_handle_register, inspectors, GetInspector, Inspector};
-pub use journaled_state::{JournalCheckpoint, JournalEntry, JournaledState};
+pub use journaled_state::{
+    JournalCheckpoint, JournalEntries, JournalEntry, JournalLoadStats, JournalObserver,
+    JournaledState, WarmAccesses,
+};
+pub use log_sink::LogEvent;
+#[cfg(feature = "metrics")]
+pub use metrics::TxMetrics;
+pub use post_block::{PostBlockCall, PostBlockCalls, Requests};
+pub use simulator::{BlockSnapshot, ChainSimulator};
+#[cfg(feature = "std")]
+pub use stats::EvmStats;
 // Reexport libraries
 
 #[doc(inline)]
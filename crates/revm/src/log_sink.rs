@@ -0,0 +1,110 @@
+//! Streaming of logs as they are emitted, see [EvmBuilder::with_log_sink].
+//!
+//! [EvmBuilder::with_log_sink]: crate::EvmBuilder::with_log_sink
+
+use crate::{
+    handler::register::{EvmHandler, HandleRegisterBox},
+    interpreter::opcode,
+    primitives::Log,
+    EvmWiring,
+};
+use core::cell::{Cell, RefCell};
+use std::{boxed::Box, rc::Rc, sync::Arc};
+
+/// Log emitted during the execution of a transaction.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogEvent {
+    /// Index of the transaction among the transactions executed by the EVM, starting at zero.
+    pub tx_index: u64,
+    /// Call depth of the emitting contract.
+    pub depth: u64,
+    /// Emitted log, with the address of the emitting contract, its topics and data.
+    pub log: Log,
+}
+
+/// Returns a register that passes every log to `sink` as soon as it is emitted.
+pub(crate) fn log_sink_register<'a, EvmWiringT: EvmWiring>(
+    sink: impl FnMut(LogEvent) + 'static,
+) -> HandleRegisterBox<'a, EvmWiringT> {
+    let sink = Rc::new(RefCell::new(sink));
+    // Shared by the handlers built when the spec changes, so the index keeps counting.
+    let tx_count = Rc::new(Cell::new(0u64));
+    Box::new(move |handler: &mut EvmHandler<'_, EvmWiringT>| {
+        let count = tx_count.clone();
+        let start = handler.pre_execution.start.clone();
+        handler.pre_execution.start = Arc::new(move |ctx| {
+            start(ctx)?;
+            count.set(count.get() + 1);
+            Ok(())
+        });
+
+        for opcode in opcode::LOG0..=opcode::LOG4 {
+            let (sink, tx_count) = (sink.clone(), tx_count.clone());
+            handler
+                .instruction_table
+                .update_boxed(opcode, move |prev, interpreter, host| {
+                    let prev_log_len = host.evm.journaled_state.logs.len();
+                    prev(interpreter, host);
+                    if host.evm.journaled_state.logs.len() == prev_log_len + 1 {
+                        let log = host.evm.journaled_state.logs.last().unwrap().to_log();
+                        (sink.borrow_mut())(LogEvent {
+                            tx_index: tx_count.get().saturating_sub(1),
+                            depth: host.evm.journaled_state.depth(),
+                            log,
+                        });
+                    }
+                });
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::BenchmarkDB,
+        primitives::{address, Address, Bytecode, Bytes, EthereumWiring, TxKind, B256},
+        Evm,
+    };
+    use std::sync::mpsc;
+
+    #[test]
+    fn logs_are_streamed() {
+        // LOG1(offset 0, size 0, topic 7)
+        let code = Bytes::from(
+            [
+                opcode::PUSH1,
+                0x07,
+                opcode::PUSH1,
+                0x00,
+                opcode::PUSH1,
+                0x00,
+                opcode::LOG1,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        );
+        let (sender, receiver) = mpsc::channel();
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .with_log_sink(move |event| sender.send(event).unwrap())
+            .build();
+
+        for tx_index in 0..2 {
+            let result = evm.transact().unwrap().result;
+            let event = receiver.try_recv().unwrap();
+            assert_eq!(event.tx_index, tx_index);
+            assert_eq!(event.depth, 1);
+            assert_eq!(event.log.address, Address::ZERO);
+            assert_eq!(event.log.topics(), [B256::with_last_byte(7)]);
+            assert_eq!(result.logs(), [event.log]);
+            assert!(receiver.try_recv().is_err());
+        }
+    }
+}
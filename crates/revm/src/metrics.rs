@@ -0,0 +1,122 @@
+//! Lightweight per-transaction metrics, enabled by the `metrics` feature.
+//!
+//! The counters are maintained by the [JournaledState](crate::JournaledState) and reported
+//! to the callback set with [EvmBuilder::with_metrics](crate::EvmBuilder::with_metrics) at the
+//! end of every transaction.
+
+use crate::{
+    handler::register::{instruction_counter_register, EvmHandler, HandleRegisterBox},
+    EvmWiring,
+};
+use core::cell::{Cell, RefCell};
+use std::{boxed::Box, rc::Rc, sync::Arc};
+
+/// Counters of a single transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TxMetrics {
+    /// Accounts fetched from the database.
+    pub db_basic_reads: u64,
+    /// Storage slots fetched from the database.
+    pub db_storage_reads: u64,
+    /// Bytecodes fetched from the database.
+    pub db_code_reads: u64,
+    /// Block hashes fetched from the database.
+    pub db_block_hash_reads: u64,
+    /// Journal checkpoints created, one per call or create frame.
+    pub checkpoints: u64,
+    /// Account accesses that were cold.
+    pub cold_account_accesses: u64,
+    /// Account accesses that were warm.
+    pub warm_account_accesses: u64,
+    /// Storage accesses that were cold.
+    pub cold_storage_accesses: u64,
+    /// Storage accesses that were warm.
+    pub warm_storage_accesses: u64,
+    /// Interpreter instructions executed.
+    pub instructions: u64,
+}
+
+/// Returns a register that counts the executed instructions and passes the metrics of every
+/// transaction to `callback` once it is executed.
+pub(crate) fn metrics_register<'a, EvmWiringT: EvmWiring>(
+    callback: impl FnMut(&TxMetrics) + 'static,
+) -> HandleRegisterBox<'a, EvmWiringT> {
+    let callback = Rc::new(RefCell::new(callback));
+    let instructions = Rc::new(Cell::new(0u64));
+    Box::new(move |handler: &mut EvmHandler<'_, EvmWiringT>| {
+        instruction_counter_register(instructions.clone())(handler);
+
+        let (counter, callback) = (instructions.clone(), callback.clone());
+        let start = handler.pre_execution.start.clone();
+        handler.pre_execution.start = Arc::new(move |ctx| {
+            counter.set(0);
+            start(ctx)
+        });
+        let counter = instructions.clone();
+        let end = handler.post_execution.end.clone();
+        handler.post_execution.end = Arc::new(move |ctx, output| {
+            let output = end(ctx, output);
+            let metrics = TxMetrics {
+                instructions: counter.get(),
+                ..ctx.evm.journaled_state.metrics
+            };
+            (callback.borrow_mut())(&metrics);
+            output
+        });
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::BenchmarkDB,
+        interpreter::opcode,
+        primitives::{address, Address, Bytecode, Bytes, EthereumWiring, TxKind},
+        Evm,
+    };
+    use std::sync::mpsc;
+
+    #[test]
+    fn metrics_are_reported_per_transaction() {
+        // SLOAD(0), SLOAD(0), BALANCE(0)
+        let code = Bytes::from(
+            [
+                opcode::PUSH0,
+                opcode::SLOAD,
+                opcode::PUSH0,
+                opcode::SLOAD,
+                opcode::PUSH0,
+                opcode::BALANCE,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        );
+        let (sender, receiver) = mpsc::channel();
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .with_metrics(move |metrics| sender.send(*metrics).unwrap())
+            .build();
+
+        for _ in 0..2 {
+            evm.transact().unwrap();
+            let metrics = receiver.try_recv().unwrap();
+            assert_eq!(metrics.instructions, 7);
+            assert_eq!(metrics.db_storage_reads, 1);
+            assert_eq!(metrics.cold_storage_accesses, 1);
+            assert_eq!(metrics.warm_storage_accesses, 1);
+            assert_eq!(metrics.db_code_reads, 0);
+            assert_eq!(metrics.db_block_hash_reads, 0);
+            assert_eq!(metrics.checkpoints, 1);
+            assert!(metrics.db_basic_reads > 0);
+            assert!(metrics.warm_account_accesses > 0);
+            assert!(receiver.try_recv().is_err());
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! System operations that are executed after the last transaction of a block.
+//!
+//! Since Prague the block ends with system calls to the EIP-7002 and EIP-7251 contracts,
+//! whose outputs are collected into the execution layer requests of EIP-7685.
+
+use crate::{
+    db::DatabaseCommit,
+    primitives::{
+        Address, Bytes, EVMError, EVMResultGeneric, ExecutionResult, B256,
+        CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, CONSOLIDATION_REQUEST_TYPE, SYSTEM_ADDRESS,
+        WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, WITHDRAWAL_REQUEST_TYPE,
+    },
+    Evm, EvmWiring,
+};
+use sha2::{Digest, Sha256};
+use std::{format, vec::Vec};
+
+/// System call executed at the end of a block, whose output is a request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PostBlockCall {
+    /// Type of the request that is created from the output of the call.
+    pub request_type: u8,
+    /// Called system contract.
+    pub address: Address,
+    /// Input of the call.
+    pub data: Bytes,
+}
+
+impl PostBlockCall {
+    /// Creates a call to `address` with empty input.
+    pub fn new(request_type: u8, address: Address) -> Self {
+        Self {
+            request_type,
+            address,
+            data: Bytes::new(),
+        }
+    }
+
+    /// EIP-7002: Dequeues the withdrawal requests of the block.
+    pub fn withdrawal_requests() -> Self {
+        Self::new(
+            WITHDRAWAL_REQUEST_TYPE,
+            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+        )
+    }
+
+    /// EIP-7251: Dequeues the consolidation requests of the block.
+    pub fn consolidation_requests() -> Self {
+        Self::new(
+            CONSOLIDATION_REQUEST_TYPE,
+            CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+        )
+    }
+}
+
+/// Ordered queue of the [PostBlockCall]s of a chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PostBlockCalls {
+    /// Calls in the order of execution.
+    pub calls: Vec<PostBlockCall>,
+}
+
+impl PostBlockCalls {
+    /// Creates an empty queue.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls of the Prague hardfork: withdrawal requests followed by consolidation requests.
+    pub fn prague() -> Self {
+        Self::new()
+            .with_call(PostBlockCall::withdrawal_requests())
+            .with_call(PostBlockCall::consolidation_requests())
+    }
+
+    /// Appends a call to the queue.
+    pub fn with_call(mut self, call: PostBlockCall) -> Self {
+        self.calls.push(call);
+        self
+    }
+
+    /// Executes the calls in order and commits their changes to the database.
+    ///
+    /// Outputs of the calls are appended to `requests`, which may already contain requests
+    /// gathered during block execution, such as EIP-6110 deposits. A call that does not
+    /// succeed makes the block invalid and is returned as [EVMError::Custom].
+    pub fn apply<EvmWiringT>(
+        &self,
+        evm: &mut Evm<'_, EvmWiringT>,
+        requests: &mut Requests,
+    ) -> EVMResultGeneric<(), EvmWiringT>
+    where
+        EvmWiringT: EvmWiring<Database: DatabaseCommit>,
+    {
+        for call in &self.calls {
+            let result =
+                evm.transact_system_call_commit(SYSTEM_ADDRESS, call.address, call.data.clone())?;
+            match result {
+                ExecutionResult::Success { output, .. } => {
+                    requests.push(call.request_type, output.into_data());
+                }
+                result => {
+                    return Err(EVMError::Custom(format!(
+                        "system call to {} failed: {result:?}",
+                        call.address
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// EIP-7685: Execution layer requests of a block.
+///
+/// Each request is encoded as its type followed by its data.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Requests(pub Vec<Bytes>);
+
+impl Requests {
+    /// Appends a request of `request_type`. Requests with empty data are omitted.
+    pub fn push(&mut self, request_type: u8, data: Bytes) {
+        if data.is_empty() {
+            return;
+        }
+        let mut request = Vec::with_capacity(data.len() + 1);
+        request.push(request_type);
+        request.extend_from_slice(&data);
+        self.0.push(request.into());
+    }
+
+    /// Returns the encoded requests.
+    pub fn as_slice(&self) -> &[Bytes] {
+        &self.0
+    }
+
+    /// Computes the `requests_hash` of the block header.
+    pub fn requests_hash(&self) -> B256 {
+        let mut hasher = Sha256::new();
+        for request in &self.0 {
+            hasher.update(Sha256::digest(request));
+        }
+        B256::from_slice(&hasher.finalize())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        interpreter::opcode::{MSTORE8, PUSH1, RETURN, REVERT},
+        primitives::{AccountInfo, Bytecode, EthereumWiring},
+    };
+
+    fn contract(code: &[u8]) -> AccountInfo {
+        AccountInfo::from_bytecode(Bytecode::new_legacy(Bytes::copy_from_slice(code)))
+    }
+
+    #[test]
+    fn collects_requests() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        // Returns a single 0xab byte.
+        db.insert_account_info(
+            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            contract(&[PUSH1, 0xab, PUSH1, 0, MSTORE8, PUSH1, 1, PUSH1, 0, RETURN]),
+        );
+        // Returns no requests.
+        db.insert_account_info(
+            CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+            contract(&[PUSH1, 0, PUSH1, 0, RETURN]),
+        );
+
+        let mut evm = Evm::<EthereumWiring<_, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .build();
+
+        let mut requests = Requests::default();
+        PostBlockCalls::prague()
+            .apply(&mut evm, &mut requests)
+            .unwrap();
+
+        let withdrawal = Bytes::from_static(&[WITHDRAWAL_REQUEST_TYPE, 0xab]);
+        assert_eq!(requests, Requests(vec![withdrawal.clone()]));
+
+        let mut hasher = Sha256::new();
+        hasher.update(Sha256::digest(&withdrawal));
+        assert_eq!(
+            requests.requests_hash(),
+            B256::from_slice(&hasher.finalize())
+        );
+        assert_eq!(
+            Requests::default().requests_hash(),
+            B256::from_slice(&Sha256::digest([]))
+        );
+    }
+
+    #[test]
+    fn failed_call_is_error() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            contract(&[PUSH1, 0, PUSH1, 0, REVERT]),
+        );
+
+        let mut evm = Evm::<EthereumWiring<_, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .build();
+
+        let mut requests = Requests::default();
+        let calls = PostBlockCalls::new().with_call(PostBlockCall::withdrawal_requests());
+        assert!(matches!(
+            calls.apply(&mut evm, &mut requests),
+            Err(EVMError::Custom(_))
+        ));
+        assert!(requests.as_slice().is_empty());
+    }
+}
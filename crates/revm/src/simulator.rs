@@ -0,0 +1,305 @@
+//! Multi-block simulation on top of [State].
+//!
+//! [ChainSimulator] executes blocks of transactions and derives the environment of every
+//! block from its parent, like a development node would.
+
+use crate::{
+    db::{states::bundle_state::BundleRetention, CacheState, Database, State, StateBuilder},
+    primitives::{
+        keccak256, BaseFeeParams, BlockEnv, CfgEnv, EVMError, Env, EthereumWiring, ExecutionResult,
+        HaltReason, HardforkSchedule, InvalidTransaction, SpecId, TxEnv, GAS_PER_BLOB, U256,
+    },
+    Evm,
+};
+use std::vec::Vec;
+
+/// Executed block of a [ChainSimulator].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockSnapshot {
+    /// Environment the block was executed with.
+    pub block: BlockEnv,
+    /// Results of the transactions, in order.
+    pub results: Vec<ExecutionResult<HaltReason>>,
+    /// Gas used by the transactions.
+    pub gas_used: u64,
+    /// Blob gas used by the transactions.
+    pub blob_gas_used: u64,
+    /// Cache of the state after the block.
+    pub cache: CacheState,
+}
+
+/// Executes blocks of transactions on top of a [State], advancing the block environment
+/// between blocks.
+///
+/// After every block, the number is incremented, the timestamp is advanced by the block time,
+/// the base fee and blob gas price are derived from the gas used by the block, and
+/// `prevrandao` is set to the hash of the previous value, see [BlockEnv::next_block].
+///
+/// Blocks are merged into the bundle of the state with their reverts, so they can be
+/// persisted with [State::take_bundle] or reverted with [ChainSimulator::revert_blocks].
+#[derive(Debug)]
+pub struct ChainSimulator<DB> {
+    state: State<DB>,
+    cfg: CfgEnv,
+    spec_id: SpecId,
+    schedule: Option<HardforkSchedule>,
+    block: BlockEnv,
+    block_time: u64,
+    base_fee_params: BaseFeeParams,
+    snapshots: Vec<BlockSnapshot>,
+}
+
+impl<DB: Database> ChainSimulator<DB> {
+    /// Creates a simulator whose first block is `block`, on top of `database`.
+    ///
+    /// Blocks are 12 seconds apart and follow the base fee rules of Ethereum by default.
+    pub fn new(database: DB, spec_id: SpecId, block: BlockEnv) -> Self {
+        Self {
+            state: StateBuilder::new_with_database(database)
+                .with_bundle_update()
+                .build(),
+            cfg: CfgEnv::default(),
+            spec_id,
+            schedule: None,
+            block,
+            block_time: 12,
+            base_fee_params: BaseFeeParams::ETHEREUM,
+            snapshots: Vec::new(),
+        }
+    }
+
+    /// Sets the configuration the transactions are executed with.
+    pub fn with_cfg(mut self, cfg: CfgEnv) -> Self {
+        self.cfg = cfg;
+        self
+    }
+
+    /// Resolves the spec of every block with `schedule` instead of using the spec the simulator
+    /// was created with, so the simulated chain can cross hardforks.
+    pub fn with_hardfork_schedule(mut self, schedule: HardforkSchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// Sets the number of seconds between two blocks.
+    pub fn with_block_time(mut self, block_time: u64) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// Sets the parameters of the base fee adjustment, for example for L2s.
+    pub fn with_base_fee_params(mut self, base_fee_params: BaseFeeParams) -> Self {
+        self.base_fee_params = base_fee_params;
+        self
+    }
+
+    /// Returns the environment of the next block.
+    pub fn block(&self) -> &BlockEnv {
+        &self.block
+    }
+
+    /// Returns the spec the next block is executed with.
+    pub fn spec_id(&self) -> SpecId {
+        self.schedule
+            .as_ref()
+            .map_or(self.spec_id, |schedule| schedule.spec_for(&self.block))
+    }
+
+    /// Returns the state the blocks are executed on.
+    pub fn state(&self) -> &State<DB> {
+        &self.state
+    }
+
+    /// Returns the state the blocks are executed on, for example to fund accounts.
+    pub fn state_mut(&mut self) -> &mut State<DB> {
+        &mut self.state
+    }
+
+    /// Returns the executed blocks, oldest first.
+    pub fn snapshots(&self) -> &[BlockSnapshot] {
+        &self.snapshots
+    }
+
+    /// Returns the executed block with the given number.
+    pub fn snapshot(&self, number: u64) -> Option<&BlockSnapshot> {
+        self.snapshots
+            .iter()
+            .find(|snapshot| snapshot.block.number == U256::from(number))
+    }
+
+    /// Executes a block made of `transactions`, then advances to the next block.
+    ///
+    /// If a transaction is invalid, the error is returned and the block is not finished: the
+    /// previous transactions of the block stay applied to the state, and the environment is
+    /// not advanced.
+    pub fn apply_block(
+        &mut self,
+        transactions: impl IntoIterator<Item = TxEnv>,
+    ) -> Result<&BlockSnapshot, EVMError<DB::Error, InvalidTransaction>> {
+        let spec_id = self.spec_id();
+        self.state
+            .set_state_clear_flag(spec_id.is_enabled_in(SpecId::SPURIOUS_DRAGON));
+        let mut results = Vec::new();
+        let (mut gas_used, mut blob_gas_used) = (0, 0);
+        for tx in transactions {
+            blob_gas_used += tx.blob_hashes.len() as u64 * GAS_PER_BLOB;
+            let result = Evm::<EthereumWiring<&mut State<DB>, ()>>::builder()
+                .with_db(&mut self.state)
+                .with_default_ext_ctx()
+                .with_env(Env::boxed(self.cfg.clone(), self.block.clone(), tx))
+                .with_spec_id(spec_id)
+                .build()
+                .transact_commit()?;
+            gas_used += result.gas_used();
+            results.push(result);
+        }
+        self.state.merge_transitions(BundleRetention::Reverts);
+
+        let prevrandao = self.block.prevrandao.map(keccak256);
+        let mut next = self.block.next_block(
+            gas_used,
+            blob_gas_used,
+            self.block_time,
+            self.base_fee_params,
+        );
+        next.prevrandao = prevrandao;
+        let block = core::mem::replace(&mut self.block, next);
+
+        self.snapshots.push(BlockSnapshot {
+            block,
+            results,
+            gas_used,
+            blob_gas_used,
+            cache: self.state.cache.clone(),
+        });
+        Ok(self.snapshots.last().expect("snapshot was just pushed"))
+    }
+
+    /// Reverts the latest `num_blocks` blocks, and returns the number of reverted blocks.
+    ///
+    /// The next block gets the environment of the oldest reverted block.
+    pub fn revert_blocks(&mut self, num_blocks: usize) -> usize {
+        let num_blocks = self.state.revert_blocks(num_blocks);
+        let remaining = self.snapshots.len() - num_blocks;
+        if let Some(oldest) = self.snapshots.drain(remaining..).next() {
+            self.block = oldest.block;
+        }
+        num_blocks
+    }
+
+    /// Consumes the simulator and returns its state.
+    pub fn into_state(self) -> State<DB> {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        primitives::{AccountInfo, Address, Bytecode, Bytes, ForkCondition, TxKind, B256},
+    };
+
+    #[test]
+    fn blocks_roll_env_and_revert() {
+        let (caller, receiver) = (Address::with_last_byte(0xa1), Address::with_last_byte(0xa2));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            caller,
+            AccountInfo::from_balance(U256::from(1_000_000_000_000_000u64)),
+        );
+        let block = BlockEnv::default()
+            .with_number(1)
+            .with_timestamp(1_000)
+            .with_gas_limit(42_000)
+            .with_basefee(1_000)
+            .with_prevrandao(B256::ZERO);
+        let mut simulator = ChainSimulator::new(db, SpecId::CANCUN, block);
+
+        let transfer = |nonce| TxEnv {
+            caller,
+            transact_to: TxKind::Call(receiver),
+            value: U256::from(1),
+            gas_limit: 21_000,
+            gas_price: U256::from(2_000),
+            nonce: Some(nonce),
+            ..Default::default()
+        };
+
+        // A full block increases the base fee by 1/8.
+        let snapshot = simulator.apply_block([transfer(0), transfer(1)]).unwrap();
+        assert_eq!(snapshot.gas_used, 42_000);
+        assert!(snapshot.results.iter().all(ExecutionResult::is_success));
+        assert_eq!(simulator.block().number, U256::from(2));
+        assert_eq!(simulator.block().timestamp, U256::from(1_012));
+        assert_eq!(simulator.block().basefee, U256::from(1_125));
+        assert_eq!(simulator.block().prevrandao, Some(keccak256(B256::ZERO)));
+
+        // An empty block decreases it by 1/8.
+        simulator.apply_block([]).unwrap();
+        assert_eq!(simulator.block().basefee, U256::from(985));
+        simulator.apply_block([transfer(2)]).unwrap();
+        assert_eq!(simulator.snapshots().len(), 3);
+        assert_eq!(
+            simulator.snapshot(3).unwrap().block.basefee,
+            U256::from(985)
+        );
+
+        let balance = |simulator: &mut ChainSimulator<_>| {
+            simulator
+                .state_mut()
+                .basic(receiver)
+                .unwrap()
+                .unwrap()
+                .balance
+        };
+        assert_eq!(balance(&mut simulator), U256::from(3));
+
+        assert_eq!(simulator.revert_blocks(2), 2);
+        assert_eq!(balance(&mut simulator), U256::from(2));
+        assert_eq!(simulator.block().number, U256::from(2));
+        assert_eq!(simulator.block().basefee, U256::from(1_125));
+        assert_eq!(simulator.snapshots().len(), 1);
+    }
+
+    #[test]
+    fn schedule_crosses_hardforks() {
+        let (caller, contract) = (Address::with_last_byte(0xa1), Address::with_last_byte(0xa2));
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            caller,
+            AccountInfo::from_balance(U256::from(1_000_000_000_000_000u64)),
+        );
+        // PUSH0, only valid from Shanghai.
+        db.insert_account_info(
+            contract,
+            AccountInfo::from_bytecode(Bytecode::new_raw(Bytes::from_static(&[0x5f, 0x00]))),
+        );
+        let block = BlockEnv::default()
+            .with_number(1)
+            .with_timestamp(1_000)
+            .with_gas_limit(30_000_000)
+            .with_prevrandao(B256::ZERO);
+        let schedule = HardforkSchedule::new()
+            .with_fork(SpecId::MERGE, ForkCondition::Block(0))
+            .with_fork(SpecId::SHANGHAI, ForkCondition::Timestamp(1_012));
+        let mut simulator =
+            ChainSimulator::new(db, SpecId::LATEST, block).with_hardfork_schedule(schedule);
+
+        let call = |nonce| TxEnv {
+            caller,
+            transact_to: TxKind::Call(contract),
+            gas_limit: 100_000,
+            nonce: Some(nonce),
+            ..Default::default()
+        };
+
+        assert_eq!(simulator.spec_id(), SpecId::MERGE);
+        let snapshot = simulator.apply_block([call(0)]).unwrap();
+        assert!(!snapshot.results[0].is_success());
+        assert_eq!(simulator.spec_id(), SpecId::SHANGHAI);
+        let snapshot = simulator.apply_block([call(1)]).unwrap();
+        assert!(snapshot.results[0].is_success());
+    }
+}
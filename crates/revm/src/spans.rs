@@ -0,0 +1,229 @@
+//! [`tracing`] spans of transactions and call frames, enabled by the `tracing` feature.
+//!
+//! A `transact` span is entered for every transaction, and a `call`, `create` or
+//! `eofcreate` span for every frame. Frame spans are nested like the frames, and record the
+//! instruction result and gas used of the frame once it returns.
+
+use crate::{
+    frame::{Frame, FrameResult},
+    interpreter::{CallInputs, CreateInputs, EOFCreateInputs},
+    primitives::{Address, ExecutionResult, HaltReasonTrait, ResultAndState, Transaction, U256},
+};
+use tracing::{debug_span, field::Empty, info_span, Span};
+
+/// Returns the span of the transaction `tx`.
+pub(crate) fn transact_span(tx: &impl Transaction) -> Span {
+    info_span!(
+        "transact",
+        caller = %tx.caller(),
+        target = ?tx.kind(),
+        value = %tx.value(),
+        gas_limit = tx.gas_limit(),
+        outcome = Empty,
+        gas_used = Empty,
+    )
+}
+
+/// Records the outcome of the transaction in its span.
+pub(crate) fn record_transact<HaltReasonT: HaltReasonTrait, E>(
+    span: &Span,
+    output: &Result<ResultAndState<HaltReasonT>, E>,
+) {
+    let Ok(output) = output else {
+        span.record("outcome", "error");
+        return;
+    };
+    let outcome = match &output.result {
+        ExecutionResult::Success { .. } => "success",
+        ExecutionResult::Revert { .. } => "revert",
+        ExecutionResult::Halt { .. } => "halt",
+    };
+    span.record("outcome", outcome);
+    span.record("gas_used", output.result.gas_used());
+}
+
+/// Returns the span of the first frame of a transaction.
+pub(crate) fn frame_span(frame: &Frame, depth: u64) -> Span {
+    let interpreter = frame.interpreter();
+    let contract = &interpreter.contract;
+    match frame {
+        Frame::Call(_) => call_span_with(
+            contract.caller,
+            contract.target_address,
+            contract.call_value,
+            interpreter.gas.limit(),
+            depth,
+        ),
+        Frame::Create(_) | Frame::EOFCreate(_) => debug_span!(
+            "create",
+            caller = %contract.caller,
+            target = %contract.target_address,
+            value = %contract.call_value,
+            gas_limit = interpreter.gas.limit(),
+            depth,
+            outcome = Empty,
+            gas_used = Empty,
+        ),
+    }
+}
+
+/// Returns the span of a call frame.
+pub(crate) fn call_span(inputs: &CallInputs, depth: u64) -> Span {
+    call_span_with(
+        inputs.caller,
+        inputs.target_address,
+        inputs.value.get(),
+        inputs.gas_limit,
+        depth,
+    )
+}
+
+fn call_span_with(
+    caller: Address,
+    target: Address,
+    value: U256,
+    gas_limit: u64,
+    depth: u64,
+) -> Span {
+    debug_span!(
+        "call",
+        caller = %caller,
+        target = %target,
+        value = %value,
+        gas_limit,
+        depth,
+        outcome = Empty,
+        gas_used = Empty,
+    )
+}
+
+/// Returns the span of a create frame.
+///
+/// The target is not known before the nonce of the caller is loaded, so it is not recorded.
+pub(crate) fn create_span(inputs: &CreateInputs, depth: u64) -> Span {
+    debug_span!(
+        "create",
+        caller = %inputs.caller,
+        scheme = ?inputs.scheme,
+        value = %inputs.value,
+        gas_limit = inputs.gas_limit,
+        depth,
+        outcome = Empty,
+        gas_used = Empty,
+    )
+}
+
+/// Returns the span of an EOF create frame.
+pub(crate) fn eofcreate_span(inputs: &EOFCreateInputs, depth: u64) -> Span {
+    debug_span!(
+        "eofcreate",
+        caller = %inputs.caller,
+        value = %inputs.value,
+        gas_limit = inputs.gas_limit,
+        depth,
+        outcome = Empty,
+        gas_used = Empty,
+    )
+}
+
+/// Records the outcome of the frame in its span.
+pub(crate) fn record_frame(span: &Span, result: &FrameResult) {
+    span.record(
+        "outcome",
+        tracing::field::debug(result.instruction_result()),
+    );
+    span.record("gas_used", result.gas().spent());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::BenchmarkDB,
+        interpreter::opcode,
+        primitives::{address, Address, Bytecode, Bytes, EthereumWiring, TxKind},
+        Evm,
+    };
+    use std::{
+        format,
+        string::String,
+        sync::{Arc, Mutex},
+        vec::Vec,
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Event, Metadata, Subscriber,
+    };
+
+    /// Collects the names of the created spans and the recorded outcomes.
+    #[derive(Clone, Default)]
+    struct Collector(Arc<Mutex<Vec<String>>>);
+
+    impl Visit for Collector {
+        fn record_debug(&mut self, field: &Field, value: &dyn core::fmt::Debug) {
+            if field.name() == "outcome" {
+                self.0.lock().unwrap().push(format!("{value:?}"));
+            }
+        }
+    }
+
+    impl Subscriber for Collector {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut spans = self.0.lock().unwrap();
+            spans.push(String::from(span.metadata().name()));
+            Id::from_u64(spans.len() as u64)
+        }
+
+        fn record(&self, _: &Id, values: &Record<'_>) {
+            values.record(&mut self.clone());
+        }
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, _: &Event<'_>) {}
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn transaction_and_frames_are_instrumented() {
+        // CALL(gas, address 0xa2, no value, no data), then STOP.
+        let code = Bytes::from(
+            [
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH0,
+                opcode::PUSH1,
+                0xa2,
+                opcode::GAS,
+                opcode::CALL,
+                opcode::STOP,
+            ]
+            .to_vec(),
+        );
+        let mut evm = Evm::<EthereumWiring<BenchmarkDB, ()>>::builder()
+            .with_db(BenchmarkDB::new_bytecode(Bytecode::new_legacy(code)))
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.caller = address!("1000000000000000000000000000000000000000");
+                tx.transact_to = TxKind::Call(Address::ZERO);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+
+        let collector = Collector::default();
+        tracing::subscriber::with_default(collector.clone(), || evm.transact().unwrap());
+        assert_eq!(
+            *collector.0.lock().unwrap(),
+            ["transact", "call", "call", "Stop", "Stop", "\"success\""]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+//! Operational statistics of a long-running [`Evm`](crate::Evm).
+//!
+//! Collection is disabled by default and is turned on with
+//! [`Evm::enable_stats`](crate::Evm::enable_stats). Once enabled, every call to
+//! `transact`, `transact_preverified` and `transact_commit` is accounted for and a
+//! snapshot can be retrieved with [`Evm::stats`](crate::Evm::stats).
+
+use crate::{
+    handler::register::HandleRegisterId,
+    primitives::{EVMError, ExecutionResult, HaltReasonTrait},
+    JournalLoadStats,
+};
+use core::{cell::Cell, time::Duration};
+use std::{collections::VecDeque, rc::Rc, string::String};
+
+/// Snapshot of the statistics gathered by an [`Evm`](crate::Evm).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EvmStats {
+    /// Number of transactions that were executed, including the ones that failed with an error.
+    pub transactions: u64,
+    /// Number of transactions that finished with [`ExecutionResult::Success`].
+    pub successful: u64,
+    /// Number of transactions that finished with [`ExecutionResult::Revert`].
+    pub reverted: u64,
+    /// Number of transactions that finished with [`ExecutionResult::Halt`].
+    pub halted: u64,
+    /// Number of transactions that returned an [`EVMError`].
+    pub failed: u64,
+    /// Total gas used by executed transactions.
+    pub gas_used: u64,
+    /// Number of interpreter instructions executed.
+    pub instructions: u64,
+    /// Wall-clock time spent inside the transact calls.
+    pub elapsed: Duration,
+    /// Journaled state lookups, accumulated over all transactions.
+    pub loads: JournalLoadStats,
+    /// Most recent errors, oldest first.
+    pub last_errors: VecDeque<String>,
+}
+
+impl EvmStats {
+    /// Returns the average gas executed per second, or `None` if no time was recorded.
+    pub fn gas_per_second(&self) -> Option<f64> {
+        per_second(self.gas_used, self.elapsed)
+    }
+
+    /// Returns the average number of interpreter instructions executed per second,
+    /// or `None` if no time was recorded.
+    pub fn instructions_per_second(&self) -> Option<f64> {
+        per_second(self.instructions, self.elapsed)
+    }
+
+    /// Returns the ratio of journaled state lookups that did not require a database
+    /// fetch, or `None` if no lookups were made.
+    pub fn cache_hit_rate(&self) -> Option<f64> {
+        let total = self.loads.total();
+        (total != 0).then(|| self.loads.hits() as f64 / total as f64)
+    }
+}
+
+fn per_second(value: u64, elapsed: Duration) -> Option<f64> {
+    let secs = elapsed.as_secs_f64();
+    (secs > 0.0).then(|| value as f64 / secs)
+}
+
+/// Collects [`EvmStats`] for an [`Evm`](crate::Evm).
+#[derive(Debug)]
+pub(crate) struct StatsCollector {
+    stats: EvmStats,
+    max_errors: usize,
+    /// Shared with the instruction table installed by [`instruction_counter_register`](crate::handler::register::instruction_counter_register).
+    instructions: Rc<Cell<u64>>,
+}
+
+impl StatsCollector {
+    pub(crate) fn new(max_errors: usize) -> Self {
+        Self {
+            stats: EvmStats::default(),
+            max_errors,
+            instructions: Rc::default(),
+        }
+    }
+
+    /// Resets all gathered statistics and sets the new error capacity.
+    pub(crate) fn reset(&mut self, max_errors: usize) {
+        self.stats = EvmStats::default();
+        self.max_errors = max_errors;
+        self.instructions.set(0);
+    }
+
+    /// Returns the counter that is incremented on every executed instruction.
+    pub(crate) fn instruction_counter(&self) -> Rc<Cell<u64>> {
+        self.instructions.clone()
+    }
+
+    /// Returns a snapshot of the statistics.
+    pub(crate) fn snapshot(&self) -> EvmStats {
+        EvmStats {
+            instructions: self.instructions.get(),
+            ..self.stats.clone()
+        }
+    }
+
+    /// Records the outcome of a single transaction.
+    pub(crate) fn record<HaltReasonT: HaltReasonTrait, DBError, TxError: core::fmt::Display>(
+        &mut self,
+        result: Result<&ExecutionResult<HaltReasonT>, &EVMError<DBError, TxError>>,
+        loads: &JournalLoadStats,
+        elapsed: Duration,
+    ) {
+        let stats = &mut self.stats;
+        stats.transactions += 1;
+        stats.elapsed += elapsed;
+        stats.loads.merge(loads);
+        match result {
+            Ok(result) => {
+                stats.gas_used += result.gas_used();
+                match result {
+                    ExecutionResult::Success { .. } => stats.successful += 1,
+                    ExecutionResult::Revert { .. } => stats.reverted += 1,
+                    ExecutionResult::Halt { .. } => stats.halted += 1,
+                }
+            }
+            Err(error) => {
+                stats.failed += 1;
+                if self.max_errors == 0 {
+                    return;
+                }
+                if stats.last_errors.len() == self.max_errors {
+                    stats.last_errors.pop_front();
+                }
+                // Database errors are not required to be printable.
+                let message = match error {
+                    EVMError::Transaction(e) => format!("transaction validation error: {e}"),
+                    EVMError::Header(e) => format!("header validation error: {e}"),
+                    EVMError::Database(_) | EVMError::InstructionDatabase { .. } => {
+                        String::from("database error")
+                    }
+                    EVMError::StateChangeInReadOnly { .. } => {
+                        String::from("state change in read-only execution")
+                    }
+                    EVMError::Precompile(e) | EVMError::Custom(e) => e.clone(),
+                };
+                stats.last_errors.push_back(message);
+            }
+        }
+    }
+}
+
+/// Identifier of the [`instruction_counter_register`](crate::handler::register::instruction_counter_register) installed by
+/// [`Evm::enable_stats`](crate::Evm::enable_stats).
+pub(crate) const INSTRUCTION_COUNTER_REGISTER: HandleRegisterId =
+    HandleRegisterId("revm::stats::instruction_counter");
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        interpreter::opcode::{self, InstructionTables},
+        primitives::{address, AccountInfo, Bytecode, EthereumWiring, TxKind, U256},
+        Evm,
+    };
+
+    #[test]
+    fn collects_transaction_stats() {
+        let contract = address!("0000000000000000000000000000000000000100");
+        let code = Bytecode::new_raw(
+            [
+                opcode::PUSH1,
+                0x01,
+                opcode::PUSH1,
+                0x00,
+                opcode::SSTORE,
+                opcode::STOP,
+            ]
+            .into(),
+        );
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(
+            contract,
+            AccountInfo {
+                code: Some(code),
+                ..Default::default()
+            },
+        );
+
+        let mut evm = Evm::<EthereumWiring<CacheDB<EmptyDB>, ()>>::builder()
+            .with_db(db)
+            .with_default_ext_ctx()
+            .modify_tx_env(|tx| {
+                tx.transact_to = TxKind::Call(contract);
+                tx.gas_limit = 100_000;
+            })
+            .build();
+        assert!(evm.stats().is_none());
+
+        evm.enable_stats(2);
+        evm.transact().unwrap();
+
+        // Not enough balance to cover the gas.
+        evm.tx_mut().gas_price = U256::from(1);
+        for _ in 0..3 {
+            assert!(evm.transact().is_err());
+        }
+
+        let stats = evm.stats().unwrap();
+        assert_eq!(stats.transactions, 4);
+        assert_eq!(stats.successful, 1);
+        assert_eq!(stats.failed, 3);
+        assert_eq!(stats.instructions, 4);
+        assert!(stats.gas_used > 0);
+        assert!(stats.loads.total() > 0);
+        assert_eq!(stats.last_errors.len(), 2);
+
+        // Modifying the EVM drops the statistics and their instruction counter.
+        let mut evm = evm
+            .modify()
+            .modify_tx_env(|tx| tx.gas_price = U256::ZERO)
+            .build();
+        assert!(evm.stats().is_none());
+        assert!(evm.handler.registers.is_empty());
+        assert!(matches!(
+            evm.handler.instruction_table,
+            InstructionTables::Plain(_)
+        ));
+
+        // Enabling them again installs a single counter.
+        evm.enable_stats(2);
+        evm.transact().unwrap();
+        assert_eq!(evm.handler.registers.len(), 1);
+        assert_eq!(evm.stats().unwrap().instructions, 4);
+
+        evm.disable_stats();
+        assert!(evm.stats().is_none());
+        assert!(evm.handler.registers.is_empty());
+    }
+}
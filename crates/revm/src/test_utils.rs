@@ -1,2 +1,187 @@
+//! Helpers for tests of code built on revm, enabled by the `test-utils` feature.
+//!
+//! [`test_evm`] builds an [`Evm`] on an in-memory database with prefunded accounts, whose
+//! keys and addresses are derived from their index by [`test_key`] and [`test_address`], so
+//! they are the same in every run. [`deploy`] and [`call`] execute and commit a transaction
+//! from one of them.
+
 #[doc(hidden)]
 pub use crate::context::evm_context::test_utils::*;
+
+use crate::{
+    db::InMemoryDB,
+    primitives::{
+        keccak256, AccountInfo, Address, Bytes, EVMError, EthereumWiring, ExecutionResult,
+        HaltReason, InvalidTransaction, TxKind, B256, U256,
+    },
+    Evm,
+};
+use core::convert::Infallible;
+use k256::ecdsa::SigningKey;
+
+/// Evm built by [`test_evm`].
+pub type TestEvm = Evm<'static, EthereumWiring<InMemoryDB, ()>>;
+
+/// Balance of the prefunded accounts, `2^64` wei (about 18.4 ether).
+pub const TEST_BALANCE: U256 = U256::from_limbs([0, 1, 0, 0]);
+
+/// Gas limit of the transactions of [`deploy`] and [`call`].
+pub const TEST_GAS_LIMIT: u64 = 30_000_000;
+
+/// Error of [`deploy`] and [`call`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TestError {
+    /// The transaction is invalid.
+    Evm(EVMError<Infallible, InvalidTransaction>),
+    /// The transaction reverted or halted, its state changes are committed.
+    Failed(ExecutionResult<HaltReason>),
+}
+
+impl From<EVMError<Infallible, InvalidTransaction>> for TestError {
+    fn from(error: EVMError<Infallible, InvalidTransaction>) -> Self {
+        Self::Evm(error)
+    }
+}
+
+/// Returns the private key of the test account `index`.
+pub fn test_key(index: u64) -> B256 {
+    keccak256([b"revm test account".as_slice(), &index.to_be_bytes()].concat())
+}
+
+/// Returns the address of the test account `index`, derived from [`test_key`].
+pub fn test_address(index: u64) -> Address {
+    let key = SigningKey::from_slice(test_key(index).as_slice()).expect("key is a valid scalar");
+    let public_key = key.verifying_key().to_encoded_point(false);
+    Address::from_raw_public_key(&public_key.as_bytes()[1..])
+}
+
+/// Returns a database with the test accounts `0..accounts`, funded with [`TEST_BALANCE`].
+pub fn prefunded_db(accounts: u64) -> InMemoryDB {
+    let mut db = InMemoryDB::default();
+    for index in 0..accounts {
+        db.insert_account_info(
+            test_address(index),
+            AccountInfo {
+                balance: TEST_BALANCE,
+                ..Default::default()
+            },
+        );
+    }
+    db
+}
+
+/// Returns an Evm of the latest spec on top of [`prefunded_db`].
+pub fn test_evm(accounts: u64) -> TestEvm {
+    Evm::<EthereumWiring<InMemoryDB, ()>>::builder()
+        .with_db(prefunded_db(accounts))
+        .with_default_ext_ctx()
+        .build()
+}
+
+/// Deploys `init_code` from `caller` and returns the address of the created contract.
+pub fn deploy(evm: &mut TestEvm, caller: Address, init_code: Bytes) -> Result<Address, TestError> {
+    let result = transact(evm, caller, TxKind::Create, init_code)?;
+    match result_address(&result) {
+        Some(address) => Ok(address),
+        None => Err(TestError::Failed(result)),
+    }
+}
+
+/// Calls `to` with `input` from `caller` and returns the output.
+pub fn call(
+    evm: &mut TestEvm,
+    caller: Address,
+    to: Address,
+    input: Bytes,
+) -> Result<Bytes, TestError> {
+    let result = transact(evm, caller, TxKind::Call(to), input)?;
+    if !result.is_success() {
+        return Err(TestError::Failed(result));
+    }
+    Ok(result.into_output().unwrap_or_default())
+}
+
+/// Returns the address created by a successful create transaction.
+fn result_address(result: &ExecutionResult<HaltReason>) -> Option<Address> {
+    match result {
+        ExecutionResult::Success { output, .. } => output.address().copied(),
+        _ => None,
+    }
+}
+
+/// Executes and commits a transaction from `caller` with its current nonce.
+fn transact(
+    evm: &mut TestEvm,
+    caller: Address,
+    transact_to: TxKind,
+    data: Bytes,
+) -> Result<ExecutionResult<HaltReason>, TestError> {
+    let tx = evm.tx_mut();
+    tx.caller = caller;
+    tx.transact_to = transact_to;
+    tx.data = data;
+    tx.value = U256::ZERO;
+    tx.nonce = None;
+    tx.gas_limit = TEST_GAS_LIMIT;
+    Ok(evm.transact_commit()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interpreter::opcode;
+
+    #[test]
+    fn deploy_and_call() {
+        // Returns the 32 bytes stored at slot 0, which the constructor sets to 42.
+        let runtime = [
+            opcode::PUSH0,
+            opcode::SLOAD,
+            opcode::PUSH0,
+            opcode::MSTORE,
+            opcode::PUSH1,
+            0x20,
+            opcode::PUSH0,
+            opcode::RETURN,
+        ];
+        // SSTORE(0, 42), then returns the runtime code, copied from the end of the init code.
+        let mut init_code = [
+            opcode::PUSH1,
+            42,
+            opcode::PUSH0,
+            opcode::SSTORE,
+            opcode::PUSH1,
+            runtime.len() as u8,
+            opcode::DUP1,
+            opcode::PUSH1,
+            0,
+            opcode::PUSH0,
+            opcode::CODECOPY,
+            opcode::PUSH0,
+            opcode::RETURN,
+        ]
+        .to_vec();
+        init_code[8] = init_code.len() as u8;
+        init_code.extend(runtime);
+
+        assert_eq!(test_address(0), test_address(0));
+        assert_ne!(test_address(0), test_address(1));
+
+        let mut evm = test_evm(2);
+        let (deployer, caller) = (test_address(0), test_address(1));
+        let contract = deploy(&mut evm, deployer, init_code.into()).unwrap();
+        assert_eq!(contract, deployer.create(0));
+
+        let output = call(&mut evm, caller, contract, Bytes::new()).unwrap();
+        assert_eq!(output[..], B256::with_last_byte(42)[..]);
+        // The nonces follow the committed transactions.
+        call(&mut evm, caller, contract, Bytes::new()).unwrap();
+
+        // Reverting init code is reported with the result.
+        let revert = [opcode::PUSH0, opcode::PUSH0, opcode::REVERT];
+        assert!(matches!(
+            deploy(&mut evm, deployer, revert.to_vec().into()),
+            Err(TestError::Failed(ExecutionResult::Revert { .. }))
+        ));
+    }
+}
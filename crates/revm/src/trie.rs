@@ -0,0 +1,244 @@
+//! Merkle-Patricia trie roots of the EVM state.
+//!
+//! Roots are computed from plain key-value pairs with [`triehash`], which is suited for
+//! verifying the state of a block against its header, but does not keep any intermediate
+//! nodes around for incremental updates.
+
+use crate::primitives::{
+    b256, db::DatabaseRef, keccak256, AccountInfo, Address, EvmState, HashMap, B256, U256,
+};
+use alloy_rlp::{RlpEncodable, RlpMaxEncodedLen};
+use hash_db::Hasher;
+use plain_hasher::PlainHasher;
+use std::vec::Vec;
+use triehash::sec_trie_root;
+
+/// Root of an empty trie, `keccak256(rlp(""))`.
+pub const EMPTY_ROOT_HASH: B256 =
+    b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+/// Keccak-256 hasher of the trie nodes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct KeccakHasher;
+
+impl Hasher for KeccakHasher {
+    type Out = B256;
+    type StdHasher = PlainHasher;
+    const LENGTH: usize = 32;
+
+    #[inline]
+    fn hash(x: &[u8]) -> Self::Out {
+        keccak256(x)
+    }
+}
+
+/// Account as it is encoded in the state trie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpMaxEncodedLen)]
+pub struct TrieAccount {
+    pub nonce: u64,
+    pub balance: U256,
+    pub storage_root: B256,
+    pub code_hash: B256,
+}
+
+impl TrieAccount {
+    /// Creates the trie account from account info and its storage root.
+    pub fn new(info: &AccountInfo, storage_root: B256) -> Self {
+        Self {
+            nonce: info.nonce,
+            balance: info.balance,
+            storage_root,
+            code_hash: info.code_hash,
+        }
+    }
+}
+
+/// Computes the storage root of an account. Zero valued slots are skipped.
+pub fn storage_root(storage: impl IntoIterator<Item = (U256, U256)>) -> B256 {
+    sec_trie_root::<KeccakHasher, _, _, _>(
+        storage
+            .into_iter()
+            .filter(|(_, value)| !value.is_zero())
+            .map(|(key, value)| {
+                (
+                    key.to_be_bytes::<32>(),
+                    alloy_rlp::encode_fixed_size(&value),
+                )
+            }),
+    )
+}
+
+/// Computes the state root from accounts and their storage roots.
+pub fn state_root(accounts: impl IntoIterator<Item = (Address, TrieAccount)>) -> B256 {
+    sec_trie_root::<KeccakHasher, _, _, _>(
+        accounts
+            .into_iter()
+            .map(|(address, account)| (address, alloy_rlp::encode_fixed_size(&account))),
+    )
+}
+
+/// Computes the state root of `changes` applied on top of the `base` state.
+///
+/// `base_accounts` lists every account of the base state together with the keys of its
+/// storage slots, as a [DatabaseRef] can only be queried and not enumerated. Values of the
+/// untouched accounts and slots are read from `base`.
+///
+/// Touched accounts that are empty are removed from the state, as specified by
+/// [EIP-161](https://eips.ethereum.org/EIPS/eip-161). Created accounts do not inherit
+/// storage of the base state.
+pub fn state_root_with_changes<DB, K>(
+    changes: &EvmState,
+    base: &DB,
+    base_accounts: impl IntoIterator<Item = (Address, K)>,
+) -> Result<B256, DB::Error>
+where
+    DB: DatabaseRef,
+    K: IntoIterator<Item = U256>,
+{
+    let mut base_keys: HashMap<Address, Vec<U256>> = base_accounts
+        .into_iter()
+        .map(|(address, keys)| (address, keys.into_iter().collect()))
+        .collect();
+
+    let mut accounts = Vec::with_capacity(base_keys.len() + changes.len());
+    for (address, account) in changes {
+        let keys = base_keys.remove(address).unwrap_or_default();
+        if account.is_selfdestructed()
+            || (account.is_touched() && account.is_empty())
+            || (account.is_loaded_as_not_existing() && !account.is_touched())
+        {
+            continue;
+        }
+        let mut storage = account
+            .storage
+            .iter()
+            .map(|(key, slot)| (*key, slot.present_value))
+            .collect::<Vec<_>>();
+        if !account.is_created() {
+            for key in keys {
+                if !account.storage.contains_key(&key) {
+                    storage.push((key, base.storage_ref(*address, key)?));
+                }
+            }
+        }
+        accounts.push((
+            *address,
+            TrieAccount::new(&account.info, storage_root(storage)),
+        ));
+    }
+
+    for (address, keys) in base_keys {
+        let Some(info) = base.basic_ref(address)? else {
+            continue;
+        };
+        let storage = keys
+            .into_iter()
+            .map(|key| Ok((key, base.storage_ref(address, key)?)))
+            .collect::<Result<Vec<_>, DB::Error>>()?;
+        accounts.push((address, TrieAccount::new(&info, storage_root(storage))));
+    }
+
+    Ok(state_root(accounts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::{CacheDB, EmptyDB},
+        primitives::{address, Account, AccountStatus, EvmStorageSlot, KECCAK_EMPTY},
+    };
+
+    #[test]
+    fn empty_state_root() {
+        assert_eq!(state_root([]), EMPTY_ROOT_HASH);
+        assert_eq!(storage_root([(U256::from(1), U256::ZERO)]), EMPTY_ROOT_HASH);
+    }
+
+    /// Post state of the Homestead `yul_example` state test of ethereum/tests, see
+    /// `tests/prague_suite/state_tests/homestead/yul/yul_example/yul.json`.
+    #[test]
+    fn ethereum_tests_post_state_root() {
+        let contract = TrieAccount {
+            nonce: 1,
+            balance: U256::from(0x0ba1a9ce0ba1a9ce_u64),
+            storage_root: storage_root([(U256::ZERO, U256::from(3))]),
+            code_hash: b256!("f3c9659b117782a41f95991df885ae92635c08a10e7300ab705ef77b00b6989b"),
+        };
+        let sender = TrieAccount {
+            nonce: 1,
+            balance: U256::from(0x0ba1a9ce0ba1a9ce_u64 - 410690),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let coinbase = TrieAccount {
+            nonce: 0,
+            balance: U256::from(410690),
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        };
+        let root = state_root([
+            (
+                address!("0000000000000000000000000000000000001000"),
+                contract,
+            ),
+            (address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b"), sender),
+            (
+                address!("2adc25665018aa1fe0e6bc666dac8fc2697ff9ba"),
+                coinbase,
+            ),
+        ]);
+        assert_eq!(
+            root,
+            b256!("154f5935a676cbe98229394ce886e15212312de9a32076027fd4b77e79ad3d18")
+        );
+    }
+
+    #[test]
+    fn changes_on_top_of_base() {
+        let unchanged = Address::with_last_byte(1);
+        let changed = Address::with_last_byte(2);
+        let info = AccountInfo::from_balance(U256::from(10));
+
+        let mut base = CacheDB::new(EmptyDB::default());
+        base.insert_account_info(unchanged, info.clone());
+        base.insert_account_info(changed, info.clone());
+        for address in [unchanged, changed] {
+            for key in 1..=2 {
+                base.insert_account_storage(address, U256::from(key), U256::from(key))
+                    .unwrap();
+            }
+        }
+
+        let mut account = Account::from(info.clone());
+        account.status = AccountStatus::Touched;
+        account.info.balance = U256::from(20);
+        account.storage.insert(
+            U256::from(2),
+            EvmStorageSlot::new_changed(U256::from(2), U256::from(3)),
+        );
+        let changes = HashMap::from_iter([(changed, account)]);
+
+        let keys = || [U256::from(1), U256::from(2)];
+        let root =
+            state_root_with_changes(&changes, &base, [(unchanged, keys()), (changed, keys())])
+                .unwrap();
+
+        let unchanged_storage = storage_root([
+            (U256::from(1), U256::from(1)),
+            (U256::from(2), U256::from(2)),
+        ]);
+        let changed_storage = storage_root([
+            (U256::from(1), U256::from(1)),
+            (U256::from(2), U256::from(3)),
+        ]);
+        let expected = state_root([
+            (unchanged, TrieAccount::new(&info, unchanged_storage)),
+            (
+                changed,
+                TrieAccount::new(&AccountInfo::from_balance(U256::from(20)), changed_storage),
+            ),
+        ]);
+        assert_eq!(root, expected);
+    }
+}
@@ -0,0 +1,99 @@
+//! Sponsored transactions of the custom chain example.
+
+#[allow(dead_code)]
+#[path = "../../../examples/custom_chain.rs"]
+pub mod custom_chain;
+
+use custom_chain::CustomChainWiring;
+use revm::{
+    db::{CacheDB, EmptyDB},
+    primitives::{
+        AccountInfo, Address, EVMError, InvalidTransaction, ResultAndState, SpecId, TxKind, U256,
+    },
+    Evm,
+};
+
+const GAS_PRICE: u64 = 10;
+
+fn caller() -> Address {
+    Address::with_last_byte(0xaa)
+}
+
+fn sponsor() -> Address {
+    Address::with_last_byte(0xbb)
+}
+
+fn receiver() -> Address {
+    Address::with_last_byte(0xcc)
+}
+
+/// Returns an EVM that transfers 100 wei from a caller with `caller_balance` to the receiver.
+fn evm(
+    caller_balance: u64,
+    sponsor_balance: u64,
+    sponsor: Option<Address>,
+) -> Evm<'static, CustomChainWiring<CacheDB<EmptyDB>, ()>> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        caller(),
+        AccountInfo::from_balance(U256::from(caller_balance)),
+    );
+    db.insert_account_info(
+        self::sponsor(),
+        AccountInfo::from_balance(U256::from(sponsor_balance)),
+    );
+
+    Evm::<CustomChainWiring<_, ()>>::builder()
+        .with_db(db)
+        .with_default_ext_ctx()
+        .with_spec_id(SpecId::CANCUN)
+        .modify_tx_env(|tx| {
+            tx.base.caller = caller();
+            tx.base.transact_to = TxKind::Call(receiver());
+            tx.base.value = U256::from(100);
+            tx.base.gas_limit = 50_000;
+            tx.base.gas_price = U256::from(GAS_PRICE);
+            tx.sponsor = sponsor;
+        })
+        .build()
+}
+
+#[test]
+fn sponsor_pays_for_gas() {
+    let ResultAndState { result, state } = evm(100, 1_000_000, Some(sponsor())).transact().unwrap();
+    assert!(result.is_success());
+    let gas_cost = U256::from(GAS_PRICE * result.gas_used());
+
+    // The caller only paid for the value, the sponsor for the gas.
+    assert_eq!(state[&caller()].info.balance, U256::ZERO);
+    assert_eq!(state[&caller()].info.nonce, 1);
+    assert_eq!(state[&receiver()].info.balance, U256::from(100));
+    assert_eq!(
+        state[&sponsor()].info.balance,
+        U256::from(1_000_000) - gas_cost
+    );
+}
+
+#[test]
+fn caller_pays_without_sponsor() {
+    let ResultAndState { result, state } = evm(1_000_100, 1_000_000, None).transact().unwrap();
+    assert!(result.is_success());
+    let gas_cost = U256::from(GAS_PRICE * result.gas_used());
+
+    assert_eq!(
+        state[&caller()].info.balance,
+        U256::from(1_000_000) - gas_cost
+    );
+    assert_eq!(state[&receiver()].info.balance, U256::from(100));
+    assert!(!state.contains_key(&sponsor()));
+}
+
+#[test]
+fn sponsor_without_funds() {
+    assert!(matches!(
+        evm(100, 1_000, Some(sponsor())).transact(),
+        Err(EVMError::Transaction(
+            InvalidTransaction::LackOfFundForMaxFee { .. }
+        ))
+    ));
+}
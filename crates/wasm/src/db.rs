@@ -0,0 +1,142 @@
+use revm::{
+    primitives::{AccountInfo, Address, Bytecode, Bytes, HashMap, B256, U256},
+    Database,
+};
+use serde::Deserialize;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const STATE_PROVIDER: &str = r#"
+/** State of the chain, read by the EVM during execution. Numbers and hashes are hex strings. */
+export interface StateProvider {
+    /** Returns the account at `address`, or `undefined` if it does not exist. */
+    basic(address: string): { balance?: string; nonce?: number; code?: string } | undefined;
+    /** Returns the value of the storage slot `slot` of `address`. */
+    storage(address: string, slot: string): string;
+    /** Returns the hash of the block with the given number. */
+    blockHash(number: bigint): string;
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// JavaScript object implementing the `StateProvider` TypeScript interface.
+    #[wasm_bindgen(typescript_type = "StateProvider")]
+    #[derive(Debug)]
+    pub type StateProvider;
+
+    #[wasm_bindgen(method, catch)]
+    fn basic(this: &StateProvider, address: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch)]
+    fn storage(this: &StateProvider, address: String, slot: String) -> Result<JsValue, JsValue>;
+
+    #[wasm_bindgen(method, catch, js_name = blockHash)]
+    fn block_hash(this: &StateProvider, number: u64) -> Result<JsValue, JsValue>;
+}
+
+/// Account as returned by [StateProvider].
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct JsAccount {
+    balance: U256,
+    nonce: u64,
+    code: Bytes,
+}
+
+impl JsAccount {
+    fn into_account_info(self) -> Result<AccountInfo, JsValue> {
+        let code = Bytecode::new_raw_checked(self.code)
+            .map_err(|error| js_sys::Error::new(&format!("invalid code: {error:?}")))?;
+        Ok(AccountInfo::new(
+            self.balance,
+            self.nonce,
+            code.hash_slow(),
+            code,
+        ))
+    }
+}
+
+/// [Database] that reads the state from a JavaScript [StateProvider].
+///
+/// Exceptions thrown by the provider are returned as database errors.
+#[derive(Debug)]
+pub struct JsDatabase {
+    provider: StateProvider,
+    /// Code of the loaded accounts, by hash.
+    contracts: HashMap<B256, Bytecode>,
+}
+
+impl JsDatabase {
+    /// Creates a new database reading from `provider`.
+    pub fn new(provider: StateProvider) -> Self {
+        Self {
+            provider,
+            contracts: HashMap::default(),
+        }
+    }
+}
+
+impl Database for JsDatabase {
+    type Error = JsValue;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let account = self.provider.basic(address.to_string())?;
+        if account.is_undefined() || account.is_null() {
+            return Ok(None);
+        }
+        let account: JsAccount = serde_wasm_bindgen::from_value(account)?;
+        let info = account.into_account_info()?;
+        if let Some(code) = &info.code {
+            self.contracts.insert(info.code_hash, code.clone());
+        }
+        Ok(Some(info))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        // The provider returns the code along with the account, so it is always cached.
+        Ok(self.contracts.get(&code_hash).cloned().unwrap_or_default())
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let value = self
+            .provider
+            .storage(address.to_string(), format!("{index:#x}"))?;
+        Ok(serde_wasm_bindgen::from_value(value)?)
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        let hash = self.provider.block_hash(number)?;
+        Ok(serde_wasm_bindgen::from_value(hash)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use revm::primitives::{bytes, KECCAK_EMPTY};
+
+    #[test]
+    fn account_info() {
+        let info = JsAccount {
+            balance: U256::from(10),
+            nonce: 1,
+            code: Bytes::new(),
+        }
+        .into_account_info()
+        .unwrap();
+        assert_eq!(info.code_hash, KECCAK_EMPTY);
+        assert_eq!(info.balance, U256::from(10));
+
+        let code = bytes!("6000");
+        let info = JsAccount {
+            code: code.clone(),
+            ..Default::default()
+        }
+        .into_account_info()
+        .unwrap();
+        let bytecode = Bytecode::new_raw(code);
+        assert_eq!(info.code_hash, bytecode.hash_slow());
+        assert_eq!(info.code, Some(bytecode));
+    }
+}
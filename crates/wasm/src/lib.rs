@@ -0,0 +1,94 @@
+//! JavaScript bindings of revm, built with `wasm-bindgen`.
+//!
+//! The bindings let browsers and other JavaScript environments simulate transactions without a
+//! server: [transact] executes a transaction on top of a state read from a JavaScript
+//! [StateProvider] and returns the result together with the changed state.
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+
+mod db;
+
+pub use db::{JsDatabase, StateProvider};
+
+use js_sys::Object;
+use revm::{
+    primitives::{BlockEnv, EVMError, EthereumWiring, InvalidTransaction, SpecId, TxEnv},
+    Evm,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_wasm_bindgen::Serializer;
+use wasm_bindgen::prelude::*;
+
+#[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+use getrandom as _;
+
+/// Serializer of the values returned to JavaScript.
+///
+/// Maps are serialized as plain objects and 64-bit numbers as `bigint`, as they do not fit in
+/// a JavaScript number.
+const SERIALIZER: Serializer = Serializer::new()
+    .serialize_maps_as_objects(true)
+    .serialize_large_number_types_as_bigints(true);
+
+/// Executes a transaction on top of the state of `state`, without committing it.
+///
+/// `block` and `tx` are objects with the fields of [BlockEnv] and [TxEnv], missing fields
+/// keep their default value. `spec` is the name of the hardfork, for example `"CANCUN"` or
+/// `"LATEST"`.
+///
+/// Returns the execution result and the changed state, in the format of
+/// [ResultAndState](revm::primitives::ResultAndState). Invalid transactions and exceptions
+/// thrown by `state` are thrown back.
+#[wasm_bindgen]
+pub fn transact(
+    state: StateProvider,
+    spec: &str,
+    block: JsValue,
+    tx: JsValue,
+) -> Result<JsValue, JsValue> {
+    let spec_id = SpecId::from(spec);
+    if spec_id == SpecId::LATEST && !spec.eq_ignore_ascii_case("latest") {
+        return Err(js_sys::Error::new(&format!("unknown spec `{spec}`")).into());
+    }
+    let block: BlockEnv = with_defaults(block)?;
+    let tx: TxEnv = with_defaults(tx)?;
+
+    let mut evm = Evm::<EthereumWiring<JsDatabase, ()>>::builder()
+        .with_db(JsDatabase::new(state))
+        .with_default_ext_ctx()
+        .with_spec_id(spec_id)
+        .with_block_env(block)
+        .with_tx_env(tx)
+        .build();
+    let result_and_state = evm.transact().map_err(into_js_error)?;
+    Ok(result_and_state.serialize(&SERIALIZER)?)
+}
+
+/// Deserializes `value`, taking missing fields from the default value of `T`.
+fn with_defaults<T: Default + Serialize + DeserializeOwned>(value: JsValue) -> Result<T, JsValue> {
+    if value.is_undefined() || value.is_null() {
+        return Ok(T::default());
+    }
+    let defaults: Object = T::default().serialize(&SERIALIZER)?.unchecked_into();
+    let merged = Object::assign(&defaults, &value.unchecked_into());
+    Ok(serde_wasm_bindgen::from_value(merged.into())?)
+}
+
+/// Converts an error of the EVM to a JavaScript exception.
+///
+/// Database errors are the exceptions thrown by the [StateProvider], and are returned as is.
+fn into_js_error(error: EVMError<JsValue, InvalidTransaction>) -> JsValue {
+    let message = match error {
+        EVMError::Database(error) | EVMError::InstructionDatabase { error, .. } => return error,
+        EVMError::Transaction(error) => format!("transaction validation error: {error}"),
+        EVMError::Header(error) => format!("header validation error: {error}"),
+        EVMError::StateChangeInReadOnly { location } => match location {
+            Some(location) => format!(
+                "state change in read-only execution at {} pc {} depth {}",
+                location.address, location.pc, location.depth
+            ),
+            None => String::from("state change in read-only execution by the transaction"),
+        },
+        EVMError::Precompile(error) | EVMError::Custom(error) => error,
+    };
+    js_sys::Error::new(&message).into()
+}
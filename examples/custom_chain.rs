@@ -0,0 +1,257 @@
+//! Template of a custom chain that plugs its own transaction type into revm.
+//!
+//! The chain extends the Ethereum transaction with an optional `sponsor` that pays for the
+//! gas of the transaction, while the caller only pays for the transferred value. The extra
+//! field is consumed by the validation, pre execution and post execution handles, which
+//! are installed by a handle register of the chain's [`revm::EvmWiring`].
+use core::{fmt::Debug, marker::PhantomData};
+use revm::{
+    db::{CacheDB, EmptyDB},
+    handler::{mainnet, register::HandleRegisters},
+    interpreter::Gas,
+    primitives::{
+        self, db::Database, spec_to_generic, AccessListItem, AccountInfo, Address,
+        AuthorizationList, BlockEnv, Bytes, EVMError, EVMResultGeneric, HaltReason,
+        InvalidTransaction, ResultAndState, Spec, SpecId, Transaction, TransactionValidation,
+        TxEnv, TxKind, B256, U256,
+    },
+    Context, Evm, EvmHandler,
+};
+use std::sync::Arc;
+
+/// Wiring of the custom chain.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CustomChainWiring<DB: Database, EXT> {
+    _phantom: PhantomData<(DB, EXT)>,
+}
+
+impl<DB: Database, EXT: Debug> primitives::EvmWiring for CustomChainWiring<DB, EXT> {
+    type Database = DB;
+    type ExternalContext = EXT;
+    type ChainContext = ();
+    type Block = BlockEnv;
+    type Transaction = CustomTxEnv;
+    type Hardfork = SpecId;
+    type HaltReason = HaltReason;
+}
+
+impl<DB: Database, EXT: Debug> revm::EvmWiring for CustomChainWiring<DB, EXT> {
+    fn handler<'evm>(hardfork: Self::Hardfork) -> EvmHandler<'evm, Self> {
+        let mut handler = EvmHandler::mainnet_with_spec(hardfork);
+        handler.append_handler_register(HandleRegisters::Plain(sponsor_handle_register::<DB, EXT>));
+        handler
+    }
+}
+
+/// Transaction of the custom chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CustomTxEnv {
+    /// Ethereum transaction fields.
+    pub base: TxEnv,
+    /// Account that pays for the gas of the transaction instead of the caller.
+    pub sponsor: Option<Address>,
+}
+
+impl Transaction for CustomTxEnv {
+    fn caller(&self) -> &Address {
+        self.base.caller()
+    }
+
+    fn gas_limit(&self) -> u64 {
+        self.base.gas_limit()
+    }
+
+    fn gas_price(&self) -> &U256 {
+        self.base.gas_price()
+    }
+
+    fn kind(&self) -> TxKind {
+        self.base.kind()
+    }
+
+    fn value(&self) -> &U256 {
+        self.base.value()
+    }
+
+    fn data(&self) -> &Bytes {
+        self.base.data()
+    }
+
+    fn nonce(&self) -> Option<u64> {
+        self.base.nonce()
+    }
+
+    fn chain_id(&self) -> Option<u64> {
+        self.base.chain_id()
+    }
+
+    fn access_list(&self) -> &[AccessListItem] {
+        self.base.access_list()
+    }
+
+    fn max_priority_fee_per_gas(&self) -> Option<&U256> {
+        self.base.max_priority_fee_per_gas()
+    }
+
+    fn blob_hashes(&self) -> &[B256] {
+        self.base.blob_hashes()
+    }
+
+    fn max_fee_per_blob_gas(&self) -> Option<&U256> {
+        self.base.max_fee_per_blob_gas()
+    }
+
+    fn authorization_list(&self) -> Option<&AuthorizationList> {
+        self.base.authorization_list()
+    }
+}
+
+impl TransactionValidation for CustomTxEnv {
+    type ValidationError = InvalidTransaction;
+}
+
+/// Replaces the handles that charge and reimburse gas, so that the sponsor pays for it.
+pub fn sponsor_handle_register<DB: Database, EXT: Debug>(
+    handler: &mut EvmHandler<'_, CustomChainWiring<DB, EXT>>,
+) {
+    spec_to_generic!(handler.spec_id, {
+        handler.validation.tx_against_state = Arc::new(validate_tx_against_state::<DB, EXT, SPEC>);
+        handler.pre_execution.deduct_caller = Arc::new(deduct_caller::<DB, EXT, SPEC>);
+        handler.post_execution.reimburse_caller = Arc::new(reimburse_caller::<DB, EXT>);
+    });
+}
+
+/// Checks that the sponsor can pay for the gas and the caller for the value.
+fn validate_tx_against_state<DB: Database, EXT: Debug, SPEC: Spec>(
+    context: &mut Context<CustomChainWiring<DB, EXT>>,
+) -> EVMResultGeneric<(), CustomChainWiring<DB, EXT>> {
+    let Some(sponsor) = context.evm.env.tx.sponsor else {
+        return mainnet::validate_tx_against_state::<_, SPEC>(context);
+    };
+    let tx = &context.evm.inner.env.tx;
+    let (caller, nonce, value) = (*tx.caller(), tx.nonce(), *tx.value());
+    let gas_cost = U256::from(tx.gas_limit()).saturating_mul(*tx.gas_price());
+
+    let inner = &mut context.evm.inner;
+    let sponsor_account = inner
+        .journaled_state
+        .load_account(sponsor, &mut inner.db)
+        .map_err(EVMError::Database)?;
+    if sponsor_account.info.balance < gas_cost {
+        return Err(EVMError::Transaction(
+            InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(gas_cost),
+                balance: Box::new(sponsor_account.info.balance),
+            },
+        ));
+    }
+
+    let caller_account = inner
+        .journaled_state
+        .load_account(caller, &mut inner.db)
+        .map_err(EVMError::Database)?;
+    let state = caller_account.info.nonce;
+    if let Some(nonce) = nonce.filter(|nonce| *nonce != state) {
+        let error = if nonce > state {
+            InvalidTransaction::NonceTooHigh { tx: nonce, state }
+        } else {
+            InvalidTransaction::NonceTooLow { tx: nonce, state }
+        };
+        return Err(EVMError::Transaction(error));
+    }
+    if caller_account.info.balance < value {
+        return Err(EVMError::Transaction(
+            InvalidTransaction::LackOfFundForMaxFee {
+                fee: Box::new(value),
+                balance: Box::new(caller_account.info.balance),
+            },
+        ));
+    }
+    Ok(())
+}
+
+/// Deducts the gas from the sponsor and bumps the nonce of the caller.
+fn deduct_caller<DB: Database, EXT: Debug, SPEC: Spec>(
+    context: &mut Context<CustomChainWiring<DB, EXT>>,
+) -> EVMResultGeneric<(), CustomChainWiring<DB, EXT>> {
+    let Some(sponsor) = context.evm.env.tx.sponsor else {
+        return mainnet::deduct_caller::<_, SPEC>(context);
+    };
+    let env = &context.evm.inner.env;
+    let caller = *env.tx.caller();
+    let is_call = env.tx.kind().is_call();
+    let gas_cost = U256::from(env.tx.gas_limit()).saturating_mul(env.effective_gas_price());
+
+    let inner = &mut context.evm.inner;
+    let mut sponsor_account = inner
+        .journaled_state
+        .load_account(sponsor, &mut inner.db)
+        .map_err(EVMError::Database)?;
+    sponsor_account.info.balance = sponsor_account.info.balance.saturating_sub(gas_cost);
+    sponsor_account.mark_touch();
+
+    let mut caller_account = inner
+        .journaled_state
+        .load_account(caller, &mut inner.db)
+        .map_err(EVMError::Database)?;
+    // Nonce for CREATE is bumped when the contract is created.
+    if is_call {
+        caller_account.info.nonce = caller_account.info.nonce.saturating_add(1);
+    }
+    caller_account.mark_touch();
+    Ok(())
+}
+
+/// Reimburses the unspent gas to the sponsor.
+fn reimburse_caller<DB: Database, EXT: Debug>(
+    context: &mut Context<CustomChainWiring<DB, EXT>>,
+    gas: &Gas,
+) -> EVMResultGeneric<(), CustomChainWiring<DB, EXT>> {
+    let Some(sponsor) = context.evm.env.tx.sponsor else {
+        return mainnet::reimburse_caller(context, gas);
+    };
+    let reimbursement = context.evm.inner.env.effective_gas_price()
+        * U256::from(gas.remaining() + gas.refunded() as u64);
+
+    let inner = &mut context.evm.inner;
+    let mut sponsor_account = inner
+        .journaled_state
+        .load_account(sponsor, &mut inner.db)
+        .map_err(EVMError::Database)?;
+    sponsor_account.info.balance = sponsor_account.info.balance.saturating_add(reimbursement);
+    Ok(())
+}
+
+fn main() -> anyhow::Result<()> {
+    let caller = Address::with_last_byte(0xaa);
+    let sponsor = Address::with_last_byte(0xbb);
+    let receiver = Address::with_last_byte(0xcc);
+    let gas_price = U256::from(10);
+
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(caller, AccountInfo::from_balance(U256::from(100)));
+    db.insert_account_info(sponsor, AccountInfo::from_balance(U256::from(1_000_000)));
+
+    let mut evm = Evm::<CustomChainWiring<_, ()>>::builder()
+        .with_db(db)
+        .with_default_ext_ctx()
+        .with_spec_id(SpecId::CANCUN)
+        .modify_tx_env(|tx| {
+            tx.base.caller = caller;
+            tx.base.transact_to = TxKind::Call(receiver);
+            tx.base.value = U256::from(100);
+            tx.base.gas_limit = 50_000;
+            tx.base.gas_price = gas_price;
+            tx.sponsor = Some(sponsor);
+        })
+        .build();
+
+    let ResultAndState { result, state } = evm.transact()?;
+    let gas_cost = gas_price * U256::from(result.gas_used());
+    println!(
+        "Sponsor paid {gas_cost} wei for {} gas, the caller has {} wei left",
+        result.gas_used(),
+        state[&caller].info.balance
+    );
+    Ok(())
+}
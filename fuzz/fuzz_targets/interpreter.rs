@@ -0,0 +1,12 @@
+//! Executes generated bytecode for every spec id and checks the invariants of the harness.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use revm_interpreter::fuzz::{generate_bytecode, FuzzHarness};
+
+fuzz_target!(|data: &[u8]| {
+    let code = generate_bytecode(data);
+    if let Err(violation) = FuzzHarness::default().run(&code) {
+        panic!("{violation:?} in {code}");
+    }
+});
@@ -0,0 +1,80 @@
+//! Applies random operations to a [Stack] and compares it with a `Vec` model.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use revm_interpreter::{primitives::U256, InstructionResult, Stack};
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&limit, mut data)) = data.split_first() else {
+        return;
+    };
+    let mut stack = Stack::new();
+    stack.set_limit(limit as usize + 1);
+    let limit = stack.limit();
+    let mut model: Vec<U256> = Vec::new();
+
+    while let Some((&op, rest)) = data.split_first() {
+        let arg = rest.first().copied().unwrap_or_default() as usize;
+        data = rest.get(1..).unwrap_or_default();
+        let (result, expected) = match op % 6 {
+            0 => {
+                let value = U256::from(arg);
+                let expected = if model.len() < limit {
+                    model.push(value);
+                    Ok(())
+                } else {
+                    Err(InstructionResult::StackOverflow)
+                };
+                (stack.push(value), expected)
+            }
+            1 => {
+                let expected = model.pop().ok_or(InstructionResult::StackUnderflow);
+                let popped = stack.pop();
+                assert_eq!(popped, expected);
+                (popped.map(drop), expected.map(drop))
+            }
+            2 => {
+                let n = arg % 16 + 1;
+                let expected = if model.len() < n {
+                    Err(InstructionResult::StackUnderflow)
+                } else if model.len() == limit {
+                    Err(InstructionResult::StackOverflow)
+                } else {
+                    model.push(model[model.len() - n]);
+                    Ok(())
+                };
+                (stack.dup(n), expected)
+            }
+            3 | 4 => {
+                let (n, m) = if op % 6 == 3 {
+                    (0, arg % 16 + 1)
+                } else {
+                    (arg % 16, arg / 16 + 1)
+                };
+                let expected = if n + m >= model.len() {
+                    Err(InstructionResult::StackUnderflow)
+                } else {
+                    let top = model.len() - 1;
+                    model.swap(top - n, top - n - m);
+                    Ok(())
+                };
+                (stack.exchange(n, m), expected)
+            }
+            _ => {
+                let len = arg.min(data.len());
+                let (slice, rest) = data.split_at(len);
+                data = rest;
+                let words = slice.chunks(32).map(U256::from_be_slice);
+                let expected = if model.len() + words.len() > limit {
+                    Err(InstructionResult::StackOverflow)
+                } else {
+                    model.extend(words);
+                    Ok(())
+                };
+                (stack.push_slice(slice), expected)
+            }
+        };
+        assert_eq!(result, expected);
+        assert_eq!(stack.data(), &model);
+    }
+});